@@ -0,0 +1,53 @@
+//! 解析性能基准测试
+//!
+//! 默认使用仓库内置的模板样本，设置 `WXML_BENCH_DIR` 环境变量可改为加载指定目录下的模板：
+//!
+//! ```sh
+//! WXML_BENCH_DIR=path/to/miniprogram cargo bench --no-default-features
+//! ```
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use miniprogram_template_parser::{
+  fixtures::{builtin_fixtures, load_fixtures, Fixture},
+  parser::Parser,
+};
+
+fn fixtures() -> Vec<Fixture> {
+  match std::env::var_os("WXML_BENCH_DIR") {
+    Some(dir) => load_fixtures(&dir).expect("failed to load WXML_BENCH_DIR"),
+    None => builtin_fixtures(),
+  }
+}
+
+fn parse(c: &mut Criterion) {
+  let fixtures = fixtures();
+  let mut group = c.benchmark_group("parse");
+  for fixture in &fixtures {
+    group.throughput(Throughput::Bytes(fixture.source.len() as u64));
+    group.bench_with_input(
+      BenchmarkId::new("owned", &fixture.name),
+      &fixture.source,
+      |b, source| b.iter(|| Parser::new(black_box(source)).parse_root().unwrap()),
+    );
+    group.bench_with_input(
+      BenchmarkId::new("borrowed", &fixture.name),
+      &fixture.source,
+      |b, source| {
+        b.iter(|| {
+          Parser::new(black_box(source))
+            .parse_root_borrowed()
+            .unwrap()
+        })
+      },
+    );
+    group.bench_with_input(
+      BenchmarkId::new("arena", &fixture.name),
+      &fixture.source,
+      |b, source| b.iter(|| Parser::new(black_box(source)).parse_arena().unwrap()),
+    );
+  }
+  group.finish();
+}
+
+criterion_group!(benches, parse);
+criterion_main!(benches);
@@ -2,34 +2,350 @@
 /* eslint-disable */
 export interface Attribute {
   name: string
+  /** 名称前缀，如 `wx:if` 的 `wx`、`capture-bind:tap` 的 `capture-bind`、`data-id` 的 `data` */
+  prefix?: string
+  /** 去掉前缀后的名称，没有前缀时与 `name` 相同 */
+  local: string
   value?: Array<AttributeValue>
+  /** 属性值的引号风格 */
+  quote: AttributeQuote
   start: Position
   end: Position
 }
 
+/** 属性值使用的引号 */
+export declare const enum AttributeQuote {
+  /** `name="value"` */
+  Double = 0,
+  /** `name='value'` */
+  Single = 1,
+  /** 无值属性 `name` 或宽松模式下的 `name=value` */
+  None = 2
+}
+
 export type AttributeValue =
   | { type: 'Text', content: string, start: Position, end: Position }
   | { type: 'Expression', content: string, start: Position, end: Position }
 
+/** 禁止使用的属性 */
+export interface BannedAttribute {
+  /** 属性名，如 `open-type` */
+  name: string
+  /** 只禁止该静态值，如 `getUserInfo`；不设置时禁止整个属性 */
+  value?: string
+  /** 只检查该标签上的属性，不设置时检查所有元素 */
+  tag?: string
+  /** 自定义的诊断信息 */
+  message?: string
+}
+
+/** 禁止使用的标签 */
+export interface BannedTag {
+  /** 标签名，如 `web-view` */
+  name: string
+  /** 自定义的诊断信息，如说明替代方案 */
+  message?: string
+}
+
+/** 检查规则产生的诊断信息 */
+export interface Diagnostic {
+  /** 规则名称，如 `no-duplicate-attrs` */
+  rule: string
+  /** 规则的稳定编号，如 `WXML2001` */
+  code: string
+  message: string
+  /** 严重级别，缺省时为 [`Severity::Warning`] */
+  severity: Severity
+  start: Position
+  end: Position
+  /** 自动修复需要的改动，规则不提供修复时为空 */
+  fix: Array<Edit>
+}
+
+/** 诊断信息的严重级别，按从重到轻排序 */
+export declare const enum Severity {
+  /** 模板无法按预期工作，如语法错误 */
+  Error = 'error',
+  /** 可能的问题，检查规则的默认级别 */
+  Warning = 'warning',
+  /** 建议，如代码风格 */
+  Hint = 'hint'
+}
+
+/** 一次文本替换：将源码中 `start..end` 字节范围替换为 `replacement`，范围为空时即为插入 */
+export interface Edit {
+  start: number
+  end: number
+  replacement: string
+}
+
 export interface Expression {
   content: string
   start: Position
   end: Position
 }
 
+/** 表达式复杂度的限制，未设置的项不检查 */
+export interface ExpressionLimits {
+  /** 单个表达式中运算符（一元、二元运算与三元运算）数量的上限 */
+  maxOperators?: number
+  /** 禁止函数调用，wxs 模块导出的函数除外 */
+  noCalls?: boolean
+  /** 禁止赋值与自增、自减 */
+  noAssignment?: boolean
+  /** 禁止 `new` */
+  noNew?: boolean
+}
+
+/** 检查配置，供 Node.js 绑定等无法直接组装 [`Linter`] 的调用方使用 */
+export interface LintConfig {
+  /** 同时启用无障碍规则，默认只启用推荐规则 */
+  accessibility?: boolean
+  /** 同时启用代码风格规则，如 `attr-order` */
+  stylistic?: boolean
+  /** 按名称禁用的规则 */
+  disabledRules?: Array<string>
+  /** 禁止使用的标签，设置后启用 `no-banned` 规则 */
+  bannedTags?: Array<BannedTag>
+  /** 禁止使用的属性，设置后启用 `no-banned` 规则 */
+  bannedAttributes?: Array<BannedAttribute>
+  /** 要求文档只有一个根元素，设置后启用 `single-root` 规则，适用于组件模板 */
+  singleRoot?: boolean
+  /** 项目支持的最低基础库版本，如 `2.10.0`，设置后启用 `min-version` 规则 */
+  targetVersion?: string
+  /** 表达式复杂度的限制，设置后启用 `no-complex-expression` 规则 */
+  expressionLimits?: ExpressionLimits
+  /** 解析选项 */
+  parser?: ParserOptions
+}
+
+/**
+ * 在后台线程中并行解析并检查多个模板文件，键为文件路径，值为源码；
+ * 结果按文件分组并按路径排序，返回 Promise；配置无效（如无法解析的 `targetVersion`）时 Promise 被拒绝
+ */
+export declare function lintProject(files: Record<string, string>, config?: LintConfig | undefined | null): Promise<Array<LintResult>>
+
+/** 模板方言 */
+export declare const enum Dialect {
+  /** 微信小程序 WXML */
+  Wxml = 'wxml',
+  /** 百度智能小程序 Swan */
+  Swan = 'swan',
+  /** 抖音小程序 TTML */
+  Ttml = 'ttml'
+}
+
+/** 查找光标所在标签的配对标签，见 [`matching::matching_tag`] */
+export declare function matchingTag(source: string, offset: number, options?: ParserOptions | undefined | null): TagMatch | null
+
+/** 一个元素的开始标签与结束标签中标签名的位置 */
+export interface TagMatch {
+  name: string
+  /** 开始标签中标签名的范围 */
+  openStart: Position
+  openEnd: Position
+  /** 结束标签中标签名的范围，自闭合元素没有结束标签 */
+  closeStart?: Position
+  closeEnd?: Position
+}
+
+/** 建议在光标处插入的结束标签，见 [`matching::auto_close_hint`] */
+export declare function autoCloseHint(source: string, offset: number): string | null
+
+/** 输出语义高亮片段，见 [`semantic`] */
+export declare function semanticTokens(source: string, options?: ParserOptions | undefined | null): Array<SemanticToken>
+
+/** 一个高亮片段，注释等片段可能跨越多行 */
+export interface SemanticToken {
+  kind: SemanticTokenKind
+  start: Position
+  end: Position
+}
+
+/** 片段种类 */
+export declare const enum SemanticTokenKind {
+  /** 开始与结束标签中的标签名 */
+  Tag = 'tag',
+  /** 普通属性名 */
+  Attribute = 'attribute',
+  /** 指令属性名，如 `wx:if`、`s-for` */
+  Directive = 'directive',
+  /** 事件绑定属性名，如 `bindtap`、`catch:tap` */
+  Event = 'event',
+  /** 属性值中的静态文本以及表达式中的字符串字面量 */
+  String = 'string',
+  Comment = 'comment',
+  /** 插值的 `{{`、`}}` */
+  Delimiter = 'delimiter',
+  Variable = 'variable',
+  /** 成员访问 `a.b` 的 `b` 与对象字面量的键 */
+  Property = 'property',
+  /** 被调用的函数或方法 */
+  Function = 'function',
+  /** `true`、`false`、`null`、`undefined` */
+  Keyword = 'keyword',
+  Number = 'number',
+  Operator = 'operator'
+}
+
+/**
+ * 分析光标处正在输入的内容，供编辑器补全使用，见 [`completion`]
+ *
+ * `offset` 为光标的字节偏移
+ */
+export declare function completionContext(source: string, offset: number): CompletionContext
+
+/** 补全上下文 */
+export interface CompletionContext {
+  kind: CompletionKind
+  /** 光标所在的最内层未闭合元素；正在输入的标签本身不计算在内 */
+  parent?: string
+  /** 光标之前已经输入的部分，如标签名 `<vi|` 中的 `vi`；文本与注释为空 */
+  prefix: string
+  /** `prefix` 的起始字节偏移，补全时替换 `start..offset` 的范围 */
+  start: number
+}
+
+/** 光标处正在输入的内容 */
+export type CompletionKind =
+  | { type: 'TagName' }
+  | { type: 'CloseTag' }
+  | { type: 'AttributeName', element: string, existing: Array<string> }
+  | { type: 'AttributeValue', element: string, attribute: string }
+  | { type: 'Expression', element?: string, attribute?: string }
+  | { type: 'Text' }
+  | { type: 'Comment' }
+
+/** 提取文档大纲，供编辑器的大纲视图使用，见 [`symbols`] */
+export declare function documentSymbols(source: string, options?: ParserOptions | undefined | null): Array<DocumentSymbol>
+
+/** 大纲中的一个符号 */
+export interface DocumentSymbol {
+  /** 显示名称：模板、模块、插槽为其名称，元素为 `view#id.class` 形式 */
+  name: string
+  /** 补充信息，如 `<wxs src>` 的路径、元素的标签名 */
+  detail?: string
+  kind: SymbolKind
+  /** 整个元素的范围 */
+  start: Position
+  end: Position
+  /** 名称所在的范围，如 `name` 属性；元素为整个元素的范围 */
+  selectionStart: Position
+  selectionEnd: Position
+  children: Array<DocumentSymbol>
+}
+
+/** 单个文件的检查结果 */
+export interface LintResult {
+  path: string
+  /** 解析过程中收集的语法错误 */
+  errors: Array<SyntaxError>
+  /** 检查规则产生的诊断信息，遇到无法恢复的语法错误时为空 */
+  diagnostics: Array<Diagnostic>
+}
+
 /** AST节点类型，代表WXML文档中的各种元素 */
 export type Node =
-  | { type: 'Element', name: string, attrs: Array<Attribute>, children: Array<Node>, selfClosing: boolean, firstAttrSameLine: boolean, start: Position, end: Position }
+  | { type: 'Element', name: string, attrs: Array<Attribute>, children: Array<Node>, selfClosing: boolean, firstAttrSameLine: boolean, recovered: boolean, closeStart?: Position, closeEnd?: Position, start: Position, end: Position }
   | { type: 'Text', content: string, start: Position, end: Position }
   | { type: 'Comment', content: string, start: Position, end: Position }
   | { type: 'Expression', content: string, start: Position, end: Position }
 
-/** 将 WXML 模板字符串解析为抽象语法树 */
-export declare function parse(source: string): Root
+/**
+ * 将任意字符串按 `{{ }}` 拆分为静态文本与表达式，与属性值使用相同的拆分规则，
+ * 可用于 JSON 配置等包含插值模板的内容
+ */
+export declare function parseInterpolation(source: string): Array<Value>
+
+/** 解析 `{{ }}` 中的表达式内容并输出为 ESTree 结构，供 eslint、babel 等 JS 工具直接使用 */
+export declare function parseExpressionEstree(content: string): any
+
+/**
+ * 将 WXML 模板字符串解析为抽象语法树，无法恢复的错误（如超出资源限制）抛出为 JS 异常，
+ * 已恢复的错误记录在 [`Root::errors`](ast::Root::errors) 中
+ */
+export declare function parse(source: string, options?: ParserOptions | undefined | null): Root
+
+/** 将 WXML 模板字符串解析为 unist / hast 兼容的语法树，见 [`unist`] */
+export declare function parseUnist(source: string, options?: ParserOptions | undefined | null): any
+
+/** 使用 `data` 渲染模板并输出为可在浏览器中预览的 HTML，见 [`preview`] */
+export declare function renderPreview(source: string, data: any, options?: ParserOptions | undefined | null): string
+
+/** 在后台线程中解析 WXML 模板，适用于体积较大的模板，返回 Promise */
+export declare function parseAsync(source: string, options?: ParserOptions | undefined | null): Promise<Root>
+
+/** 在后台线程中并行解析多个模板文件，返回 Promise */
+export declare function parseFiles(files: Array<SourceFile>, options?: ParserOptions | undefined | null): Promise<Array<ParseResult>>
+
+/** Name and span of an opening tag, from `<` to the closing `>`. */
+export interface OpenTag {
+  name: string
+  start: Position
+  end: Position
+}
+
+/** 解析器选项，所有字段均为可选，未设置时使用默认行为 */
+export interface ParserOptions {
+  /** 宽松模式，容忍实际项目中常见的不规范写法，如不带引号的属性值 `data-index=1` */
+  lenient?: boolean
+  /** 模板方言，决定哪个内联脚本标签按原始文本解析，默认为 [`Dialect::Wxml`] */
+  dialect?: Dialect
+  /**
+   * 额外的原始文本标签，其内容不作为标记解析，如 `["markdown"]`；
+   * `wxs` 与方言的内联脚本标签始终是原始文本标签
+   */
+  rawTextTags?: Array<string>
+  /** Vue 兼容模式，`v-if`、`:prop`、`@event` 等属性带引号的值整体作为一个表达式，见 [`vue`](crate::vue) */
+  vue?: boolean
+  /** 节点之间只包含空白的文本如何表示，默认为 [`WhitespaceMode::Drop`] */
+  whitespace?: WhitespaceMode
+  /** 元素的最大嵌套层数，超出时报告 `ResourceLimitExceeded` 错误，默认不限制 */
+  maxDepth?: number
+  /** 单个元素的最大属性数量，默认不限制 */
+  maxAttrs?: number
+  /** 源码的最大长度（字节），默认不限制 */
+  maxSourceLength?: number
+  /** 在 [`Root::stats`](crate::ast::Root::stats) 中附带节点数量、嵌套深度与解析耗时，默认关闭 */
+  stats?: boolean
+  /**
+   * 位置的计算单位，默认 `offset` 按字节、`column` 按字符计算，见 [`encoding`](crate::encoding)
+   *
+   * 设置后 `offset` 不再是字节偏移，不能再用于切分 Rust 字符串；适合直接交给编辑器或 JS 使用
+   */
+  positionEncoding?: PositionEncoding
+  /**
+   * 把注释从兄弟节点中移出，附着到相邻的节点上并记录在 [`Root::comments`](crate::ast::Root::comments) 中，
+   * 注释指令与条件编译标记仍保留在树中，见 [`comments`](crate::comments)，默认关闭
+   */
+  attachComments?: boolean
+}
+
+/** 单个模板的解析统计信息 */
+export interface ParseStats {
+  /** 节点总数，包括元素、文本、注释与插值表达式 */
+  nodeCount: number
+  elementCount: number
+  /** `{{ }}` 表达式总数，包括文本中的插值与属性值中的表达式 */
+  expressionCount: number
+  /** 元素的最大嵌套层数，顶层元素为 1 */
+  maxDepth: number
+  /** 解析耗时（毫秒），不包括统计本身；wasm32 上没有可用的时钟，始终为 0 */
+  duration: number
+}
+
+/** 单个文件的解析结果 */
+export interface ParseResult {
+  path: string
+  /** 解析出的语法树，遇到无法恢复的错误时为空 */
+  root?: Root
+  /** 解析过程中收集的语法错误 */
+  errors: Array<SyntaxError>
+}
 
 /** 定义位置信息，用于标记AST节点在源码中的位置 */
 export interface Position {
-  /** chars 索引, 从 0 开始 */
+  /** 字节偏移，从 0 开始；设置了位置编码时按对应单位计算，见 [`encoding`](crate::encoding) */
   offset: number
   /** 行号，从1开始 */
   line: number
@@ -37,18 +353,71 @@ export interface Position {
   column: number
 }
 
+/** 位置中 `offset` 与 `column` 的计算单位 */
+export declare const enum PositionEncoding {
+  /** 字节，即 Rust 字符串的下标 */
+  Utf8 = 'utf8',
+  /** UTF-16 码元，即 JavaScript 字符串的下标与 LSP 默认的列号 */
+  Utf16 = 'utf16',
+  /** Unicode 字符 */
+  Utf32 = 'utf32'
+}
+
+/** 注释相对于所属节点的位置 */
+export declare const enum CommentPlacement {
+  /** 位于节点之前 */
+  Leading = 'leading',
+  /** 位于节点之后 */
+  Trailing = 'trailing',
+  /** 位于元素内部，元素没有其他子节点 */
+  Dangling = 'dangling'
+}
+
+/** 附着到节点上的注释 */
+export interface AttachedComment {
+  content: string
+  placement: CommentPlacement
+  /** 所属节点的起始位置；文档中只有注释时，悬空注释属于根节点，为 `None` */
+  owner?: Position
+  start: Position
+  end: Position
+}
+
 export interface Root {
   children: Array<Node>
   start: Position
   end: Position
+  /** 解析统计信息，仅在设置 [`ParserOptions::stats`](crate::options::ParserOptions::stats) 时存在 */
+  stats?: ParseStats
+  /**
+   * 附着到节点上的注释，仅在设置 [`ParserOptions::attach_comments`](crate::options::ParserOptions::attach_comments)
+   * 或调用 [`attach_comments`](crate::comments::attach_comments) 后存在，此时树中只保留注释指令与条件编译标记的注释节点
+   */
+  comments?: Array<AttachedComment>
+  /** 解析时已恢复的语法错误，如不构成标签的 `<`、跳过的无法解析的属性，与 [`Parser::errors`](crate::parser::Parser::errors) 相同 */
+  errors: Array<SyntaxError>
+}
+
+/** 待解析的模板文件 */
+export interface SourceFile {
+  path: string
+  source: string
 }
 
 /** Syntax error when parsing tags, not `<script>` or `<style>` tag. */
 export interface SyntaxError {
   kind: SyntaxErrorKind
+  /** Stable error code of `kind`, such as `WXML1003`. */
+  code: string
   offset: number
   line: number
   column: number
+  /**
+   * The opening tag that an `ExpectCloseTag` error refers to, so editors can
+   * highlight both ends of a mismatch. `None` for a closing tag without any
+   * open element.
+   */
+  openTag?: OpenTag
 }
 
 export declare const enum SyntaxErrorKind {
@@ -64,9 +433,42 @@ export declare const enum SyntaxErrorKind {
   ExpectSelfCloseTag = 9,
   ExpectTagName = 10,
   ExpectTextNode = 11,
-  ExpectExpression = 12
+  ExpectExpression = 12,
+  ResourceLimitExceeded = 13,
+  IgnoredDeclaration = 14,
+  StrayLessThan = 15
+}
+
+/** 符号种类 */
+export declare const enum SymbolKind {
+  /** `<template name="...">` */
+  Template = 'template',
+  /** `<wxs module="...">` */
+  Module = 'module',
+  /** `<slot name="...">` */
+  Slot = 'slot',
+  /** 带有 `id` 或 `class` 的顶层元素 */
+  Element = 'element'
 }
 
 export type Value =
   | { type: 'Text', content: string, start: Position, end: Position }
   | { type: 'Expression', content: string, start: Position, end: Position }
+
+/**
+ * 节点之间只含空白的文本的处理方式
+ *
+ * 只影响后面紧跟标签、注释或 `{{` 的空白，如 `<view/>\n  <text/>`、`{{a}} {{b}}`；
+ * 除 [`WhitespaceMode::Preserve`] 外，结束标签与文件结尾之前的空白总是被丢弃。
+ * 包含非空白字符的文本总是原样保留，包括首尾空白
+ */
+export declare const enum WhitespaceMode {
+  /** 丢弃，不产生节点 */
+  Drop = 'drop',
+  /** 原样保留为文本节点 */
+  Keep = 'keep',
+  /** 保留为内容是单个空格的文本节点，与 WXML 渲染时空白折叠的效果一致 */
+  Collapse = 'collapse',
+  /** 原样保留所有空白，包括结束标签与文件结尾之前的空白，是否有意义由使用方决定 */
+  Preserve = 'preserve'
+}
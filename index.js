@@ -375,6 +375,26 @@ if (!nativeBinding) {
   throw new Error(`Failed to load native binding`)
 }
 
-const { parse, SyntaxErrorKind } = nativeBinding
+const { AttributeQuote, autoCloseHint, CommentPlacement, completionContext, Dialect, documentSymbols, lintProject, matchingTag, parse, parseAsync, parseExpressionEstree, parseFiles, parseInterpolation, parseUnist, PositionEncoding, renderPreview, semanticTokens, SemanticTokenKind, Severity, SymbolKind, SyntaxErrorKind, WhitespaceMode } = nativeBinding
+export { AttributeQuote }
+export { autoCloseHint }
+export { CommentPlacement }
+export { completionContext }
+export { Dialect }
+export { documentSymbols }
+export { lintProject }
+export { matchingTag }
 export { parse }
+export { parseAsync }
+export { parseExpressionEstree }
+export { parseFiles }
+export { parseInterpolation }
+export { parseUnist }
+export { PositionEncoding }
+export { renderPreview }
+export { semanticTokens }
+export { SemanticTokenKind }
+export { Severity }
+export { SymbolKind }
 export { SyntaxErrorKind }
+export { WhitespaceMode }
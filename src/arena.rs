@@ -0,0 +1,332 @@
+//! 扁平存储的 AST
+//!
+//! 所有节点、属性与属性值分别存放在连续的 `Vec` 中，通过 [`NodeId`] 等下标相互引用，
+//! 文本直接借用源码。解析一个模板只需少量的大块分配，适合构建时批量解析成千上万个模板的工具。
+//! 节点按解析完成的顺序编号（子节点先于父节点），需要文档顺序时使用 [`Arena::preorder`]
+
+use std::ops::Range;
+
+use crate::{
+  ast::{AttributeQuote, Position},
+  parser::{Build, PResult, Parser},
+};
+
+/// 节点下标
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(u32);
+
+/// 属性下标
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct AttrId(u32);
+
+/// 属性值下标
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ValueId(u32);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeKind<'s> {
+  Element {
+    name: &'s str,
+    self_closing: bool,
+    first_attr_same_line: bool,
+    recovered: bool,
+    close_start: Option<Position>,
+    close_end: Option<Position>,
+  },
+  Text(&'s str),
+  Comment(&'s str),
+  Expression(&'s str),
+}
+
+#[derive(Debug, Clone)]
+pub struct NodeData<'s> {
+  pub kind: NodeKind<'s>,
+  /// 父元素，顶层节点为 `None`
+  pub parent: Option<NodeId>,
+  pub start: Position,
+  pub end: Position,
+  /// 子节点在 `Arena::child_ids` 中的范围
+  children: Range<u32>,
+  /// 属性在 `Arena::attrs` 中的范围
+  attrs: Range<u32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AttrData<'s> {
+  pub name: &'s str,
+  pub quote: AttributeQuote,
+  pub start: Position,
+  pub end: Position,
+  /// 属性值在 `Arena::values` 中的范围，无值属性为 `None`
+  values: Option<Range<u32>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueData<'s> {
+  Text {
+    content: &'s str,
+    start: Position,
+    end: Position,
+  },
+  Expression {
+    content: &'s str,
+    start: Position,
+    end: Position,
+  },
+}
+
+/// 扁平存储的文档
+#[derive(Debug, Clone)]
+pub struct Arena<'s> {
+  nodes: Vec<NodeData<'s>>,
+  /// 所有元素的子节点下标，每个元素占用一段连续范围
+  child_ids: Vec<NodeId>,
+  attrs: Vec<AttrData<'s>>,
+  values: Vec<ValueData<'s>>,
+  roots: Vec<NodeId>,
+  pub start: Position,
+  pub end: Position,
+}
+
+impl<'s> Parser<'s> {
+  /// 解析为扁平存储的 AST，见 [`arena`](crate::arena)
+  pub fn parse_arena(&mut self) -> PResult<Arena<'s>> {
+    let mut arena = Arena {
+      nodes: Vec::new(),
+      child_ids: Vec::new(),
+      attrs: Vec::new(),
+      values: Vec::new(),
+      roots: Vec::new(),
+      start: Position::SYNTHETIC,
+      end: Position::SYNTHETIC,
+    };
+    let (roots, start, end) = self.parse_document(&mut arena)?;
+    arena.roots = roots;
+    arena.start = start;
+    arena.end = end;
+    Ok(arena)
+  }
+}
+
+impl<'s> Arena<'s> {
+  /// 节点总数
+  pub fn len(&self) -> usize {
+    self.nodes.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.nodes.is_empty()
+  }
+
+  /// 顶层节点
+  pub fn roots(&self) -> &[NodeId] {
+    &self.roots
+  }
+
+  pub fn node(&self, id: NodeId) -> &NodeData<'s> {
+    &self.nodes[id.0 as usize]
+  }
+
+  /// 元素的子节点，其他节点返回空切片
+  pub fn children(&self, id: NodeId) -> &[NodeId] {
+    let range = &self.node(id).children;
+    &self.child_ids[range.start as usize..range.end as usize]
+  }
+
+  /// 元素的属性，其他节点返回空切片
+  pub fn attrs(&self, id: NodeId) -> &[AttrData<'s>] {
+    let range = &self.node(id).attrs;
+    &self.attrs[range.start as usize..range.end as usize]
+  }
+
+  /// 按名称查找元素的属性
+  pub fn attr(&self, id: NodeId, name: &str) -> Option<&AttrData<'s>> {
+    self.attrs(id).iter().find(|attr| attr.name == name)
+  }
+
+  /// 属性值，无值属性返回 `None`
+  pub fn values(&self, attr: &AttrData<'s>) -> Option<&[ValueData<'s>]> {
+    let range = attr.values.as_ref()?;
+    Some(&self.values[range.start as usize..range.end as usize])
+  }
+
+  /// 所有节点的下标，按编号顺序（子节点先于父节点）
+  pub fn ids(&self) -> impl Iterator<Item = NodeId> {
+    (0..self.nodes.len() as u32).map(NodeId)
+  }
+
+  /// 按文档顺序（先序）遍历所有节点
+  pub fn preorder(&self) -> Preorder<'_, 's> {
+    Preorder {
+      arena: self,
+      stack: self.roots.iter().rev().copied().collect(),
+    }
+  }
+}
+
+/// 先序遍历迭代器，见 [`Arena::preorder`]
+pub struct Preorder<'a, 's> {
+  arena: &'a Arena<'s>,
+  stack: Vec<NodeId>,
+}
+
+impl Iterator for Preorder<'_, '_> {
+  type Item = NodeId;
+
+  fn next(&mut self) -> Option<NodeId> {
+    let id = self.stack.pop()?;
+    self
+      .stack
+      .extend(self.arena.children(id).iter().rev().copied());
+    Some(id)
+  }
+}
+
+/// 连续下标的范围，下标由同一个 `Vec` 依次分配，因此首尾即可确定范围
+fn id_range(ids: impl IntoIterator<Item = u32>, empty_at: usize) -> Range<u32> {
+  let mut ids = ids.into_iter();
+  match ids.next() {
+    Some(first) => first..ids.last().unwrap_or(first) + 1,
+    None => empty_at as u32..empty_at as u32,
+  }
+}
+
+impl<'s> Build<'s> for Arena<'s> {
+  type Node = NodeId;
+  type Attribute = AttrId;
+  type AttributeValue = ValueId;
+
+  fn element(
+    &mut self,
+    name: &'s str,
+    attrs: Vec<AttrId>,
+    children: Vec<NodeId>,
+    self_closing: bool,
+    first_attr_same_line: bool,
+    recovered: bool,
+    close_start: Option<Position>,
+    close_end: Option<Position>,
+    start: Position,
+    end: Position,
+  ) -> NodeId {
+    let id = NodeId(self.nodes.len() as u32);
+    for child in &children {
+      self.nodes[child.0 as usize].parent = Some(id);
+    }
+    let child_start = self.child_ids.len() as u32;
+    self.child_ids.extend(children);
+    self.nodes.push(NodeData {
+      kind: NodeKind::Element {
+        name,
+        self_closing,
+        first_attr_same_line,
+        recovered,
+        close_start,
+        close_end,
+      },
+      parent: None,
+      start,
+      end,
+      children: child_start..self.child_ids.len() as u32,
+      attrs: id_range(attrs.into_iter().map(|id| id.0), self.attrs.len()),
+    });
+    id
+  }
+
+  fn text(&mut self, content: &'s str, start: Position, end: Position) -> NodeId {
+    self.leaf(NodeKind::Text(content), start, end)
+  }
+
+  fn comment(&mut self, content: &'s str, start: Position, end: Position) -> NodeId {
+    self.leaf(NodeKind::Comment(content), start, end)
+  }
+
+  fn expression(&mut self, content: &'s str, start: Position, end: Position) -> NodeId {
+    self.leaf(NodeKind::Expression(content), start, end)
+  }
+
+  fn attribute(
+    &mut self,
+    name: &'s str,
+    value: Option<Vec<ValueId>>,
+    quote: AttributeQuote,
+    start: Position,
+    end: Position,
+  ) -> AttrId {
+    let values = value.map(|values| id_range(values.into_iter().map(|id| id.0), self.values.len()));
+    self.attrs.push(AttrData {
+      name,
+      quote,
+      start,
+      end,
+      values,
+    });
+    AttrId(self.attrs.len() as u32 - 1)
+  }
+
+  fn attribute_start(&self, attr: &AttrId) -> Position {
+    self.attrs[attr.0 as usize].start
+  }
+
+  fn text_value(&mut self, content: &'s str, start: Position, end: Position) -> ValueId {
+    self.values.push(ValueData::Text {
+      content,
+      start,
+      end,
+    });
+    ValueId(self.values.len() as u32 - 1)
+  }
+
+  fn expression_value(&mut self, content: &'s str, start: Position, end: Position) -> ValueId {
+    self.values.push(ValueData::Expression {
+      content,
+      start,
+      end,
+    });
+    ValueId(self.values.len() as u32 - 1)
+  }
+}
+
+impl<'s> Arena<'s> {
+  fn leaf(&mut self, kind: NodeKind<'s>, start: Position, end: Position) -> NodeId {
+    self.nodes.push(NodeData {
+      kind,
+      parent: None,
+      start,
+      end,
+      children: 0..0,
+      attrs: 0..0,
+    });
+    NodeId(self.nodes.len() as u32 - 1)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn flat_document() {
+    let source = r#"<view class="a {{b}}"><text>hi</text>{{c}}<!-- d --></view><image src="x"/>"#;
+    let arena = Parser::new(source).parse_arena().unwrap();
+    assert_eq!(arena.len(), 6);
+    let kinds: Vec<_> = arena
+      .preorder()
+      .map(|id| match arena.node(id).kind {
+        NodeKind::Element { name, .. } => name,
+        NodeKind::Text(content) | NodeKind::Comment(content) | NodeKind::Expression(content) => {
+          content
+        }
+      })
+      .collect();
+    assert_eq!(kinds, ["view", "text", "hi", "c", " d ", "image"]);
+
+    let view = arena.roots()[0];
+    let class = arena.attr(view, "class").unwrap();
+    assert_eq!(arena.values(class).unwrap().len(), 2);
+    let text = arena.children(view)[0];
+    assert_eq!(arena.node(text).parent, Some(view));
+    assert_eq!(arena.attrs(arena.roots()[1])[0].name, "src");
+    assert!(arena.attrs(text).is_empty());
+  }
+}
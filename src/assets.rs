@@ -0,0 +1,208 @@
+//! 静态资源引用
+//!
+//! 收集模板中引用的图片、视频等静态资源：`src`、`poster` 属性，以及内联样式中 `background-image`、
+//! `background` 的 `url()`，供构建工具复制资源文件、检查缺失的文件
+//!
+//! 属性值包含表达式时，只收集能在编译期确定的值，如 `src="/images/{{ 'logo' + '.png' }}"`；
+//! `<import>`、`<include>`、`<wxs>` 等语法标签的 `src` 引用的是模板与脚本，不属于资源
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  ast::{AttributeValue, Node, Position, Root},
+  builtins::TEMPLATE_TAGS,
+  dialect::Dialect,
+  expr::to_display_string,
+  optimize::constant_value,
+  project::resolve,
+  style::parse_style,
+};
+
+/// 资源的引用方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AssetKind {
+  /// `src` 属性，如 `<image src>`、`<video src>`
+  Src,
+  /// `<video poster>`
+  Poster,
+  /// 内联样式中的 `background-image` 或 `background`
+  BackgroundImage,
+}
+
+/// 一处资源引用
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetReference {
+  pub kind: AssetKind,
+  /// 所在元素的标签名
+  pub element: String,
+  /// 书写的路径，如 `./logo.png`、`/images/bg.jpg`、`https://cdn.example.com/a.png`
+  pub path: String,
+  /// 属性或样式声明的位置
+  pub start: Position,
+  pub end: Position,
+}
+
+impl AssetReference {
+  /// 是否为远程地址或内联数据，这类资源不需要复制
+  pub fn is_remote(&self) -> bool {
+    [
+      "http://",
+      "https://",
+      "//",
+      "data:",
+      "cloud://",
+      "wxfile://",
+    ]
+    .iter()
+    .any(|prefix| self.path.starts_with(prefix))
+  }
+
+  /// 以模板路径 `from` 解析资源的项目内路径；远程资源返回 `None`
+  ///
+  /// 以 `/` 开头的路径相对于项目根目录，其余相对于模板所在目录
+  pub fn resolve(&self, from: &str) -> Option<String> {
+    (!self.is_remote()).then(|| resolve(from, &self.path))
+  }
+}
+
+/// 收集文档中的资源引用，按文档顺序排列
+pub fn collect_assets(root: &Root) -> Vec<AssetReference> {
+  let mut assets = Vec::new();
+  root.walk(&mut |node| {
+    let Node::Element { name, attrs, .. } = node else {
+      return;
+    };
+    let code_tag = TEMPLATE_TAGS.contains(&name.as_str())
+      || Dialect::ALL
+        .iter()
+        .any(|dialect| dialect.script_tag() == name);
+    for attr in attrs {
+      let Some(values) = attr.value.as_deref() else {
+        continue;
+      };
+      let kind = match attr.name.as_str() {
+        "src" if !code_tag => AssetKind::Src,
+        "poster" => AssetKind::Poster,
+        "style" => {
+          for declaration in parse_style(values) {
+            let property = declaration.property.as_deref();
+            if !matches!(property, Some("background-image" | "background")) {
+              continue;
+            }
+            let Some(value) = static_value(&declaration.value) else {
+              continue;
+            };
+            for path in css_urls(&value) {
+              assets.push(AssetReference {
+                kind: AssetKind::BackgroundImage,
+                element: name.clone(),
+                path,
+                start: declaration.start,
+                end: declaration.end,
+              });
+            }
+          }
+          continue;
+        }
+        _ => continue,
+      };
+      let Some(path) = static_value(values).filter(|path| !path.trim().is_empty()) else {
+        continue;
+      };
+      assets.push(AssetReference {
+        kind,
+        element: name.clone(),
+        path: path.trim().to_string(),
+        start: attr.start,
+        end: attr.end,
+      });
+    }
+  });
+  assets
+}
+
+/// 在 `exists` 中找不到的本地资源，`from` 为模板的路径，`exists` 接收解析后的项目内路径
+pub fn missing_assets<'a>(
+  assets: &'a [AssetReference],
+  from: &str,
+  exists: impl Fn(&str) -> bool,
+) -> Vec<&'a AssetReference> {
+  assets
+    .iter()
+    .filter(|asset| asset.resolve(from).is_some_and(|path| !exists(&path)))
+    .collect()
+}
+
+/// 拼接属性值，表达式必须能在编译期求值
+fn static_value(values: &[AttributeValue]) -> Option<String> {
+  let mut result = String::new();
+  for value in values {
+    match value {
+      AttributeValue::Text { content, .. } => result.push_str(content),
+      AttributeValue::Expression { content, .. } => {
+        result.push_str(&to_display_string(&constant_value(content)?))
+      }
+    }
+  }
+  Some(result)
+}
+
+/// 样式值中 `url()` 的地址
+fn css_urls(value: &str) -> Vec<String> {
+  let mut urls = Vec::new();
+  let mut rest = value;
+  while let Some(index) = rest.find("url(") {
+    rest = &rest[index + 4..];
+    let Some(end) = rest.find(')') else {
+      break;
+    };
+    let url = rest[..end].trim().trim_matches(['"', '\'']).trim();
+    if !url.is_empty() {
+      urls.push(url.to_string());
+    }
+    rest = &rest[end + 1..];
+  }
+  urls
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::parser::Parser;
+
+  #[test]
+  fn asset_references() {
+    let source = r#"<import src="./a.wxml"/><wxs module="m" src="./m.wxs"/>
+<image src="./logo.png"/><image src="{{avatar}}"/><image src="/images/{{ 'icon' + '.png' }}"/>
+<video src="https://cdn.example.com/a.mp4" poster="../poster.jpg"/>
+<view style="color: red; background-image: url('./bg.png'), url(data:image/png;base64,AAAA)"/>"#;
+    let root = Parser::new(source).parse_root().unwrap();
+    let assets = collect_assets(&root);
+    let found: Vec<_> = assets
+      .iter()
+      .map(|asset| (asset.kind, asset.path.as_str()))
+      .collect();
+    assert_eq!(
+      found,
+      [
+        (AssetKind::Src, "./logo.png"),
+        (AssetKind::Src, "/images/icon.png"),
+        (AssetKind::Src, "https://cdn.example.com/a.mp4"),
+        (AssetKind::Poster, "../poster.jpg"),
+        (AssetKind::BackgroundImage, "./bg.png"),
+        (AssetKind::BackgroundImage, "data:image/png;base64,AAAA"),
+      ]
+    );
+
+    let existing = ["pages/index/logo.png", "images/icon.png"];
+    let missing: Vec<_> = missing_assets(&assets, "pages/index/index.wxml", |path| {
+      existing.contains(&path)
+    })
+    .into_iter()
+    .map(|asset| asset.resolve("pages/index/index.wxml").unwrap())
+    .collect();
+    assert_eq!(missing, ["pages/poster.jpg", "pages/index/bg.png"]);
+  }
+}
@@ -1,16 +1,27 @@
 //! 抽象语法树(AST)相关的数据结构
 
+#[cfg(feature = "napi")]
 use napi_derive::napi;
-use std::fmt;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt};
 
-use crate::error::SyntaxError;
+use crate::{
+  classes::{parse_class_list, ClassToken},
+  comments::AttachedComment,
+  dataset::{collect_dataset, DatasetEntry},
+  dialect::Dialect,
+  error::SyntaxError,
+  stats::ParseStats,
+  style::{parse_style, StyleDeclaration},
+};
 
 /// 定义位置信息，用于标记AST节点在源码中的位置
 
-#[derive(Debug, Clone, Copy)]
-#[napi(object)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "napi", napi(object))]
 pub struct Position {
-  /// chars 索引, 从 0 开始
+  /// 字节偏移，从 0 开始；设置了位置编码时按对应单位计算，见 [`encoding`](crate::encoding)
   pub offset: u32,
   /// 行号，从1开始
   pub line: u32,
@@ -18,14 +29,51 @@ pub struct Position {
   pub column: u32,
 }
 
+impl Position {
+  /// 合成位置，用于以编程方式创建、在源码中不存在的节点
+  ///
+  /// 行号与列号均从 1 开始，因此 `line == 0` 不会出现在解析结果中，可作为合成标记
+  pub const SYNTHETIC: Position = Position {
+    offset: 0,
+    line: 0,
+    column: 0,
+  };
+
+  /// 是否为合成位置
+  pub fn is_synthetic(&self) -> bool {
+    self.line == 0
+  }
+
+  /// 计算经过 `text` 之后到达的位置，`offset` 按字节计算；合成位置保持不变
+  pub fn advance(mut self, text: &str) -> Position {
+    if self.is_synthetic() {
+      return self;
+    }
+    for ch in text.chars() {
+      self.offset += ch.len_utf8() as u32;
+      if ch == '\n' {
+        self.line += 1;
+        self.column = 1;
+      } else {
+        self.column += 1;
+      }
+    }
+    self
+  }
+}
+
 impl fmt::Display for Position {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if self.is_synthetic() {
+      return write!(f, "<synthetic>");
+    }
     write!(f, "{} {}:{}", self.offset, self.line, self.column)
   }
 }
 
-#[derive(Debug, Clone)]
-#[napi]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all_fields = "camelCase")]
+#[cfg_attr(feature = "napi", napi)]
 pub enum Value {
   /// 静态值
   Text {
@@ -41,25 +89,60 @@ pub enum Value {
   },
 }
 
-#[derive(Debug, Clone)]
-#[napi(object)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi", napi(object))]
 pub struct Root {
   pub children: Vec<Node>,
   pub start: Position,
   pub end: Position,
+  /// 解析统计信息，仅在设置 [`ParserOptions::stats`](crate::options::ParserOptions::stats) 时存在
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub stats: Option<ParseStats>,
+  /// 附着到节点上的注释，仅在设置 [`ParserOptions::attach_comments`](crate::options::ParserOptions::attach_comments)
+  /// 或调用 [`attach_comments`](crate::comments::attach_comments) 后存在，此时树中只保留注释指令与条件编译标记的注释节点
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub comments: Option<Vec<AttachedComment>>,
+  /// 解析时已恢复的语法错误，如不构成标签的 `<`、跳过的无法解析的属性，与 [`Parser::errors`](crate::parser::Parser::errors) 相同
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub errors: Vec<SyntaxError>,
 }
 
-#[derive(Debug, Clone)]
-#[napi(object)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi", napi(object))]
 pub struct Attribute {
   pub name: String,
+  /// 名称前缀，如 `wx:if` 的 `wx`、`capture-bind:tap` 的 `capture-bind`、`data-id` 的 `data`
+  pub prefix: Option<String>,
+  /// 去掉前缀后的名称，没有前缀时与 `name` 相同
+  pub local: String,
   pub value: Option<Vec<AttributeValue>>,
+  /// 属性值的引号风格
+  pub quote: AttributeQuote,
   pub start: Position,
   pub end: Position,
 }
 
-#[derive(Debug, Clone)]
-#[napi]
+/// 属性值使用的引号
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "napi", napi)]
+pub enum AttributeQuote {
+  /// `name="value"`
+  Double,
+  /// `name='value'`
+  Single,
+  /// 无值属性 `name` 或宽松模式下的 `name=value`
+  None,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(tag = "type", rename_all_fields = "camelCase")]
+#[cfg_attr(feature = "napi", napi)]
 pub enum AttributeValue {
   Text {
     content: String,
@@ -73,8 +156,9 @@ pub enum AttributeValue {
   },
 }
 
-#[derive(Debug, Clone)]
-#[napi(object)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi", napi(object))]
 pub struct Expression {
   pub content: String,
   pub start: Position,
@@ -82,8 +166,10 @@ pub struct Expression {
 }
 
 /// AST节点类型，代表WXML文档中的各种元素
-#[derive(Debug, Clone)]
-#[napi]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(tag = "type", rename_all_fields = "camelCase")]
+#[cfg_attr(feature = "napi", napi)]
 pub enum Node {
   /// 元素节点，如 <view>, <button> 等
   Element {
@@ -92,6 +178,15 @@ pub enum Node {
     children: Vec<Node>,
     self_closing: bool,
     first_attr_same_line: bool,
+    /// 解析时遇到错误并跳过了部分内容（如无法解析的属性、片段中未闭合的元素），
+    /// 对应的错误记录在 [`Parser::errors`](crate::parser::Parser::errors) 中
+    #[serde(default)]
+    recovered: bool,
+    /// 结束标签中标签名的范围，如 `</view >` 中的 `view`；自闭合、没有结束标签与合成的元素为 `None`
+    #[serde(default)]
+    close_start: Option<Position>,
+    #[serde(default)]
+    close_end: Option<Position>,
     start: Position,
     end: Position,
   },
@@ -111,3 +206,556 @@ pub enum Node {
     end: Position,
   },
 }
+
+/// 把 `<block>` 展开为其子节点
+fn splice_blocks(nodes: &[Node]) -> Vec<&Node> {
+  let mut result = Vec::new();
+  for node in nodes {
+    match node {
+      Node::Element { children, .. } if node.is_block() => result.extend(splice_blocks(children)),
+      _ => result.push(node),
+    }
+  }
+  result
+}
+
+impl Root {
+  /// 渲染后实际位于顶层的节点，见 [`Node::rendered_children`]
+  pub fn rendered_children(&self) -> Vec<&Node> {
+    splice_blocks(&self.children)
+  }
+
+  /// 深度优先（先序）遍历所有节点
+  pub fn walk<'a, F>(&'a self, f: &mut F)
+  where
+    F: FnMut(&'a Node),
+  {
+    for child in self.children.iter() {
+      child.walk(f);
+    }
+  }
+
+  /// 深度优先遍历所有节点，允许就地修改
+  pub fn walk_mut<F>(&mut self, f: &mut F)
+  where
+    F: FnMut(&mut Node),
+  {
+    for child in self.children.iter_mut() {
+      child.walk_mut(f);
+    }
+  }
+}
+
+impl Attribute {
+  /// 创建一个带合成位置的属性，`value` 为 `None` 时表示无值属性（如 `<input disabled />`）
+  pub fn new(name: impl Into<String>, value: Option<Vec<AttributeValue>>) -> Self {
+    let quote = if value.is_some() {
+      AttributeQuote::Double
+    } else {
+      AttributeQuote::None
+    };
+    let name = name.into();
+    let (prefix, local) = split_attr_name(&name);
+    Self {
+      name,
+      prefix,
+      local,
+      value,
+      quote,
+      start: Position::SYNTHETIC,
+      end: Position::SYNTHETIC,
+    }
+  }
+
+  /// 修改属性名称，同时更新 `prefix` 与 `local`
+  pub fn rename(&mut self, name: impl Into<String>) {
+    self.name = name.into();
+    (self.prefix, self.local) = split_attr_name(&self.name);
+  }
+
+  /// 创建一个只包含静态文本值的属性
+  pub fn text(name: impl Into<String>, content: impl Into<String>) -> Self {
+    Self::new(name, Some(vec![AttributeValue::text(content)]))
+  }
+
+  /// 创建一个只包含单个表达式值的属性，如 `wx:if="{{cond}}"`
+  pub fn expression(name: impl Into<String>, content: impl Into<String>) -> Self {
+    Self::new(name, Some(vec![AttributeValue::expression(content)]))
+  }
+
+  /// 是否为合成属性
+  pub fn is_synthetic(&self) -> bool {
+    self.start.is_synthetic()
+  }
+
+  /// 是否为事件绑定，如 `bindtap`、`catch:tap`、`capture-bind:tap`、`mut-bind:tap`
+  pub fn is_event_binding(&self) -> bool {
+    matches!(
+      self.prefix.as_deref(),
+      Some("bind" | "catch" | "capture-bind" | "capture-catch" | "mut-bind")
+    ) || self.name.starts_with("bind")
+      || self.name.starts_with("catch")
+  }
+
+  /// 按 WXML 运行时语义将属性值解释为布尔值
+  ///
+  /// * 无值属性（`<button disabled>`）为 `true`
+  /// * 静态文本：非空即为 `true`，注意 `hidden="false"` 在运行时同样为 `true`
+  /// * 单个字面量表达式：`{{true}}`、`{{false}}`、`{{0}}` 等按 JS 真值规则计算
+  /// * 其他动态值无法静态确定，返回 `None`
+  pub fn as_bool(&self) -> Option<bool> {
+    let values = match &self.value {
+      None => return Some(true),
+      Some(values) => values,
+    };
+    if let Some(content) = self.single_expression() {
+      return match content {
+        "true" => Some(true),
+        "false" | "null" | "undefined" => Some(false),
+        _ => {
+          if let Some(text) = parse_string_literal(content) {
+            return Some(!text.is_empty());
+          }
+          content.parse::<f64>().ok().map(|n| n != 0.0 && !n.is_nan())
+        }
+      };
+    }
+    if let Some(text) = self.as_static_string() {
+      return Some(!text.is_empty());
+    }
+    // 包含非空静态文本时，拼接结果必然是非空字符串
+    values
+      .iter()
+      .any(|value| matches!(value, AttributeValue::Text { content, .. } if !content.is_empty()))
+      .then_some(true)
+  }
+
+  /// 将属性值解释为数字，支持 `"12"`、`"{{ 1.5 }}"` 这类静态可确定的写法
+  pub fn as_number(&self) -> Option<f64> {
+    let content = match self.single_expression() {
+      Some(content) => content.to_string(),
+      None => self.as_static_string()?,
+    };
+    content.trim().parse::<f64>().ok()
+  }
+
+  /// 当属性值不包含任何表达式时，返回拼接后的静态文本
+  ///
+  /// 无值属性返回 `None`
+  pub fn as_static_string(&self) -> Option<String> {
+    let values = self.value.as_ref()?;
+    let mut result = String::new();
+    for value in values {
+      match value {
+        AttributeValue::Text { content, .. } => result.push_str(content),
+        AttributeValue::Expression { .. } => return None,
+      }
+    }
+    Some(result)
+  }
+
+  /// 将属性值拆分为模板字面量的静态部分与表达式，无值属性返回 `None`
+  ///
+  /// 相邻的静态文本合并为一段，结果满足 `quasis.len() == expressions.len() + 1`
+  pub fn template_parts(&self) -> Option<TemplateParts> {
+    let values = self.value.as_ref()?;
+    let mut parts = TemplateParts {
+      quasis: vec![String::new()],
+      expressions: Vec::new(),
+    };
+    for value in values {
+      match value {
+        AttributeValue::Text { content, .. } => parts.quasis.last_mut().unwrap().push_str(content),
+        AttributeValue::Expression { content, .. } => {
+          parts.expressions.push(content.clone());
+          parts.quasis.push(String::new());
+        }
+      }
+    }
+    Some(parts)
+  }
+
+  /// 将属性值转换为 JS 模板字符串，如 `class="cls1 {{tst}} cls2"` 得到 `` `cls1 ${tst} cls2` ``
+  ///
+  /// 无值属性返回 `None`。注意模板字符串的结果总是字符串，而 `"{{ count }}"` 这类只有一个表达式的值
+  /// 在运行时保留表达式的类型，生成代码时应直接使用表达式
+  pub fn to_template_string(&self) -> Option<String> {
+    self
+      .template_parts()
+      .map(|parts| parts.to_template_string())
+  }
+
+  /// 属性值恰好是一个表达式时返回其内容
+  fn single_expression(&self) -> Option<&str> {
+    match self.value.as_deref()? {
+      [AttributeValue::Expression { content, .. }] => Some(content),
+      _ => None,
+    }
+  }
+
+  /// 将 `class` 属性值拆分为类名，见 [`parse_class_list`](crate::classes::parse_class_list)
+  pub fn class_list(&self) -> Vec<ClassToken> {
+    self
+      .value
+      .as_deref()
+      .map(parse_class_list)
+      .unwrap_or_default()
+  }
+
+  /// 将 `style` 属性值拆分为样式声明，见 [`parse_style`](crate::style::parse_style)
+  pub fn style_declarations(&self) -> Vec<StyleDeclaration> {
+    self.value.as_deref().map(parse_style).unwrap_or_default()
+  }
+
+  /// `generic:*` 属性解析为抽象节点绑定，其他属性返回 `None`
+  pub fn as_generic_binding(&self) -> Option<GenericBinding> {
+    if self.prefix.as_deref() != Some("generic") {
+      return None;
+    }
+    let component = self
+      .as_static_string()
+      .map(|value| value.trim().to_string())
+      .filter(|value| !value.is_empty());
+    Some(GenericBinding {
+      name: self.local.clone(),
+      component,
+      start: self.start,
+      end: self.end,
+    })
+  }
+
+  /// 修改属性后将其位置标记为失效
+  pub fn invalidate_positions(&mut self) {
+    self.start = Position::SYNTHETIC;
+    self.end = Position::SYNTHETIC;
+  }
+}
+
+/// 抽象节点绑定：在使用组件时通过 `generic:selectable="custom-radio"` 为组件的抽象节点指定实现
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenericBinding {
+  /// 抽象节点名称，即 `generic:` 之后的部分
+  pub name: String,
+  /// 指定的组件标签名，值为空或包含表达式时为 `None`
+  pub component: Option<String>,
+  pub start: Position,
+  pub end: Position,
+}
+
+/// 属性值对应的模板字面量，结构与 ESTree 的 `TemplateLiteral` 一致
+///
+/// 静态部分与表达式交替出现：`quasis[0] ${expressions[0]} quasis[1] ...`，静态部分为未转义的原文
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateParts {
+  pub quasis: Vec<String>,
+  pub expressions: Vec<String>,
+}
+
+impl TemplateParts {
+  /// 是否不包含表达式
+  pub fn is_static(&self) -> bool {
+    self.expressions.is_empty()
+  }
+
+  /// 输出为 JS 模板字符串，静态部分中的 `` ` ``、`\` 与 `${` 会被转义
+  pub fn to_template_string(&self) -> String {
+    let mut output = String::from("`");
+    for (index, quasi) in self.quasis.iter().enumerate() {
+      output.push_str(
+        &quasi
+          .replace('\\', "\\\\")
+          .replace('`', "\\`")
+          .replace("${", "\\${"),
+      );
+      if let Some(expression) = self.expressions.get(index) {
+        output.push_str("${");
+        output.push_str(expression);
+        output.push('}');
+      }
+    }
+    output.push('`');
+    output
+  }
+}
+
+/// 解析单引号或双引号包裹的简单字符串字面量（不处理转义）
+fn parse_string_literal(content: &str) -> Option<&str> {
+  let quote = content.chars().next()?;
+  if (quote == '\'' || quote == '"') && content.len() >= 2 && content.ends_with(quote) {
+    let inner = &content[1..content.len() - 1];
+    if !inner.contains(quote) {
+      return Some(inner);
+    }
+  }
+  None
+}
+
+/// 拆分属性名称的前缀与本地名称
+///
+/// 在第一个 `:` 处拆分（`wx:for-item`、`bind:tap`、`capture-bind:tap`、`model:value`），
+/// `data-*` 按 `data-` 拆分，Swan 指令 `s-if`、`s-for` 等按 `s-` 拆分，
+/// Vue 简写 `:prop`、`@event`、`#slot` 以首字符为前缀；其余名称没有前缀
+pub fn split_attr_name(name: &str) -> (Option<String>, String) {
+  let (prefix, local) = split_attr_name_str(name);
+  (prefix.map(str::to_string), local.to_string())
+}
+
+/// 与 [`split_attr_name`] 相同，但返回源字符串的切片
+pub(crate) fn split_attr_name_str(name: &str) -> (Option<&str>, &str) {
+  if let Some(prefix @ (":" | "@" | "#")) = name.get(..1) {
+    return (Some(prefix), &name[1..]);
+  }
+  if let Some((prefix, local)) = name.split_once(':') {
+    return (Some(prefix), local);
+  }
+  if let Some(local) = name.strip_prefix("data-") {
+    return (Some("data"), local);
+  }
+  match name.strip_prefix("s-") {
+    Some(local) if Dialect::Swan.directive(name).is_some() => (Some("s"), local),
+    _ => (None, name),
+  }
+}
+
+impl AttributeValue {
+  /// 创建带合成位置的静态文本值
+  pub fn text(content: impl Into<String>) -> Self {
+    AttributeValue::Text {
+      content: content.into(),
+      start: Position::SYNTHETIC,
+      end: Position::SYNTHETIC,
+    }
+  }
+
+  /// 创建带合成位置的表达式值
+  pub fn expression(content: impl Into<String>) -> Self {
+    AttributeValue::Expression {
+      content: content.into(),
+      start: Position::SYNTHETIC,
+      end: Position::SYNTHETIC,
+    }
+  }
+
+  pub fn start(&self) -> Position {
+    match self {
+      AttributeValue::Text { start, .. } | AttributeValue::Expression { start, .. } => *start,
+    }
+  }
+
+  pub fn end(&self) -> Position {
+    match self {
+      AttributeValue::Text { end, .. } | AttributeValue::Expression { end, .. } => *end,
+    }
+  }
+}
+
+impl Node {
+  /// 创建一个空的元素节点，位置为合成位置
+  pub fn element(name: impl Into<String>) -> Self {
+    Node::Element {
+      name: name.into(),
+      attrs: Vec::new(),
+      children: Vec::new(),
+      self_closing: false,
+      first_attr_same_line: true,
+      recovered: false,
+      close_start: None,
+      close_end: None,
+      start: Position::SYNTHETIC,
+      end: Position::SYNTHETIC,
+    }
+  }
+
+  /// 创建文本节点
+  pub fn text(content: impl Into<String>) -> Self {
+    Node::Text {
+      content: content.into(),
+      start: Position::SYNTHETIC,
+      end: Position::SYNTHETIC,
+    }
+  }
+
+  /// 创建注释节点
+  pub fn comment(content: impl Into<String>) -> Self {
+    Node::Comment {
+      content: content.into(),
+      start: Position::SYNTHETIC,
+      end: Position::SYNTHETIC,
+    }
+  }
+
+  /// 创建表达式节点 `{{ content }}`
+  pub fn expression(content: impl Into<String>) -> Self {
+    Node::Expression {
+      content: content.into(),
+      start: Position::SYNTHETIC,
+      end: Position::SYNTHETIC,
+    }
+  }
+
+  /// 追加属性（仅对元素节点生效）
+  pub fn with_attr(mut self, attr: Attribute) -> Self {
+    if let Some(attrs) = self.attrs_mut() {
+      attrs.push(attr);
+    }
+    self
+  }
+
+  /// 追加子节点（仅对元素节点生效），会同时取消自闭合
+  pub fn with_child(mut self, child: Node) -> Self {
+    if let Node::Element {
+      children,
+      self_closing,
+      ..
+    } = &mut self
+    {
+      children.push(child);
+      *self_closing = false;
+    }
+    self
+  }
+
+  /// 设置是否自闭合（仅对元素节点生效）
+  pub fn with_self_closing(mut self, value: bool) -> Self {
+    if let Node::Element { self_closing, .. } = &mut self {
+      *self_closing = value;
+    }
+    self
+  }
+
+  /// 节点起始位置
+  pub fn start(&self) -> Position {
+    match self {
+      Node::Element { start, .. }
+      | Node::Text { start, .. }
+      | Node::Comment { start, .. }
+      | Node::Expression { start, .. } => *start,
+    }
+  }
+
+  /// 节点结束位置
+  pub fn end(&self) -> Position {
+    match self {
+      Node::Element { end, .. }
+      | Node::Text { end, .. }
+      | Node::Comment { end, .. }
+      | Node::Expression { end, .. } => *end,
+    }
+  }
+
+  /// 是否为只包含空白的文本节点
+  pub fn is_whitespace(&self) -> bool {
+    matches!(self, Node::Text { content, .. } if content.trim().is_empty())
+  }
+
+  /// 是否为 `<block>`：它只用于把子节点分组以便统一使用条件、循环等指令，本身不渲染任何内容
+  pub fn is_block(&self) -> bool {
+    self.name() == Some("block")
+  }
+
+  /// 渲染后实际位于该元素中的子节点：`<block>`（包括多层嵌套）被展开为其子节点，不考虑条件与循环指令。
+  /// 非元素节点返回空列表
+  pub fn rendered_children(&self) -> Vec<&Node> {
+    splice_blocks(self.children().unwrap_or_default())
+  }
+
+  /// 是否为以编程方式创建（或修改后位置已失效）的节点
+  pub fn is_synthetic(&self) -> bool {
+    self.start().is_synthetic()
+  }
+
+  /// 元素名称，非元素节点返回 `None`
+  pub fn name(&self) -> Option<&str> {
+    match self {
+      Node::Element { name, .. } => Some(name),
+      _ => None,
+    }
+  }
+
+  /// 元素属性，非元素节点返回 `None`
+  pub fn attrs(&self) -> Option<&[Attribute]> {
+    match self {
+      Node::Element { attrs, .. } => Some(attrs),
+      _ => None,
+    }
+  }
+
+  /// 可变元素属性，非元素节点返回 `None`
+  pub fn attrs_mut(&mut self) -> Option<&mut Vec<Attribute>> {
+    match self {
+      Node::Element { attrs, .. } => Some(attrs),
+      _ => None,
+    }
+  }
+
+  /// 子节点，非元素节点返回 `None`
+  pub fn children(&self) -> Option<&[Node]> {
+    match self {
+      Node::Element { children, .. } => Some(children),
+      _ => None,
+    }
+  }
+
+  /// 可变子节点，非元素节点返回 `None`
+  pub fn children_mut(&mut self) -> Option<&mut Vec<Node>> {
+    match self {
+      Node::Element { children, .. } => Some(children),
+      _ => None,
+    }
+  }
+
+  /// 将节点自身的位置标记为失效
+  ///
+  /// 修改节点内容后，原有位置不再对应源码，调用此方法避免下游工具使用过期的偏移量。
+  /// 子节点与属性的位置保持不变，它们在源码中仍然有效
+  pub fn invalidate_positions(&mut self) {
+    match self {
+      Node::Element { start, end, .. }
+      | Node::Text { start, end, .. }
+      | Node::Comment { start, end, .. }
+      | Node::Expression { start, end, .. } => {
+        *start = Position::SYNTHETIC;
+        *end = Position::SYNTHETIC;
+      }
+    }
+  }
+
+  /// 按运行时键名收集 `data-*` 属性，见 [`collect_dataset`](crate::dataset::collect_dataset)；
+  /// 非元素节点返回空表
+  pub fn dataset(&self) -> BTreeMap<String, DatasetEntry> {
+    self.attrs().map(collect_dataset).unwrap_or_default()
+  }
+
+  /// 按名称查找第一个匹配的属性，非元素节点返回 `None`
+  pub fn attr(&self, name: &str) -> Option<&Attribute> {
+    self.attrs()?.iter().find(|attr| attr.name == name)
+  }
+
+  /// 深度优先（先序）遍历当前节点及其后代
+  pub fn walk<'a, F>(&'a self, f: &mut F)
+  where
+    F: FnMut(&'a Node),
+  {
+    f(self);
+    if let Some(children) = self.children() {
+      for child in children.iter() {
+        child.walk(f);
+      }
+    }
+  }
+
+  /// 深度优先（先序）遍历当前节点及其后代，允许就地修改
+  pub fn walk_mut<F>(&mut self, f: &mut F)
+  where
+    F: FnMut(&mut Node),
+  {
+    f(self);
+    if let Some(children) = self.children_mut() {
+      for child in children.iter_mut() {
+        child.walk_mut(f);
+      }
+    }
+  }
+}
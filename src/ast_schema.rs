@@ -0,0 +1,220 @@
+//! 导出序列化后 AST 的结构描述
+//!
+//! 由 AST 类型上派生的 `JsonSchema` 生成，描述的是序列化（如 `parse` 的返回值、`wxml parse --json` 的输出）得到的 JSON，
+//! 字段名、`type` 标签与可选字段都与 serde 的配置一致。下游的 JS 工具可以用 [`ast_json_schema`] 校验数据，
+//! 或把 [`ast_typescript`] 的输出保存为 `.d.ts`，AST 变化时重新生成即可保持同步。
+//! 需要开启 `json-schema` feature，命令行工具通过 `wxml schema` 输出
+
+use std::fmt::Write;
+
+use schemars::generate::SchemaSettings;
+use serde_json::Value;
+
+use crate::ast::Root;
+
+/// [`Root`] 序列化结果的 JSON Schema（draft 2020-12），其他类型位于 `$defs` 中
+pub fn ast_json_schema() -> Value {
+  SchemaSettings::draft2020_12()
+    .for_serialize()
+    .into_generator()
+    .into_root_schema_for::<Root>()
+    .to_value()
+}
+
+/// 由 [`ast_json_schema`] 生成的 TypeScript 类型声明，按名称排序，`Root` 在最后
+pub fn ast_typescript() -> String {
+  let schema = ast_json_schema();
+  let mut out = String::from("// 由 `wxml schema --typescript` 生成，请勿手动修改\n");
+  if let Some(Value::Object(defs)) = schema.get("$defs") {
+    for (name, def) in defs {
+      declaration(&mut out, name, def);
+    }
+  }
+  declaration(&mut out, "Root", &schema);
+  out
+}
+
+fn declaration(out: &mut String, name: &str, schema: &Value) {
+  out.push('\n');
+  doc_comment(out, schema, "");
+  if schema.get("properties").is_some() {
+    let _ = writeln!(out, "export interface {name} {}", object_body(schema));
+  } else if let Some(Value::Array(variants)) = schema.get("oneOf").or_else(|| schema.get("anyOf")) {
+    let _ = writeln!(out, "export type {name} =");
+    for variant in variants {
+      let _ = writeln!(out, "  | {}", ts_type(variant));
+    }
+  } else {
+    let _ = writeln!(out, "export type {name} = {}", ts_type(schema));
+  }
+}
+
+fn doc_comment(out: &mut String, schema: &Value, indent: &str) {
+  let Some(description) = schema.get("description").and_then(Value::as_str) else {
+    return;
+  };
+  if !description.contains('\n') {
+    let _ = writeln!(out, "{indent}/** {description} */");
+    return;
+  }
+  let _ = writeln!(out, "{indent}/**");
+  for line in description.lines() {
+    let _ = writeln!(out, "{indent} * {line}");
+  }
+  let _ = writeln!(out, "{indent} */");
+}
+
+/// 对象的字段及其是否可选，必需字段按声明顺序在前，可选字段在后
+fn fields(schema: &Value) -> Vec<(&str, bool, &Value)> {
+  let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+    return Vec::new();
+  };
+  let required: Vec<_> = schema
+    .get("required")
+    .and_then(Value::as_array)
+    .into_iter()
+    .flatten()
+    .filter_map(Value::as_str)
+    .collect();
+  let optional = properties
+    .keys()
+    .map(String::as_str)
+    .filter(|key| !required.contains(key));
+  required
+    .iter()
+    .copied()
+    .chain(optional)
+    .filter_map(|key| {
+      let property = properties.get(key)?;
+      Some((key, !required.contains(&key), property))
+    })
+    .collect()
+}
+
+fn field(key: &str, optional: bool, property: &Value) -> String {
+  let mark = if optional { "?" } else { "" };
+  format!("{key}{mark}: {}", ts_type(property))
+}
+
+fn object_body(schema: &Value) -> String {
+  let mut body = String::from("{\n");
+  for (key, optional, property) in fields(schema) {
+    doc_comment(&mut body, property, "  ");
+    let _ = writeln!(body, "  {}", field(key, optional, property));
+  }
+  body.push('}');
+  body
+}
+
+/// 单行的对象类型，用于联合类型的成员
+fn inline_object(schema: &Value) -> String {
+  let fields: Vec<_> = fields(schema)
+    .into_iter()
+    .map(|(key, optional, property)| field(key, optional, property))
+    .collect();
+  format!("{{ {} }}", fields.join(", "))
+}
+
+fn ts_type(schema: &Value) -> String {
+  if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+    return reference
+      .rsplit('/')
+      .next()
+      .unwrap_or(reference)
+      .to_string();
+  }
+  if let Some(value) = schema.get("const") {
+    return literal(value);
+  }
+  if let Some(Value::Array(values)) = schema.get("enum") {
+    return values.iter().map(literal).collect::<Vec<_>>().join(" | ");
+  }
+  if let Some(Value::Array(variants)) = schema.get("oneOf").or_else(|| schema.get("anyOf")) {
+    return variants.iter().map(ts_type).collect::<Vec<_>>().join(" | ");
+  }
+  match schema.get("type") {
+    Some(Value::Array(types)) => types
+      .iter()
+      .filter_map(Value::as_str)
+      .map(|kind| primitive(schema, kind))
+      .collect::<Vec<_>>()
+      .join(" | "),
+    Some(Value::String(kind)) => primitive(schema, kind),
+    _ => "unknown".to_string(),
+  }
+}
+
+fn primitive(schema: &Value, kind: &str) -> String {
+  match kind {
+    "string" => "string".to_string(),
+    "integer" | "number" => "number".to_string(),
+    "boolean" => "boolean".to_string(),
+    "null" => "null".to_string(),
+    "array" => {
+      let items = schema.get("items").map_or("unknown".to_string(), ts_type);
+      format!("Array<{items}>")
+    }
+    "object" if schema.get("properties").is_some() => inline_object(schema),
+    _ => "unknown".to_string(),
+  }
+}
+
+fn literal(value: &Value) -> String {
+  match value {
+    Value::String(text) => format!("'{text}'"),
+    value => value.to_string(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn typescript_matches_serialized_ast() {
+    let schema = ast_json_schema();
+    assert_eq!(schema["title"], "Root");
+    assert_eq!(
+      schema["$defs"]["Node"]["oneOf"][0]["required"],
+      serde_json::json!([
+        "type",
+        "name",
+        "attrs",
+        "children",
+        "selfClosing",
+        "firstAttrSameLine",
+        "recovered",
+        "closeStart",
+        "closeEnd",
+        "start",
+        "end"
+      ])
+    );
+
+    let typescript = ast_typescript();
+    assert!(typescript.contains(
+      "
+/** 属性值使用的引号 */
+export type AttributeQuote =
+  | 'Double'
+  | 'Single'
+  | 'None'
+"
+    ));
+    assert!(typescript.contains(
+      "
+export type AttributeValue =
+  | { type: 'Text', content: string, start: Position, end: Position }
+  | { type: 'Expression', content: string, start: Position, end: Position }
+"
+    ));
+    assert!(typescript.contains(
+      "
+export interface Root {
+  children: Array<Node>
+  start: Position
+  end: Position
+"
+    ));
+  }
+}
@@ -0,0 +1,146 @@
+//! 元素属性的映射视图
+//!
+//! 元素的属性以 `Vec` 保存，保留重复的属性以便检查与格式化原样输出。按名称查找属性时，
+//! 通过 [`Node::attr_map`] 取得去重后的映射，并显式选择重复属性的处理方式，见 [`DuplicatePolicy`]
+
+use std::{error::Error, fmt};
+
+use crate::ast::{Attribute, Node, Position};
+
+/// 同名属性出现多次时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+  /// 保留第一次出现的属性
+  FirstWins,
+  /// 保留最后一次出现的属性
+  LastWins,
+  /// 返回 [`DuplicateAttributeError`]
+  Error,
+}
+
+/// [`DuplicatePolicy::Error`] 时遇到的重复属性
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateAttributeError {
+  pub name: String,
+  /// 第一次出现的属性的起始位置
+  pub first: Position,
+  /// 重复的属性的位置
+  pub start: Position,
+  pub end: Position,
+}
+
+impl fmt::Display for DuplicateAttributeError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "duplicate attribute `{}` at line {}, column {}, first defined at line {}, column {}",
+      self.name, self.start.line, self.start.column, self.first.line, self.first.column
+    )
+  }
+}
+
+impl Error for DuplicateAttributeError {}
+
+/// 名称唯一的属性映射，按保留的属性在源码中的顺序排列
+#[derive(Debug, Clone, Default)]
+pub struct AttrMap<'a> {
+  attrs: Vec<&'a Attribute>,
+}
+
+impl<'a> AttrMap<'a> {
+  /// 按 `policy` 去重
+  pub fn new(
+    attrs: &'a [Attribute],
+    policy: DuplicatePolicy,
+  ) -> Result<Self, DuplicateAttributeError> {
+    let mut kept: Vec<&Attribute> = Vec::with_capacity(attrs.len());
+    for attr in attrs {
+      let Some(index) = kept.iter().position(|kept| kept.name == attr.name) else {
+        kept.push(attr);
+        continue;
+      };
+      match policy {
+        DuplicatePolicy::FirstWins => {}
+        DuplicatePolicy::LastWins => {
+          kept.remove(index);
+          kept.push(attr);
+        }
+        DuplicatePolicy::Error => {
+          return Err(DuplicateAttributeError {
+            name: attr.name.clone(),
+            first: kept[index].start,
+            start: attr.start,
+            end: attr.end,
+          })
+        }
+      }
+    }
+    Ok(Self { attrs: kept })
+  }
+
+  pub fn get(&self, name: &str) -> Option<&'a Attribute> {
+    self.attrs.iter().find(|attr| attr.name == name).copied()
+  }
+
+  pub fn contains(&self, name: &str) -> bool {
+    self.get(name).is_some()
+  }
+
+  /// 属性的静态值，见 [`Attribute::as_static_string`]
+  pub fn static_value(&self, name: &str) -> Option<String> {
+    self.get(name)?.as_static_string()
+  }
+
+  pub fn names(&self) -> impl Iterator<Item = &'a str> + '_ {
+    self.attrs.iter().map(|attr| attr.name.as_str())
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = &'a Attribute> + '_ {
+    self.attrs.iter().copied()
+  }
+
+  pub fn len(&self) -> usize {
+    self.attrs.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.attrs.is_empty()
+  }
+}
+
+impl Node {
+  /// 元素属性的映射视图，非元素节点为空映射
+  pub fn attr_map(&self, policy: DuplicatePolicy) -> Result<AttrMap<'_>, DuplicateAttributeError> {
+    AttrMap::new(self.attrs().unwrap_or_default(), policy)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::parser::Parser;
+
+  #[test]
+  fn duplicate_policies() {
+    let root = Parser::new(r#"<view class="a" id="x" class="b" hidden/>"#)
+      .parse_root()
+      .unwrap();
+    let node = &root.children[0];
+
+    let first = node.attr_map(DuplicatePolicy::FirstWins).unwrap();
+    assert_eq!(first.names().collect::<Vec<_>>(), ["class", "id", "hidden"]);
+    assert_eq!(first.static_value("class").as_deref(), Some("a"));
+
+    let last = node.attr_map(DuplicatePolicy::LastWins).unwrap();
+    assert_eq!(last.names().collect::<Vec<_>>(), ["id", "class", "hidden"]);
+    assert_eq!(last.static_value("class").as_deref(), Some("b"));
+    assert!(last.contains("hidden"));
+    assert_eq!(last.len(), 3);
+
+    let error = node.attr_map(DuplicatePolicy::Error).unwrap_err();
+    assert_eq!(
+      error.to_string(),
+      "duplicate attribute `class` at line 1, column 24, first defined at line 1, column 7"
+    );
+  }
+}
@@ -0,0 +1,88 @@
+//! 批量解析、检查多个模板文件
+
+#[cfg(feature = "napi")]
+use napi_derive::napi;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  ast::Root,
+  error::SyntaxError,
+  lint::{Diagnostic, LintConfig, LintConfigError, Linter},
+  options::ParserOptions,
+  parser::Parser,
+};
+
+/// 待解析的模板文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "napi", napi(object))]
+pub struct SourceFile {
+  pub path: String,
+  pub source: String,
+}
+
+/// 单个文件的解析结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "napi", napi(object))]
+pub struct ParseResult {
+  pub path: String,
+  /// 解析出的语法树，遇到无法恢复的错误时为空
+  pub root: Option<Root>,
+  /// 解析过程中收集的语法错误
+  pub errors: Vec<SyntaxError>,
+}
+
+/// 单个文件的检查结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "napi", napi(object))]
+pub struct LintResult {
+  pub path: String,
+  /// 解析过程中收集的语法错误
+  pub errors: Vec<SyntaxError>,
+  /// 检查规则产生的诊断信息，遇到无法恢复的语法错误时为空
+  pub diagnostics: Vec<Diagnostic>,
+}
+
+/// 解析单个文件，收集所有错误而不是在第一个错误处中断
+pub fn parse_file(path: impl Into<String>, source: &str, options: &ParserOptions) -> ParseResult {
+  let mut parser = Parser::with_options(source, options.clone());
+  let root = parser.parse_root().ok();
+  ParseResult {
+    path: path.into(),
+    root,
+    errors: parser.errors().to_vec(),
+  }
+}
+
+/// 使用 rayon 线程池并行解析多个文件，结果顺序与输入一致
+pub fn parse_files(files: Vec<(String, String)>, options: &ParserOptions) -> Vec<ParseResult> {
+  files
+    .into_par_iter()
+    .map(|(path, source)| parse_file(path, &source, options))
+    .collect()
+}
+
+/// 使用 rayon 线程池并行解析并检查多个文件，结果顺序与输入一致；配置无效时不检查任何文件
+pub fn lint_files(
+  files: Vec<(String, String)>,
+  config: &LintConfig,
+) -> Result<Vec<LintResult>, LintConfigError> {
+  let linter = Linter::from_config(config)?;
+  let options = config.parser.clone().unwrap_or_default();
+  let results = files
+    .into_par_iter()
+    .map(|(path, source)| {
+      let result = parse_file(path, &source, &options);
+      LintResult {
+        diagnostics: result
+          .root
+          .as_ref()
+          .map(|root| linter.lint_source(root, &source))
+          .unwrap_or_default(),
+        path: result.path,
+        errors: result.errors,
+      }
+    })
+    .collect();
+  Ok(results)
+}
@@ -0,0 +1,392 @@
+//! `wxml` 命令行工具：解析、检查与格式化 WXML 模板
+//!
+//! 未指定文件或文件为 `-` 时从标准输入读取
+
+use std::{
+  fs,
+  io::{self, Read},
+  path::PathBuf,
+  process::ExitCode,
+};
+
+use clap::{Parser as ClapParser, Subcommand};
+use miniprogram_template_parser::{
+  ast_schema::{ast_json_schema, ast_typescript},
+  builtins::Version,
+  diagnostics::{DiagnosticBag, DiagnosticBagOptions, Severity},
+  format::{format, FormatOptions},
+  lint::{rules, Diagnostic, Linter},
+  parse_file, ParseResult, ParserOptions, Root, WhitespaceMode,
+};
+
+#[derive(ClapParser)]
+#[command(
+  name = "wxml",
+  version,
+  about = "Parse, lint and format WXML templates"
+)]
+struct Cli {
+  /// 宽松模式，容忍不带引号的属性值等不规范写法
+  #[arg(long, global = true)]
+  lenient: bool,
+
+  #[command(subcommand)]
+  command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+  /// 输出语法树
+  Parse {
+    files: Vec<PathBuf>,
+    /// 以 JSON 格式输出
+    #[arg(long)]
+    json: bool,
+    /// 以缩进的树形结构输出，每个节点一行并标注字节范围
+    #[arg(long, conflicts_with = "json")]
+    tree: bool,
+    /// 只输出统计信息：节点数量、嵌套深度与解析耗时
+    #[arg(long)]
+    stats: bool,
+  },
+  /// 检查语法错误并执行检查规则
+  Lint {
+    files: Vec<PathBuf>,
+    /// 每个文件输出一行 JSON，包含语法错误与诊断信息
+    #[arg(long)]
+    json: bool,
+    /// 应用规则附带的自动修复并写回文件，标准输入的修复结果输出到标准输出
+    #[arg(long)]
+    fix: bool,
+    /// 同时启用代码风格规则，如 `attr-order`
+    #[arg(long)]
+    stylistic: bool,
+    /// 只报告错误，忽略警告与提示
+    #[arg(long)]
+    quiet: bool,
+    /// 错误总数达到该值后不再检查后续文件
+    #[arg(long, value_name = "N")]
+    max_errors: Option<usize>,
+    /// 每个文件最多输出的诊断信息数量
+    #[arg(long, value_name = "N")]
+    max_per_file: Option<usize>,
+    /// 项目支持的最低基础库版本，报告该版本尚不支持的组件与属性语法
+    #[arg(long, value_name = "VERSION")]
+    target_version: Option<Version>,
+  },
+  /// 格式化模板，默认输出到标准输出
+  Format {
+    files: Vec<PathBuf>,
+    /// 直接写回文件
+    #[arg(long)]
+    write: bool,
+  },
+  /// 检查语法、规则与格式，存在错误、警告或未格式化的文件时以非零状态退出
+  Check {
+    files: Vec<PathBuf>,
+    /// 只报告错误，检查规则的警告不影响退出状态
+    #[arg(long)]
+    quiet: bool,
+    /// 项目支持的最低基础库版本，报告该版本尚不支持的组件与属性语法
+    #[arg(long, value_name = "VERSION")]
+    target_version: Option<Version>,
+  },
+  /// 输出序列化后 AST 的 JSON Schema
+  Schema {
+    /// 输出 TypeScript 类型声明
+    #[arg(long)]
+    typescript: bool,
+  },
+}
+
+struct Input {
+  /// 显示用的路径，标准输入为 `<stdin>`
+  name: String,
+  path: Option<PathBuf>,
+  source: String,
+}
+
+fn read_inputs(files: &[PathBuf]) -> io::Result<Vec<Input>> {
+  let stdin = PathBuf::from("-");
+  if files.is_empty() || files == [stdin] {
+    let mut source = String::new();
+    io::stdin().read_to_string(&mut source)?;
+    return Ok(vec![Input {
+      name: "<stdin>".to_string(),
+      path: None,
+      source,
+    }]);
+  }
+  files
+    .iter()
+    .map(|path| {
+      Ok(Input {
+        name: path.display().to_string(),
+        path: Some(path.clone()),
+        source: fs::read_to_string(path)?,
+      })
+    })
+    .collect()
+}
+
+/// 输出语法错误，返回是否存在错误级别的问题；警告级别的问题（如文本中的 `<`）只输出
+fn report_syntax_errors(input: &Input, result: &ParseResult) -> bool {
+  let mut failed = result.root.is_none();
+  for error in &result.errors {
+    let severity = Diagnostic::from(error).severity;
+    failed |= severity == Severity::Error;
+    eprintln!(
+      "{}:{}:{}: {severity}[{}]: {}",
+      input.name, error.line, error.column, error.code, error.kind
+    );
+    if let Some(open_tag) = &error.open_tag {
+      eprintln!(
+        "{}:{}:{}: note: `<{}>` opened here",
+        input.name, open_tag.start.line, open_tag.start.column, open_tag.name
+      );
+    }
+  }
+  failed
+}
+
+/// 是否应以非零状态退出：存在错误级别的语法错误，或不轻于 `failing` 的检查规则诊断信息
+fn fails(result: &ParseResult, diagnostics: &[Diagnostic], failing: Severity) -> bool {
+  result.root.is_none()
+    || result
+      .errors
+      .iter()
+      .any(|error| Diagnostic::from(error).severity == Severity::Error)
+    || diagnostics
+      .iter()
+      .any(|diagnostic| diagnostic.severity <= failing)
+}
+
+/// 执行检查规则并输出不轻于 `failing` 的诊断信息，返回是否存在这样的诊断信息；提示不影响结果
+fn report_lint(input: &Input, linter: &Linter, root: &Root, failing: Severity) -> bool {
+  let mut diagnostics = linter.lint_source(root, &input.source);
+  diagnostics.retain(|diagnostic| diagnostic.severity <= failing);
+  for diagnostic in &diagnostics {
+    eprintln!(
+      "{}:{}:{}: {}[{}]: {}",
+      input.name,
+      diagnostic.start.line,
+      diagnostic.start.column,
+      diagnostic.rule,
+      diagnostic.code,
+      diagnostic.message
+    );
+  }
+  !diagnostics.is_empty()
+}
+
+fn run(cli: Cli) -> io::Result<bool> {
+  let options = ParserOptions {
+    lenient: Some(cli.lenient),
+    ..Default::default()
+  };
+  let mut ok = true;
+  match cli.command {
+    Command::Parse {
+      files,
+      json,
+      tree,
+      stats,
+    } => {
+      let options = ParserOptions {
+        stats: Some(stats),
+        ..options
+      };
+      for input in read_inputs(&files)? {
+        let result = parse_file(&input.name, &input.source, &options);
+        ok &= !report_syntax_errors(&input, &result);
+        if let Some(root) = &result.root {
+          if let Some(stats) = root.stats.as_ref().filter(|_| !json) {
+            println!(
+              "{}: {} nodes, {} elements, {} expressions, max depth {}, {:.3}ms",
+              input.name,
+              stats.node_count,
+              stats.element_count,
+              stats.expression_count,
+              stats.max_depth,
+              stats.duration
+            );
+          } else if tree {
+            print!("{}", root.dump_tree());
+          } else if json {
+            println!("{}", serde_json::to_string_pretty(root)?);
+          } else {
+            println!("{root:#?}");
+          }
+        }
+      }
+    }
+    Command::Lint {
+      files,
+      json,
+      fix,
+      stylistic,
+      quiet,
+      max_errors,
+      max_per_file,
+      target_version,
+    } => {
+      let mut linter = Linter::default();
+      if stylistic {
+        linter = linter.with_rules(rules::stylistic());
+      }
+      if let Some(target) = target_version {
+        linter = linter.with_rule(rules::MinVersion { target });
+      }
+      // 语法警告（如文本中的 `<`）与提示不影响退出状态，`--quiet` 时检查规则的警告也不影响
+      let failing = if quiet {
+        Severity::Error
+      } else {
+        Severity::Warning
+      };
+      let mut bag = DiagnosticBag::new(DiagnosticBagOptions {
+        max_per_file,
+        max_errors,
+        min_severity: quiet.then_some(Severity::Error),
+      });
+      for mut input in read_inputs(&files)? {
+        if fix {
+          let fixed = linter.fix(&input.source, &options);
+          match &input.path {
+            Some(path) if fixed != input.source => fs::write(path, &fixed)?,
+            Some(_) => {}
+            None => print!("{fixed}"),
+          }
+          input.source = fixed;
+        }
+        let result = parse_file(&input.name, &input.source, &options);
+        if json {
+          let diagnostics = result
+            .root
+            .as_ref()
+            .map(|root| linter.lint_source(root, &input.source))
+            .unwrap_or_default();
+          ok &= !fails(&result, &diagnostics, failing);
+          // 与文本输出一样，`--quiet` 时只输出错误
+          let shown = |severity: Severity| !quiet || severity == Severity::Error;
+          let errors: Vec<_> = result
+            .errors
+            .iter()
+            .filter(|error| shown(Diagnostic::from(*error).severity))
+            .map(|error| error.to_json_value())
+            .collect();
+          let diagnostics: Vec<_> = diagnostics
+            .into_iter()
+            .filter(|diagnostic| shown(diagnostic.severity))
+            .collect();
+          println!(
+            "{}",
+            serde_json::json!({
+              "file": input.name,
+              "errors": errors,
+              "diagnostics": diagnostics,
+            })
+          );
+          continue;
+        }
+        let diagnostics = result
+          .root
+          .as_ref()
+          .map(|root| linter.lint_source(root, &input.source))
+          .unwrap_or_default();
+        ok &= !fails(&result, &diagnostics, failing);
+        let errors = result.errors.iter().map(Diagnostic::from);
+        bag.add(&input.name, errors.chain(diagnostics));
+        if bag.should_bail() {
+          eprintln!("too many errors, skipping remaining files");
+          break;
+        }
+      }
+      if !bag.is_empty() {
+        eprintln!("{bag}");
+      }
+    }
+    Command::Format { files, write } => {
+      // 保留只含空白的文本，如 `{{a}} {{b}}` 中的空格
+      let options = ParserOptions {
+        whitespace: Some(WhitespaceMode::Preserve),
+        ..options
+      };
+      let format_options = FormatOptions {
+        parser: options.clone(),
+        ..Default::default()
+      };
+      for input in read_inputs(&files)? {
+        let result = parse_file(&input.name, &input.source, &options);
+        if report_syntax_errors(&input, &result) {
+          // 存在语法错误时不输出，避免丢失内容
+          ok = false;
+          continue;
+        }
+        let formatted = format(result.root.as_ref().unwrap(), &format_options);
+        match (&input.path, write) {
+          (Some(path), true) => {
+            if formatted != input.source {
+              fs::write(path, formatted)?;
+            }
+          }
+          _ => print!("{formatted}"),
+        }
+      }
+    }
+    Command::Check {
+      files,
+      quiet,
+      target_version,
+    } => {
+      let mut linter = Linter::default();
+      if let Some(target) = target_version {
+        linter = linter.with_rule(rules::MinVersion { target });
+      }
+      let failing = if quiet {
+        Severity::Error
+      } else {
+        Severity::Warning
+      };
+      let format_options = FormatOptions {
+        parser: ParserOptions {
+          whitespace: Some(WhitespaceMode::Preserve),
+          ..options.clone()
+        },
+        ..Default::default()
+      };
+      for input in read_inputs(&files)? {
+        let result = parse_file(&input.name, &input.source, &options);
+        ok &= !report_syntax_errors(&input, &result);
+        let Some(root) = &result.root else {
+          continue;
+        };
+        ok &= !report_lint(&input, &linter, root, failing);
+        let preserved = parse_file(&input.name, &input.source, &format_options.parser);
+        let formatted = preserved.root.map(|root| format(&root, &format_options));
+        if formatted.as_ref() != Some(&input.source) {
+          ok = false;
+          eprintln!("{}: not formatted", input.name);
+        }
+      }
+    }
+    Command::Schema { typescript } => {
+      if typescript {
+        print!("{}", ast_typescript());
+      } else {
+        println!("{}", serde_json::to_string_pretty(&ast_json_schema())?);
+      }
+    }
+  }
+  Ok(ok)
+}
+
+fn main() -> ExitCode {
+  match run(Cli::parse()) {
+    Ok(true) => ExitCode::SUCCESS,
+    Ok(false) => ExitCode::FAILURE,
+    Err(error) => {
+      eprintln!("error: {error}");
+      ExitCode::from(2)
+    }
+  }
+}
@@ -0,0 +1,379 @@
+//! 借用源码的 AST
+//!
+//! 结构与 [`ast`](crate::ast) 一一对应，但标签名、属性名、文本与表达式均为指向源码的 `Cow<'s, str>`，
+//! 解析时不复制任何字符串，适合批量处理大量模板、分配开销占主导的场景。
+//! 需要修改时可直接替换为 `Cow::Owned`，需要脱离源码生命周期时使用 `into_owned` 转换为 [`ast`](crate::ast) 中的类型。
+//! 仅提供 Rust 接口
+
+use std::borrow::Cow;
+
+use serde::Serialize;
+
+use crate::{
+  ast::{self, split_attr_name_str, AttributeQuote, Position},
+  parser::{Build, PResult, Parser},
+};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Root<'s> {
+  pub children: Vec<Node<'s>>,
+  pub start: Position,
+  pub end: Position,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all_fields = "camelCase")]
+pub enum Node<'s> {
+  Element {
+    name: Cow<'s, str>,
+    attrs: Vec<Attribute<'s>>,
+    children: Vec<Node<'s>>,
+    self_closing: bool,
+    first_attr_same_line: bool,
+    recovered: bool,
+    close_start: Option<Position>,
+    close_end: Option<Position>,
+    start: Position,
+    end: Position,
+  },
+  Text {
+    content: Cow<'s, str>,
+    start: Position,
+    end: Position,
+  },
+  Comment {
+    content: Cow<'s, str>,
+    start: Position,
+    end: Position,
+  },
+  Expression {
+    content: Cow<'s, str>,
+    start: Position,
+    end: Position,
+  },
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Attribute<'s> {
+  pub name: Cow<'s, str>,
+  pub prefix: Option<Cow<'s, str>>,
+  pub local: Cow<'s, str>,
+  pub value: Option<Vec<AttributeValue<'s>>>,
+  pub quote: AttributeQuote,
+  pub start: Position,
+  pub end: Position,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all_fields = "camelCase")]
+pub enum AttributeValue<'s> {
+  Text {
+    content: Cow<'s, str>,
+    start: Position,
+    end: Position,
+  },
+  Expression {
+    content: Cow<'s, str>,
+    start: Position,
+    end: Position,
+  },
+}
+
+impl<'s> Parser<'s> {
+  /// 解析为借用源码的 AST，见 [`borrowed`](crate::borrowed)
+  pub fn parse_root_borrowed(&mut self) -> PResult<Root<'s>> {
+    let (children, start, end) = self.parse_document(&mut Borrowed)?;
+    Ok(Root {
+      children,
+      start,
+      end,
+    })
+  }
+}
+
+impl<'s> Root<'s> {
+  /// 深度优先（先序）遍历所有节点
+  pub fn walk<'a, F>(&'a self, f: &mut F)
+  where
+    F: FnMut(&'a Node<'s>),
+  {
+    for child in &self.children {
+      child.walk(f);
+    }
+  }
+
+  /// 转换为拥有所有权的 AST
+  pub fn into_owned(self) -> ast::Root {
+    ast::Root {
+      children: self.children.into_iter().map(Node::into_owned).collect(),
+      start: self.start,
+      end: self.end,
+      stats: None,
+      comments: None,
+      errors: Vec::new(),
+    }
+  }
+}
+
+impl<'s> Node<'s> {
+  /// 元素的标签名
+  pub fn name(&self) -> Option<&str> {
+    match self {
+      Node::Element { name, .. } => Some(name),
+      _ => None,
+    }
+  }
+
+  /// 元素的子节点
+  pub fn children(&self) -> Option<&[Node<'s>]> {
+    match self {
+      Node::Element { children, .. } => Some(children),
+      _ => None,
+    }
+  }
+
+  /// 深度优先（先序）遍历当前节点及其后代
+  pub fn walk<'a, F>(&'a self, f: &mut F)
+  where
+    F: FnMut(&'a Node<'s>),
+  {
+    f(self);
+    if let Node::Element { children, .. } = self {
+      for child in children {
+        child.walk(f);
+      }
+    }
+  }
+
+  /// 转换为拥有所有权的节点
+  pub fn into_owned(self) -> ast::Node {
+    match self {
+      Node::Element {
+        name,
+        attrs,
+        children,
+        self_closing,
+        first_attr_same_line,
+        recovered,
+        close_start,
+        close_end,
+        start,
+        end,
+      } => ast::Node::Element {
+        name: name.into_owned(),
+        attrs: attrs.into_iter().map(Attribute::into_owned).collect(),
+        children: children.into_iter().map(Node::into_owned).collect(),
+        self_closing,
+        first_attr_same_line,
+        recovered,
+        close_start,
+        close_end,
+        start,
+        end,
+      },
+      Node::Text {
+        content,
+        start,
+        end,
+      } => ast::Node::Text {
+        content: content.into_owned(),
+        start,
+        end,
+      },
+      Node::Comment {
+        content,
+        start,
+        end,
+      } => ast::Node::Comment {
+        content: content.into_owned(),
+        start,
+        end,
+      },
+      Node::Expression {
+        content,
+        start,
+        end,
+      } => ast::Node::Expression {
+        content: content.into_owned(),
+        start,
+        end,
+      },
+    }
+  }
+}
+
+impl<'s> Attribute<'s> {
+  /// 转换为拥有所有权的属性
+  pub fn into_owned(self) -> ast::Attribute {
+    ast::Attribute {
+      name: self.name.into_owned(),
+      prefix: self.prefix.map(Cow::into_owned),
+      local: self.local.into_owned(),
+      value: self
+        .value
+        .map(|values| values.into_iter().map(AttributeValue::into_owned).collect()),
+      quote: self.quote,
+      start: self.start,
+      end: self.end,
+    }
+  }
+}
+
+impl<'s> AttributeValue<'s> {
+  /// 转换为拥有所有权的属性值
+  pub fn into_owned(self) -> ast::AttributeValue {
+    match self {
+      AttributeValue::Text {
+        content,
+        start,
+        end,
+      } => ast::AttributeValue::Text {
+        content: content.into_owned(),
+        start,
+        end,
+      },
+      AttributeValue::Expression {
+        content,
+        start,
+        end,
+      } => ast::AttributeValue::Expression {
+        content: content.into_owned(),
+        start,
+        end,
+      },
+    }
+  }
+}
+
+/// 构造借用源码的节点
+struct Borrowed;
+
+impl<'s> Build<'s> for Borrowed {
+  type Node = Node<'s>;
+  type Attribute = Attribute<'s>;
+  type AttributeValue = AttributeValue<'s>;
+
+  fn element(
+    &mut self,
+    name: &'s str,
+    attrs: Vec<Attribute<'s>>,
+    children: Vec<Node<'s>>,
+    self_closing: bool,
+    first_attr_same_line: bool,
+    recovered: bool,
+    close_start: Option<Position>,
+    close_end: Option<Position>,
+    start: Position,
+    end: Position,
+  ) -> Node<'s> {
+    Node::Element {
+      name: name.into(),
+      attrs,
+      children,
+      self_closing,
+      first_attr_same_line,
+      recovered,
+      close_start,
+      close_end,
+      start,
+      end,
+    }
+  }
+
+  fn text(&mut self, content: &'s str, start: Position, end: Position) -> Node<'s> {
+    Node::Text {
+      content: content.into(),
+      start,
+      end,
+    }
+  }
+
+  fn comment(&mut self, content: &'s str, start: Position, end: Position) -> Node<'s> {
+    Node::Comment {
+      content: content.into(),
+      start,
+      end,
+    }
+  }
+
+  fn expression(&mut self, content: &'s str, start: Position, end: Position) -> Node<'s> {
+    Node::Expression {
+      content: content.into(),
+      start,
+      end,
+    }
+  }
+
+  fn attribute(
+    &mut self,
+    name: &'s str,
+    value: Option<Vec<AttributeValue<'s>>>,
+    quote: AttributeQuote,
+    start: Position,
+    end: Position,
+  ) -> Attribute<'s> {
+    let (prefix, local) = split_attr_name_str(name);
+    Attribute {
+      name: name.into(),
+      prefix: prefix.map(Cow::Borrowed),
+      local: local.into(),
+      value,
+      quote,
+      start,
+      end,
+    }
+  }
+
+  fn attribute_start(&self, attr: &Attribute<'s>) -> Position {
+    attr.start
+  }
+
+  fn text_value(&mut self, content: &'s str, start: Position, end: Position) -> AttributeValue<'s> {
+    AttributeValue::Text {
+      content: content.into(),
+      start,
+      end,
+    }
+  }
+
+  fn expression_value(
+    &mut self,
+    content: &'s str,
+    start: Position,
+    end: Position,
+  ) -> AttributeValue<'s> {
+    AttributeValue::Expression {
+      content: content.into(),
+      start,
+      end,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn borrows_from_source() {
+    let source =
+      r#"<view wx:if="{{ok}}" class="a {{b}}"><!-- c --><text>hi {{name}}</text></view>"#;
+    let root = Parser::new(source).parse_root_borrowed().unwrap();
+    let mut borrowed = true;
+    root.walk(&mut |node| {
+      if let Node::Element { name, attrs, .. } = node {
+        borrowed &= matches!(name, Cow::Borrowed(_));
+        borrowed &= attrs
+          .iter()
+          .all(|attr| matches!(attr.local, Cow::Borrowed(_)));
+      }
+    });
+    assert!(borrowed);
+    assert_eq!(
+      serde_json::to_value(root.into_owned()).unwrap(),
+      serde_json::to_value(Parser::new(source).parse_root().unwrap()).unwrap()
+    );
+  }
+}
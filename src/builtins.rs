@@ -0,0 +1,723 @@
+//! 微信小程序内置组件与 WXML 语法标签
+
+use std::{fmt, str::FromStr};
+
+use crate::dialect::Directive;
+
+/// WXML 语法标签，不渲染为组件
+pub const TEMPLATE_TAGS: &[&str] = &["block", "template", "import", "include", "wxs", "slot"];
+
+/// 内置组件名称
+pub const BUILTIN_COMPONENTS: &[&str] = &[
+  // 视图容器
+  "view",
+  "scroll-view",
+  "swiper",
+  "swiper-item",
+  "movable-area",
+  "movable-view",
+  "cover-view",
+  "cover-image",
+  "match-media",
+  "page-container",
+  "root-portal",
+  "share-element",
+  "grid-view",
+  "list-view",
+  "sticky-header",
+  "sticky-section",
+  // 基础内容
+  "icon",
+  "text",
+  "rich-text",
+  "progress",
+  // 表单组件
+  "button",
+  "checkbox",
+  "checkbox-group",
+  "editor",
+  "form",
+  "input",
+  "keyboard-accessory",
+  "label",
+  "picker",
+  "picker-view",
+  "picker-view-column",
+  "radio",
+  "radio-group",
+  "slider",
+  "switch",
+  "textarea",
+  // 导航
+  "functional-page-navigator",
+  "navigator",
+  // 媒体组件
+  "audio",
+  "camera",
+  "channel-live",
+  "channel-video",
+  "image",
+  "live-player",
+  "live-pusher",
+  "video",
+  "voip-room",
+  // 地图与画布
+  "map",
+  "canvas",
+  // 开放能力
+  "web-view",
+  "ad",
+  "ad-custom",
+  "official-account",
+  "open-data",
+  "store-product",
+  // 页面属性配置
+  "navigation-bar",
+  "page-meta",
+];
+
+/// 是否为内置组件或 WXML 语法标签
+pub fn is_builtin_tag(name: &str) -> bool {
+  TEMPLATE_TAGS.contains(&name) || BUILTIN_COMPONENTS.contains(&name)
+}
+
+/// 内置组件的属性定义
+#[derive(Debug, Clone, Copy)]
+pub struct AttrSpec {
+  pub name: &'static str,
+  /// 允许的取值，为空表示不限制
+  pub values: &'static [&'static str],
+}
+
+/// 内置组件定义
+#[derive(Debug, Clone, Copy)]
+pub struct ComponentSpec {
+  pub name: &'static str,
+  /// 组件特有的属性，不含 id、class 等通用属性
+  pub attrs: &'static [AttrSpec],
+}
+
+impl ComponentSpec {
+  /// 按名称查找属性定义
+  pub fn attr(&self, name: &str) -> Option<&'static AttrSpec> {
+    self.attrs.iter().find(|attr| attr.name == name)
+  }
+}
+
+const fn any(name: &'static str) -> AttrSpec {
+  AttrSpec { name, values: &[] }
+}
+
+const fn one_of(name: &'static str, values: &'static [&'static str]) -> AttrSpec {
+  AttrSpec { name, values }
+}
+
+const HOVER: [AttrSpec; 4] = [
+  any("hover-class"),
+  any("hover-stop-propagation"),
+  any("hover-start-time"),
+  any("hover-stay-time"),
+];
+
+const CONFIRM_TYPES: &[&str] = &["send", "search", "next", "go", "done"];
+
+/// 内置组件属性目录，未收录的内置组件不做属性校验
+pub const COMPONENT_CATALOG: &[ComponentSpec] = &[
+  ComponentSpec {
+    name: "view",
+    attrs: &HOVER,
+  },
+  ComponentSpec {
+    name: "cover-view",
+    attrs: &[any("scroll-top"), any("marker-id"), any("slot")],
+  },
+  ComponentSpec {
+    name: "cover-image",
+    attrs: &[any("src"), any("referrer-policy")],
+  },
+  ComponentSpec {
+    name: "scroll-view",
+    attrs: &[
+      any("scroll-x"),
+      any("scroll-y"),
+      any("upper-threshold"),
+      any("lower-threshold"),
+      any("scroll-top"),
+      any("scroll-left"),
+      any("scroll-into-view"),
+      any("scroll-with-animation"),
+      any("enable-back-to-top"),
+      any("enable-flex"),
+      any("scroll-anchoring"),
+      any("enable-passive"),
+      any("refresher-enabled"),
+      any("refresher-threshold"),
+      one_of("refresher-default-style", &["black", "white", "none"]),
+      any("refresher-background"),
+      any("refresher-triggered"),
+      any("enhanced"),
+      any("bounces"),
+      any("show-scrollbar"),
+      any("paging-enabled"),
+      any("fast-deceleration"),
+      one_of("type", &["list", "custom", "nested"]),
+      any("associative-container"),
+      any("reverse"),
+      any("clip"),
+      any("cache-extent"),
+      any("min-drag-distance"),
+      any("padding"),
+      one_of(
+        "scroll-into-view-alignment",
+        &["start", "center", "end", "nearest"],
+      ),
+      any("scroll-into-view-within-extent"),
+    ],
+  },
+  ComponentSpec {
+    name: "swiper",
+    attrs: &[
+      any("indicator-dots"),
+      any("indicator-color"),
+      any("indicator-active-color"),
+      any("autoplay"),
+      any("current"),
+      any("interval"),
+      any("duration"),
+      any("circular"),
+      any("vertical"),
+      any("previous-margin"),
+      any("next-margin"),
+      any("snap-to-edge"),
+      any("display-multiple-items"),
+      one_of(
+        "easing-function",
+        &[
+          "default",
+          "linear",
+          "easeInCubic",
+          "easeOutCubic",
+          "easeInOutCubic",
+        ],
+      ),
+      one_of(
+        "layout-type",
+        &["normal", "stackLeft", "stackRight", "tinder", "transformer"],
+      ),
+      any("indicator-margin"),
+      any("indicator-spacing"),
+      any("indicator-radius"),
+      any("indicator-width"),
+      any("indicator-height"),
+      any("indicator-alignment"),
+      any("indicator-offset"),
+      any("scroll-with-animation"),
+      any("cache-extent"),
+    ],
+  },
+  ComponentSpec {
+    name: "swiper-item",
+    attrs: &[any("item-id"), any("skip-hidden-item-layout")],
+  },
+  ComponentSpec {
+    name: "movable-area",
+    attrs: &[any("scale-area")],
+  },
+  ComponentSpec {
+    name: "movable-view",
+    attrs: &[
+      one_of("direction", &["all", "vertical", "horizontal", "none"]),
+      any("inertia"),
+      any("out-of-bounds"),
+      any("x"),
+      any("y"),
+      any("damping"),
+      any("friction"),
+      any("disabled"),
+      any("scale"),
+      any("scale-min"),
+      any("scale-max"),
+      any("scale-value"),
+      any("animation"),
+    ],
+  },
+  ComponentSpec {
+    name: "icon",
+    attrs: &[
+      one_of(
+        "type",
+        &[
+          "success",
+          "success_no_circle",
+          "info",
+          "warn",
+          "waiting",
+          "cancel",
+          "download",
+          "search",
+          "clear",
+        ],
+      ),
+      any("size"),
+      any("color"),
+    ],
+  },
+  ComponentSpec {
+    name: "text",
+    attrs: &[
+      any("selectable"),
+      any("user-select"),
+      one_of("space", &["ensp", "emsp", "nbsp"]),
+      any("decode"),
+    ],
+  },
+  ComponentSpec {
+    name: "rich-text",
+    attrs: &[
+      any("nodes"),
+      one_of("space", &["ensp", "emsp", "nbsp"]),
+      any("user-select"),
+    ],
+  },
+  ComponentSpec {
+    name: "progress",
+    attrs: &[
+      any("percent"),
+      any("show-info"),
+      any("border-radius"),
+      any("font-size"),
+      any("stroke-width"),
+      any("color"),
+      any("activeColor"),
+      any("backgroundColor"),
+      any("active"),
+      one_of("active-mode", &["backwards", "forwards"]),
+      any("duration"),
+    ],
+  },
+  ComponentSpec {
+    name: "button",
+    attrs: &[
+      one_of("size", &["default", "mini"]),
+      one_of("type", &["primary", "default", "warn"]),
+      any("plain"),
+      any("disabled"),
+      any("loading"),
+      one_of("form-type", &["submit", "reset"]),
+      one_of(
+        "open-type",
+        &[
+          "contact",
+          "liveActivity",
+          "share",
+          "getPhoneNumber",
+          "getRealtimePhoneNumber",
+          "getUserInfo",
+          "launchApp",
+          "openSetting",
+          "feedback",
+          "chooseAvatar",
+          "agreePrivacyAuthorization",
+        ],
+      ),
+      HOVER[0],
+      HOVER[1],
+      HOVER[2],
+      HOVER[3],
+      one_of("lang", &["en", "zh_CN", "zh_TW"]),
+      any("session-from"),
+      any("send-message-title"),
+      any("send-message-path"),
+      any("send-message-img"),
+      any("app-parameter"),
+      any("show-message-card"),
+      any("phone-number-no-quota-toast"),
+      any("need-show-entrance"),
+      any("entrance-path"),
+      any("activity-type"),
+    ],
+  },
+  ComponentSpec {
+    name: "checkbox",
+    attrs: &[any("value"), any("disabled"), any("checked"), any("color")],
+  },
+  ComponentSpec {
+    name: "checkbox-group",
+    attrs: &[any("name")],
+  },
+  ComponentSpec {
+    name: "form",
+    attrs: &[any("report-submit"), any("report-submit-timeout")],
+  },
+  ComponentSpec {
+    name: "input",
+    attrs: &[
+      any("value"),
+      one_of(
+        "type",
+        &[
+          "text",
+          "number",
+          "idcard",
+          "digit",
+          "safe-password",
+          "nickname",
+        ],
+      ),
+      any("password"),
+      any("placeholder"),
+      any("placeholder-style"),
+      any("placeholder-class"),
+      any("disabled"),
+      any("maxlength"),
+      any("cursor-spacing"),
+      any("auto-focus"),
+      any("focus"),
+      one_of("confirm-type", CONFIRM_TYPES),
+      any("always-embed"),
+      any("confirm-hold"),
+      any("cursor"),
+      any("cursor-color"),
+      any("selection-start"),
+      any("selection-end"),
+      any("adjust-position"),
+      any("hold-keyboard"),
+      any("safe-password-cert-path"),
+      any("safe-password-length"),
+      any("safe-password-time-stamp"),
+      any("safe-password-nonce"),
+      any("safe-password-salt"),
+      any("safe-password-custom-hash"),
+      any("name"),
+    ],
+  },
+  ComponentSpec {
+    name: "label",
+    attrs: &[any("for")],
+  },
+  ComponentSpec {
+    name: "picker",
+    attrs: &[
+      one_of(
+        "mode",
+        &["selector", "multiSelector", "time", "date", "region"],
+      ),
+      any("header-text"),
+      any("disabled"),
+      any("range"),
+      any("range-key"),
+      any("value"),
+      any("start"),
+      any("end"),
+      one_of("fields", &["year", "month", "day"]),
+      any("custom-item"),
+      one_of("level", &["province", "city", "region", "sub-district"]),
+      any("name"),
+    ],
+  },
+  ComponentSpec {
+    name: "picker-view",
+    attrs: &[
+      any("value"),
+      any("indicator-style"),
+      any("indicator-class"),
+      any("mask-style"),
+      any("mask-class"),
+      any("immediate-change"),
+      any("name"),
+    ],
+  },
+  ComponentSpec {
+    name: "radio",
+    attrs: &[any("value"), any("checked"), any("disabled"), any("color")],
+  },
+  ComponentSpec {
+    name: "radio-group",
+    attrs: &[any("name")],
+  },
+  ComponentSpec {
+    name: "slider",
+    attrs: &[
+      any("min"),
+      any("max"),
+      any("step"),
+      any("disabled"),
+      any("value"),
+      any("color"),
+      any("selected-color"),
+      any("activeColor"),
+      any("backgroundColor"),
+      any("block-size"),
+      any("block-color"),
+      any("show-value"),
+      any("name"),
+    ],
+  },
+  ComponentSpec {
+    name: "switch",
+    attrs: &[
+      any("checked"),
+      any("disabled"),
+      one_of("type", &["switch", "checkbox"]),
+      any("color"),
+      any("name"),
+    ],
+  },
+  ComponentSpec {
+    name: "textarea",
+    attrs: &[
+      any("value"),
+      any("placeholder"),
+      any("placeholder-style"),
+      any("placeholder-class"),
+      any("disabled"),
+      any("maxlength"),
+      any("focus"),
+      any("auto-focus"),
+      any("auto-height"),
+      any("fixed"),
+      any("cursor-spacing"),
+      any("cursor"),
+      any("cursor-color"),
+      any("show-confirm-bar"),
+      any("selection-start"),
+      any("selection-end"),
+      any("adjust-position"),
+      any("hold-keyboard"),
+      any("disable-default-padding"),
+      one_of(
+        "confirm-type",
+        &["send", "search", "next", "go", "done", "return"],
+      ),
+      any("confirm-hold"),
+      one_of("adjust-keyboard-to", &["cursor", "bottom"]),
+      any("name"),
+    ],
+  },
+  ComponentSpec {
+    name: "navigator",
+    attrs: &[
+      one_of("target", &["self", "miniProgram"]),
+      any("url"),
+      one_of(
+        "open-type",
+        &[
+          "navigate",
+          "redirect",
+          "switchTab",
+          "reLaunch",
+          "navigateBack",
+          "exit",
+        ],
+      ),
+      any("delta"),
+      any("app-id"),
+      any("path"),
+      any("extra-data"),
+      one_of("version", &["develop", "trial", "release"]),
+      any("short-link"),
+      HOVER[0],
+      HOVER[1],
+      HOVER[2],
+      HOVER[3],
+    ],
+  },
+  ComponentSpec {
+    name: "image",
+    attrs: &[
+      any("src"),
+      one_of(
+        "mode",
+        &[
+          "scaleToFill",
+          "aspectFit",
+          "aspectFill",
+          "widthFix",
+          "heightFix",
+          "top",
+          "bottom",
+          "center",
+          "left",
+          "right",
+          "top left",
+          "top right",
+          "bottom left",
+          "bottom right",
+        ],
+      ),
+      any("webp"),
+      any("lazy-load"),
+      any("show-menu-by-longpress"),
+      any("fade-in"),
+      // 无障碍替代文本，见 `a11y-image-alt` 规则
+      any("alt"),
+    ],
+  },
+  ComponentSpec {
+    name: "camera",
+    attrs: &[
+      one_of("mode", &["normal", "scanCode"]),
+      one_of("resolution", &["low", "medium", "high"]),
+      one_of("device-position", &["front", "back"]),
+      one_of("flash", &["auto", "on", "off", "torch"]),
+      one_of("frame-size", &["small", "medium", "large"]),
+    ],
+  },
+  ComponentSpec {
+    name: "canvas",
+    attrs: &[
+      one_of("type", &["2d", "webgl"]),
+      any("canvas-id"),
+      any("disable-scroll"),
+    ],
+  },
+  ComponentSpec {
+    name: "web-view",
+    attrs: &[any("src")],
+  },
+];
+
+/// 按名称查找内置组件定义
+pub fn component_spec(name: &str) -> Option<&'static ComponentSpec> {
+  COMPONENT_CATALOG.iter().find(|spec| spec.name == name)
+}
+
+/// 所有组件都支持的属性：通用属性、事件绑定、指令（包括其他方言的指令）、数据集等
+pub fn is_common_attr(name: &str) -> bool {
+  const COMMON: &[&str] = &["id", "class", "style", "hidden", "slot", "animation"];
+  const PREFIXES: &[&str] = &[
+    "wx:",
+    "bind",
+    "catch",
+    "capture-bind:",
+    "capture-catch:",
+    "mut-bind",
+    "data-",
+    "aria-",
+    "generic:",
+    "mark:",
+  ];
+  COMMON.contains(&name)
+    || PREFIXES.iter().any(|prefix| name.starts_with(prefix))
+    || Directive::of(name).is_some()
+}
+
+/// 基础库版本号，如 `2.10.0`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Version {
+  pub major: u32,
+  pub minor: u32,
+  pub patch: u32,
+}
+
+impl Version {
+  pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+    Self {
+      major,
+      minor,
+      patch,
+    }
+  }
+}
+
+impl FromStr for Version {
+  type Err = String;
+
+  /// 解析 `major.minor.patch`，省略的部分为 0，如 `2.10` 即 `2.10.0`
+  fn from_str(text: &str) -> Result<Self, String> {
+    let mut parts = text.trim().split('.');
+    let mut next = |required: bool| match parts.next() {
+      Some(part) => part
+        .parse::<u32>()
+        .map_err(|_| format!("invalid version `{text}`")),
+      None if required => Err(format!("invalid version `{text}`")),
+      None => Ok(0),
+    };
+    let version = Version::new(next(true)?, next(false)?, next(false)?);
+    if parts.next().is_some() {
+      return Err(format!("invalid version `{text}`"));
+    }
+    Ok(version)
+  }
+}
+
+impl fmt::Display for Version {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+  }
+}
+
+/// 需要一定基础库版本才能使用的语法或组件
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feature {
+  /// 内置组件，按标签名匹配
+  Component(&'static str),
+  /// 属性语法，按属性名前缀匹配，如 `mut-bind`
+  AttrPrefix(&'static str),
+}
+
+/// 特性及其最低基础库版本
+#[derive(Debug, Clone, Copy)]
+pub struct VersionRequirement {
+  pub feature: Feature,
+  pub since: Version,
+}
+
+const fn since(feature: Feature, major: u32, minor: u32, patch: u32) -> VersionRequirement {
+  VersionRequirement {
+    feature,
+    since: Version::new(major, minor, patch),
+  }
+}
+
+/// 微信基础库中晚于 1.0.0 引入的常用语法与组件，未收录的视为所有版本可用
+pub const VERSION_REQUIREMENTS: &[VersionRequirement] = &[
+  // 事件绑定
+  since(Feature::AttrPrefix("bind:"), 1, 5, 0),
+  since(Feature::AttrPrefix("catch:"), 1, 5, 0),
+  since(Feature::AttrPrefix("capture-bind:"), 1, 5, 0),
+  since(Feature::AttrPrefix("capture-catch:"), 1, 5, 0),
+  since(Feature::AttrPrefix("mut-bind"), 2, 8, 2),
+  // 抽象节点与简易双向绑定
+  since(Feature::AttrPrefix("generic:"), 1, 9, 6),
+  since(Feature::AttrPrefix("model:"), 2, 9, 3),
+  // 组件
+  since(Feature::Component("movable-area"), 1, 2, 0),
+  since(Feature::Component("movable-view"), 1, 2, 0),
+  since(Feature::Component("cover-view"), 1, 4, 0),
+  since(Feature::Component("cover-image"), 1, 4, 0),
+  since(Feature::Component("rich-text"), 1, 4, 0),
+  since(Feature::Component("open-data"), 1, 4, 0),
+  since(Feature::Component("camera"), 1, 6, 0),
+  since(Feature::Component("web-view"), 1, 6, 4),
+  since(Feature::Component("live-player"), 1, 7, 0),
+  since(Feature::Component("live-pusher"), 1, 7, 0),
+  since(Feature::Component("functional-page-navigator"), 2, 1, 0),
+  since(Feature::Component("official-account"), 2, 3, 0),
+  since(Feature::Component("editor"), 2, 7, 0),
+  since(Feature::Component("page-meta"), 2, 9, 0),
+  since(Feature::Component("navigation-bar"), 2, 9, 0),
+  since(Feature::Component("keyboard-accessory"), 2, 10, 0),
+  since(Feature::Component("match-media"), 2, 11, 1),
+  since(Feature::Component("voip-room"), 2, 11, 0),
+  since(Feature::Component("page-container"), 2, 16, 0),
+  since(Feature::Component("root-portal"), 2, 25, 2),
+];
+
+/// 使用内置组件需要的最低基础库版本
+pub fn component_since(name: &str) -> Option<Version> {
+  VERSION_REQUIREMENTS
+    .iter()
+    .find(|requirement| matches!(requirement.feature, Feature::Component(tag) if tag == name))
+    .map(|requirement| requirement.since)
+}
+
+/// 属性使用的语法及其需要的最低基础库版本，如 `mut-bind:tap` 为 `("mut-bind", 2.8.2)`
+pub fn attr_since(name: &str) -> Option<(&'static str, Version)> {
+  VERSION_REQUIREMENTS
+    .iter()
+    .find_map(|requirement| match requirement.feature {
+      Feature::AttrPrefix(prefix) if name.starts_with(prefix) => Some((prefix, requirement.since)),
+      _ => None,
+    })
+}
@@ -0,0 +1,130 @@
+//! 按内容哈希缓存解析结果
+//!
+//! 开发服务器在文件变化时往往重新解析所有模板。[`ParseCache`] 为每个路径记录源码的哈希与解析结果，
+//! 内容未变化时直接返回上次的结果；结果包装在 `Arc` 中，读取只需复制指针。
+//! 缓存可以在线程之间共享，解析在锁外进行。仅提供 Rust 接口
+
+use std::{
+  collections::HashMap,
+  hash::{DefaultHasher, Hash, Hasher},
+  sync::{Arc, Mutex},
+};
+
+use crate::{
+  batch::{parse_file, ParseResult},
+  options::ParserOptions,
+};
+
+/// 解析结果缓存，所有文件使用相同的解析选项
+#[derive(Debug, Default)]
+pub struct ParseCache {
+  options: ParserOptions,
+  state: Mutex<CacheState>,
+}
+
+#[derive(Debug, Default)]
+struct CacheState {
+  /// 路径 → 源码哈希与解析结果，每个路径只保留最新内容的结果
+  entries: HashMap<String, (u64, Arc<ParseResult>)>,
+  hits: usize,
+  misses: usize,
+}
+
+fn content_hash(source: &str) -> u64 {
+  let mut hasher = DefaultHasher::new();
+  source.hash(&mut hasher);
+  hasher.finish()
+}
+
+impl ParseCache {
+  pub fn new(options: ParserOptions) -> Self {
+    Self {
+      options,
+      state: Mutex::default(),
+    }
+  }
+
+  pub fn options(&self) -> &ParserOptions {
+    &self.options
+  }
+
+  /// 取得 `path` 的解析结果，内容与上次相同时不重新解析
+  pub fn get_or_parse(&self, path: &str, source: &str) -> Arc<ParseResult> {
+    let hash = content_hash(source);
+    {
+      let mut state = self.state.lock().unwrap();
+      if let Some(result) = state
+        .entries
+        .get(path)
+        .filter(|(cached, _)| *cached == hash)
+        .map(|(_, result)| result.clone())
+      {
+        state.hits += 1;
+        return result;
+      }
+    }
+    let result = Arc::new(parse_file(path, source, &self.options));
+    let mut state = self.state.lock().unwrap();
+    state.misses += 1;
+    state
+      .entries
+      .insert(path.to_string(), (hash, result.clone()));
+    result
+  }
+
+  /// 已缓存的解析结果，不检查内容是否变化
+  pub fn get(&self, path: &str) -> Option<Arc<ParseResult>> {
+    let state = self.state.lock().unwrap();
+    state.entries.get(path).map(|(_, result)| result.clone())
+  }
+
+  /// 移除文件的缓存，如文件被删除时
+  pub fn invalidate(&self, path: &str) {
+    self.state.lock().unwrap().entries.remove(path);
+  }
+
+  pub fn clear(&self) {
+    self.state.lock().unwrap().entries.clear();
+  }
+
+  /// 缓存的文件数量
+  pub fn len(&self) -> usize {
+    self.state.lock().unwrap().entries.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// 命中与未命中的次数
+  pub fn hit_stats(&self) -> (usize, usize) {
+    let state = self.state.lock().unwrap();
+    (state.hits, state.misses)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn reuses_unchanged_sources() {
+    let cache = ParseCache::default();
+    let first = cache.get_or_parse("a.wxml", "<view>{{a}}</view>");
+    let second = cache.get_or_parse("a.wxml", "<view>{{a}}</view>");
+    assert!(Arc::ptr_eq(&first, &second));
+
+    let changed = cache.get_or_parse("a.wxml", "<view>{{b}}</view>");
+    assert!(!Arc::ptr_eq(&first, &changed));
+    assert!(Arc::ptr_eq(&cache.get("a.wxml").unwrap(), &changed));
+
+    let other = cache.get_or_parse("b.wxml", "<view>{{b}}</view>");
+    assert!(!Arc::ptr_eq(&other, &changed));
+    assert_eq!(other.path, "b.wxml");
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.hit_stats(), (1, 3));
+
+    cache.invalidate("a.wxml");
+    assert!(cache.get("a.wxml").is_none());
+  }
+}
@@ -0,0 +1,299 @@
+//! `class` 属性拆分
+//!
+//! 按空白将 `class` 属性值拆分为类名，完全静态的类名与包含 `{{}}` 表达式的动态类名分开表示，
+//! 便于检查规则与 WXSS 中的类名比对，以及格式化工具排序、去重。
+//! [`check_class_usage`] 与 WXSS 工具提供的类名比对，找出未定义的类名与未使用的样式
+
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  ast::{AttributeValue, Position, Root},
+  expr::{parse_expression, Expr, ObjectProperty},
+};
+
+/// `class` 属性中以空白分隔的一个类名
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all_fields = "camelCase")]
+pub enum ClassToken {
+  /// 静态类名，如 `btn`
+  Static {
+    name: String,
+    start: Position,
+    end: Position,
+  },
+  /// 包含表达式的类名，如 `{{active ? 'on' : ''}}`、`btn-{{type}}`
+  Dynamic {
+    parts: Vec<AttributeValue>,
+    start: Position,
+    end: Position,
+  },
+}
+
+impl ClassToken {
+  /// 静态类名，动态类名返回 `None`
+  pub fn static_name(&self) -> Option<&str> {
+    match self {
+      ClassToken::Static { name, .. } => Some(name),
+      ClassToken::Dynamic { .. } => None,
+    }
+  }
+
+  pub fn start(&self) -> Position {
+    match self {
+      ClassToken::Static { start, .. } | ClassToken::Dynamic { start, .. } => *start,
+    }
+  }
+
+  pub fn end(&self) -> Position {
+    match self {
+      ClassToken::Static { end, .. } | ClassToken::Dynamic { end, .. } => *end,
+    }
+  }
+}
+
+/// 按空白拆分 `class` 属性值，相连的文本与表达式属于同一个类名
+pub fn parse_class_list(values: &[AttributeValue]) -> Vec<ClassToken> {
+  let mut tokens = Vec::new();
+  let mut parts = Vec::new();
+  for value in values {
+    let (content, start) = match value {
+      AttributeValue::Text { content, start, .. } => (content, *start),
+      AttributeValue::Expression { .. } => {
+        parts.push(value.clone());
+        continue;
+      }
+    };
+    let mut piece = 0;
+    for (index, ch) in content.char_indices() {
+      if ch.is_whitespace() {
+        push_text(
+          &mut parts,
+          &content[piece..index],
+          start.advance(&content[..piece]),
+        );
+        flush(&mut tokens, &mut parts);
+        piece = index + ch.len_utf8();
+      }
+    }
+    push_text(
+      &mut parts,
+      &content[piece..],
+      start.advance(&content[..piece]),
+    );
+  }
+  flush(&mut tokens, &mut parts);
+  tokens
+}
+
+fn push_text(parts: &mut Vec<AttributeValue>, content: &str, start: Position) {
+  if !content.is_empty() {
+    parts.push(AttributeValue::Text {
+      content: content.to_string(),
+      start,
+      end: start.advance(content),
+    });
+  }
+}
+
+fn flush(tokens: &mut Vec<ClassToken>, parts: &mut Vec<AttributeValue>) {
+  let parts = std::mem::take(parts);
+  let (Some(first), Some(last)) = (parts.first(), parts.last()) else {
+    return;
+  };
+  let (start, end) = (first.start(), last.end());
+  let token = match parts.as_slice() {
+    [AttributeValue::Text { content, .. }] => ClassToken::Static {
+      name: content.clone(),
+      start,
+      end,
+    },
+    _ => ClassToken::Dynamic { parts, start, end },
+  };
+  tokens.push(token);
+}
+
+/// 模板中的一处静态类名
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClassReference {
+  pub name: String,
+  pub start: Position,
+  pub end: Position,
+}
+
+/// 模板与 WXSS 中类名的比对结果
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClassUsage {
+  /// 模板中使用但没有定义的静态类名，按出现顺序排列
+  pub undefined: Vec<ClassReference>,
+  /// 已定义但模板中没有使用的类名，按名称排序
+  pub unused: Vec<String>,
+}
+
+/// 将模板中的类名与 `known`（通常由 WXSS 工具提取）比对
+///
+/// 检查 `class` 以及 `hover-class`、`placeholder-class`、`custom-class` 等以 `-class` 结尾的属性。
+/// 只有静态类名会被报告为未定义；判断是否使用时也考虑动态部分：`btn-{{type}}` 视为使用了所有 `btn-` 开头的类名，
+/// 表达式中的字符串字面量（如 `{{active ? 'on' : ''}}` 中的 `on`）视为使用的类名
+pub fn check_class_usage<S: AsRef<str>>(
+  root: &Root,
+  known: impl IntoIterator<Item = S>,
+) -> ClassUsage {
+  let known: BTreeSet<String> = known
+    .into_iter()
+    .map(|name| name.as_ref().to_string())
+    .collect();
+  let mut used = BTreeSet::new();
+  let mut patterns = Vec::new();
+  let mut usage = ClassUsage::default();
+  root.walk(&mut |node| {
+    let attrs = node.attrs().unwrap_or_default();
+    for attr in attrs {
+      if attr.name != "class" && !attr.name.ends_with("-class") {
+        continue;
+      }
+      for token in attr.class_list() {
+        match token {
+          ClassToken::Static { name, start, end } => {
+            if !known.contains(&name) {
+              usage.undefined.push(ClassReference {
+                name: name.clone(),
+                start,
+                end,
+              });
+            }
+            used.insert(name);
+          }
+          ClassToken::Dynamic { parts, .. } => {
+            for part in &parts {
+              if let AttributeValue::Expression { content, .. } = part {
+                if let Ok(expr) = parse_expression(content) {
+                  string_literals(&expr, &mut used);
+                }
+              }
+            }
+            if parts
+              .iter()
+              .any(|part| matches!(part, AttributeValue::Text { .. }))
+            {
+              patterns.push(parts);
+            }
+          }
+        }
+      }
+    }
+  });
+  usage.unused = known
+    .into_iter()
+    .filter(|name| {
+      !used.contains(name) && !patterns.iter().any(|parts| matches_pattern(name, parts))
+    })
+    .collect();
+  usage
+}
+
+/// 类名是否可能由静态文本与表达式拼接得到，表达式可以是任意内容
+fn matches_pattern(name: &str, parts: &[AttributeValue]) -> bool {
+  match parts.split_first() {
+    None => name.is_empty(),
+    Some((AttributeValue::Text { content, .. }, rest)) => name
+      .strip_prefix(content.as_str())
+      .is_some_and(|rest_name| matches_pattern(rest_name, rest)),
+    Some((AttributeValue::Expression { .. }, rest)) => name
+      .char_indices()
+      .map(|(index, _)| index)
+      .chain([name.len()])
+      .any(|index| matches_pattern(&name[index..], rest)),
+  }
+}
+
+/// 收集表达式可能产生的类名，即字符串字面量中以空白分隔的部分
+fn string_literals(expr: &Expr, out: &mut BTreeSet<String>) {
+  match expr {
+    Expr::String(value) => out.extend(value.split_whitespace().map(str::to_string)),
+    Expr::Null | Expr::Undefined | Expr::Bool(_) | Expr::Number(_) | Expr::Ident(_) => {}
+    Expr::Array(items) => items.iter().for_each(|item| string_literals(item, out)),
+    Expr::Object(properties) => {
+      for property in properties {
+        match property {
+          ObjectProperty::Property { value, .. } | ObjectProperty::Spread(value) => {
+            string_literals(value, out)
+          }
+        }
+      }
+    }
+    // 成员名与下标不会成为类名
+    Expr::Member { .. } | Expr::Index { .. } => {}
+    Expr::Call { args, .. } => args.iter().for_each(|arg| string_literals(arg, out)),
+    Expr::Unary { argument, .. } | Expr::Spread(argument) => string_literals(argument, out),
+    Expr::Binary { left, right, .. } => {
+      string_literals(left, out);
+      string_literals(right, out);
+    }
+    Expr::Conditional {
+      consequent,
+      alternate,
+      ..
+    } => {
+      string_literals(consequent, out);
+      string_literals(alternate, out);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{ast::Node, parser::Parser};
+
+  #[test]
+  fn splits_class_list() {
+    let source = r#"<view class=" btn  btn-{{type}} {{active ? 'on' : ''}}
+  large"/>"#;
+    let root = Parser::new(source).parse_root().unwrap();
+    let Node::Element { attrs, .. } = &root.children[0] else {
+      unreachable!()
+    };
+    let tokens = attrs[0].class_list();
+    let names: Vec<_> = tokens.iter().map(|token| token.static_name()).collect();
+    assert_eq!(names, [Some("btn"), None, None, Some("large")]);
+    let spans: Vec<_> = tokens
+      .iter()
+      .map(|token| &source[token.start().offset as usize..token.end().offset as usize])
+      .collect();
+    assert_eq!(
+      spans,
+      ["btn", "btn-{{type}}", "{{active ? 'on' : ''}}", "large"]
+    );
+  }
+
+  #[test]
+  fn checks_class_usage() {
+    let source = r#"<view class="card {{active ? 'on' : ''}}" hover-class="card-hover">
+  <text class="btn btn-{{type}} title"/>
+</view>"#;
+    let root = Parser::new(source).parse_root().unwrap();
+    let usage = check_class_usage(
+      &root,
+      [
+        "card",
+        "card-hover",
+        "on",
+        "off",
+        "btn",
+        "btn-primary",
+        "footer",
+      ],
+    );
+    let undefined: Vec<_> = usage
+      .undefined
+      .iter()
+      .map(|class| (class.name.as_str(), class.start.line))
+      .collect();
+    assert_eq!(undefined, [("title", 2)]);
+    assert_eq!(usage.unused, ["footer", "off"]);
+  }
+}
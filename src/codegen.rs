@@ -0,0 +1,198 @@
+//! 将 AST 重新输出为 WXML 源码
+
+use crate::{
+  ast::{Attribute, AttributeQuote, AttributeValue, Node, Position, Root},
+  helpers::escape_mustache,
+  optimize::{fold_attribute_part, fold_text},
+  options::ParserOptions,
+  sourcemap::{SourceMap, SourceMapBuilder},
+};
+
+/// 属性值的引号输出风格
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum QuoteStyle {
+  /// 保留作者原有的引号，无引号的值会回退为双引号
+  #[default]
+  Preserve,
+  /// 统一使用双引号，值中包含双引号时回退为单引号
+  Double,
+  /// 统一使用单引号，值中包含单引号时回退为双引号
+  Single,
+}
+
+/// 代码生成选项
+#[derive(Debug, Clone, Default)]
+pub struct CodegenOptions {
+  pub quote: QuoteStyle,
+  /// 输出时折叠常量表达式，规则见 [`fold_constants`](crate::optimize::fold_constants)
+  pub fold_constants: bool,
+  /// 源码的解析选项，原始文本标签（见 [`ParserOptions::raw_text_tags`]）的内容按原样输出，不转义 `{{`
+  pub parser: ParserOptions,
+}
+
+/// 将整个文档输出为 WXML 源码
+pub fn generate(root: &Root, options: &CodegenOptions) -> String {
+  let mut codegen = Codegen::new(options);
+  for node in &root.children {
+    codegen.print_node(node);
+  }
+  codegen.finish()
+}
+
+/// 将整个文档输出为 WXML 源码，同时生成输出位置到源码位置的映射
+pub fn generate_with_source_map(root: &Root, options: &CodegenOptions) -> (String, SourceMap) {
+  let mut codegen = Codegen::new(options);
+  codegen.source_map = Some(SourceMapBuilder::default());
+  for node in &root.children {
+    codegen.print_node(node);
+  }
+  let map = codegen.source_map.take().unwrap().build(&codegen.output);
+  (codegen.finish(), map)
+}
+
+/// 将单个节点输出为 WXML 源码
+pub fn generate_node(node: &Node, options: &CodegenOptions) -> String {
+  let mut codegen = Codegen::new(options);
+  codegen.print_node(node);
+  codegen.finish()
+}
+
+/// 将单个属性输出为 WXML 源码，如 `class="a {{b}}"`
+pub fn generate_attribute(attr: &Attribute, options: &CodegenOptions) -> String {
+  let mut codegen = Codegen::new(options);
+  codegen.print_attribute(attr);
+  codegen.finish()
+}
+
+struct Codegen<'a> {
+  options: &'a CodegenOptions,
+  output: String,
+  source_map: Option<SourceMapBuilder>,
+}
+
+impl<'a> Codegen<'a> {
+  fn new(options: &'a CodegenOptions) -> Self {
+    Self {
+      options,
+      output: String::new(),
+      source_map: None,
+    }
+  }
+
+  fn finish(self) -> String {
+    self.output
+  }
+
+  /// 记录当前输出位置对应的源码位置
+  fn mark(&mut self, original: Position) {
+    if let Some(source_map) = &mut self.source_map {
+      source_map.mark(self.output.len(), original);
+    }
+  }
+
+  fn print_node(&mut self, node: &Node) {
+    self.mark(node.start());
+    match node {
+      Node::Element {
+        name,
+        attrs,
+        children,
+        self_closing,
+        ..
+      } => {
+        self.output.push('<');
+        self.output.push_str(name);
+        for attr in attrs {
+          self.output.push(' ');
+          self.print_attribute(attr);
+        }
+        if *self_closing && children.is_empty() {
+          self.output.push_str(" />");
+          return;
+        }
+        self.output.push('>');
+        let raw_text = self.options.parser.is_raw_text_tag(name);
+        for child in children {
+          match child {
+            Node::Text { content, .. } if raw_text => {
+              self.mark(child.start());
+              self.output.push_str(content);
+            }
+            _ => self.print_node(child),
+          }
+        }
+        self.output.push_str("</");
+        self.output.push_str(name);
+        self.output.push('>');
+      }
+      Node::Text { content, .. } => self.output.push_str(&escape_mustache(content)),
+      Node::Comment { content, .. } => {
+        self.output.push_str("<!--");
+        self.output.push_str(content);
+        self.output.push_str("-->");
+      }
+      Node::Expression { content, .. } => {
+        if let Some(text) = self
+          .options
+          .fold_constants
+          .then(|| fold_text(content))
+          .flatten()
+        {
+          self.output.push_str(&text);
+          return;
+        }
+        self.output.push_str("{{");
+        self.output.push_str(content);
+        self.output.push_str("}}");
+      }
+    }
+  }
+
+  fn print_attribute(&mut self, attr: &Attribute) {
+    self.mark(attr.start);
+    self.output.push_str(&attr.name);
+    let Some(values) = &attr.value else {
+      return;
+    };
+    let mut value = String::new();
+    for part in values {
+      match part {
+        AttributeValue::Text { content, .. } => value.push_str(&escape_mustache(content)),
+        AttributeValue::Expression { content, .. } => {
+          let single = values.len() == 1;
+          if let Some(text) = self
+            .options
+            .fold_constants
+            .then(|| fold_attribute_part(content, single))
+            .flatten()
+          {
+            value.push_str(&text);
+            continue;
+          }
+          value.push_str("{{");
+          value.push_str(content);
+          value.push_str("}}");
+        }
+      }
+    }
+    let quote = self.pick_quote(attr.quote, &value);
+    self.output.push('=');
+    self.output.push(quote);
+    self.output.push_str(&value);
+    self.output.push(quote);
+  }
+
+  /// 根据选项和属性值内容选择引号，保证输出仍然可以被正确解析
+  fn pick_quote(&self, original: AttributeQuote, value: &str) -> char {
+    let preferred = match (self.options.quote, original) {
+      (QuoteStyle::Preserve, AttributeQuote::Single) | (QuoteStyle::Single, _) => '\'',
+      _ => '"',
+    };
+    let fallback = if preferred == '"' { '\'' } else { '"' };
+    if value.contains(preferred) && !value.contains(fallback) {
+      fallback
+    } else {
+      preferred
+    }
+  }
+}
@@ -0,0 +1,182 @@
+//! 注释附着
+//!
+//! 解析器默认把注释作为普通的兄弟节点保留在树中。格式化与代码改写工具移动、删除节点时，
+//! 需要让注释跟随它说明的节点一起移动，因此可以把注释从树中移出，附着到相邻的节点上：
+//!
+//! - 与前一个节点的结尾在同一行的注释是该节点的尾随注释，如 `<text/> <!-- 标题 -->`
+//! - 其余注释是后一个节点的前置注释
+//! - 后面没有节点的注释是前一个节点的尾随注释
+//! - 所在层级没有其他节点的注释是父元素的悬空注释，如 `<view><!-- TODO --></view>`
+//!
+//! 只包含空白的文本不作为相邻节点。注释指令（见 [`directive`](crate::lint::directive)）与条件编译标记
+//! （见 [`ifdef`](crate::ifdef)）的作用取决于它们在兄弟节点中的位置，始终作为注释节点保留在树中，不进行附着。设置
+//! [`ParserOptions::attach_comments`](crate::options::ParserOptions::attach_comments) 时解析器自动进行附着
+
+#[cfg(feature = "napi")]
+use napi_derive::napi;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  ast::{Node, Position, Root},
+  ifdef::is_ifdef_marker,
+  lint::directive::CommentDirective,
+};
+
+/// 注释相对于所属节点的位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "napi", napi(string_enum = "lowercase"))]
+#[serde(rename_all = "lowercase")]
+pub enum CommentPlacement {
+  /// 位于节点之前
+  Leading,
+  /// 位于节点之后
+  Trailing,
+  /// 位于元素内部，元素没有其他子节点
+  Dangling,
+}
+
+/// 附着到节点上的注释
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi", napi(object))]
+pub struct AttachedComment {
+  pub content: String,
+  pub placement: CommentPlacement,
+  /// 所属节点的起始位置；文档中只有注释时，悬空注释属于根节点，为 `None`
+  pub owner: Option<Position>,
+  pub start: Position,
+  pub end: Position,
+}
+
+/// 把文档中的注释节点移出，附着到相邻的节点上，结果按文档顺序记录在 [`Root::comments`] 中
+pub fn attach_comments(root: &mut Root) {
+  let mut comments = Vec::new();
+  attach(&mut root.children, None, &mut comments);
+  comments.sort_by_key(|comment| comment.start.offset);
+  root.comments = Some(comments);
+}
+
+/// 附着到 `node` 上的注释，按文档顺序排列；未进行附着时为空
+pub fn comments_of<'a>(
+  root: &'a Root,
+  node: &Node,
+) -> impl Iterator<Item = &'a AttachedComment> + 'a {
+  let start = node.start();
+  root
+    .comments
+    .iter()
+    .flatten()
+    .filter(move |comment| comment.owner == Some(start))
+}
+
+fn attach(children: &mut Vec<Node>, parent: Option<Position>, comments: &mut Vec<AttachedComment>) {
+  for child in children.iter_mut() {
+    let start = child.start();
+    if let Some(children) = child.children_mut() {
+      attach(children, Some(start), comments);
+    }
+  }
+  if !children.iter().any(is_attachable) {
+    return;
+  }
+
+  let nodes = std::mem::take(children);
+  let significant: Vec<usize> = nodes
+    .iter()
+    .enumerate()
+    .filter(|(_, node)| !matches!(node, Node::Comment { .. }) && !node.is_whitespace())
+    .map(|(index, _)| index)
+    .collect();
+  for (index, node) in nodes.iter().enumerate() {
+    let Node::Comment {
+      content,
+      start,
+      end,
+    } = node
+    else {
+      continue;
+    };
+    if !is_attachable(node) {
+      continue;
+    }
+    let split = significant.partition_point(|&sibling| sibling < index);
+    let previous = split.checked_sub(1).map(|i| &nodes[significant[i]]);
+    let next = significant.get(split).map(|&i| &nodes[i]);
+    let (placement, owner) = match (previous, next) {
+      (Some(previous), _) if previous.end().line == start.line => {
+        (CommentPlacement::Trailing, Some(previous.start()))
+      }
+      (_, Some(next)) => (CommentPlacement::Leading, Some(next.start())),
+      (Some(previous), None) => (CommentPlacement::Trailing, Some(previous.start())),
+      (None, None) => (CommentPlacement::Dangling, parent),
+    };
+    comments.push(AttachedComment {
+      content: content.clone(),
+      placement,
+      owner,
+      start: *start,
+      end: *end,
+    });
+  }
+  *children = nodes
+    .into_iter()
+    .filter(|node| !is_attachable(node))
+    .collect();
+}
+
+/// 是否为需要附着的注释，注释指令与条件编译标记除外
+fn is_attachable(node: &Node) -> bool {
+  match node {
+    Node::Comment { content, .. } => {
+      CommentDirective::parse(content).is_none() && !is_ifdef_marker(content)
+    }
+    _ => false,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{options::ParserOptions, parser::Parser};
+
+  #[test]
+  fn attach_to_neighbours() {
+    let source = "<!-- 页面 -->\n<view>\n  <text/> <!-- 标题 -->\n  <!-- 按钮 -->\n  <button/>\n  <!-- 结尾 -->\n</view>\n<view><!-- TODO --></view>";
+    let options = ParserOptions {
+      attach_comments: Some(true),
+      ..Default::default()
+    };
+    let root = Parser::with_options(source, options).parse_root().unwrap();
+    let view = &root.children[0];
+    let children = view.children().unwrap();
+    assert_eq!(children.len(), 2);
+
+    let contents = |node| {
+      comments_of(&root, node)
+        .map(|comment| (comment.content.trim(), comment.placement))
+        .collect::<Vec<_>>()
+    };
+    assert_eq!(contents(view), [("页面", CommentPlacement::Leading)]);
+    assert_eq!(
+      contents(&children[0]),
+      [("标题", CommentPlacement::Trailing)]
+    );
+    assert_eq!(
+      contents(&children[1]),
+      [
+        ("按钮", CommentPlacement::Leading),
+        ("结尾", CommentPlacement::Trailing)
+      ]
+    );
+    assert_eq!(
+      contents(&root.children[1]),
+      [("TODO", CommentPlacement::Dangling)]
+    );
+
+    let root = Parser::new(source).parse_root().unwrap();
+    assert!(root.comments.is_none());
+    assert!(matches!(root.children[0], Node::Comment { .. }));
+  }
+}
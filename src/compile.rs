@@ -0,0 +1,496 @@
+//! 编译为 JavaScript 渲染函数
+//!
+//! 把模板编译为一个独立的 JS 函数源码，在微信环境之外以数据调用即可得到渲染结果，
+//! 便于实验性框架或测试工具直接运行 WXML。语义与 [`render`](crate::render) 一致：
+//!
+//! - 处理 `{{ }}` 插值、`wx:if`/`wx:elif`/`wx:else`、`wx:for` 以及 `<template is>`，`wx:for` 的优先级高于 `wx:if`
+//! - `<block>` 会被展开，注释、`<wxs>`、`<import>`、`<include>` 与模板定义不输出，只含空白的文本被忽略
+//! - `wx:` 开头的属性被去掉；只包含单个表达式的属性值保留表达式的类型，其余拼接为字符串，无值属性为 `true`
+//!
+//! 表达式中的变量从数据中读取，`wx:for` 的循环变量通过以原型链相连的作用域对象提供，
+//! 模板只能访问 `data` 传入的数据。`<wxs>` 模块需要由调用方放入数据中
+
+use std::{error::Error, fmt};
+
+use crate::{
+  ast::{Attribute, AttributeValue, Node, Position, Root, TemplateParts},
+  expr::{parse_expression, parse_object_body, Expr, ObjectProperty, UnaryOp},
+};
+
+/// 渲染函数的输出形式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RenderMode {
+  /// 生成 `function render(data, h)`，通过调用方传入的 `h(tag, attrs, children)` 创建虚拟节点，
+  /// 返回顶层节点的数组，文本节点为字符串
+  #[default]
+  VirtualDom,
+  /// 生成 `function render(data)`，返回拼接后的 HTML 字符串，文本与属性值经过 HTML 转义
+  String,
+}
+
+/// 编译选项
+#[derive(Debug, Clone, Default)]
+pub struct CompileOptions {
+  pub mode: RenderMode,
+}
+
+/// 编译错误
+#[derive(Debug, Clone)]
+pub struct CompileError {
+  pub message: String,
+  /// 出错的节点或属性的位置
+  pub start: Position,
+  pub end: Position,
+}
+
+impl fmt::Display for CompileError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{} at {}", self.message, self.start)
+  }
+}
+
+impl Error for CompileError {}
+
+/// 运行时辅助函数：`_s` 为插值的字符串形式，`_l` 遍历 `wx:for` 的值，`_tpl` 渲染模板
+const RUNTIME: &str = r#"  const _s = (v) => (v == null ? "" : String(v));
+  const _l = (ctx, items, item, index, fn) => {
+    const entries =
+      Array.isArray(items) || typeof items === "string" ? Array.from(items).map((v, i) => [v, i])
+      : typeof items === "number" ? Array.from({ length: items }, (_, i) => [i, i])
+      : items && typeof items === "object" ? Object.keys(items).map((k) => [items[k], k])
+      : [];
+    return entries.flatMap(([v, i]) => fn(Object.assign(Object.create(ctx), { [item]: v, [index]: i })));
+  };
+  const _tpl = (name, data) => {
+    if (!Object.prototype.hasOwnProperty.call(_T, name)) throw new Error(`template \`${name}\` is not defined`);
+    return _T[name](data);
+  };
+"#;
+
+/// 字符串模式下的 `_t` 与 `_h`：文本与属性值经过 HTML 转义
+const STRING_RUNTIME: &str = r#"  const _e = (v) => String(v).replace(/&/g, "&amp;").replace(/</g, "&lt;").replace(/>/g, "&gt;").replace(/"/g, "&quot;");
+  const _t = (v) => _e(_s(v));
+  const _h = (tag, attrs, children) =>
+    "<" + tag + Object.keys(attrs).map((k) => (attrs[k] === true ? " " + k : " " + k + '="' + _e(_s(attrs[k])) + '"')).join("") +
+    ">" + children.join("") + "</" + tag + ">";
+"#;
+
+/// 将整个文档编译为 JS 渲染函数的源码
+pub fn compile(root: &Root, options: &CompileOptions) -> Result<String, CompileError> {
+  // 同名模板以第一个定义为准
+  let mut templates: Vec<(String, &Node)> = Vec::new();
+  root.walk(&mut |node| {
+    if node.name() == Some("template") {
+      if let Some(name) = node.attr("name").and_then(Attribute::as_static_string) {
+        if templates.iter().all(|(existing, _)| *existing != name) {
+          templates.push((name, node));
+        }
+      }
+    }
+  });
+
+  let mut output = String::new();
+  match options.mode {
+    RenderMode::VirtualDom => {
+      output.push_str("function render(_ctx, _h) {\n");
+      output.push_str(RUNTIME);
+      output.push_str("  const _t = _s;\n");
+    }
+    RenderMode::String => {
+      output.push_str("function render(_ctx) {\n");
+      output.push_str(RUNTIME);
+      output.push_str(STRING_RUNTIME);
+    }
+  }
+  output.push_str("  const _T = {\n");
+  for (name, template) in &templates {
+    let children = template.children().unwrap_or_default();
+    output.push_str(&format!(
+      "    {}: (_ctx) => {},\n",
+      js_string(name),
+      compile_children(children)?
+    ));
+  }
+  output.push_str("  };\n");
+  let body = compile_children(&root.children)?;
+  match options.mode {
+    RenderMode::VirtualDom => output.push_str(&format!("  return {body};\n}}\n")),
+    RenderMode::String => output.push_str(&format!("  return {body}.join(\"\");\n}}\n")),
+  }
+  Ok(output)
+}
+
+/// 条件分支：`None` 表示 `wx:else`
+type Branch<'a> = (Option<String>, &'a Node);
+
+/// 编译一系列兄弟节点，结果为渲染出的节点数组的表达式
+fn compile_children(nodes: &[Node]) -> Result<String, CompileError> {
+  let mut items = Vec::new();
+  let mut chain: Vec<Branch> = Vec::new();
+  for node in nodes {
+    match node {
+      Node::Element { .. } => {
+        if node.attr("wx:for").is_some() {
+          flush_chain(&mut chain, &mut items)?;
+          items.push(format!("...{}", compile_for(node)?));
+        } else if let Some(attr) = node.attr("wx:if") {
+          flush_chain(&mut chain, &mut items)?;
+          chain.push((Some(compile_attr_value(attr)?), node));
+        } else if let Some(attr) = node.attr("wx:elif") {
+          chain.push((Some(compile_attr_value(attr)?), node));
+        } else if node.attr("wx:else").is_some() {
+          chain.push((None, node));
+          flush_chain(&mut chain, &mut items)?;
+        } else {
+          flush_chain(&mut chain, &mut items)?;
+          items.extend(compile_node(node)?);
+        }
+      }
+      Node::Text { content, .. } => {
+        if !content.trim().is_empty() {
+          flush_chain(&mut chain, &mut items)?;
+          items.push(format!("_t({})", js_string(content)));
+        }
+      }
+      Node::Expression {
+        content,
+        start,
+        end,
+      } => {
+        flush_chain(&mut chain, &mut items)?;
+        items.push(format!(
+          "_t({})",
+          compile_expression(content, *start, *end)?
+        ));
+      }
+      // 注释不打断条件链
+      Node::Comment { .. } => {}
+    }
+  }
+  flush_chain(&mut chain, &mut items)?;
+  Ok(format!("[{}]", items.join(", ")))
+}
+
+/// 把条件链输出为嵌套的三元表达式
+fn flush_chain(chain: &mut Vec<Branch>, items: &mut Vec<String>) -> Result<(), CompileError> {
+  if chain.is_empty() {
+    return Ok(());
+  }
+  let mut expression = String::from("[]");
+  for (condition, node) in chain.drain(..).rev() {
+    let body = format!("[{}]", compile_node(node)?.join(", "));
+    expression = match condition {
+      Some(condition) => format!("({condition} ? {body} : {expression})"),
+      None => body,
+    };
+  }
+  items.push(format!("...{expression}"));
+  Ok(())
+}
+
+/// 编译 `wx:for` 元素，结果为所有迭代渲染出的节点数组的表达式
+fn compile_for(node: &Node) -> Result<String, CompileError> {
+  let items = compile_attr_value(node.attr("wx:for").unwrap())?;
+  let item = static_attr(node, "wx:for-item").unwrap_or_else(|| "item".to_string());
+  let index = static_attr(node, "wx:for-index").unwrap_or_else(|| "index".to_string());
+  let mut body = format!("[{}]", compile_node(node)?.join(", "));
+  if let Some(attr) = node.attr("wx:if") {
+    body = format!("({} ? {body} : [])", compile_attr_value(attr)?);
+  }
+  Ok(format!(
+    "_l(_ctx, {items}, {}, {}, (_ctx) => {body})",
+    js_string(&item),
+    js_string(&index)
+  ))
+}
+
+/// 编译不考虑条件与循环的元素，结果为数组元素的表达式
+fn compile_node(node: &Node) -> Result<Vec<String>, CompileError> {
+  let Node::Element {
+    name,
+    attrs,
+    children,
+    ..
+  } = node
+  else {
+    return Ok(Vec::new());
+  };
+  match name.as_str() {
+    "block" => Ok(vec![format!("...{}", compile_children(children)?)]),
+    "template" => match node.attr("is") {
+      Some(is) => Ok(vec![format!(
+        "..._tpl({}, {})",
+        compile_attr_value(is)?,
+        compile_template_data(node)?
+      )]),
+      None => Ok(Vec::new()),
+    },
+    "import" | "include" | "wxs" => Ok(Vec::new()),
+    _ => {
+      let mut properties = Vec::new();
+      for attr in attrs {
+        if attr.prefix.as_deref() == Some("wx") {
+          continue;
+        }
+        properties.push(format!(
+          "{}: {}",
+          js_string(&attr.name),
+          compile_attr_value(attr)?
+        ));
+      }
+      Ok(vec![format!(
+        "_h({}, {{{}}}, {})",
+        js_string(name),
+        properties.join(", "),
+        compile_children(children)?
+      )])
+    }
+  }
+}
+
+/// `<template is>` 的 `data`，内容是省略了花括号的对象字面量
+fn compile_template_data(node: &Node) -> Result<String, CompileError> {
+  let Some(
+    [AttributeValue::Expression {
+      content,
+      start,
+      end,
+    }],
+  ) = node.attr("data").and_then(|attr| attr.value.as_deref())
+  else {
+    return Ok("{}".to_string());
+  };
+  let properties = parse_object_body(content).map_err(|error| CompileError {
+    message: format!("invalid template data `{content}`: {}", error.message),
+    start: *start,
+    end: *end,
+  })?;
+  let mut output = String::new();
+  print_expr(&Expr::Object(properties), &mut output);
+  Ok(output)
+}
+
+/// 属性值：无值属性为 `true`，静态文本为字符串，单个表达式保留类型，其余拼接为模板字符串
+fn compile_attr_value(attr: &Attribute) -> Result<String, CompileError> {
+  let Some(values) = &attr.value else {
+    return Ok("true".to_string());
+  };
+  if let [AttributeValue::Expression {
+    content,
+    start,
+    end,
+  }] = values.as_slice()
+  {
+    return compile_expression(content, *start, *end);
+  }
+  let parts = attr.template_parts().unwrap();
+  if parts.is_static() {
+    return Ok(js_string(&parts.quasis[0]));
+  }
+  let mut expressions = Vec::new();
+  for value in values {
+    if let AttributeValue::Expression {
+      content,
+      start,
+      end,
+    } = value
+    {
+      expressions.push(format!(
+        "_s({})",
+        compile_expression(content, *start, *end)?
+      ));
+    }
+  }
+  Ok(
+    TemplateParts {
+      quasis: parts.quasis,
+      expressions,
+    }
+    .to_template_string(),
+  )
+}
+
+fn compile_expression(
+  content: &str,
+  start: Position,
+  end: Position,
+) -> Result<String, CompileError> {
+  let expr = parse_expression(content).map_err(|error| CompileError {
+    message: format!("invalid expression `{content}`: {}", error.message),
+    start,
+    end,
+  })?;
+  let mut output = String::new();
+  print_expr(&expr, &mut output);
+  Ok(output)
+}
+
+/// 输出表达式的 JS 源码，变量改为从作用域对象 `_ctx` 读取；复合表达式均加括号，不必考虑优先级
+fn print_expr(expr: &Expr, out: &mut String) {
+  match expr {
+    Expr::Null => out.push_str("null"),
+    Expr::Undefined => out.push_str("undefined"),
+    Expr::Bool(value) => out.push_str(if *value { "true" } else { "false" }),
+    // 超出范围的字面量（如 `1e400`）解析为无穷大，Rust 输出为 `inf`
+    Expr::Number(value) if value.is_infinite() => out.push_str("Infinity"),
+    Expr::Number(value) => out.push_str(&value.to_string()),
+    Expr::String(value) => out.push_str(&js_string(value)),
+    Expr::Ident(name) => {
+      out.push_str("_ctx.");
+      out.push_str(name);
+    }
+    Expr::Array(items) => {
+      out.push('[');
+      print_list(items, out);
+      out.push(']');
+    }
+    Expr::Object(properties) => {
+      out.push('{');
+      for (index, property) in properties.iter().enumerate() {
+        if index > 0 {
+          out.push_str(", ");
+        }
+        match property {
+          ObjectProperty::Property { key, value } => {
+            out.push_str(&js_string(key));
+            out.push_str(": ");
+            print_expr(value, out);
+          }
+          ObjectProperty::Spread(value) => {
+            out.push_str("...");
+            print_expr(value, out);
+          }
+        }
+      }
+      out.push('}');
+    }
+    Expr::Member { object, property } => {
+      print_object(object, out);
+      out.push('.');
+      out.push_str(property);
+    }
+    Expr::Index { object, index } => {
+      print_object(object, out);
+      out.push('[');
+      print_expr(index, out);
+      out.push(']');
+    }
+    Expr::Call { callee, args } => {
+      print_object(callee, out);
+      out.push('(');
+      print_list(args, out);
+      out.push(')');
+    }
+    Expr::Unary { op, argument } => {
+      out.push('(');
+      out.push_str(op.as_str());
+      if *op == UnaryOp::Typeof {
+        out.push(' ');
+      }
+      print_expr(argument, out);
+      out.push(')');
+    }
+    Expr::Binary { op, left, right } => {
+      out.push('(');
+      print_expr(left, out);
+      out.push(' ');
+      out.push_str(op.as_str());
+      out.push(' ');
+      print_expr(right, out);
+      out.push(')');
+    }
+    Expr::Conditional {
+      test,
+      consequent,
+      alternate,
+    } => {
+      out.push('(');
+      print_expr(test, out);
+      out.push_str(" ? ");
+      print_expr(consequent, out);
+      out.push_str(" : ");
+      print_expr(alternate, out);
+      out.push(')');
+    }
+    Expr::Spread(argument) => {
+      out.push_str("...");
+      print_expr(argument, out);
+    }
+  }
+}
+
+/// 成员访问与调用的对象，数字字面量需要加括号，如 `(1).toFixed(2)`
+fn print_object(object: &Expr, out: &mut String) {
+  if let Expr::Number(_) = object {
+    out.push('(');
+    print_expr(object, out);
+    out.push(')');
+  } else {
+    print_expr(object, out);
+  }
+}
+
+fn print_list(items: &[Expr], out: &mut String) {
+  for (index, item) in items.iter().enumerate() {
+    if index > 0 {
+      out.push_str(", ");
+    }
+    print_expr(item, out);
+  }
+}
+
+fn js_string(value: &str) -> String {
+  serde_json::to_string(value).unwrap()
+}
+
+fn static_attr(node: &Node, name: &str) -> Option<String> {
+  node.attr(name).and_then(Attribute::as_static_string)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::parser::Parser;
+
+  #[test]
+  fn compile_render_function() {
+    let source = r#"<template name="row"><text>{{index}}:{{name}}</text></template>
+<view class="list {{cls}}" hidden>
+  <block wx:for="{{users}}" wx:for-item="user" wx:key="id">
+    <template wx:if="{{user.active}}" is="row" data="{{...user, index}}"/>
+    <text wx:elif="{{user.age > 60}}">senior</text>
+    <text wx:else>-</text>
+  </block>
+</view>"#;
+    let root = Parser::new(source).parse_root().unwrap();
+    let output = compile(&root, &CompileOptions::default()).unwrap();
+    assert!(output.starts_with("function render(_ctx, _h) {\n"));
+    assert!(output.contains(
+      r#"    "row": (_ctx) => [_h("text", {}, [_t(_ctx.index), _t(":"), _t(_ctx.name)])],"#
+    ));
+    assert!(output.ends_with(
+      r#"  return [_h("view", {"class": `list ${_s(_ctx.cls)}`, "hidden": true}, [..._l(_ctx, _ctx.users, "user", "index", (_ctx) => [...[...(_ctx.user.active ? [..._tpl("row", {..._ctx.user, "index": _ctx.index})] : ((_ctx.user.age > 60) ? [_h("text", {}, [_t("senior")])] : [_h("text", {}, [_t("-")])]))]])])];
+}
+"#
+    ));
+
+    let options = CompileOptions {
+      mode: RenderMode::String,
+    };
+    let output = compile(&root, &options).unwrap();
+    assert!(output.starts_with("function render(_ctx) {\n"));
+    assert!(output.contains("].join(\"\");\n"));
+
+    let root = Parser::new("<text>{{ a + }}</text>").parse_root().unwrap();
+    let error = compile(&root, &CompileOptions::default()).unwrap_err();
+    assert_eq!(error.start.offset, 6);
+  }
+
+  #[test]
+  fn number_literals() {
+    let compile = |content| compile_expression(content, Position::SYNTHETIC, Position::SYNTHETIC);
+    assert_eq!(compile("1.5 + 2e3").unwrap(), "(1.5 + 2000)");
+    assert_eq!(compile("1e400").unwrap(), "Infinity");
+    assert_eq!(compile("-1e400").unwrap(), "(-Infinity)");
+  }
+}
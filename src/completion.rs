@@ -0,0 +1,307 @@
+//! 编辑器补全上下文
+//!
+//! 编辑器中的模板在输入过程中几乎总是不完整的，这里只扫描光标之前的内容：按顺序跳过已完成的标签、
+//! 注释与插值并记录未闭合的元素，最后一个未完成的结构就是光标所在的位置。
+//! 语言服务据此决定提供标签名、属性名、属性值还是表达式的补全，无需自行实现解析的启发式规则
+
+#[cfg(feature = "napi")]
+use napi_derive::napi;
+use serde::{Deserialize, Serialize};
+
+use crate::helpers::{is_attr_name_char, is_tag_name_char, starts_markup};
+
+/// 光标处正在输入的内容
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all_fields = "camelCase")]
+#[cfg_attr(feature = "napi", napi)]
+pub enum CompletionKind {
+  /// 开始标签的名称，如 `<vi|`
+  TagName,
+  /// 结束标签的名称，如 `</vi|`，通常补全为 [`CompletionContext::parent`]
+  CloseTag,
+  /// 属性名，如 `<view cl|`；`existing` 为光标之前已有的属性
+  AttributeName {
+    element: String,
+    existing: Vec<String>,
+  },
+  /// 属性值中的静态文本，如 `<view class="a b|`
+  AttributeValue { element: String, attribute: String },
+  /// `{{ }}` 中的表达式，位于属性值中时带有所在的元素与属性
+  Expression {
+    element: Option<String>,
+    attribute: Option<String>,
+  },
+  /// 元素之间的文本，包括 `<wxs>` 等原始文本标签的内容
+  Text,
+  /// 注释
+  Comment,
+}
+
+/// 补全上下文
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi", napi(object))]
+pub struct CompletionContext {
+  pub kind: CompletionKind,
+  /// 光标所在的最内层未闭合元素；正在输入的标签本身不计算在内
+  pub parent: Option<String>,
+  /// 光标之前已经输入的部分，如标签名 `<vi|` 中的 `vi`；文本与注释为空
+  pub prefix: String,
+  /// `prefix` 的起始字节偏移，补全时替换 `start..offset` 的范围
+  pub start: u32,
+}
+
+/// 分析 `offset`（字节偏移）处的补全上下文，超出源码长度时按源码末尾处理
+pub fn completion_context(source: &str, offset: u32) -> CompletionContext {
+  let mut end = (offset as usize).min(source.len());
+  while !source.is_char_boundary(end) {
+    end -= 1;
+  }
+  let prefix = &source[..end];
+  let mut stack: Vec<&str> = Vec::new();
+  let context = |kind, stack: &[&str], typed: &str, start: usize| CompletionContext {
+    kind,
+    parent: stack.last().map(|name| name.to_string()),
+    prefix: typed.to_string(),
+    start: start as u32,
+  };
+
+  let mut index = 0;
+  while index < prefix.len() {
+    let rest = &prefix[index..];
+    if let Some(comment) = rest.strip_prefix("<!--") {
+      match comment.find("-->") {
+        Some(close) => index += 4 + close + 3,
+        None => return context(CompletionKind::Comment, &stack, "", end),
+      }
+      continue;
+    }
+    if let Some(expression) = rest.strip_prefix("{{") {
+      match expression.find("}}") {
+        Some(close) => index += 2 + close + 2,
+        None => {
+          let kind = CompletionKind::Expression {
+            element: None,
+            attribute: None,
+          };
+          return context(kind, &stack, expression, index + 2);
+        }
+      }
+      continue;
+    }
+    if rest == "<" || (rest.starts_with('<') && starts_markup(rest)) {
+      let Some(len) = tag_end(rest) else {
+        let (kind, typed, start) = in_tag(rest);
+        return context(kind, &stack, typed, index + start);
+      };
+      index += len;
+      if let Some(name) = apply_tag(&rest[..len], &mut stack) {
+        // 原始文本标签的内容不作为标记扫描
+        if name == "wxs" {
+          match prefix[index..].find("</wxs") {
+            Some(close) => index += close,
+            None => return context(CompletionKind::Text, &stack, "", end),
+          }
+        }
+      }
+      continue;
+    }
+    index += rest.chars().next().map_or(1, char::len_utf8);
+  }
+  context(CompletionKind::Text, &stack, "", end)
+}
+
+/// 找到标签结尾 `>` 之后的位置，跳过引号中的内容；标签不完整时返回 `None`
+fn tag_end(tag: &str) -> Option<usize> {
+  let mut quote = None;
+  for (index, ch) in tag.char_indices().skip(1) {
+    match quote {
+      Some(q) if ch == q => quote = None,
+      Some(_) => {}
+      None if ch == '"' || ch == '\'' => quote = Some(ch),
+      None if ch == '>' => return Some(index + 1),
+      None => {}
+    }
+  }
+  None
+}
+
+/// 根据完整的标签更新未闭合元素的栈，返回新打开的元素
+fn apply_tag<'s>(tag: &'s str, stack: &mut Vec<&'s str>) -> Option<&'s str> {
+  if let Some(close) = tag.strip_prefix("</") {
+    let name = take_while(close, is_tag_name_char);
+    if let Some(position) = stack.iter().rposition(|open| *open == name) {
+      stack.truncate(position);
+    }
+    return None;
+  }
+  let name = take_while(&tag[1..], is_tag_name_char);
+  let self_closing = tag[..tag.len() - 1].trim_end().ends_with('/');
+  if name.is_empty() || self_closing {
+    return None;
+  }
+  stack.push(name);
+  Some(name)
+}
+
+/// 分析未完成的标签 `tag`（从 `<` 到光标），返回种类、已输入的部分及其相对 `tag` 的起始位置
+fn in_tag(tag: &str) -> (CompletionKind, &str, usize) {
+  if let Some(close) = tag.strip_prefix("</") {
+    return (
+      CompletionKind::CloseTag,
+      take_while(close, is_tag_name_char),
+      2,
+    );
+  }
+  let name = take_while(&tag[1..], is_tag_name_char);
+  let mut index = 1 + name.len();
+  if index == tag.len() {
+    return (CompletionKind::TagName, name, 1);
+  }
+  let element = name.to_string();
+  let mut existing = Vec::new();
+  loop {
+    index += tag[index..].len()
+      - tag[index..]
+        .trim_start_matches(|c: char| c.is_whitespace() || c == '/')
+        .len();
+    let attr = take_while(&tag[index..], is_attr_name_char);
+    if index + attr.len() == tag.len() {
+      let kind = CompletionKind::AttributeName { element, existing };
+      return (kind, attr, index);
+    }
+    index += attr.len();
+    if attr.is_empty() {
+      // 无法识别的字符，跳过以免停在原地
+      index += tag[index..].chars().next().map_or(1, char::len_utf8);
+      continue;
+    }
+    existing.push(attr.to_string());
+    let after_name = tag[index..].trim_start();
+    if !after_name.starts_with('=') {
+      continue;
+    }
+    index = tag.len() - after_name.len() + 1;
+    index += tag[index..].len() - tag[index..].trim_start().len();
+    let attribute = attr.to_string();
+    let value = &tag[index..];
+    match value.chars().next() {
+      Some(quote @ ('"' | '\'')) => match value[1..].find(quote) {
+        Some(close) => index += close + 2,
+        None => {
+          let content = &value[1..];
+          // 光标位于尚未闭合的 `{{` 中
+          if let Some(open) = content
+            .rfind("{{")
+            .filter(|open| !content[*open..].contains("}}"))
+          {
+            let kind = CompletionKind::Expression {
+              element: Some(element),
+              attribute: Some(attribute),
+            };
+            return (kind, &content[open + 2..], index + 1 + open + 2);
+          }
+          let kind = CompletionKind::AttributeValue { element, attribute };
+          return (kind, content, index + 1);
+        }
+      },
+      _ => {
+        let unquoted = take_while(value, |c| !c.is_whitespace());
+        if unquoted.len() == value.len() {
+          let kind = CompletionKind::AttributeValue { element, attribute };
+          return (kind, unquoted, index);
+        }
+        index += unquoted.len();
+      }
+    }
+  }
+}
+
+fn take_while(source: &str, predicate: impl Fn(char) -> bool) -> &str {
+  let end = source
+    .char_indices()
+    .find(|(_, c)| !predicate(*c))
+    .map_or(source.len(), |(index, _)| index);
+  &source[..end]
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// `|` 标记光标位置
+  fn at(source: &str) -> CompletionContext {
+    let offset = source.find('|').unwrap();
+    completion_context(&source.replace('|', ""), offset as u32)
+  }
+
+  #[test]
+  fn completion_contexts() {
+    let context = at("<view><text>hi</text><vi|");
+    assert_eq!(context.kind, CompletionKind::TagName);
+    assert_eq!(
+      (
+        context.parent.as_deref(),
+        context.prefix.as_str(),
+        context.start
+      ),
+      (Some("view"), "vi", 22)
+    );
+
+    let context = at("<view><image/></|");
+    assert_eq!(context.kind, CompletionKind::CloseTag);
+    assert_eq!(context.parent.as_deref(), Some("view"));
+
+    let context = at(r#"<view><button type="primary" dis|"#);
+    assert_eq!(
+      context.kind,
+      CompletionKind::AttributeName {
+        element: "button".to_string(),
+        existing: vec!["type".to_string()],
+      }
+    );
+    assert_eq!(context.prefix, "dis");
+
+    let context = at(r#"<view class="a > b" hidden data-x=1 id="x|"#);
+    assert_eq!(
+      context.kind,
+      CompletionKind::AttributeValue {
+        element: "view".to_string(),
+        attribute: "id".to_string(),
+      }
+    );
+    assert_eq!((context.prefix.as_str(), context.start), ("x", 40));
+
+    let context = at(r#"<view class="a {{ user.na|"#);
+    assert_eq!(
+      context.kind,
+      CompletionKind::Expression {
+        element: Some("view".to_string()),
+        attribute: Some("class".to_string()),
+      }
+    );
+    assert_eq!(context.prefix, " user.na");
+
+    let context = at("<view>{{ a }} <!-- x --> {{ b|");
+    assert_eq!(
+      context.kind,
+      CompletionKind::Expression {
+        element: None,
+        attribute: None,
+      }
+    );
+    assert_eq!(
+      (context.prefix.as_str(), context.parent.as_deref()),
+      (" b", Some("view"))
+    );
+
+    assert_eq!(at("<view><!-- <te|").kind, CompletionKind::Comment);
+    let context = at("<wxs module=\"m\">var a = '<vi|");
+    assert_eq!(
+      (context.kind, context.parent.as_deref()),
+      (CompletionKind::Text, Some("wxs"))
+    );
+    let context = at("<view></view> a < b |");
+    assert_eq!((context.kind, context.parent), (CompletionKind::Text, None));
+  }
+}
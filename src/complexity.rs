@@ -0,0 +1,255 @@
+//! 模板复杂度分析
+//!
+//! 统计嵌套深度、`wx:for`（及其他方言的对应指令）循环数量、单个元素上的表达式数量以及内联 `<wxs>` 的体积，
+//! 超出阈值时给出可维护性警告，用于在代码评审或 CI 中发现需要拆分为组件的模板
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  ast::{AttributeValue, Node, Position, Root},
+  dialect::Directive,
+};
+
+/// 复杂度阈值，超出时产生 [`ComplexityWarning`]，设置为 `None` 表示不检查
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ComplexityThresholds {
+  /// 元素的最大嵌套层数，默认为 12
+  pub max_depth: Option<u32>,
+  /// 整个模板中 `wx:for` 的最大数量，默认为 10
+  pub max_for_loops: Option<u32>,
+  /// 单个元素上的最大表达式数量，包括属性值与直接子节点中的 `{{ }}`，默认为 8
+  pub max_expressions_per_node: Option<u32>,
+  /// 单个内联 `<wxs>` 模块的最大长度（字节），默认为 2048
+  pub max_inline_wxs_size: Option<u32>,
+}
+
+impl Default for ComplexityThresholds {
+  fn default() -> Self {
+    Self {
+      max_depth: Some(12),
+      max_for_loops: Some(10),
+      max_expressions_per_node: Some(8),
+      max_inline_wxs_size: Some(2048),
+    }
+  }
+}
+
+/// 复杂度指标
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ComplexityMetric {
+  Depth,
+  ForLoops,
+  ExpressionsPerNode,
+  InlineWxsSize,
+}
+
+/// 超出阈值的复杂度指标
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComplexityWarning {
+  pub metric: ComplexityMetric,
+  /// 实际值
+  pub value: u32,
+  /// 阈值
+  pub threshold: u32,
+  pub message: String,
+  /// 超出阈值的元素：嵌套过深时为首个超出层数的元素，循环过多时为超出数量的那个 `wx:for` 所在元素
+  pub start: Position,
+  pub end: Position,
+}
+
+/// 模板的复杂度指标与警告
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComplexityReport {
+  /// 元素的最大嵌套层数，顶层元素为 1
+  pub max_depth: u32,
+  /// `wx:for` 的数量
+  pub for_loops: u32,
+  /// 单个元素上表达式数量的最大值
+  pub max_expressions_per_node: u32,
+  /// 所有内联 `<wxs>` 模块的总长度（字节），不包括通过 `src` 引用的文件
+  pub inline_wxs_size: u32,
+  /// 按出现顺序排列的警告
+  pub warnings: Vec<ComplexityWarning>,
+}
+
+/// 分析模板的复杂度，按 `thresholds` 给出警告
+pub fn analyze_complexity(root: &Root, thresholds: &ComplexityThresholds) -> ComplexityReport {
+  let mut analyzer = Analyzer {
+    thresholds,
+    report: ComplexityReport::default(),
+  };
+  for child in &root.children {
+    analyzer.visit(child, 1);
+  }
+  analyzer.report
+}
+
+struct Analyzer<'a> {
+  thresholds: &'a ComplexityThresholds,
+  report: ComplexityReport,
+}
+
+impl Analyzer<'_> {
+  fn visit(&mut self, node: &Node, depth: u32) {
+    let Node::Element {
+      name,
+      attrs,
+      children,
+      ..
+    } = node
+    else {
+      return;
+    };
+    self.report.max_depth = self.report.max_depth.max(depth);
+    if self
+      .thresholds
+      .max_depth
+      .is_some_and(|max| depth == max + 1)
+    {
+      self.warn(
+        ComplexityMetric::Depth,
+        depth,
+        self.thresholds.max_depth,
+        format!("`<{name}>` is nested {depth} levels deep"),
+        node,
+      );
+    }
+
+    if node.directive(Directive::For).is_some() {
+      self.report.for_loops += 1;
+      let count = self.report.for_loops;
+      if self
+        .thresholds
+        .max_for_loops
+        .is_some_and(|max| count == max + 1)
+      {
+        self.warn(
+          ComplexityMetric::ForLoops,
+          count,
+          self.thresholds.max_for_loops,
+          format!("template contains more than {} `wx:for` loops", count - 1),
+          node,
+        );
+      }
+    }
+
+    let expressions = attrs
+      .iter()
+      .flat_map(|attr| attr.value.iter().flatten())
+      .filter(|value| matches!(value, AttributeValue::Expression { .. }))
+      .count()
+      + children
+        .iter()
+        .filter(|child| matches!(child, Node::Expression { .. }))
+        .count();
+    let expressions = expressions as u32;
+    self.report.max_expressions_per_node = self.report.max_expressions_per_node.max(expressions);
+    if self
+      .thresholds
+      .max_expressions_per_node
+      .is_some_and(|max| expressions > max)
+    {
+      self.warn(
+        ComplexityMetric::ExpressionsPerNode,
+        expressions,
+        self.thresholds.max_expressions_per_node,
+        format!("`<{name}>` contains {expressions} expressions"),
+        node,
+      );
+    }
+
+    if name == "wxs" && node.attr("src").is_none() {
+      let size: u32 = children
+        .iter()
+        .map(|child| match child {
+          Node::Text { content, .. } => content.len() as u32,
+          _ => 0,
+        })
+        .sum();
+      self.report.inline_wxs_size += size;
+      if self
+        .thresholds
+        .max_inline_wxs_size
+        .is_some_and(|max| size > max)
+      {
+        self.warn(
+          ComplexityMetric::InlineWxsSize,
+          size,
+          self.thresholds.max_inline_wxs_size,
+          format!("inline `<wxs>` module is {size} bytes, consider moving it to a .wxs file"),
+          node,
+        );
+      }
+    }
+
+    for child in children {
+      self.visit(child, depth + 1);
+    }
+  }
+
+  fn warn(
+    &mut self,
+    metric: ComplexityMetric,
+    value: u32,
+    threshold: Option<u32>,
+    message: String,
+    node: &Node,
+  ) {
+    self.report.warnings.push(ComplexityWarning {
+      metric,
+      value,
+      threshold: threshold.unwrap_or_default(),
+      message,
+      start: node.start(),
+      end: node.end(),
+    });
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::parser::Parser;
+
+  #[test]
+  fn reports_complexity() {
+    let root = Parser::new(
+      r#"<view><view><view wx:for="{{a}}" class="{{b}} {{c}}">{{d}}</view></view></view>
+<view wx:for="{{e}}"/><view wx:for="{{f}}"/>
+<wxs module="m">var x = 1;</wxs><wxs src="./m.wxs" module="n"/>"#,
+    )
+    .parse_root()
+    .unwrap();
+    let report = analyze_complexity(&root, &ComplexityThresholds::default());
+    assert_eq!(report.max_depth, 3);
+    assert_eq!(report.for_loops, 3);
+    assert_eq!(report.max_expressions_per_node, 4);
+    assert_eq!(report.inline_wxs_size, 10);
+    assert!(report.warnings.is_empty());
+
+    let thresholds = ComplexityThresholds {
+      max_depth: Some(1),
+      max_for_loops: Some(1),
+      max_expressions_per_node: Some(3),
+      max_inline_wxs_size: None,
+    };
+    let report = analyze_complexity(&root, &thresholds);
+    let warnings: Vec<_> = report
+      .warnings
+      .iter()
+      .map(|warning| (warning.metric, warning.value, warning.start.line))
+      .collect();
+    assert_eq!(
+      warnings,
+      [
+        (ComplexityMetric::Depth, 2, 1),
+        (ComplexityMetric::ExpressionsPerNode, 4, 1),
+        (ComplexityMetric::ForLoops, 2, 2),
+      ]
+    );
+  }
+}
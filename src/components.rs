@@ -0,0 +1,241 @@
+//! 自定义组件使用情况
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+  ast::{GenericBinding, Node, Position, Root},
+  builtins::is_builtin_tag,
+};
+
+/// 自定义组件的一次使用
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentOccurrence {
+  pub start: Position,
+  pub end: Position,
+  /// 使用时书写的属性名称
+  pub attrs: Vec<String>,
+  /// `generic:*` 属性为抽象节点指定的实现
+  pub generics: Vec<GenericBinding>,
+}
+
+/// 某个自定义组件在模板中的全部使用
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentUsage {
+  /// 标签名，即 `usingComponents` 中的键
+  pub name: String,
+  /// 按出现顺序排列
+  pub occurrences: Vec<ComponentOccurrence>,
+}
+
+impl ComponentUsage {
+  /// 所有使用中出现过的属性名称，去重并排序
+  pub fn attr_names(&self) -> Vec<&str> {
+    let mut names: Vec<&str> = self
+      .occurrences
+      .iter()
+      .flat_map(|occurrence| occurrence.attrs.iter().map(String::as_str))
+      .collect();
+    names.sort_unstable();
+    names.dedup();
+    names
+  }
+}
+
+/// 收集模板中所有非内置标签的使用情况，结果按标签名排序
+pub fn collect_component_usages(root: &Root) -> Vec<ComponentUsage> {
+  let mut usages: BTreeMap<&str, Vec<ComponentOccurrence>> = BTreeMap::new();
+  root.walk(&mut |node| {
+    if let Node::Element {
+      name,
+      attrs,
+      start,
+      end,
+      ..
+    } = node
+    {
+      if !is_builtin_tag(name) {
+        usages.entry(name).or_default().push(ComponentOccurrence {
+          start: *start,
+          end: *end,
+          attrs: attrs.iter().map(|attr| attr.name.clone()).collect(),
+          generics: attrs
+            .iter()
+            .filter_map(|attr| attr.as_generic_binding())
+            .collect(),
+        });
+      }
+    }
+  });
+  usages
+    .into_iter()
+    .map(|(name, occurrences)| ComponentUsage {
+      name: name.to_string(),
+      occurrences,
+    })
+    .collect()
+}
+
+/// `usingComponents` 检查的问题类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ComponentIssueKind {
+  /// 使用了没有在 `usingComponents` 中声明的组件
+  Undeclared,
+  /// 声明了但模板中没有使用的组件
+  Unused,
+}
+
+/// 一处组件声明与使用不一致的问题
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentIssue {
+  pub kind: ComponentIssueKind,
+  /// 组件的标签名
+  pub name: String,
+  pub message: String,
+  /// 使用的位置：标签或 `generic:*` 属性；未使用的组件没有位置
+  pub start: Option<Position>,
+  pub end: Option<Position>,
+}
+
+/// 对照页面或组件的 JSON 配置检查模板中的自定义组件
+///
+/// 标签与 `generic:*` 指定的实现都需要在 `usingComponents` 中声明，`componentGenerics` 中的抽象节点可以直接使用。
+/// 全局组件声明在 `app.json` 中，需要调用方合并到 `config` 的 `usingComponents` 后再检查。
+/// 未声明的问题按出现顺序排列，每处使用一条；未使用的问题按名称排列在最后
+pub fn check_using_components(root: &Root, config: &Value) -> Vec<ComponentIssue> {
+  let keys = |field: &str| -> BTreeSet<&str> {
+    config
+      .get(field)
+      .and_then(Value::as_object)
+      .map(|object| object.keys().map(String::as_str).collect())
+      .unwrap_or_default()
+  };
+  let declared = keys("usingComponents");
+  let abstract_nodes = keys("componentGenerics");
+  let mut used = BTreeSet::new();
+  let mut issues = Vec::new();
+  let mut check = |name: &str, start: Position, end: Position| {
+    if declared.contains(name) {
+      used.insert(name.to_string());
+    } else if !abstract_nodes.contains(name) {
+      issues.push(ComponentIssue {
+        kind: ComponentIssueKind::Undeclared,
+        name: name.to_string(),
+        message: format!("component `{name}` is not declared in usingComponents"),
+        start: Some(start),
+        end: Some(end),
+      });
+    }
+  };
+  for usage in collect_component_usages(root) {
+    for occurrence in &usage.occurrences {
+      check(&usage.name, occurrence.start, occurrence.end);
+      for generic in &occurrence.generics {
+        if let Some(component) = &generic.component {
+          check(component, generic.start, generic.end);
+        }
+      }
+    }
+  }
+  issues.sort_by_key(|issue| issue.start.map(|start| start.offset));
+  for name in declared {
+    if !used.contains(name) {
+      issues.push(ComponentIssue {
+        kind: ComponentIssueKind::Unused,
+        name: name.to_string(),
+        message: format!("component `{name}` is declared in usingComponents but never used"),
+        start: None,
+        end: None,
+      });
+    }
+  }
+  issues
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::parser::Parser;
+
+  #[test]
+  fn collects_custom_tags() {
+    let root = Parser::new(
+      r#"<view><van-button type="primary" bind:click="go"/><block wx:if="{{a}}"><van-button size="small"/></block><my-card/></view>"#,
+    )
+    .parse_root()
+    .unwrap();
+    let usages = collect_component_usages(&root);
+    let names: Vec<_> = usages.iter().map(|usage| usage.name.as_str()).collect();
+    assert_eq!(names, ["my-card", "van-button"]);
+    assert_eq!(usages[1].occurrences.len(), 2);
+    assert_eq!(usages[1].attr_names(), ["bind:click", "size", "type"]);
+  }
+
+  #[test]
+  fn collects_generic_bindings() {
+    let root =
+      Parser::new(r#"<selectable-group generic:selectable="custom-radio" generic:item="{{x}}"/>"#)
+        .parse_root()
+        .unwrap();
+    let generics = &collect_component_usages(&root)[0].occurrences[0].generics;
+    assert_eq!(generics.len(), 2);
+    assert_eq!(generics[0].name, "selectable");
+    assert_eq!(generics[0].component.as_deref(), Some("custom-radio"));
+    assert_eq!(generics[1].component, None);
+  }
+
+  #[test]
+  fn checks_using_components() {
+    let root = Parser::new(
+      r#"<van-button/><selectable-group generic:selectable="custom-radio"><selectable/></selectable-group>
+<my-card/><my-card/>"#,
+    )
+    .parse_root()
+    .unwrap();
+    let config = serde_json::json!({
+      "usingComponents": {
+        "van-button": "@vant/weapp/button/index",
+        "selectable-group": "/components/group/index",
+        "van-icon": "@vant/weapp/icon/index"
+      },
+      "componentGenerics": { "selectable": true }
+    });
+    let issues: Vec<_> = check_using_components(&root, &config)
+      .into_iter()
+      .map(|issue| {
+        (
+          issue.kind,
+          issue.name,
+          issue.start.map(|start| start.offset),
+        )
+      })
+      .collect();
+    assert_eq!(
+      issues,
+      [
+        (
+          ComponentIssueKind::Undeclared,
+          "custom-radio".to_string(),
+          Some(31)
+        ),
+        (
+          ComponentIssueKind::Undeclared,
+          "my-card".to_string(),
+          Some(98)
+        ),
+        (
+          ComponentIssueKind::Undeclared,
+          "my-card".to_string(),
+          Some(108)
+        ),
+        (ComponentIssueKind::Unused, "van-icon".to_string(), None),
+      ]
+    );
+  }
+}
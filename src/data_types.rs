@@ -0,0 +1,388 @@
+//! 页面数据的 TypeScript 类型生成
+//!
+//! 根据模板中的绑定推断页面 `data` 的结构，生成 TypeScript 接口，页面代码标注 `data` 的类型后即可在编译期发现与模板不一致的字段：
+//!
+//! - 变量与成员访问路径对应对象的字段，如 `{{ user.name }}` 得到 `user: { name: unknown }`
+//! - `wx:for` 的列表为数组，列表项（`item` 或 `wx:for-item` 指定的名称）上的访问对应数组元素的字段；
+//!   `index` 为局部变量，不属于页面数据
+//! - `length` 视为数组或字符串的长度，不记录为字段
+//! - 以数字字面量或变量为下标的访问对应数组元素，以字符串字面量为下标的访问对应对象的字段
+//! - 调用的函数为函数类型；调用 [`BUILTIN_METHODS`] 中的方法时只记录被调用的值
+//!
+//! 模板无法说明字段的具体类型，叶子字段为 `unknown`。`<template name>` 定义的内容通过 `data` 传入参数，
+//! 不属于页面数据；wxs 模块名也不计入
+
+use std::{collections::BTreeMap, fmt::Write};
+
+use crate::{
+  ast::{AttributeValue, Node, Root},
+  dialect::{Dialect, Directive},
+  expr::{parse_expression, Expr, ObjectProperty, BUILTIN_METHODS},
+  template_data::parse_template_data,
+};
+
+/// 推断出的数据结构
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum DataShape {
+  /// 只知道被使用，类型未知
+  #[default]
+  Unknown,
+  /// 被调用的函数
+  Function,
+  Array(Box<DataShape>),
+  /// 对象，字段按名称排序
+  Object(BTreeMap<String, DataShape>),
+}
+
+/// 类型生成选项
+#[derive(Debug, Clone)]
+pub struct DataTypesOptions {
+  /// 生成的接口名称
+  pub interface_name: String,
+  /// 所有字段标记为可选，适合 `data` 中的字段会在之后通过 `setData` 补充的页面
+  pub optional: bool,
+  pub indent_width: usize,
+}
+
+impl Default for DataTypesOptions {
+  fn default() -> Self {
+    Self {
+      interface_name: "Data".to_string(),
+      optional: false,
+      indent_width: 2,
+    }
+  }
+}
+
+/// 访问路径中的一段
+#[derive(Debug, Clone)]
+enum Segment {
+  Field(String),
+  Element,
+}
+
+impl DataShape {
+  /// 合并两处推断的结果，已知的结构优先
+  fn merge(&mut self, other: DataShape) {
+    match (&mut *self, other) {
+      (_, DataShape::Unknown) => {}
+      (DataShape::Object(fields), DataShape::Object(others)) => {
+        for (key, shape) in others {
+          fields.entry(key).or_default().merge(shape);
+        }
+      }
+      (DataShape::Array(element), DataShape::Array(other)) => element.merge(*other),
+      (DataShape::Unknown, other) => *self = other,
+      _ => {}
+    }
+  }
+
+  /// 沿路径记录结构，路径末端为 `leaf`
+  fn insert(&mut self, path: &[Segment], leaf: DataShape) {
+    let shape = path
+      .iter()
+      .rev()
+      .fold(leaf, |shape, segment| match segment {
+        Segment::Field(name) => DataShape::Object(BTreeMap::from([(name.clone(), shape)])),
+        Segment::Element => DataShape::Array(Box::new(shape)),
+      });
+    self.merge(shape);
+  }
+
+  fn write_typescript(&self, out: &mut String, options: &DataTypesOptions, depth: usize) {
+    match self {
+      DataShape::Unknown => out.push_str("unknown"),
+      DataShape::Function => out.push_str("(...args: any[]) => unknown"),
+      DataShape::Array(element) => {
+        out.push_str("Array<");
+        element.write_typescript(out, options, depth);
+        out.push('>');
+      }
+      DataShape::Object(fields) => {
+        out.push_str("{\n");
+        write_fields(out, fields, options, depth + 1);
+        out.push_str(&" ".repeat(options.indent_width * depth));
+        out.push('}');
+      }
+    }
+  }
+}
+
+fn write_fields(
+  out: &mut String,
+  fields: &BTreeMap<String, DataShape>,
+  options: &DataTypesOptions,
+  depth: usize,
+) {
+  let indent = " ".repeat(options.indent_width * depth);
+  for (key, shape) in fields {
+    let valid_identifier = key
+      .chars()
+      .enumerate()
+      .all(|(i, c)| c == '_' || c == '$' || c.is_alphabetic() || (i > 0 && c.is_numeric()));
+    let _ = if valid_identifier {
+      write!(out, "{indent}{key}")
+    } else {
+      write!(out, "{indent}{key:?}")
+    };
+    out.push_str(if options.optional { "?: " } else { ": " });
+    shape.write_typescript(out, options, depth);
+    out.push('\n');
+  }
+}
+
+/// 推断模板使用的页面数据的结构，结果总是 [`DataShape::Object`]
+pub fn data_shape(root: &Root) -> DataShape {
+  let mut modules = Vec::new();
+  root.walk(&mut |node| {
+    if Dialect::ALL
+      .iter()
+      .any(|dialect| node.name() == Some(dialect.script_tag()))
+    {
+      if let Some(module) = node.attr("module").and_then(|attr| attr.as_static_string()) {
+        modules.push(module);
+      }
+    }
+  });
+  let mut inference = Inference {
+    shape: DataShape::Object(BTreeMap::new()),
+    locals: modules.into_iter().map(|module| (module, None)).collect(),
+  };
+  for child in &root.children {
+    inference.node(child);
+  }
+  inference.shape
+}
+
+/// 生成描述页面数据的 TypeScript 接口
+pub fn data_typescript(root: &Root, options: &DataTypesOptions) -> String {
+  let mut out = format!("export interface {} {{\n", options.interface_name);
+  if let DataShape::Object(fields) = data_shape(root) {
+    write_fields(&mut out, &fields, options, 1);
+  }
+  out.push_str("}\n");
+  out
+}
+
+struct Inference {
+  shape: DataShape,
+  /// 作用域中的局部变量，列表项对应列表元素的路径，其他局部变量（下标、wxs 模块）为 `None`
+  locals: Vec<(String, Option<Vec<Segment>>)>,
+}
+
+impl Inference {
+  fn node(&mut self, node: &Node) {
+    match node {
+      Node::Expression { content, .. } => self.content(content),
+      Node::Element {
+        name,
+        attrs,
+        children,
+        ..
+      } => {
+        if name == "template" && node.attr("name").is_some() {
+          return;
+        }
+        let scope = self.locals.len();
+        if let Some(attr) = node.directive(Directive::For) {
+          // 列表指令在外层作用域中求值
+          let list = match attr.value.as_deref() {
+            Some([AttributeValue::Expression { content, .. }]) => parse_expression(content).ok(),
+            _ => None,
+          };
+          let element = list.and_then(|list| {
+            self.expr(&list);
+            let mut path = self.path(&list)?;
+            self.shape.insert(&path, DataShape::Array(Box::default()));
+            path.push(Segment::Element);
+            Some(path)
+          });
+          let local = |directive, default: &str| {
+            node
+              .directive(directive)
+              .and_then(|attr| attr.as_static_string())
+              .unwrap_or_else(|| default.to_string())
+          };
+          self
+            .locals
+            .push((local(Directive::ForItem, "item"), element));
+          self
+            .locals
+            .push((local(Directive::ForIndex, "index"), None));
+        }
+        let call_data = name == "template" && node.attr("is").is_some();
+        for attr in attrs {
+          if Directive::of(&attr.name).is_some_and(|(_, directive)| directive == Directive::For) {
+            continue;
+          }
+          for value in attr.value.iter().flatten() {
+            let AttributeValue::Expression { content, .. } = value else {
+              continue;
+            };
+            if call_data && attr.name == "data" {
+              for argument in parse_template_data(content).unwrap_or_default() {
+                self.expr(&argument.value);
+              }
+            } else {
+              self.content(content);
+            }
+          }
+        }
+        for child in children {
+          self.node(child);
+        }
+        self.locals.truncate(scope);
+      }
+      _ => {}
+    }
+  }
+
+  fn content(&mut self, content: &str) {
+    if let Ok(expr) = parse_expression(content) {
+      self.expr(&expr);
+    }
+  }
+
+  /// 成员访问链对应的数据路径；以局部变量开头时换算为列表元素的路径，无法对应页面数据时为 `None`
+  fn path(&self, expr: &Expr) -> Option<Vec<Segment>> {
+    match expr {
+      Expr::Ident(name) => match self.locals.iter().rev().find(|(local, _)| local == name) {
+        Some((_, path)) => path.clone(),
+        None => Some(vec![Segment::Field(name.clone())]),
+      },
+      // 数组与字符串的长度不是数据的字段
+      Expr::Member { object, property } if property == "length" => self.path(object),
+      Expr::Member { object, property } => {
+        let mut path = self.path(object)?;
+        path.push(Segment::Field(property.clone()));
+        Some(path)
+      }
+      Expr::Index { object, index } => {
+        let mut path = self.path(object)?;
+        path.push(match &**index {
+          Expr::String(key) => Segment::Field(key.clone()),
+          Expr::Number(_) | Expr::Ident(_) => Segment::Element,
+          _ => return None,
+        });
+        Some(path)
+      }
+      _ => None,
+    }
+  }
+
+  fn record(&mut self, expr: &Expr, leaf: DataShape) {
+    if let Some(path) = self.path(expr) {
+      self.shape.insert(&path, leaf);
+    }
+  }
+
+  fn expr(&mut self, expr: &Expr) {
+    match expr {
+      Expr::Null | Expr::Undefined | Expr::Bool(_) | Expr::Number(_) | Expr::String(_) => {}
+      Expr::Ident(_) => self.record(expr, DataShape::Unknown),
+      Expr::Member { object, .. } => {
+        if self.path(expr).is_some() {
+          self.record(expr, DataShape::Unknown);
+        } else {
+          self.expr(object);
+        }
+      }
+      Expr::Index { object, index } => {
+        self.expr(index);
+        if self.path(expr).is_some() {
+          self.record(expr, DataShape::Unknown);
+        } else {
+          self.expr(object);
+        }
+      }
+      Expr::Call { callee, args } => {
+        match &**callee {
+          Expr::Member { object, property } if BUILTIN_METHODS.contains(&property.as_str()) => {
+            self.expr(object)
+          }
+          callee if self.path(callee).is_some() => self.record(callee, DataShape::Function),
+          callee => self.expr(callee),
+        }
+        args.iter().for_each(|arg| self.expr(arg));
+      }
+      Expr::Array(items) => items.iter().for_each(|item| self.expr(item)),
+      Expr::Object(properties) => {
+        for property in properties {
+          match property {
+            ObjectProperty::Property { value, .. } | ObjectProperty::Spread(value) => {
+              self.expr(value)
+            }
+          }
+        }
+      }
+      Expr::Unary { argument, .. } | Expr::Spread(argument) => self.expr(argument),
+      Expr::Binary { left, right, .. } => {
+        self.expr(left);
+        self.expr(right);
+      }
+      Expr::Conditional {
+        test,
+        consequent,
+        alternate,
+      } => {
+        self.expr(test);
+        self.expr(consequent);
+        self.expr(alternate);
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::parser::Parser;
+
+  #[test]
+  fn page_data_interface() {
+    let source = r#"<wxs module="fmt" src="./fmt.wxs"/>
+<view wx:if="{{user.profile.name}}" class="{{theme}}">{{ fmt.price(order.total) }}</view>
+<view wx:for="{{groups}}" wx:for-item="group" wx:key="id">
+  <text>{{ group.title.trim() }} {{ index }} {{ group.items.length }}</text>
+  <view wx:for="{{group.items}}">{{ item.name }} {{ tags[index] }} {{ config['max-count'] }}</view>
+</view>
+<button bindtap="onTap" disabled="{{ isDisabled(order) }}">{{ list.length }}</button>
+<template name="card"><view>{{ title }}</view></template>
+<template is="card" data="{{ title: heading }}"/>"#;
+    let root = Parser::new(source).parse_root().unwrap();
+    assert_eq!(
+      data_typescript(
+        &root,
+        &DataTypesOptions {
+          interface_name: "IndexData".to_string(),
+          ..Default::default()
+        }
+      ),
+      r#"export interface IndexData {
+  config: {
+    "max-count": unknown
+  }
+  groups: Array<{
+    items: Array<{
+      name: unknown
+    }>
+    title: unknown
+  }>
+  heading: unknown
+  isDisabled: (...args: any[]) => unknown
+  list: unknown
+  order: {
+    total: unknown
+  }
+  tags: Array<unknown>
+  theme: unknown
+  user: {
+    profile: {
+      name: unknown
+    }
+  }
+}
+"#
+    );
+  }
+}
@@ -0,0 +1,118 @@
+//! `data-*` 自定义数据
+//!
+//! 事件对象的 `currentTarget.dataset` 由元素上的 `data-*` 属性生成。键名按运行时的规则转换：
+//! 去掉 `data-` 前缀后整体转为小写，再把 `-` 加字母转为大写字母，如 `data-element-type` 为 `elementType`、
+//! `data-elementType` 为 `elementtype`
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::ast::{Attribute, AttributeValue, Position};
+
+/// `data-*` 属性值的形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DatasetValueKind {
+  /// 无值属性，运行时为 `true`
+  Flag,
+  /// 静态文本，运行时为字符串
+  Static,
+  /// 只有一个表达式，运行时为表达式的值，可以是任意类型
+  Expression,
+  /// 静态文本与表达式拼接，运行时为字符串
+  Interpolated,
+}
+
+/// 一个 `data-*` 属性
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasetEntry {
+  /// 书写的属性名，如 `data-item-id`
+  pub attribute: String,
+  pub kind: DatasetValueKind,
+  /// 静态文本或表达式的内容；无值属性与拼接值为 `None`
+  pub value: Option<String>,
+  pub start: Position,
+  pub end: Position,
+}
+
+/// `data-*` 属性名对应的 dataset 键名，其他属性返回 `None`
+pub fn dataset_key(attribute: &str) -> Option<String> {
+  let name = attribute.strip_prefix("data-")?.to_lowercase();
+  let mut key = String::with_capacity(name.len());
+  let mut upper = false;
+  for ch in name.chars() {
+    match ch {
+      '-' => upper = true,
+      _ if upper => {
+        key.extend(ch.to_uppercase());
+        upper = false;
+      }
+      _ => key.push(ch),
+    }
+  }
+  Some(key)
+}
+
+/// 按 dataset 键名收集 `data-*` 属性，键名相同时后出现的属性生效
+pub fn collect_dataset(attrs: &[Attribute]) -> BTreeMap<String, DatasetEntry> {
+  let mut dataset = BTreeMap::new();
+  for attr in attrs {
+    let Some(key) = dataset_key(&attr.name) else {
+      continue;
+    };
+    let (kind, value) = match attr.value.as_deref() {
+      None => (DatasetValueKind::Flag, None),
+      Some([AttributeValue::Expression { content, .. }]) => {
+        (DatasetValueKind::Expression, Some(content.clone()))
+      }
+      Some(_) => match attr.as_static_string() {
+        Some(text) => (DatasetValueKind::Static, Some(text)),
+        None => (DatasetValueKind::Interpolated, None),
+      },
+    };
+    dataset.insert(
+      key,
+      DatasetEntry {
+        attribute: attr.name.clone(),
+        kind,
+        value,
+        start: attr.start,
+        end: attr.end,
+      },
+    );
+  }
+  dataset
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::parser::Parser;
+
+  #[test]
+  fn dataset_entries() {
+    let root = Parser::new(
+      r#"<view data-item-id="{{item.id}}" data-elementType="card" data-url="/a?id={{id}}" data-active bindtap="tap"/>"#,
+    )
+    .parse_root()
+    .unwrap();
+    let dataset = root.children[0].dataset();
+    let entries: Vec<_> = dataset
+      .iter()
+      .map(|(key, entry)| (key.as_str(), entry.kind, entry.value.as_deref()))
+      .collect();
+    assert_eq!(
+      entries,
+      [
+        ("active", DatasetValueKind::Flag, None),
+        ("elementtype", DatasetValueKind::Static, Some("card")),
+        ("itemId", DatasetValueKind::Expression, Some("item.id")),
+        ("url", DatasetValueKind::Interpolated, None),
+      ]
+    );
+    assert_eq!(dataset_key("data-a--b").as_deref(), Some("aB"));
+    assert_eq!(dataset_key("id"), None);
+  }
+}
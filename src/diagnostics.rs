@@ -0,0 +1,321 @@
+//! 诊断信息的严重级别与按文件汇总
+//!
+//! 语法错误与检查规则的诊断信息统一为 [`Diagnostic`]，每条带有 [`Severity`]。
+//! [`DiagnosticBag`] 按文件收集诊断信息：排序、去除重复项、按配置截断，并统计各级别的数量，
+//! 错误数量达到上限时 [`DiagnosticBag::should_bail`] 提示调用方停止处理后续文件，适合在 CI 中使用。
+//! 通过 [`Display`](fmt::Display) 输出按文件分组的可读报告。仅提供 Rust 接口
+
+use std::fmt;
+
+#[cfg(feature = "napi")]
+use napi_derive::napi;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  ast::Position,
+  batch::LintResult,
+  error::{SyntaxError, SyntaxErrorKind},
+  lint::Diagnostic,
+};
+
+/// 诊断信息的严重级别，按从重到轻排序
+#[derive(
+  Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[cfg_attr(feature = "napi", napi(string_enum = "lowercase"))]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+  /// 模板无法按预期工作，如语法错误
+  Error,
+  /// 可能的问题，检查规则的默认级别
+  #[default]
+  Warning,
+  /// 建议，如代码风格
+  Hint,
+}
+
+impl Severity {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Severity::Error => "error",
+      Severity::Warning => "warning",
+      Severity::Hint => "hint",
+    }
+  }
+}
+
+impl fmt::Display for Severity {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
+impl From<&SyntaxError> for Diagnostic {
+  /// 语法错误的规则名称为 `syntax`，起止位置均为出错的位置
+  fn from(error: &SyntaxError) -> Self {
+    let position = Position {
+      offset: error.offset,
+      line: error.line,
+      column: error.column,
+    };
+    Diagnostic {
+      rule: "syntax".to_string(),
+      code: error.code.clone(),
+      message: error.kind.to_string(),
+      severity: match error.kind {
+        SyntaxErrorKind::IgnoredDeclaration | SyntaxErrorKind::StrayLessThan => Severity::Warning,
+        _ => Severity::Error,
+      },
+      start: position,
+      end: position,
+      fix: Vec::new(),
+    }
+  }
+}
+
+/// [`DiagnosticBag`] 的配置
+#[derive(Debug, Clone, Default)]
+pub struct DiagnosticBagOptions {
+  /// 每个文件最多保留的诊断信息数量，超出部分只计数
+  pub max_per_file: Option<usize>,
+  /// 所有文件的错误总数达到该值后 [`DiagnosticBag::should_bail`] 返回 `true`
+  pub max_errors: Option<usize>,
+  /// 只保留不轻于该级别的诊断信息，如设为 [`Severity::Warning`] 时忽略提示
+  pub min_severity: Option<Severity>,
+}
+
+/// 单个文件整理后的诊断信息
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileDiagnostics {
+  pub path: String,
+  /// 按位置排序、已去重的诊断信息
+  pub diagnostics: Vec<Diagnostic>,
+  /// 超出 [`DiagnosticBagOptions::max_per_file`] 而未保留的数量
+  pub omitted: usize,
+}
+
+/// 按文件汇总的诊断信息
+#[derive(Debug, Clone, Default)]
+pub struct DiagnosticBag {
+  options: DiagnosticBagOptions,
+  files: Vec<FileDiagnostics>,
+  /// 各级别的数量，包括被截断的诊断信息
+  counts: [usize; 3],
+}
+
+impl DiagnosticBag {
+  pub fn new(options: DiagnosticBagOptions) -> Self {
+    Self {
+      options,
+      ..Default::default()
+    }
+  }
+
+  /// 添加一个文件的诊断信息，没有诊断信息的文件不记录
+  pub fn add(
+    &mut self,
+    path: impl Into<String>,
+    diagnostics: impl IntoIterator<Item = Diagnostic>,
+  ) {
+    let mut diagnostics: Vec<_> = diagnostics
+      .into_iter()
+      .filter(|diagnostic| {
+        self
+          .options
+          .min_severity
+          .is_none_or(|min| diagnostic.severity <= min)
+      })
+      .collect();
+    diagnostics.sort_by(|a, b| {
+      (
+        a.start.offset,
+        a.severity,
+        &a.code,
+        a.end.offset,
+        &a.message,
+      )
+        .cmp(&(
+          b.start.offset,
+          b.severity,
+          &b.code,
+          b.end.offset,
+          &b.message,
+        ))
+    });
+    diagnostics.dedup_by(|a, b| {
+      a.code == b.code
+        && a.start.offset == b.start.offset
+        && a.end.offset == b.end.offset
+        && a.message == b.message
+    });
+    if diagnostics.is_empty() {
+      return;
+    }
+    for diagnostic in &diagnostics {
+      self.counts[diagnostic.severity as usize] += 1;
+    }
+    let limit = self.options.max_per_file.unwrap_or(usize::MAX);
+    let omitted = diagnostics.len().saturating_sub(limit);
+    diagnostics.truncate(limit);
+    self.files.push(FileDiagnostics {
+      path: path.into(),
+      diagnostics,
+      omitted,
+    });
+  }
+
+  /// 添加 [`lint_files`](crate::batch::lint_files) 的结果，语法错误与检查规则的诊断信息合并为同一文件
+  pub fn add_lint_result(&mut self, result: LintResult) {
+    let errors = result.errors.iter().map(Diagnostic::from);
+    let diagnostics = errors.chain(result.diagnostics).collect::<Vec<_>>();
+    self.add(result.path, diagnostics);
+  }
+
+  /// 存在诊断信息的文件，按添加顺序排列
+  pub fn files(&self) -> &[FileDiagnostics] {
+    &self.files
+  }
+
+  /// 指定级别的诊断信息数量，包括被截断的部分
+  pub fn count(&self, severity: Severity) -> usize {
+    self.counts[severity as usize]
+  }
+
+  pub fn error_count(&self) -> usize {
+    self.count(Severity::Error)
+  }
+
+  pub fn has_errors(&self) -> bool {
+    self.error_count() > 0
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.files.is_empty()
+  }
+
+  /// 错误总数是否已达到 [`DiagnosticBagOptions::max_errors`]
+  pub fn should_bail(&self) -> bool {
+    self
+      .options
+      .max_errors
+      .is_some_and(|max| self.error_count() >= max)
+  }
+}
+
+fn plural(count: usize, word: &str) -> String {
+  if count == 1 {
+    format!("{count} {word}")
+  } else {
+    format!("{count} {word}s")
+  }
+}
+
+impl fmt::Display for DiagnosticBag {
+  /// 按文件分组输出，每行依次为位置、级别、信息与规则
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for file in &self.files {
+      writeln!(f, "{}", file.path)?;
+      let locations: Vec<_> = file
+        .diagnostics
+        .iter()
+        .map(|diagnostic| format!("{}:{}", diagnostic.start.line, diagnostic.start.column))
+        .collect();
+      let location_width = locations.iter().map(String::len).max().unwrap_or(0);
+      let message_width = file
+        .diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.message.chars().count())
+        .max()
+        .unwrap_or(0);
+      for (diagnostic, location) in file.diagnostics.iter().zip(&locations) {
+        writeln!(
+          f,
+          "  {location:<location_width$}  {:<7}  {:<message_width$}  {} {}",
+          diagnostic.severity.as_str(),
+          diagnostic.message,
+          diagnostic.rule,
+          diagnostic.code,
+        )?;
+      }
+      if file.omitted > 0 {
+        writeln!(f, "  ... and {} more", file.omitted)?;
+      }
+      writeln!(f)?;
+    }
+    let total: usize = self.counts.iter().sum();
+    write!(
+      f,
+      "{} ({}, {}, {})",
+      plural(total, "problem"),
+      plural(self.count(Severity::Error), "error"),
+      plural(self.count(Severity::Warning), "warning"),
+      plural(self.count(Severity::Hint), "hint"),
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    batch::{lint_files, parse_file},
+    lint::LintConfig,
+  };
+
+  #[test]
+  fn aggregates_per_file() {
+    let files = vec![
+      (
+        "a.wxml".to_string(),
+        r#"<view class="a" class="b">{{}}</view><view id="a" id="b">{{ }}</view>"#.to_string(),
+      ),
+      ("b.wxml".to_string(), "<view/>".to_string()),
+      (
+        "c.wxml".to_string(),
+        r#"<image class="a" class="b" src="x"/>"#.to_string(),
+      ),
+    ];
+    let config = LintConfig {
+      stylistic: Some(true),
+      ..Default::default()
+    };
+    let mut bag = DiagnosticBag::new(DiagnosticBagOptions {
+      max_per_file: Some(2),
+      max_errors: Some(1),
+      min_severity: Some(Severity::Warning),
+    });
+    for result in lint_files(files, &config).unwrap() {
+      bag.add_lint_result(result);
+    }
+    assert!(!bag.should_bail());
+
+    // 重复的诊断信息只保留一条
+    let result = parse_file("d.wxml", "<view><text>", &Default::default());
+    bag.add(
+      result.path,
+      result
+        .errors
+        .iter()
+        .chain(&result.errors)
+        .map(Diagnostic::from),
+    );
+    assert!(bag.should_bail());
+    assert_eq!(bag.files().len(), 3);
+    assert_eq!(
+      bag.to_string(),
+      "a.wxml
+  1:17  warning  duplicate attribute `class`  no-duplicate-attrs WXML2001
+  1:27  warning  empty mustache expression    no-empty-expression WXML2002
+  ... and 2 more
+
+c.wxml
+  1:18  warning  duplicate attribute `class`  no-duplicate-attrs WXML2001
+
+d.wxml
+  1:13  error    expected close tag  syntax WXML1003
+
+6 problems (1 error, 5 warnings, 0 hints)"
+    );
+  }
+}
@@ -0,0 +1,183 @@
+//! 小程序模板方言
+//!
+//! 各平台的模板语法基本一致，区别主要在于指令前缀与内联脚本标签：
+//!
+//! | 方言 | 指令 | 内联脚本 |
+//! | --- | --- | --- |
+//! | 微信 WXML | `wx:if`、`wx:for` | `<wxs>` |
+//! | 百度 Swan | `s-if`、`s-for` | `<filter>` |
+//! | 抖音 TTML | `tt:if`、`tt:for` | `<sjs>` |
+//!
+//! 解析器通过 [`ParserOptions::dialect`](crate::options::ParserOptions::dialect) 决定哪个脚本标签按原始文本解析；
+//! 指令的识别（[`Directive::of`]、[`Node::directive`]）则同时接受所有方言的写法，
+//! 因此检查规则无需知道模板来自哪个平台。Swan 的 `s-` 指令与 `data-*` 一样拆分出前缀 `s`
+
+#[cfg(feature = "napi")]
+use napi_derive::napi;
+use serde::{Deserialize, Serialize};
+
+use crate::ast::{Attribute, Node};
+
+/// 模板方言
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "napi", napi(string_enum = "lowercase"))]
+#[serde(rename_all = "lowercase")]
+pub enum Dialect {
+  /// 微信小程序 WXML
+  #[default]
+  Wxml,
+  /// 百度智能小程序 Swan
+  Swan,
+  /// 抖音小程序 TTML
+  Ttml,
+}
+
+/// 条件渲染与列表渲染指令
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Directive {
+  If,
+  Elif,
+  Else,
+  For,
+  ForItem,
+  ForIndex,
+  /// 列表项的 key，Swan 没有对应指令，而是写在 `s-for` 中：`s-for="item in list trackBy item.id"`
+  Key,
+}
+
+const WXML_DIRECTIVES: &[Directive] = &[
+  Directive::If,
+  Directive::Elif,
+  Directive::Else,
+  Directive::For,
+  Directive::ForItem,
+  Directive::ForIndex,
+  Directive::Key,
+];
+
+const SWAN_DIRECTIVES: &[Directive] = &[
+  Directive::If,
+  Directive::Elif,
+  Directive::Else,
+  Directive::For,
+  Directive::ForItem,
+  Directive::ForIndex,
+];
+
+impl Dialect {
+  pub const ALL: [Dialect; 3] = [Dialect::Wxml, Dialect::Swan, Dialect::Ttml];
+
+  /// 指令属性的前缀，如 `wx:`、`s-`
+  pub fn directive_prefix(&self) -> &'static str {
+    match self {
+      Dialect::Wxml => "wx:",
+      Dialect::Swan => "s-",
+      Dialect::Ttml => "tt:",
+    }
+  }
+
+  /// 内联脚本标签，内容按原始文本解析
+  pub fn script_tag(&self) -> &'static str {
+    match self {
+      Dialect::Wxml => "wxs",
+      Dialect::Swan => "filter",
+      Dialect::Ttml => "sjs",
+    }
+  }
+
+  /// 该方言支持的指令
+  pub fn directives(&self) -> &'static [Directive] {
+    match self {
+      Dialect::Wxml | Dialect::Ttml => WXML_DIRECTIVES,
+      Dialect::Swan => SWAN_DIRECTIVES,
+    }
+  }
+
+  /// 指令在该方言中的属性名，如 Swan 的 [`Directive::Elif`] 为 `s-elif`
+  pub fn directive_name(&self, directive: Directive) -> String {
+    format!("{}{}", self.directive_prefix(), directive.as_str())
+  }
+
+  /// 按该方言识别指令属性
+  pub fn directive(&self, attr_name: &str) -> Option<Directive> {
+    let name = attr_name.strip_prefix(self.directive_prefix())?;
+    self
+      .directives()
+      .iter()
+      .copied()
+      .find(|directive| directive.as_str() == name)
+  }
+}
+
+impl Directive {
+  /// 去掉前缀后的名称，如 `for-item`
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Directive::If => "if",
+      Directive::Elif => "elif",
+      Directive::Else => "else",
+      Directive::For => "for",
+      Directive::ForItem => "for-item",
+      Directive::ForIndex => "for-index",
+      Directive::Key => "key",
+    }
+  }
+
+  /// 按任意方言识别指令属性，返回属性所属的方言与指令
+  pub fn of(attr_name: &str) -> Option<(Dialect, Directive)> {
+    Dialect::ALL
+      .into_iter()
+      .find_map(|dialect| Some((dialect, dialect.directive(attr_name)?)))
+  }
+}
+
+impl Node {
+  /// 查找任意方言中表示 `directive` 的属性，如 [`Directive::If`] 对应 `wx:if`、`s-if` 或 `tt:if`
+  pub fn directive(&self, directive: Directive) -> Option<&Attribute> {
+    self
+      .attrs()?
+      .iter()
+      .find(|attr| Directive::of(&attr.name).is_some_and(|(_, found)| found == directive))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{parse, ParserOptions};
+
+  #[test]
+  fn dialects() {
+    assert_eq!(
+      Directive::of("s-elif"),
+      Some((Dialect::Swan, Directive::Elif))
+    );
+    assert_eq!(
+      Directive::of("tt:key"),
+      Some((Dialect::Ttml, Directive::Key))
+    );
+    assert_eq!(Directive::of("s-key"), None);
+    assert_eq!(Directive::of("wx:bogus"), None);
+    assert_eq!(Dialect::Swan.directive_name(Directive::For), "s-for");
+
+    let source = r#"<view s-for="item in list" s-if="{{a}}"/><filter module="f"><b>x</b></filter>"#;
+    let root = parse(source.to_string(), None).unwrap();
+    assert_eq!(root.children[1].children().unwrap()[0].name(), Some("b"));
+    let options = ParserOptions {
+      dialect: Some(Dialect::Swan),
+      ..Default::default()
+    };
+    let root = parse(source.to_string(), Some(options)).unwrap();
+    let view = &root.children[0];
+    assert_eq!(view.directive(Directive::For).unwrap().name, "s-for");
+    let attr = view.directive(Directive::If).unwrap();
+    assert_eq!(
+      (attr.prefix.as_deref(), attr.local.as_str()),
+      (Some("s"), "if")
+    );
+    assert!(matches!(
+      root.children[1].children().unwrap(),
+      [Node::Text { content, .. }] if content == "<b>x</b>"
+    ));
+  }
+}
@@ -0,0 +1,321 @@
+//! 模板结构差异
+//!
+//! 比较两棵语法树，输出插入、删除、移动节点以及修改属性、文本的操作，每个操作同时带有
+//! 旧源码与新源码中的位置，可用于代码评审工具标注改动，或在热更新时只替换变化的部分。
+//!
+//! 同一父节点下的子节点先按最长公共子序列对齐：元素按标签名与 `wx:key`/`id` 匹配，其余节点按类型匹配；
+//! 未对齐的节点中，内容完全相同（忽略位置）的旧节点与新节点视为移动，其余分别为删除与插入。
+//! 对齐的元素继续比较属性与子节点；标签名不同的元素不会对齐，表现为删除旧元素并插入新元素
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  ast::{Attribute, Node, Position, Root},
+  codegen::{generate_attribute, generate_node, CodegenOptions},
+};
+
+/// 一个结构编辑操作，`old_*` 为旧源码中的位置，`new_*` 为新源码中的位置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all_fields = "camelCase")]
+pub enum DiffOp {
+  /// 插入节点，`node` 为新树中的节点
+  Insert {
+    node: Node,
+    new_start: Position,
+    new_end: Position,
+  },
+  /// 删除节点
+  Remove {
+    old_start: Position,
+    old_end: Position,
+  },
+  /// 节点内容不变，移动到了同一父节点下的其他位置
+  Move {
+    old_start: Position,
+    old_end: Position,
+    new_start: Position,
+    new_end: Position,
+  },
+  /// 添加、删除或修改属性；`old`、`new` 为属性的源码，如 `class="a"`，不存在时为 `None`。
+  /// 位置为属性本身的位置，属性不存在时为所在元素的位置
+  ChangeAttribute {
+    name: String,
+    old: Option<String>,
+    new: Option<String>,
+    old_start: Position,
+    old_end: Position,
+    new_start: Position,
+    new_end: Position,
+  },
+  /// 修改文本、注释或插值表达式的内容
+  ChangeText {
+    old: String,
+    new: String,
+    old_start: Position,
+    old_end: Position,
+    new_start: Position,
+    new_end: Position,
+  },
+}
+
+/// 比较两个文档，按新文档中的先序遍历顺序输出编辑操作，删除操作排在所在父节点的其他操作之前
+pub fn diff(old: &Root, new: &Root) -> Vec<DiffOp> {
+  let mut ops = Vec::new();
+  diff_children(&old.children, &new.children, &mut ops);
+  ops
+}
+
+/// 比较两个节点；节点类型或标签名不同时输出删除与插入
+pub fn diff_nodes(old: &Node, new: &Node) -> Vec<DiffOp> {
+  let mut ops = Vec::new();
+  if matches(old, new) {
+    diff_matched(old, new, &mut ops);
+  } else {
+    ops.push(remove(old));
+    ops.push(insert(new));
+  }
+  ops
+}
+
+/// 两个节点能否对齐
+fn matches(old: &Node, new: &Node) -> bool {
+  match (old, new) {
+    (Node::Element { name: a, .. }, Node::Element { name: b, .. }) => {
+      a == b && key(old) == key(new)
+    }
+    (Node::Text { .. }, Node::Text { .. })
+    | (Node::Comment { .. }, Node::Comment { .. })
+    | (Node::Expression { .. }, Node::Expression { .. }) => true,
+    _ => false,
+  }
+}
+
+/// 元素的标识：`wx:key` 只用于区分循环项，因此与 `id` 一起参与匹配
+fn key(node: &Node) -> (Option<String>, Option<String>) {
+  let value = |name| node.attr(name).and_then(Attribute::as_static_string);
+  (value("wx:key"), value("id"))
+}
+
+fn source(node: &Node) -> String {
+  generate_node(node, &CodegenOptions::default())
+}
+
+fn insert(node: &Node) -> DiffOp {
+  DiffOp::Insert {
+    node: node.clone(),
+    new_start: node.start(),
+    new_end: node.end(),
+  }
+}
+
+fn remove(node: &Node) -> DiffOp {
+  DiffOp::Remove {
+    old_start: node.start(),
+    old_end: node.end(),
+  }
+}
+
+fn diff_children(old: &[Node], new: &[Node], ops: &mut Vec<DiffOp>) {
+  let pairs = align(old, new);
+  let mut old_matched = vec![false; old.len()];
+  let mut new_matched: Vec<Option<usize>> = vec![None; new.len()];
+  for &(i, j) in &pairs {
+    old_matched[i] = true;
+    new_matched[j] = Some(i);
+  }
+
+  // 未对齐的节点中内容相同的视为移动
+  let mut moved: Vec<Option<usize>> = vec![None; new.len()];
+  let mut old_sources: Vec<Option<String>> = old
+    .iter()
+    .enumerate()
+    .map(|(i, node)| (!old_matched[i]).then(|| source(node)))
+    .collect();
+  for (j, node) in new.iter().enumerate() {
+    if new_matched[j].is_some() {
+      continue;
+    }
+    let new_source = source(node);
+    if let Some(i) = old_sources
+      .iter()
+      .position(|old_source| old_source.as_ref() == Some(&new_source))
+    {
+      old_sources[i] = None;
+      old_matched[i] = true;
+      moved[j] = Some(i);
+    }
+  }
+
+  for (i, node) in old.iter().enumerate() {
+    if !old_matched[i] {
+      ops.push(remove(node));
+    }
+  }
+  for (j, node) in new.iter().enumerate() {
+    if let Some(i) = new_matched[j] {
+      diff_matched(&old[i], node, ops);
+    } else if let Some(i) = moved[j] {
+      ops.push(DiffOp::Move {
+        old_start: old[i].start(),
+        old_end: old[i].end(),
+        new_start: node.start(),
+        new_end: node.end(),
+      });
+    } else {
+      ops.push(insert(node));
+    }
+  }
+}
+
+/// 按最长公共子序列对齐两组子节点，返回下标对
+fn align(old: &[Node], new: &[Node]) -> Vec<(usize, usize)> {
+  let (n, m) = (old.len(), new.len());
+  // lengths[i][j] 为 old[i..] 与 new[j..] 的最长公共子序列长度
+  let mut lengths = vec![vec![0u32; m + 1]; n + 1];
+  for i in (0..n).rev() {
+    for j in (0..m).rev() {
+      lengths[i][j] = if matches(&old[i], &new[j]) {
+        lengths[i + 1][j + 1] + 1
+      } else {
+        lengths[i + 1][j].max(lengths[i][j + 1])
+      };
+    }
+  }
+  let mut pairs = Vec::new();
+  let (mut i, mut j) = (0, 0);
+  while i < n && j < m {
+    if matches(&old[i], &new[j]) && lengths[i][j] == lengths[i + 1][j + 1] + 1 {
+      pairs.push((i, j));
+      i += 1;
+      j += 1;
+    } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+      i += 1;
+    } else {
+      j += 1;
+    }
+  }
+  pairs
+}
+
+fn diff_matched(old: &Node, new: &Node, ops: &mut Vec<DiffOp>) {
+  match (old, new) {
+    (
+      Node::Element {
+        attrs: old_attrs,
+        children: old_children,
+        ..
+      },
+      Node::Element {
+        attrs: new_attrs,
+        children: new_children,
+        ..
+      },
+    ) => {
+      diff_attrs(old, old_attrs, new, new_attrs, ops);
+      diff_children(old_children, new_children, ops);
+    }
+    (
+      Node::Text { content: a, .. }
+      | Node::Comment { content: a, .. }
+      | Node::Expression { content: a, .. },
+      Node::Text { content: b, .. }
+      | Node::Comment { content: b, .. }
+      | Node::Expression { content: b, .. },
+    ) if a != b => ops.push(DiffOp::ChangeText {
+      old: a.clone(),
+      new: b.clone(),
+      old_start: old.start(),
+      old_end: old.end(),
+      new_start: new.start(),
+      new_end: new.end(),
+    }),
+    _ => {}
+  }
+}
+
+fn diff_attrs(
+  old: &Node,
+  old_attrs: &[Attribute],
+  new: &Node,
+  new_attrs: &[Attribute],
+  ops: &mut Vec<DiffOp>,
+) {
+  let options = CodegenOptions::default();
+  let mut change = |name: &str, a: Option<&Attribute>, b: Option<&Attribute>| {
+    let old_source = a.map(|attr| generate_attribute(attr, &options));
+    let new_source = b.map(|attr| generate_attribute(attr, &options));
+    if old_source != new_source {
+      ops.push(DiffOp::ChangeAttribute {
+        name: name.to_string(),
+        old: old_source,
+        new: new_source,
+        old_start: a.map_or(old.start(), |attr| attr.start),
+        old_end: a.map_or(old.end(), |attr| attr.end),
+        new_start: b.map_or(new.start(), |attr| attr.start),
+        new_end: b.map_or(new.end(), |attr| attr.end),
+      });
+    }
+  };
+  for attr in old_attrs {
+    if !new_attrs.iter().any(|other| other.name == attr.name) {
+      change(&attr.name, Some(attr), None);
+    }
+  }
+  for attr in new_attrs {
+    let previous = old_attrs.iter().find(|other| other.name == attr.name);
+    change(&attr.name, previous, Some(attr));
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::parser::Parser;
+
+  #[test]
+  fn structural_diff() {
+    let old = Parser::new(
+      r#"<view class="a" hidden><text>hi</text><image id="logo"/><button>ok</button><!--c--></view>"#,
+    )
+    .parse_root()
+    .unwrap();
+    let new = Parser::new(
+      r#"<view class="b" bindtap="t"><button>ok</button><text>hello</text><image id="logo"/><icon/></view>"#,
+    )
+    .parse_root()
+    .unwrap();
+    let ops: Vec<_> = diff(&old, &new)
+      .into_iter()
+      .map(|op| match op {
+        DiffOp::Insert {
+          node, new_start, ..
+        } => {
+          format!("insert <{}> @{}", node.name().unwrap(), new_start.offset)
+        }
+        DiffOp::Remove { old_start, .. } => format!("remove @{}", old_start.offset),
+        DiffOp::Move {
+          old_start,
+          new_start,
+          ..
+        } => format!("move @{} -> @{}", old_start.offset, new_start.offset),
+        DiffOp::ChangeAttribute { name, old, new, .. } => {
+          format!("attr {name}: {old:?} -> {new:?}")
+        }
+        DiffOp::ChangeText { old, new, .. } => format!("text {old:?} -> {new:?}"),
+      })
+      .collect();
+    assert_eq!(
+      ops,
+      [
+        r#"attr hidden: Some("hidden") -> None"#,
+        r#"attr class: Some("class=\"a\"") -> Some("class=\"b\"")"#,
+        r#"attr bindtap: None -> Some("bindtap=\"t\"")"#,
+        "remove @75",
+        "move @56 -> @28",
+        r#"text "hi" -> "hello""#,
+        "insert <icon> @83",
+      ]
+    );
+    assert!(diff(&old, &old).is_empty());
+  }
+}
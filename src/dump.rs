@@ -0,0 +1,143 @@
+//! 语法树的文本输出，用于调试
+//!
+//! 每个节点一行，子节点缩进两个空格，格式为 `类型@起始..结束` 加上名称或内容，起止为字节偏移量：
+//!
+//! ```text
+//! Root@0..31
+//!   Element@0..31 <view>
+//!     Attribute@6..15 class
+//!       Text@13..14 "a"
+//!     Text@16..19 "hi "
+//!     Expression@19..24 "x"
+//! ```
+//!
+//! 合成节点没有源码位置，显示为 `类型@?`；自闭合与错误恢复的元素在名称后标注 `self-closing`、`recovered`
+
+use std::fmt::Write;
+
+use crate::ast::{Attribute, AttributeValue, Node, Position, Root};
+
+impl Root {
+  /// 输出缩进的语法树，见 [`dump`](crate::dump)
+  pub fn dump_tree(&self) -> String {
+    let mut out = String::new();
+    line(&mut out, 0, "Root", self.start, self.end, "");
+    for child in &self.children {
+      dump_node(&mut out, child, 1);
+    }
+    out
+  }
+}
+
+impl Node {
+  /// 输出以该节点为根的缩进语法树，见 [`dump`](crate::dump)
+  pub fn dump_tree(&self) -> String {
+    let mut out = String::new();
+    dump_node(&mut out, self, 0);
+    out
+  }
+}
+
+fn dump_node(out: &mut String, node: &Node, depth: usize) {
+  let (start, end) = (node.start(), node.end());
+  match node {
+    Node::Element {
+      name,
+      attrs,
+      children,
+      self_closing,
+      recovered,
+      ..
+    } => {
+      let mut detail = format!("<{name}>");
+      if *self_closing {
+        detail.push_str(" self-closing");
+      }
+      if *recovered {
+        detail.push_str(" recovered");
+      }
+      line(out, depth, "Element", start, end, &detail);
+      for attr in attrs {
+        dump_attribute(out, attr, depth + 1);
+      }
+      for child in children {
+        dump_node(out, child, depth + 1);
+      }
+    }
+    Node::Text { content, .. } => line(out, depth, "Text", start, end, &format!("{content:?}")),
+    Node::Comment { content, .. } => {
+      line(out, depth, "Comment", start, end, &format!("{content:?}"))
+    }
+    Node::Expression { content, .. } => line(
+      out,
+      depth,
+      "Expression",
+      start,
+      end,
+      &format!("{content:?}"),
+    ),
+  }
+}
+
+fn dump_attribute(out: &mut String, attr: &Attribute, depth: usize) {
+  line(out, depth, "Attribute", attr.start, attr.end, &attr.name);
+  for value in attr.value.iter().flatten() {
+    let (kind, content, start, end) = match value {
+      AttributeValue::Text {
+        content,
+        start,
+        end,
+      } => ("Text", content, *start, *end),
+      AttributeValue::Expression {
+        content,
+        start,
+        end,
+      } => ("Expression", content, *start, *end),
+    };
+    line(out, depth + 1, kind, start, end, &format!("{content:?}"));
+  }
+}
+
+fn line(out: &mut String, depth: usize, kind: &str, start: Position, end: Position, detail: &str) {
+  let indent = "  ".repeat(depth);
+  if start.is_synthetic() {
+    write!(out, "{indent}{kind}@?").unwrap();
+  } else {
+    write!(out, "{indent}{kind}@{}..{}", start.offset, end.offset).unwrap();
+  }
+  if !detail.is_empty() {
+    write!(out, " {detail}").unwrap();
+  }
+  out.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{ast::Node, parser::Parser};
+
+  #[test]
+  fn dump_tree() {
+    let root = Parser::new("<view class=\"a\"\n>hi {{x}}<!--c--><image src/></view>")
+      .parse_root()
+      .unwrap();
+    assert_eq!(
+      root.dump_tree(),
+      r#"Root@0..52
+  Element@0..52 <view>
+    Attribute@6..15 class
+      Text@13..14 "a"
+    Text@17..20 "hi "
+    Expression@20..25 "x"
+    Comment@25..33 "c"
+    Element@33..45 <image> self-closing
+      Attribute@40..43 src
+"#
+    );
+    assert_eq!(
+      Node::element("view")
+        .with_child(Node::text("x"))
+        .dump_tree(),
+      "Element@? <view>\n  Text@? \"x\"\n"
+    );
+  }
+}
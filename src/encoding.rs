@@ -0,0 +1,179 @@
+//! 位置编码
+//!
+//! 解析器内部按字节偏移记录位置，列号按字符计算。编辑器与语言服务器使用的单位各不相同，
+//! 如 LSP 默认按 UTF-16 码元计算列号，直接使用字节偏移会在包含中文等非 ASCII 字符的模板中错位。
+//! 设置 [`ParserOptions::position_encoding`](crate::options::ParserOptions::position_encoding) 后，
+//! 解析结果中的所有位置在输出前按指定编码换算。自行换算位置时使用 [`LineIndex`]
+
+#[cfg(feature = "napi")]
+use napi_derive::napi;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  ast::{AttributeValue, Node, Position, Root},
+  error::SyntaxError,
+  line_index::LineIndex,
+};
+
+/// 位置中 `offset` 与 `column` 的计算单位
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "napi", napi(string_enum = "lowercase"))]
+#[serde(rename_all = "lowercase")]
+pub enum PositionEncoding {
+  /// 字节，即 Rust 字符串的下标
+  #[default]
+  Utf8,
+  /// UTF-16 码元，即 JavaScript 字符串的下标与 LSP 默认的列号
+  Utf16,
+  /// Unicode 字符
+  Utf32,
+}
+
+impl PositionEncoding {
+  /// `text` 按该编码计算的长度
+  pub fn len(&self, text: &str) -> u32 {
+    match self {
+      PositionEncoding::Utf8 => text.len() as u32,
+      PositionEncoding::Utf16 => text.chars().map(char::len_utf16).sum::<usize>() as u32,
+      PositionEncoding::Utf32 => text.chars().count() as u32,
+    }
+  }
+}
+
+/// 把解析器产生的位置换算为指定编码
+pub(crate) struct Encoder<'s> {
+  index: LineIndex<'s>,
+  encoding: PositionEncoding,
+}
+
+impl<'s> Encoder<'s> {
+  pub(crate) fn new(source: &'s str, encoding: PositionEncoding) -> Self {
+    Self {
+      index: LineIndex::new(source),
+      encoding,
+    }
+  }
+
+  /// 换算单个位置：`offset` 为从文件开始、`column` 为从行首开始按编码计算的长度，列号仍从 1 开始。
+  /// 合成位置与超出源码范围的位置保持不变
+  pub(crate) fn encode(&self, position: Position) -> Position {
+    if position.is_synthetic() {
+      return position;
+    }
+    self
+      .index
+      .position(position.offset as usize, self.encoding)
+      .unwrap_or(position)
+  }
+
+  pub(crate) fn encode_root(&self, root: &mut Root) {
+    root.start = self.encode(root.start);
+    root.end = self.encode(root.end);
+    for comment in root.comments.iter_mut().flatten() {
+      comment.owner = comment.owner.map(|owner| self.encode(owner));
+      comment.start = self.encode(comment.start);
+      comment.end = self.encode(comment.end);
+    }
+    for node in &mut root.children {
+      node.walk_mut(&mut |node| self.encode_node(node));
+    }
+  }
+
+  fn encode_node(&self, node: &mut Node) {
+    match node {
+      Node::Element {
+        attrs,
+        close_start,
+        close_end,
+        start,
+        end,
+        ..
+      } => {
+        for attr in attrs {
+          attr.start = self.encode(attr.start);
+          attr.end = self.encode(attr.end);
+          for value in attr.value.iter_mut().flatten() {
+            let (AttributeValue::Text { start, end, .. }
+            | AttributeValue::Expression { start, end, .. }) = value;
+            *start = self.encode(*start);
+            *end = self.encode(*end);
+          }
+        }
+        *close_start = close_start.map(|position| self.encode(position));
+        *close_end = close_end.map(|position| self.encode(position));
+        *start = self.encode(*start);
+        *end = self.encode(*end);
+      }
+      Node::Text { start, end, .. }
+      | Node::Comment { start, end, .. }
+      | Node::Expression { start, end, .. } => {
+        *start = self.encode(*start);
+        *end = self.encode(*end);
+      }
+    }
+  }
+
+  pub(crate) fn encode_error(&self, error: &mut SyntaxError) {
+    let position = self.encode(Position {
+      offset: error.offset,
+      line: error.line,
+      column: error.column,
+    });
+    error.offset = position.offset;
+    error.column = position.column;
+    if let Some(tag) = &mut error.open_tag {
+      tag.start = self.encode(tag.start);
+      tag.end = self.encode(tag.end);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{options::ParserOptions, parser::Parser};
+
+  #[test]
+  fn position_encodings() {
+    let source = "<view>中文😀\n<text a=\"é\"/></view>";
+    let positions = |encoding| {
+      let options = ParserOptions {
+        position_encoding: encoding,
+        ..Default::default()
+      };
+      let root = Parser::with_options(source, options).parse_root().unwrap();
+      let view = &root.children[0];
+      let text = &view.children().unwrap()[1];
+      let attr_end = text.attrs().unwrap()[0].end;
+      (text.start(), attr_end.column, root.end.offset)
+    };
+    let position = |offset, line, column| Position {
+      offset,
+      line,
+      column,
+    };
+    // 默认：字节偏移，列号按字符计算
+    assert_eq!(positions(None), (position(17, 2, 1), 12, 38));
+    assert_eq!(
+      positions(Some(PositionEncoding::Utf8)),
+      (position(17, 2, 1), 13, 38)
+    );
+    assert_eq!(
+      positions(Some(PositionEncoding::Utf16)),
+      (position(11, 2, 1), 12, 31)
+    );
+    assert_eq!(
+      positions(Some(PositionEncoding::Utf32)),
+      (position(10, 2, 1), 12, 30)
+    );
+
+    let options = ParserOptions {
+      position_encoding: Some(PositionEncoding::Utf16),
+      ..Default::default()
+    };
+    let mut parser = Parser::with_options("<view>😀</text>", options);
+    let error = parser.parse_root().unwrap_err();
+    assert_eq!((error.offset, error.column), (8, 9));
+    assert_eq!(parser.errors()[0].offset, 8);
+  }
+}
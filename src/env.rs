@@ -0,0 +1,237 @@
+//! 构建期环境变量替换
+//!
+//! 把模板中的占位符替换为构建时确定的值，如接口地址、版本号、CDN 前缀。支持两种占位符：
+//!
+//! - [`Placeholder::Delimited`]：静态文本与属性值中以定界符包围的名称，如 `%CDN_URL%/logo.png`
+//! - [`Placeholder::ProcessEnv`]：恰好为 `process.env.NAME` 的插值表达式，如 `{{ process.env.VERSION }}`，
+//!   替换后成为静态文本
+//!
+//! 替换的值按原样写入语法树，不做转义
+
+use serde::{Deserialize, Serialize};
+
+use crate::ast::{AttributeValue, Node, Position, Root};
+
+/// 占位符语法
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Placeholder {
+  /// 以 `open`、`close` 包围的名称，名称由字母、数字与 `_` 组成且不以数字开头
+  Delimited { open: String, close: String },
+  /// `{{ process.env.NAME }}`
+  ProcessEnv,
+}
+
+impl Default for Placeholder {
+  /// `%NAME%`
+  fn default() -> Self {
+    Placeholder::Delimited {
+      open: "%".to_string(),
+      close: "%".to_string(),
+    }
+  }
+}
+
+/// 一处占位符
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Substitution {
+  pub name: String,
+  /// 替换后的值，变量未定义时为 `None`，占位符保持原样
+  pub value: Option<String>,
+  /// 占位符在源码中的位置
+  pub start: Position,
+  pub end: Position,
+}
+
+/// 替换文档中的占位符，`lookup` 按名称查找变量的值，如 `|name| std::env::var(name).ok()`。
+/// 按文档顺序返回找到的所有占位符，包括未定义而没有替换的
+pub fn substitute_env(
+  root: &mut Root,
+  placeholder: &Placeholder,
+  lookup: &dyn Fn(&str) -> Option<String>,
+) -> Vec<Substitution> {
+  let mut report = Vec::new();
+  for node in &mut root.children {
+    node.walk_mut(&mut |node| match node {
+      Node::Text { content, start, .. } => {
+        replace_delimited(content, *start, placeholder, lookup, &mut report)
+      }
+      Node::Expression {
+        content,
+        start,
+        end,
+      } => {
+        if let Some(value) =
+          replace_process_env(content, *start, *end, placeholder, lookup, &mut report)
+        {
+          *node = Node::Text {
+            content: value,
+            start: *start,
+            end: *end,
+          };
+        }
+      }
+      Node::Element { attrs, .. } => {
+        for value in attrs
+          .iter_mut()
+          .flat_map(|attr| attr.value.iter_mut().flatten())
+        {
+          match value {
+            AttributeValue::Text { content, start, .. } => {
+              replace_delimited(content, *start, placeholder, lookup, &mut report)
+            }
+            AttributeValue::Expression {
+              content,
+              start,
+              end,
+            } => {
+              if let Some(text) =
+                replace_process_env(content, *start, *end, placeholder, lookup, &mut report)
+              {
+                *value = AttributeValue::Text {
+                  content: text,
+                  start: *start,
+                  end: *end,
+                };
+              }
+            }
+          }
+        }
+      }
+      Node::Comment { .. } => {}
+    });
+  }
+  report
+}
+
+fn replace_delimited(
+  content: &mut String,
+  start: Position,
+  placeholder: &Placeholder,
+  lookup: &dyn Fn(&str) -> Option<String>,
+  report: &mut Vec<Substitution>,
+) {
+  let Placeholder::Delimited { open, close } = placeholder else {
+    return;
+  };
+  if open.is_empty() || close.is_empty() || !content.contains(open.as_str()) {
+    return;
+  }
+  let mut output = String::with_capacity(content.len());
+  // `content` 中已处理到的位置，以及对应的源码位置
+  let (mut index, mut position) = (0, start);
+  while let Some(found) = content[index..].find(open.as_str()) {
+    let name_start = index + found + open.len();
+    let name_len = content[name_start..]
+      .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+      .unwrap_or(content.len() - name_start);
+    let name = &content[name_start..name_start + name_len];
+    let valid = !name.is_empty()
+      && !name.starts_with(|c: char| c.is_ascii_digit())
+      && content[name_start + name_len..].starts_with(close.as_str());
+    if !valid {
+      // 不是占位符，保留定界符并继续向后查找
+      output.push_str(&content[index..name_start]);
+      position = position.advance(&content[index..name_start]);
+      index = name_start;
+      continue;
+    }
+    let placeholder_start = index + found;
+    let placeholder_end = name_start + name_len + close.len();
+    output.push_str(&content[index..placeholder_start]);
+    let begin = position.advance(&content[index..placeholder_start]);
+    let end = begin.advance(&content[placeholder_start..placeholder_end]);
+    let value = lookup(name);
+    output.push_str(
+      value
+        .as_deref()
+        .unwrap_or(&content[placeholder_start..placeholder_end]),
+    );
+    report.push(Substitution {
+      name: name.to_string(),
+      value,
+      start: begin,
+      end,
+    });
+    (index, position) = (placeholder_end, end);
+  }
+  output.push_str(&content[index..]);
+  *content = output;
+}
+
+/// 表达式恰好为 `process.env.NAME` 且变量已定义时返回变量的值
+fn replace_process_env(
+  content: &str,
+  start: Position,
+  end: Position,
+  placeholder: &Placeholder,
+  lookup: &dyn Fn(&str) -> Option<String>,
+  report: &mut Vec<Substitution>,
+) -> Option<String> {
+  if *placeholder != Placeholder::ProcessEnv {
+    return None;
+  }
+  let name = content.trim().strip_prefix("process.env.")?;
+  if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+    return None;
+  }
+  let value = lookup(name);
+  report.push(Substitution {
+    name: name.to_string(),
+    value: value.clone(),
+    start,
+    end,
+  });
+  value
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    codegen::{generate, CodegenOptions},
+    parser::Parser,
+  };
+
+  #[test]
+  fn env_substitution() {
+    let lookup = |name: &str| match name {
+      "CDN" => Some("https://cdn.example.com".to_string()),
+      "VERSION" => Some("1.2.0".to_string()),
+      _ => None,
+    };
+    let source = r#"<image src="%CDN%/logo.png" alt="100% %MISSING%"/><text>v%VERSION% {{ process.env.VERSION }}</text>"#;
+    let mut root = Parser::new(source).parse_root().unwrap();
+    let report = substitute_env(&mut root, &Placeholder::default(), &lookup);
+    assert_eq!(
+      generate(&root, &CodegenOptions::default()),
+      r#"<image src="https://cdn.example.com/logo.png" alt="100% %MISSING%" /><text>v1.2.0 {{process.env.VERSION}}</text>"#
+    );
+    let found: Vec<_> = report
+      .iter()
+      .map(|s| {
+        (
+          s.name.as_str(),
+          s.value.is_some(),
+          s.start.offset,
+          s.end.offset,
+        )
+      })
+      .collect();
+    assert_eq!(
+      found,
+      [
+        ("CDN", true, 12, 17),
+        ("MISSING", false, 38, 47),
+        ("VERSION", true, 57, 66)
+      ]
+    );
+
+    let report = substitute_env(&mut root, &Placeholder::ProcessEnv, &lookup);
+    assert_eq!(report.len(), 1);
+    assert_eq!(
+      generate(&root, &CodegenOptions::default()),
+      r#"<image src="https://cdn.example.com/logo.png" alt="100% %MISSING%" /><text>v1.2.0 1.2.0</text>"#
+    );
+  }
+}
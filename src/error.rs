@@ -1,18 +1,42 @@
+#[cfg(feature = "napi")]
 use napi_derive::napi;
+use serde::{Deserialize, Serialize};
 use std::{borrow::Cow, error::Error, fmt};
 
-#[derive(Debug, Clone, Copy)]
-#[napi(object)]
+use crate::ast::Position;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi", napi(object))]
 /// Syntax error when parsing tags, not `<script>` or `<style>` tag.
 pub struct SyntaxError {
   pub kind: SyntaxErrorKind,
+  /// Stable error code of `kind`, such as `WXML1003`. See [`SyntaxErrorKind::code`].
+  pub code: String,
   pub offset: u32,
   pub line: u32,
   pub column: u32,
+  /// The opening tag that an `ExpectCloseTag` error refers to, so editors can
+  /// highlight both ends of a mismatch. `None` for a closing tag without any
+  /// open element.
+  #[serde(default)]
+  pub open_tag: Option<OpenTag>,
+}
+
+/// Name and span of an opening tag, from `<` to the closing `>`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "napi", napi(object))]
+pub struct OpenTag {
+  pub name: String,
+  pub start: Position,
+  pub end: Position,
 }
 
-#[derive(Debug, Clone, Copy)]
-#[napi]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "napi", napi)]
 pub enum SyntaxErrorKind {
   ExpectAttrName,
   ExpectAttrValue,
@@ -27,6 +51,36 @@ pub enum SyntaxErrorKind {
   ExpectTagName,
   ExpectTextNode,
   ExpectExpression,
+  ResourceLimitExceeded,
+  IgnoredDeclaration,
+  StrayLessThan,
+}
+
+impl SyntaxErrorKind {
+  /// Stable code for this kind of error.
+  ///
+  /// Codes never change once assigned, so editors and CI tools can key off them
+  /// instead of the English message. New kinds get the next free number.
+  pub fn code(&self) -> &'static str {
+    match self {
+      SyntaxErrorKind::ExpectAttrName => "WXML1001",
+      SyntaxErrorKind::ExpectAttrValue => "WXML1002",
+      SyntaxErrorKind::ExpectCloseTag => "WXML1003",
+      SyntaxErrorKind::ExpectComment => "WXML1004",
+      SyntaxErrorKind::ExpectDoctype => "WXML1005",
+      SyntaxErrorKind::ExpectElement => "WXML1006",
+      SyntaxErrorKind::ExpectFrontMatter => "WXML1007",
+      SyntaxErrorKind::ExpectIdentifier => "WXML1008",
+      SyntaxErrorKind::ExpectMustacheInterpolation => "WXML1009",
+      SyntaxErrorKind::ExpectSelfCloseTag => "WXML1010",
+      SyntaxErrorKind::ExpectTagName => "WXML1011",
+      SyntaxErrorKind::ExpectTextNode => "WXML1012",
+      SyntaxErrorKind::ExpectExpression => "WXML1013",
+      SyntaxErrorKind::ResourceLimitExceeded => "WXML1014",
+      SyntaxErrorKind::IgnoredDeclaration => "WXML1015",
+      SyntaxErrorKind::StrayLessThan => "WXML1016",
+    }
+  }
 }
 
 impl fmt::Display for SyntaxErrorKind {
@@ -45,6 +99,13 @@ impl fmt::Display for SyntaxErrorKind {
       SyntaxErrorKind::ExpectTagName => "expected tag name".into(),
       SyntaxErrorKind::ExpectTextNode => "expected text node".into(),
       SyntaxErrorKind::ExpectExpression => "expected expression".into(),
+      SyntaxErrorKind::ResourceLimitExceeded => {
+        "nesting depth, attribute count or source length exceeds the configured limit".into()
+      }
+      SyntaxErrorKind::IgnoredDeclaration => {
+        "`<!DOCTYPE>` and `<?xml ?>` declarations are not supported and were ignored".into()
+      }
+      SyntaxErrorKind::StrayLessThan => "`<` does not start a tag and was treated as text".into(),
     };
 
     write!(f, "{reason}")
@@ -61,4 +122,23 @@ impl fmt::Display for SyntaxError {
   }
 }
 
+impl SyntaxError {
+  /// Serialize the error as a JSON object, including its code.
+  pub fn to_json(&self) -> String {
+    self.to_json_value().to_string()
+  }
+
+  /// Same as [`SyntaxError::to_json`], but returns the JSON value for embedding in larger reports.
+  pub fn to_json_value(&self) -> serde_json::Value {
+    serde_json::json!({
+      "code": self.code,
+      "message": self.kind.to_string(),
+      "offset": self.offset,
+      "line": self.line,
+      "column": self.column,
+      "openTag": self.open_tag,
+    })
+  }
+}
+
 impl Error for SyntaxError {}
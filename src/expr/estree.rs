@@ -0,0 +1,147 @@
+use serde_json::{json, Value};
+
+use super::{BinaryOp, Expr, ObjectProperty};
+
+impl Expr {
+  /// 转换为 [ESTree](https://github.com/estree/estree) 结构的 JSON，
+  /// 便于 eslint 规则、babel visitor 等现有 JS 工具直接处理
+  ///
+  /// 表达式语法树不记录位置，输出的节点不包含 `range`、`loc`；
+  /// `undefined` 按 JavaScript 的语义输出为同名的 `Identifier`
+  pub fn to_estree(&self) -> Value {
+    match self {
+      Expr::Null => json!({ "type": "Literal", "value": null, "raw": "null" }),
+      Expr::Undefined => identifier("undefined"),
+      Expr::Bool(value) => json!({ "type": "Literal", "value": value, "raw": value.to_string() }),
+      Expr::Number(value) => json!({ "type": "Literal", "value": value }),
+      Expr::String(value) => json!({ "type": "Literal", "value": value }),
+      Expr::Ident(name) => identifier(name),
+      Expr::Array(elements) => json!({
+        "type": "ArrayExpression",
+        "elements": elements.iter().map(Expr::to_estree).collect::<Vec<_>>(),
+      }),
+      Expr::Object(properties) => json!({
+        "type": "ObjectExpression",
+        "properties": properties.iter().map(property).collect::<Vec<_>>(),
+      }),
+      Expr::Member { object, property } => json!({
+        "type": "MemberExpression",
+        "object": object.to_estree(),
+        "property": identifier(property),
+        "computed": false,
+        "optional": false,
+      }),
+      Expr::Index { object, index } => json!({
+        "type": "MemberExpression",
+        "object": object.to_estree(),
+        "property": index.to_estree(),
+        "computed": true,
+        "optional": false,
+      }),
+      Expr::Call { callee, args } => json!({
+        "type": "CallExpression",
+        "callee": callee.to_estree(),
+        "arguments": args.iter().map(Expr::to_estree).collect::<Vec<_>>(),
+        "optional": false,
+      }),
+      Expr::Unary { op, argument } => json!({
+        "type": "UnaryExpression",
+        "operator": op.as_str(),
+        "prefix": true,
+        "argument": argument.to_estree(),
+      }),
+      Expr::Binary { op, left, right } => {
+        let kind = match op {
+          BinaryOp::And | BinaryOp::Or | BinaryOp::Nullish => "LogicalExpression",
+          _ => "BinaryExpression",
+        };
+        json!({
+          "type": kind,
+          "operator": op.as_str(),
+          "left": left.to_estree(),
+          "right": right.to_estree(),
+        })
+      }
+      Expr::Conditional {
+        test,
+        consequent,
+        alternate,
+      } => json!({
+        "type": "ConditionalExpression",
+        "test": test.to_estree(),
+        "consequent": consequent.to_estree(),
+        "alternate": alternate.to_estree(),
+      }),
+      Expr::Spread(argument) => json!({
+        "type": "SpreadElement",
+        "argument": argument.to_estree(),
+      }),
+    }
+  }
+}
+
+fn identifier(name: &str) -> Value {
+  json!({ "type": "Identifier", "name": name })
+}
+
+fn property(property: &ObjectProperty) -> Value {
+  match property {
+    ObjectProperty::Property { key, value } => {
+      let is_identifier = key
+        .chars()
+        .next()
+        .is_some_and(|ch| ch.is_alphabetic() || ch == '_' || ch == '$')
+        && key
+          .chars()
+          .all(|ch| ch.is_alphanumeric() || ch == '_' || ch == '$');
+      let key_node = if is_identifier {
+        identifier(key)
+      } else {
+        json!({ "type": "Literal", "value": key })
+      };
+      json!({
+        "type": "Property",
+        "key": key_node,
+        "value": value.to_estree(),
+        "kind": "init",
+        "computed": false,
+        "method": false,
+        "shorthand": matches!(value, Expr::Ident(name) if name == key),
+      })
+    }
+    ObjectProperty::Spread(argument) => json!({
+      "type": "SpreadElement",
+      "argument": argument.to_estree(),
+    }),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use crate::expr::parse_expression;
+
+  #[test]
+  fn estree_shape() {
+    let expr = parse_expression("!a.b[0] && f({ c, 'd-e': 1, ...g }) ? x : undefined").unwrap();
+    let tree = expr.to_estree();
+    assert_eq!(tree["type"], "ConditionalExpression");
+    let test = &tree["test"];
+    assert_eq!(test["type"], "LogicalExpression");
+    assert_eq!(test["left"]["operator"], "!");
+    assert_eq!(test["left"]["argument"]["computed"], true);
+    assert_eq!(test["left"]["argument"]["object"]["property"]["name"], "b");
+    let properties = &test["right"]["arguments"][0]["properties"];
+    assert_eq!(properties[0]["shorthand"], true);
+    assert_eq!(
+      properties[1]["key"],
+      json!({ "type": "Literal", "value": "d-e" })
+    );
+    assert_eq!(properties[2]["type"], "SpreadElement");
+    assert_eq!(
+      tree["alternate"],
+      json!({ "type": "Identifier", "name": "undefined" })
+    );
+  }
+}
@@ -0,0 +1,504 @@
+//! 表达式求值，语义尽量贴近 JavaScript；`undefined` 与 `null` 均以 `Value::Null` 表示
+//!
+//! 求值器可用于渲染与常量折叠，不会访问数据以外的任何状态
+
+use std::collections::{HashMap, HashSet};
+
+use serde_json::{Map, Value};
+
+use super::{BinaryOp, Expr, ExprError, ObjectProperty, UnaryOp};
+
+/// 可在表达式中调用的函数
+pub type Function = Box<dyn Fn(&[Value]) -> Result<Value, String> + Send + Sync>;
+
+/// 内置的纯函数方法，如 `price.toFixed(2)`、`list.join(',')`
+pub const BUILTIN_METHODS: &[&str] = &[
+  "toFixed",
+  "toString",
+  "indexOf",
+  "includes",
+  "join",
+  "slice",
+  "toUpperCase",
+  "toLowerCase",
+  "trim",
+  "split",
+];
+
+/// 安全的表达式求值器
+///
+/// 只能读取传入的数据，不会产生副作用；函数调用受白名单限制：
+/// 通过 [`Sandbox::with_function`] 注册的函数（如 `util.format`）以及允许的内置方法
+pub struct Sandbox {
+  functions: HashMap<String, Function>,
+  methods: HashSet<&'static str>,
+}
+
+impl Default for Sandbox {
+  /// 允许所有内置方法，不包含自定义函数
+  fn default() -> Self {
+    Self {
+      functions: HashMap::new(),
+      methods: BUILTIN_METHODS.iter().copied().collect(),
+    }
+  }
+}
+
+impl Sandbox {
+  /// 创建不允许任何函数调用的求值器
+  pub fn empty() -> Self {
+    Self {
+      functions: HashMap::new(),
+      methods: HashSet::new(),
+    }
+  }
+
+  /// 注册可调用的函数，`path` 为调用时的名称，如 `format` 或 `util.format`
+  pub fn with_function<F>(mut self, path: impl Into<String>, function: F) -> Self
+  where
+    F: Fn(&[Value]) -> Result<Value, String> + Send + Sync + 'static,
+  {
+    self.functions.insert(path.into(), Box::new(function));
+    self
+  }
+
+  /// 允许调用指定的内置方法，`name` 须在 [`BUILTIN_METHODS`] 中
+  pub fn with_method(mut self, name: &str) -> Self {
+    if let Some(name) = BUILTIN_METHODS.iter().find(|method| **method == name) {
+      self.methods.insert(name);
+    }
+    self
+  }
+
+  /// 禁止调用指定的内置方法
+  pub fn without_method(mut self, name: &str) -> Self {
+    self.methods.remove(name);
+    self
+  }
+
+  /// 以 JSON 对象作为数据求值
+  pub fn evaluate(&self, expr: &Expr, data: &Value) -> Result<Value, ExprError> {
+    self.evaluate_with(expr, &|name| data.get(name).cloned())
+  }
+
+  /// 在给定作用域中求值，`lookup` 按名称查找变量，未定义的变量视为 `undefined`
+  pub fn evaluate_with(
+    &self,
+    expr: &Expr,
+    lookup: &dyn Fn(&str) -> Option<Value>,
+  ) -> Result<Value, ExprError> {
+    let eval = |expr| self.evaluate_with(expr, lookup);
+    let value = match expr {
+      Expr::Null | Expr::Undefined => Value::Null,
+      Expr::Bool(value) => Value::Bool(*value),
+      Expr::Number(value) => number(*value),
+      Expr::String(value) => Value::String(value.clone()),
+      Expr::Ident(name) => lookup(name).unwrap_or(Value::Null),
+      Expr::Array(items) => Value::Array(self.evaluate_list(items, lookup)?),
+      Expr::Object(properties) => {
+        let mut object = Map::new();
+        for property in properties {
+          match property {
+            ObjectProperty::Property { key, value } => {
+              object.insert(key.clone(), eval(value)?);
+            }
+            ObjectProperty::Spread(value) => {
+              if let Value::Object(spread) = eval(value)? {
+                object.extend(spread);
+              }
+            }
+          }
+        }
+        Value::Object(object)
+      }
+      Expr::Member { object, property } => member(&eval(object)?, property),
+      Expr::Index { object, index } => {
+        let object = eval(object)?;
+        match eval(index)? {
+          Value::Number(index) => match (&object, index.as_u64()) {
+            (Value::Array(items), Some(index)) => {
+              items.get(index as usize).cloned().unwrap_or(Value::Null)
+            }
+            _ => member(&object, &index.to_string()),
+          },
+          index => member(&object, &to_display_string(&index)),
+        }
+      }
+      Expr::Call { callee, args } => {
+        let args = self.evaluate_list(args, lookup)?;
+        self.call(callee, &args, lookup)?
+      }
+      Expr::Unary { op, argument } => {
+        let argument = eval(argument)?;
+        match op {
+          UnaryOp::Not => Value::Bool(!truthy(&argument)),
+          UnaryOp::Minus => number(-to_number(&argument)),
+          UnaryOp::Plus => number(to_number(&argument)),
+          UnaryOp::Typeof => Value::String(type_of(&argument).to_string()),
+        }
+      }
+      Expr::Binary { op, left, right } => {
+        let left = eval(left)?;
+        // 逻辑运算短路求值
+        match op {
+          BinaryOp::And if !truthy(&left) => return Ok(left),
+          BinaryOp::Or if truthy(&left) => return Ok(left),
+          BinaryOp::Nullish if !left.is_null() => return Ok(left),
+          BinaryOp::And | BinaryOp::Or | BinaryOp::Nullish => return eval(right),
+          _ => {}
+        }
+        binary(*op, &left, &eval(right)?)
+      }
+      Expr::Conditional {
+        test,
+        consequent,
+        alternate,
+      } => {
+        if truthy(&eval(test)?) {
+          eval(consequent)?
+        } else {
+          eval(alternate)?
+        }
+      }
+      Expr::Spread(_) => return Err(ExprError::new("unexpected spread `...`", 0)),
+    };
+    Ok(value)
+  }
+
+  /// 求值数组元素或调用参数，展开其中的 `...items`：数组展开为元素，字符串展开为字符
+  fn evaluate_list(
+    &self,
+    items: &[Expr],
+    lookup: &dyn Fn(&str) -> Option<Value>,
+  ) -> Result<Vec<Value>, ExprError> {
+    let mut values = Vec::with_capacity(items.len());
+    for item in items {
+      let Expr::Spread(argument) = item else {
+        values.push(self.evaluate_with(item, lookup)?);
+        continue;
+      };
+      match self.evaluate_with(argument, lookup)? {
+        Value::Array(items) => values.extend(items),
+        Value::String(text) => values.extend(text.chars().map(|ch| Value::String(ch.to_string()))),
+        value => {
+          return Err(ExprError::new(
+            format!("cannot spread {}", type_of(&value)),
+            0,
+          ))
+        }
+      }
+    }
+    Ok(values)
+  }
+
+  fn call(
+    &self,
+    callee: &Expr,
+    args: &[Value],
+    lookup: &dyn Fn(&str) -> Option<Value>,
+  ) -> Result<Value, ExprError> {
+    let path = callee_path(callee);
+    if let Some(function) = path.as_deref().and_then(|path| self.functions.get(path)) {
+      return function(args).map_err(|message| ExprError::new(message, 0));
+    }
+    if let Expr::Member { object, property } = callee {
+      if let Some(method) = self.methods.get(property.as_str()) {
+        let object = self.evaluate_with(object, lookup)?;
+        return Ok(call_method(&object, method, args));
+      }
+    }
+    let name = path.unwrap_or_else(|| "expression".to_string());
+    Err(ExprError::new(
+      format!("call to `{name}` is not allowed"),
+      0,
+    ))
+  }
+}
+
+/// 由标识符与静态成员访问组成的调用路径，如 `util.format`
+fn callee_path(callee: &Expr) -> Option<String> {
+  match callee {
+    Expr::Ident(name) => Some(name.clone()),
+    Expr::Member { object, property } => Some(format!("{}.{property}", callee_path(object)?)),
+    _ => None,
+  }
+}
+
+/// 执行内置方法，接收者类型不匹配时返回 `undefined`
+fn call_method(object: &Value, method: &str, args: &[Value]) -> Value {
+  let arg = |index: usize| args.get(index).unwrap_or(&Value::Null);
+  // `slice` 的下标参数，支持负数
+  let bound = |index: usize, len: usize, default: usize| match arg(index) {
+    Value::Null => default,
+    value => {
+      let n = to_number(value);
+      if n < 0.0 {
+        len.saturating_sub((-n) as usize)
+      } else {
+        (n as usize).min(len)
+      }
+    }
+  };
+  match (object, method) {
+    (Value::Number(_), "toFixed") => {
+      let digits = to_number(arg(0)).clamp(0.0, 100.0) as usize;
+      Value::String(format!("{:.digits$}", to_number(object)))
+    }
+    (_, "toString") => Value::String(to_display_string(object)),
+    (Value::String(value), "indexOf") => {
+      let needle = to_display_string(arg(0));
+      number(
+        value
+          .find(&needle)
+          .map_or(-1.0, |index| value[..index].encode_utf16().count() as f64),
+      )
+    }
+    (Value::Array(items), "indexOf") => number(
+      items
+        .iter()
+        .position(|item| strict_equals(item, arg(0)))
+        .map_or(-1.0, |index| index as f64),
+    ),
+    (Value::String(value), "includes") => Value::Bool(value.contains(&to_display_string(arg(0)))),
+    (Value::Array(items), "includes") => {
+      Value::Bool(items.iter().any(|item| strict_equals(item, arg(0))))
+    }
+    (Value::Array(items), "join") => {
+      let separator = match arg(0) {
+        Value::Null => ",".to_string(),
+        value => to_display_string(value),
+      };
+      Value::String(
+        items
+          .iter()
+          .map(to_display_string)
+          .collect::<Vec<_>>()
+          .join(&separator),
+      )
+    }
+    (Value::Array(items), "slice") => {
+      let start = bound(0, items.len(), 0);
+      let end = bound(1, items.len(), items.len());
+      Value::Array(items.get(start..end).unwrap_or_default().to_vec())
+    }
+    (Value::String(value), "slice") => {
+      let chars: Vec<char> = value.chars().collect();
+      let start = bound(0, chars.len(), 0);
+      let end = bound(1, chars.len(), chars.len());
+      Value::String(chars.get(start..end).unwrap_or_default().iter().collect())
+    }
+    (Value::String(value), "toUpperCase") => Value::String(value.to_uppercase()),
+    (Value::String(value), "toLowerCase") => Value::String(value.to_lowercase()),
+    (Value::String(value), "trim") => Value::String(value.trim().to_string()),
+    (Value::String(value), "split") => {
+      let parts: Vec<Value> = match arg(0) {
+        Value::Null => vec![Value::String(value.clone())],
+        separator => {
+          let separator = to_display_string(separator);
+          if separator.is_empty() {
+            value
+              .chars()
+              .map(|ch| Value::String(ch.to_string()))
+              .collect()
+          } else {
+            value
+              .split(separator.as_str())
+              .map(|part| Value::String(part.to_string()))
+              .collect()
+          }
+        }
+      };
+      Value::Array(parts)
+    }
+    _ => Value::Null,
+  }
+}
+
+fn binary(op: BinaryOp, left: &Value, right: &Value) -> Value {
+  match op {
+    BinaryOp::Add => {
+      if is_primitive_number(left) && is_primitive_number(right) {
+        number(to_number(left) + to_number(right))
+      } else {
+        Value::String(to_display_string(left) + &to_display_string(right))
+      }
+    }
+    BinaryOp::Sub => number(to_number(left) - to_number(right)),
+    BinaryOp::Mul => number(to_number(left) * to_number(right)),
+    BinaryOp::Div => number(to_number(left) / to_number(right)),
+    BinaryOp::Rem => number(to_number(left) % to_number(right)),
+    BinaryOp::Lt | BinaryOp::Gt | BinaryOp::LtEq | BinaryOp::GtEq => {
+      let ordering = match (left, right) {
+        (Value::String(left), Value::String(right)) => Some(left.cmp(right)),
+        _ => to_number(left).partial_cmp(&to_number(right)),
+      };
+      Value::Bool(ordering.is_some_and(|ordering| match op {
+        BinaryOp::Lt => ordering.is_lt(),
+        BinaryOp::Gt => ordering.is_gt(),
+        BinaryOp::LtEq => ordering.is_le(),
+        _ => ordering.is_ge(),
+      }))
+    }
+    BinaryOp::Eq => Value::Bool(loose_equals(left, right)),
+    BinaryOp::NotEq => Value::Bool(!loose_equals(left, right)),
+    BinaryOp::StrictEq => Value::Bool(strict_equals(left, right)),
+    BinaryOp::StrictNotEq => Value::Bool(!strict_equals(left, right)),
+    BinaryOp::And | BinaryOp::Or | BinaryOp::Nullish => unreachable!("handled by evaluate_with"),
+  }
+}
+
+/// 读取对象属性，数组与字符串支持 `length`
+fn member(object: &Value, property: &str) -> Value {
+  match object {
+    Value::Object(map) => map.get(property).cloned().unwrap_or(Value::Null),
+    Value::Array(items) if property == "length" => Value::from(items.len()),
+    Value::Array(items) => property
+      .parse::<usize>()
+      .ok()
+      .and_then(|index| items.get(index).cloned())
+      .unwrap_or(Value::Null),
+    Value::String(value) if property == "length" => Value::from(value.encode_utf16().count()),
+    Value::String(value) => property
+      .parse::<usize>()
+      .ok()
+      .and_then(|index| value.chars().nth(index))
+      .map(|ch| Value::String(ch.to_string()))
+      .unwrap_or(Value::Null),
+    _ => Value::Null,
+  }
+}
+
+/// 将 `f64` 转换为 JSON 值，整数保持整数形式，`NaN` 与无穷大转换为 `null`
+pub(crate) fn number(value: f64) -> Value {
+  if value.fract() == 0.0 && value.abs() < 9007199254740992.0 {
+    Value::from(value as i64)
+  } else {
+    serde_json::Number::from_f64(value).map_or(Value::Null, Value::Number)
+  }
+}
+
+fn is_primitive_number(value: &Value) -> bool {
+  matches!(value, Value::Null | Value::Bool(_) | Value::Number(_))
+}
+
+fn to_number(value: &Value) -> f64 {
+  match value {
+    Value::Null => 0.0,
+    Value::Bool(value) => *value as u8 as f64,
+    Value::Number(value) => value.as_f64().unwrap_or(f64::NAN),
+    Value::String(value) => {
+      let value = value.trim();
+      if value.is_empty() {
+        0.0
+      } else {
+        value.parse().unwrap_or(f64::NAN)
+      }
+    }
+    Value::Array(_) | Value::Object(_) => f64::NAN,
+  }
+}
+
+/// JavaScript 的真值判断
+pub(crate) fn truthy(value: &Value) -> bool {
+  match value {
+    Value::Null => false,
+    Value::Bool(value) => *value,
+    Value::Number(value) => value.as_f64().is_some_and(|n| n != 0.0 && !n.is_nan()),
+    Value::String(value) => !value.is_empty(),
+    Value::Array(_) | Value::Object(_) => true,
+  }
+}
+
+fn type_of(value: &Value) -> &'static str {
+  match value {
+    Value::Null => "undefined",
+    Value::Bool(_) => "boolean",
+    Value::Number(_) => "number",
+    Value::String(_) => "string",
+    Value::Array(_) | Value::Object(_) => "object",
+  }
+}
+
+fn loose_equals(left: &Value, right: &Value) -> bool {
+  match (left, right) {
+    (Value::Null, Value::Null) => true,
+    (Value::Null, _) | (_, Value::Null) => false,
+    (Value::String(left), Value::String(right)) => left == right,
+    (Value::Array(_) | Value::Object(_), _) | (_, Value::Array(_) | Value::Object(_)) => {
+      left == right
+    }
+    _ => to_number(left) == to_number(right),
+  }
+}
+
+fn strict_equals(left: &Value, right: &Value) -> bool {
+  match (left, right) {
+    (Value::Number(left), Value::Number(right)) => left.as_f64() == right.as_f64(),
+    _ => left == right,
+  }
+}
+
+/// 插值输出时的字符串形式，`null`/`undefined` 输出为空字符串
+pub(crate) fn to_display_string(value: &Value) -> String {
+  match value {
+    Value::Null => String::new(),
+    Value::Bool(value) => value.to_string(),
+    Value::Number(value) => value.to_string(),
+    Value::String(value) => value.clone(),
+    Value::Array(items) => items
+      .iter()
+      .map(to_display_string)
+      .collect::<Vec<_>>()
+      .join(","),
+    Value::Object(_) => "[object Object]".to_string(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::expr::parse_expression;
+  use serde_json::json;
+
+  fn eval(sandbox: &Sandbox, source: &str) -> Result<Value, ExprError> {
+    let data = json!({ "a": 2, "user": { "name": "Ann", "tags": ["x", "y"] }, "price": 1.5 });
+    sandbox.evaluate(&parse_expression(source).unwrap(), &data)
+  }
+
+  #[test]
+  fn sandbox_calls() {
+    let sandbox =
+      Sandbox::default().with_function("util.double", |args| Ok(number(to_number(&args[0]) * 2.0)));
+    assert_eq!(
+      eval(&sandbox, "a * 3 + 1 > 6 ? 'big' : 'small'").unwrap(),
+      json!("big")
+    );
+    assert_eq!(
+      eval(&sandbox, "user.tags.join('-') + user.missing").unwrap(),
+      json!("x-y")
+    );
+    assert_eq!(eval(&sandbox, "price.toFixed(2)").unwrap(), json!("1.50"));
+    assert_eq!(eval(&sandbox, "util.double(a) === 4").unwrap(), json!(true));
+    assert_eq!(
+      eval(&sandbox, "[...user.tags, ...'ab', a]").unwrap(),
+      json!(["x", "y", "a", "b", 2])
+    );
+    assert_eq!(eval(&sandbox, "util.double(...[a])").unwrap(), json!(4));
+    assert_eq!(
+      eval(&sandbox, "[...a]").unwrap_err().message,
+      "cannot spread number"
+    );
+    assert_eq!(
+      eval(&sandbox, "user.name || 'anonymous'").unwrap(),
+      json!("Ann")
+    );
+
+    let sandbox = Sandbox::empty().with_method("join");
+    assert_eq!(eval(&sandbox, "user.tags.join()").unwrap(), json!("x,y"));
+    assert_eq!(
+      eval(&sandbox, "price.toFixed(2)").unwrap_err().message,
+      "call to `price.toFixed` is not allowed"
+    );
+    assert!(eval(&sandbox, "alert(1)").is_err());
+  }
+}
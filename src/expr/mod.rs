@@ -0,0 +1,179 @@
+//! 插值表达式 `{{ }}` 的语法树与解析
+//!
+//! WXML 表达式是 JavaScript 表达式的子集：字面量、成员访问、函数调用、一元/二元运算、
+//! 三元运算以及数组、对象字面量。解析结果可以通过 [`Expr::to_estree`] 转换为 ESTree 结构
+
+mod estree;
+mod eval;
+mod parser;
+
+use std::{error::Error, fmt, ops::Range};
+
+pub(crate) use eval::{to_display_string, truthy};
+pub use eval::{Function, Sandbox, BUILTIN_METHODS};
+pub(crate) use parser::PUNCTUATORS;
+pub use parser::{parse_expression, parse_expression_with_spans, parse_object_body};
+
+/// 表达式语法树
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+  /// `null`
+  Null,
+  /// `undefined`
+  Undefined,
+  Bool(bool),
+  Number(f64),
+  String(String),
+  /// 变量引用，如 `item`
+  Ident(String),
+  /// 数组字面量 `[a, b]`
+  Array(Vec<Expr>),
+  /// 对象字面量 `{a: 1, ...b}`
+  Object(Vec<ObjectProperty>),
+  /// 静态成员访问 `a.b`
+  Member {
+    object: Box<Expr>,
+    property: String,
+  },
+  /// 计算成员访问 `a[b]`
+  Index {
+    object: Box<Expr>,
+    index: Box<Expr>,
+  },
+  /// 函数调用 `f(a)`、`a.b(c)`
+  Call {
+    callee: Box<Expr>,
+    args: Vec<Expr>,
+  },
+  Unary {
+    op: UnaryOp,
+    argument: Box<Expr>,
+  },
+  Binary {
+    op: BinaryOp,
+    left: Box<Expr>,
+    right: Box<Expr>,
+  },
+  /// 三元运算 `test ? consequent : alternate`
+  Conditional {
+    test: Box<Expr>,
+    consequent: Box<Expr>,
+    alternate: Box<Expr>,
+  },
+  /// 展开 `...items`，只出现在数组字面量的元素与函数调用的参数中
+  Spread(Box<Expr>),
+}
+
+/// 表达式中运算符与函数调用在内容中的字节范围，由 [`parse_expression_with_spans`] 返回
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExprSpans {
+  /// 一元、二元运算符与三元运算的 `?`，按源码顺序排列
+  pub operators: Vec<Range<usize>>,
+  /// 函数调用从被调用的表达式开始到 `)` 的范围，按 `)` 的位置排列，
+  /// 与后序遍历（先被调用的表达式，再参数，最后调用本身）得到的 [`Expr::Call`] 顺序一致
+  pub calls: Vec<Range<usize>>,
+}
+
+/// 对象字面量中的属性
+#[derive(Debug, Clone, PartialEq)]
+pub enum ObjectProperty {
+  /// `key: value`，简写 `{key}` 的值为同名变量
+  Property { key: String, value: Expr },
+  /// `...value`
+  Spread(Expr),
+}
+
+/// 一元运算符
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnaryOp {
+  /// `!`
+  Not,
+  /// `-`
+  Minus,
+  /// `+`
+  Plus,
+  /// `typeof`
+  Typeof,
+}
+
+impl UnaryOp {
+  /// 运算符的源码形式
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      UnaryOp::Not => "!",
+      UnaryOp::Minus => "-",
+      UnaryOp::Plus => "+",
+      UnaryOp::Typeof => "typeof",
+    }
+  }
+}
+
+/// 二元运算符，包括逻辑运算
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+  Add,
+  Sub,
+  Mul,
+  Div,
+  Rem,
+  Lt,
+  Gt,
+  LtEq,
+  GtEq,
+  Eq,
+  NotEq,
+  StrictEq,
+  StrictNotEq,
+  And,
+  Or,
+  Nullish,
+}
+
+impl BinaryOp {
+  /// 运算符的源码形式
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      BinaryOp::Add => "+",
+      BinaryOp::Sub => "-",
+      BinaryOp::Mul => "*",
+      BinaryOp::Div => "/",
+      BinaryOp::Rem => "%",
+      BinaryOp::Lt => "<",
+      BinaryOp::Gt => ">",
+      BinaryOp::LtEq => "<=",
+      BinaryOp::GtEq => ">=",
+      BinaryOp::Eq => "==",
+      BinaryOp::NotEq => "!=",
+      BinaryOp::StrictEq => "===",
+      BinaryOp::StrictNotEq => "!==",
+      BinaryOp::And => "&&",
+      BinaryOp::Or => "||",
+      BinaryOp::Nullish => "??",
+    }
+  }
+}
+
+/// 表达式解析或求值错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExprError {
+  pub message: String,
+  /// 出错位置在表达式内容中的字节偏移
+  pub offset: usize,
+}
+
+impl ExprError {
+  pub(crate) fn new(message: impl Into<String>, offset: usize) -> Self {
+    Self {
+      message: message.into(),
+      offset,
+    }
+  }
+}
+
+impl fmt::Display for ExprError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{} at offset {}", self.message, self.offset)
+  }
+}
+
+impl Error for ExprError {}
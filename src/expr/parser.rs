@@ -0,0 +1,478 @@
+use super::{BinaryOp, Expr, ExprError, ExprSpans, ObjectProperty, UnaryOp};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+  Number(f64),
+  String(String),
+  Ident(String),
+  Punct(&'static str),
+}
+
+/// 表达式允许的最大嵌套层数，括号、数组、对象、一元运算符与条件表达式的分支各算一层
+const MAX_DEPTH: usize = 128;
+
+/// 按长度降序排列，保证优先匹配最长的运算符
+pub(crate) const PUNCTUATORS: &[&str] = &[
+  "===", "!==", "...", "==", "!=", "<=", ">=", "&&", "||", "??", "+", "-", "*", "/", "%", "<", ">",
+  "!", "?", ":", ".", ",", "(", ")", "[", "]", "{", "}",
+];
+
+/// 解析 `{{ }}` 中的表达式内容
+pub fn parse_expression(source: &str) -> Result<Expr, ExprError> {
+  parse_expression_with_spans(source).map(|(expr, _)| expr)
+}
+
+/// 解析表达式，同时返回运算符与函数调用在内容中的位置，供需要定位到表达式内部的检查使用
+pub fn parse_expression_with_spans(source: &str) -> Result<(Expr, ExprSpans), ExprError> {
+  let mut parser = ExprParser::new(source)?;
+  let expr = parser.parse_expr()?;
+  parser.expect_end()?;
+  Ok((expr, parser.spans))
+}
+
+/// 解析不带花括号的对象字面量内容，如 `<template data="{{ ...item, index }}">` 中的 `...item, index`
+pub fn parse_object_body(source: &str) -> Result<Vec<ObjectProperty>, ExprError> {
+  let mut parser = ExprParser::new(source)?;
+  let properties = parser.parse_object_properties(None)?;
+  parser.expect_end()?;
+  Ok(properties)
+}
+
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>, ExprError> {
+  let bytes = source.as_bytes();
+  let mut tokens = Vec::new();
+  let mut pos = 0;
+  while pos < bytes.len() {
+    let ch = source[pos..].chars().next().unwrap();
+    if ch.is_whitespace() {
+      pos += ch.len_utf8();
+      continue;
+    }
+    let start = pos;
+    if ch.is_ascii_digit() || (ch == '.' && bytes.get(pos + 1).is_some_and(u8::is_ascii_digit)) {
+      while pos < bytes.len() && (bytes[pos].is_ascii_alphanumeric() || bytes[pos] == b'.') {
+        pos += 1;
+      }
+      let text = &source[start..pos];
+      let value = match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => i64::from_str_radix(hex, 16).ok().map(|n| n as f64),
+        None => text.parse().ok(),
+      };
+      let value = value.ok_or_else(|| ExprError::new(format!("invalid number `{text}`"), start))?;
+      tokens.push((Token::Number(value), start));
+    } else if ch == '"' || ch == '\'' {
+      let (value, len) = parse_string(&source[pos..])
+        .ok_or_else(|| ExprError::new("unterminated string literal", start))?;
+      pos += len;
+      tokens.push((Token::String(value), start));
+    } else if ch.is_alphabetic() || ch == '_' || ch == '$' {
+      let len = source[pos..]
+        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+        .unwrap_or(source.len() - pos);
+      pos += len;
+      tokens.push((Token::Ident(source[start..pos].to_string()), start));
+    } else if let Some(punct) = PUNCTUATORS
+      .iter()
+      .find(|punct| source[pos..].starts_with(**punct))
+    {
+      pos += punct.len();
+      tokens.push((Token::Punct(punct), start));
+    } else {
+      return Err(ExprError::new(
+        format!("unexpected character `{ch}`"),
+        start,
+      ));
+    }
+  }
+  Ok(tokens)
+}
+
+/// 解析以引号开头的字符串字面量，返回字符串值与消耗的字节数
+fn parse_string(source: &str) -> Option<(String, usize)> {
+  let mut chars = source.char_indices();
+  let (_, quote) = chars.next()?;
+  let mut value = String::new();
+  while let Some((index, ch)) = chars.next() {
+    match ch {
+      '\\' => {
+        let (_, escaped) = chars.next()?;
+        value.push(match escaped {
+          'n' => '\n',
+          't' => '\t',
+          'r' => '\r',
+          '0' => '\0',
+          other => other,
+        });
+      }
+      _ if ch == quote => return Some((value, index + 1)),
+      _ => value.push(ch),
+    }
+  }
+  None
+}
+
+/// 二元运算符及其优先级，数值越大结合越紧
+fn binary_op(punct: &str) -> Option<(BinaryOp, u8)> {
+  let op = match punct {
+    "||" => (BinaryOp::Or, 1),
+    "??" => (BinaryOp::Nullish, 1),
+    "&&" => (BinaryOp::And, 2),
+    "==" => (BinaryOp::Eq, 3),
+    "!=" => (BinaryOp::NotEq, 3),
+    "===" => (BinaryOp::StrictEq, 3),
+    "!==" => (BinaryOp::StrictNotEq, 3),
+    "<" => (BinaryOp::Lt, 4),
+    ">" => (BinaryOp::Gt, 4),
+    "<=" => (BinaryOp::LtEq, 4),
+    ">=" => (BinaryOp::GtEq, 4),
+    "+" => (BinaryOp::Add, 5),
+    "-" => (BinaryOp::Sub, 5),
+    "*" => (BinaryOp::Mul, 6),
+    "/" => (BinaryOp::Div, 6),
+    "%" => (BinaryOp::Rem, 6),
+    _ => return None,
+  };
+  Some(op)
+}
+
+struct ExprParser {
+  tokens: Vec<(Token, usize)>,
+  pos: usize,
+  /// 源码长度，用于报告结尾处的错误
+  len: usize,
+  spans: ExprSpans,
+  /// 当前的嵌套层数
+  depth: usize,
+}
+
+impl ExprParser {
+  fn new(source: &str) -> Result<Self, ExprError> {
+    Ok(Self {
+      tokens: tokenize(source)?,
+      pos: 0,
+      len: source.len(),
+      spans: ExprSpans::default(),
+      depth: 0,
+    })
+  }
+
+  fn peek(&self) -> Option<&Token> {
+    self.tokens.get(self.pos).map(|(token, _)| token)
+  }
+
+  /// 当前 token 的偏移量
+  fn offset(&self) -> usize {
+    self
+      .tokens
+      .get(self.pos)
+      .map_or(self.len, |(_, offset)| *offset)
+  }
+
+  fn next(&mut self) -> Option<Token> {
+    let token = self.tokens.get(self.pos).map(|(token, _)| token.clone());
+    self.pos += 1;
+    token
+  }
+
+  fn eat(&mut self, punct: &str) -> bool {
+    if matches!(self.peek(), Some(Token::Punct(p)) if *p == punct) {
+      self.pos += 1;
+      return true;
+    }
+    false
+  }
+
+  fn expect(&mut self, punct: &str) -> Result<(), ExprError> {
+    if self.eat(punct) {
+      Ok(())
+    } else {
+      Err(self.unexpected(&format!("expected `{punct}`")))
+    }
+  }
+
+  fn expect_end(&self) -> Result<(), ExprError> {
+    match self.peek() {
+      None => Ok(()),
+      Some(_) => Err(self.unexpected("unexpected token")),
+    }
+  }
+
+  fn unexpected(&self, message: &str) -> ExprError {
+    match self.peek() {
+      None => ExprError::new(format!("{message}, found end of expression"), self.len),
+      Some(_) => ExprError::new(message, self.offset()),
+    }
+  }
+
+  /// 进入一层嵌套，超过 [`MAX_DEPTH`] 时返回错误，避免 `((((…))))` 这样的输入耗尽栈空间
+  fn nested<T>(
+    &mut self,
+    parse: impl FnOnce(&mut Self) -> Result<T, ExprError>,
+  ) -> Result<T, ExprError> {
+    if self.depth == MAX_DEPTH {
+      return Err(ExprError::new(
+        "expression is nested too deeply",
+        self.offset(),
+      ));
+    }
+    self.depth += 1;
+    let result = parse(self);
+    self.depth -= 1;
+    result
+  }
+
+  fn parse_expr(&mut self) -> Result<Expr, ExprError> {
+    self.nested(Self::parse_conditional)
+  }
+
+  fn parse_conditional(&mut self) -> Result<Expr, ExprError> {
+    let test = self.parse_binary(1)?;
+    let offset = self.offset();
+    if !self.eat("?") {
+      return Ok(test);
+    }
+    self.spans.operators.push(offset..offset + 1);
+    let consequent = self.parse_expr()?;
+    self.expect(":")?;
+    let alternate = self.parse_expr()?;
+    Ok(Expr::Conditional {
+      test: Box::new(test),
+      consequent: Box::new(consequent),
+      alternate: Box::new(alternate),
+    })
+  }
+
+  fn parse_binary(&mut self, min_precedence: u8) -> Result<Expr, ExprError> {
+    let mut left = self.parse_unary()?;
+    while let Some(Token::Punct(punct)) = self.peek() {
+      let Some((op, precedence)) = binary_op(punct) else {
+        break;
+      };
+      if precedence < min_precedence {
+        break;
+      }
+      let offset = self.offset();
+      self
+        .spans
+        .operators
+        .push(offset..offset + op.as_str().len());
+      self.pos += 1;
+      let right = self.parse_binary(precedence + 1)?;
+      left = Expr::Binary {
+        op,
+        left: Box::new(left),
+        right: Box::new(right),
+      };
+    }
+    Ok(left)
+  }
+
+  fn parse_unary(&mut self) -> Result<Expr, ExprError> {
+    let op = match self.peek() {
+      Some(Token::Punct("!")) => UnaryOp::Not,
+      Some(Token::Punct("-")) => UnaryOp::Minus,
+      Some(Token::Punct("+")) => UnaryOp::Plus,
+      Some(Token::Ident(name)) if name == "typeof" => UnaryOp::Typeof,
+      _ => return self.parse_postfix(),
+    };
+    let offset = self.offset();
+    self
+      .spans
+      .operators
+      .push(offset..offset + op.as_str().len());
+    self.pos += 1;
+    Ok(Expr::Unary {
+      op,
+      argument: Box::new(self.nested(Self::parse_unary)?),
+    })
+  }
+
+  fn parse_postfix(&mut self) -> Result<Expr, ExprError> {
+    let start = self.offset();
+    let mut expr = self.parse_primary()?;
+    loop {
+      if self.eat(".") {
+        match self.next() {
+          Some(Token::Ident(property)) => {
+            expr = Expr::Member {
+              object: Box::new(expr),
+              property,
+            }
+          }
+          _ => {
+            self.pos -= 1;
+            return Err(self.unexpected("expected property name"));
+          }
+        }
+      } else if self.eat("[") {
+        let index = self.parse_expr()?;
+        self.expect("]")?;
+        expr = Expr::Index {
+          object: Box::new(expr),
+          index: Box::new(index),
+        };
+      } else if self.eat("(") {
+        let args = self.parse_list(")")?;
+        // 参数列表以 `)` 结束
+        self
+          .spans
+          .calls
+          .push(start..self.tokens[self.pos - 1].1 + 1);
+        expr = Expr::Call {
+          callee: Box::new(expr),
+          args,
+        };
+      } else {
+        return Ok(expr);
+      }
+    }
+  }
+
+  /// 解析逗号分隔的表达式列表，直到 `close`（允许尾逗号与展开语法）
+  fn parse_list(&mut self, close: &str) -> Result<Vec<Expr>, ExprError> {
+    let mut items = Vec::new();
+    while !self.eat(close) {
+      if self.eat("...") {
+        items.push(Expr::Spread(Box::new(self.parse_expr()?)));
+      } else {
+        items.push(self.parse_expr()?);
+      }
+      if !self.eat(",") {
+        self.expect(close)?;
+        break;
+      }
+    }
+    Ok(items)
+  }
+
+  fn parse_primary(&mut self) -> Result<Expr, ExprError> {
+    let offset = self.offset();
+    let expr = match self.next() {
+      Some(Token::Number(value)) => Expr::Number(value),
+      Some(Token::String(value)) => Expr::String(value),
+      Some(Token::Ident(name)) => match name.as_str() {
+        "true" => Expr::Bool(true),
+        "false" => Expr::Bool(false),
+        "null" => Expr::Null,
+        "undefined" => Expr::Undefined,
+        _ => Expr::Ident(name),
+      },
+      Some(Token::Punct("(")) => {
+        let expr = self.parse_expr()?;
+        self.expect(")")?;
+        expr
+      }
+      Some(Token::Punct("[")) => Expr::Array(self.parse_list("]")?),
+      Some(Token::Punct("{")) => Expr::Object(self.parse_object_properties(Some("}"))?),
+      Some(_) => return Err(ExprError::new("unexpected token", offset)),
+      None => return Err(self.unexpected("expected expression")),
+    };
+    Ok(expr)
+  }
+
+  /// 解析对象属性列表，`close` 为 `None` 时解析到表达式结尾
+  fn parse_object_properties(
+    &mut self,
+    close: Option<&str>,
+  ) -> Result<Vec<ObjectProperty>, ExprError> {
+    let mut properties = Vec::new();
+    loop {
+      match close {
+        Some(close) if self.eat(close) => break,
+        None if self.peek().is_none() => break,
+        _ => {}
+      }
+      if self.eat("...") {
+        properties.push(ObjectProperty::Spread(self.parse_expr()?));
+      } else {
+        let offset = self.offset();
+        let (key, shorthand) = match self.next() {
+          Some(Token::Ident(name)) => (name, true),
+          Some(Token::String(value)) => (value, false),
+          Some(Token::Number(value)) => (value.to_string(), false),
+          _ => return Err(ExprError::new("expected property name", offset)),
+        };
+        let value = if self.eat(":") {
+          self.parse_expr()?
+        } else if shorthand {
+          Expr::Ident(key.clone())
+        } else {
+          return Err(self.unexpected("expected `:`"));
+        };
+        properties.push(ObjectProperty::Property { key, value });
+      }
+      if !self.eat(",") {
+        if let Some(close) = close {
+          self.expect(close)?;
+        }
+        break;
+      }
+    }
+    Ok(properties)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parse_precedence_and_members() {
+    let expr = parse_expression("a.b[0] + 1 * 2 > 3 ? 'x' : fn(c, ...)").unwrap_err();
+    assert_eq!(expr.offset, 36);
+    assert_eq!(
+      parse_expression("[...a, b]").unwrap(),
+      Expr::Array(vec![
+        Expr::Spread(Box::new(Expr::Ident("a".into()))),
+        Expr::Ident("b".into())
+      ])
+    );
+    assert_eq!(parse_expression("...a").unwrap_err().offset, 0);
+
+    let expr = parse_expression("!a.b[i] || c * 2 + 1").unwrap();
+    let Expr::Binary {
+      op: BinaryOp::Or,
+      left,
+      right,
+    } = expr
+    else {
+      panic!("expected `||` at the top level");
+    };
+    assert!(matches!(
+      *left,
+      Expr::Unary {
+        op: UnaryOp::Not,
+        ..
+      }
+    ));
+    assert!(matches!(
+      *right,
+      Expr::Binary {
+        op: BinaryOp::Add,
+        ..
+      }
+    ));
+
+    let properties = parse_object_body("...item, index, 'a-b': [1, 2]").unwrap();
+    assert_eq!(properties.len(), 3);
+    assert_eq!(
+      properties[1],
+      ObjectProperty::Property {
+        key: "index".into(),
+        value: Expr::Ident("index".into())
+      }
+    );
+  }
+
+  #[test]
+  fn nesting_limit() {
+    let nested = |depth: usize| format!("{}a{}", "(".repeat(depth), ")".repeat(depth));
+    assert!(parse_expression(&nested(100)).is_ok());
+    let error = parse_expression(&nested(10_000)).unwrap_err();
+    assert_eq!(error.message, "expression is nested too deeply");
+    assert_eq!(error.offset, MAX_DEPTH);
+    assert!(parse_expression(&format!("{}a", "!".repeat(10_000))).is_err());
+    assert!(parse_expression(&"a ? b : ".repeat(10_000)).is_err());
+    assert!(parse_expression(&format!("{}1{}", "[".repeat(10_000), "]".repeat(10_000))).is_err());
+  }
+}
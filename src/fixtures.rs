@@ -0,0 +1,78 @@
+//! 基准测试用的模板样本
+//!
+//! 仓库内置了几份有代表性的大型 WXML 模板（列表渲染密集、属性密集、文本与嵌套密集），
+//! 供 `benches/parse.rs` 检测词法与状态机的性能回退；
+//! 也可以通过 [`load_fixtures`] 加载自己项目中的模板，用同一套基准测试衡量真实场景的解析耗时
+
+use std::{fs, io, path::Path};
+
+/// 一份模板样本
+#[derive(Debug, Clone)]
+pub struct Fixture {
+  /// 样本名称，内置样本为文件名，加载的样本为相对于目录的路径
+  pub name: String,
+  pub source: String,
+}
+
+/// 仓库内置的模板样本
+pub fn builtin_fixtures() -> Vec<Fixture> {
+  [
+    ("list.wxml", include_str!("../benches/fixtures/list.wxml")),
+    ("form.wxml", include_str!("../benches/fixtures/form.wxml")),
+    (
+      "article.wxml",
+      include_str!("../benches/fixtures/article.wxml"),
+    ),
+  ]
+  .into_iter()
+  .map(|(name, source)| Fixture {
+    name: name.to_string(),
+    source: source.to_string(),
+  })
+  .collect()
+}
+
+/// 递归加载目录下所有 `.wxml` 文件，按路径排序
+pub fn load_fixtures(dir: impl AsRef<Path>) -> io::Result<Vec<Fixture>> {
+  let dir = dir.as_ref();
+  let mut fixtures = Vec::new();
+  visit(dir, dir, &mut fixtures)?;
+  fixtures.sort_by(|a, b| a.name.cmp(&b.name));
+  Ok(fixtures)
+}
+
+fn visit(base: &Path, dir: &Path, fixtures: &mut Vec<Fixture>) -> io::Result<()> {
+  for entry in fs::read_dir(dir)? {
+    let path = entry?.path();
+    if path.is_dir() {
+      visit(base, &path, fixtures)?;
+    } else if path.extension().is_some_and(|ext| ext == "wxml") {
+      let name = path.strip_prefix(base).unwrap_or(&path);
+      fixtures.push(Fixture {
+        name: name.to_string_lossy().replace('\\', "/"),
+        source: fs::read_to_string(&path)?,
+      });
+    }
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::parser::Parser;
+
+  #[test]
+  fn fixtures_parse_cleanly() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("benches/fixtures");
+    let loaded = load_fixtures(dir).unwrap();
+    let builtin = builtin_fixtures();
+    let names: Vec<_> = loaded.iter().map(|fixture| fixture.name.as_str()).collect();
+    assert_eq!(names, ["article.wxml", "form.wxml", "list.wxml"]);
+    for fixture in &builtin {
+      let mut parser = Parser::new(&fixture.source);
+      parser.parse_root().unwrap();
+      assert!(parser.errors().is_empty(), "{}", fixture.name);
+    }
+  }
+}
@@ -0,0 +1,268 @@
+//! 模板格式化
+//!
+//! 在 [`codegen`](crate::codegen) 的基础上按层级缩进输出：元素各占一行；`<text>`、不包含子元素的元素
+//! 以及相邻的文本、表达式与注释原样输出，以保留其中有意义的空白。
+//!
+//! 只含空白的文本也可能有意义，如 `{{a}} {{b}}` 中的空格，格式化的 AST 应以
+//! [`WhitespaceMode::Preserve`](crate::options::WhitespaceMode::Preserve) 解析；
+//! 元素之间只含空白的文本由格式化重新生成
+
+use crate::{
+  ast::{Node, Position, Root},
+  codegen::{generate_attribute, generate_node, CodegenOptions, QuoteStyle},
+  helpers::escape_mustache,
+  options::ParserOptions,
+  sourcemap::{SourceMap, SourceMapBuilder},
+};
+
+/// 格式化选项
+#[derive(Debug, Clone)]
+pub struct FormatOptions {
+  /// 每级缩进的空格数
+  pub indent_width: usize,
+  /// 属性值的引号风格
+  pub quote: QuoteStyle,
+  /// 源码的解析选项，原始文本标签的内容按原样输出，见 [`CodegenOptions::parser`]
+  pub parser: ParserOptions,
+}
+
+impl Default for FormatOptions {
+  fn default() -> Self {
+    Self {
+      indent_width: 2,
+      quote: QuoteStyle::Preserve,
+      parser: ParserOptions::default(),
+    }
+  }
+}
+
+/// 格式化整个文档，输出以换行结尾
+pub fn format(root: &Root, options: &FormatOptions) -> String {
+  let mut formatter = Formatter::new(options, None);
+  formatter.print_children(&root.children, 0);
+  formatter.output
+}
+
+/// 格式化整个文档，同时生成输出位置到源码位置的映射
+pub fn format_with_source_map(root: &Root, options: &FormatOptions) -> (String, SourceMap) {
+  let mut formatter = Formatter::new(options, Some(SourceMapBuilder::default()));
+  formatter.print_children(&root.children, 0);
+  let map = formatter.source_map.unwrap().build(&formatter.output);
+  (formatter.output, map)
+}
+
+struct Formatter<'a> {
+  options: &'a FormatOptions,
+  codegen: CodegenOptions,
+  output: String,
+  source_map: Option<SourceMapBuilder>,
+}
+
+impl<'a> Formatter<'a> {
+  fn new(options: &'a FormatOptions, source_map: Option<SourceMapBuilder>) -> Self {
+    Self {
+      options,
+      codegen: CodegenOptions {
+        quote: options.quote,
+        parser: options.parser.clone(),
+        ..Default::default()
+      },
+      output: String::new(),
+      source_map,
+    }
+  }
+
+  fn indent(&mut self, depth: usize) {
+    self
+      .output
+      .extend(std::iter::repeat_n(' ', depth * self.options.indent_width));
+  }
+
+  /// 记录当前输出位置对应的源码位置
+  fn mark(&mut self, original: Position) {
+    if let Some(source_map) = &mut self.source_map {
+      source_map.mark(self.output.len(), original);
+    }
+  }
+
+  /// 依次输出子节点：元素各占一行；相邻的文本、表达式与注释是一段连续的内容，原样输出在同一行，
+  /// 只有其首尾以及只含空白的文本替换为换行与缩进
+  fn print_children(&mut self, children: &[Node], depth: usize) {
+    let mut run = Vec::new();
+    for child in children {
+      if let Node::Element { .. } = child {
+        self.print_run(&run, depth);
+        run.clear();
+        self.print_element(child, depth);
+      } else {
+        run.push(child);
+      }
+    }
+    self.print_run(&run, depth);
+  }
+
+  fn print_run(&mut self, run: &[&Node], depth: usize) {
+    let blank =
+      |node: &&Node| matches!(node, Node::Text { content, .. } if content.trim().is_empty());
+    let (Some(first), Some(last)) = (
+      run.iter().position(|node| !blank(node)),
+      run.iter().rposition(|node| !blank(node)),
+    ) else {
+      return;
+    };
+    self.indent(depth);
+    for (index, node) in run.iter().enumerate().take(last + 1).skip(first) {
+      match node {
+        Node::Text { content, .. } => {
+          let mut content = content.as_str();
+          if index == first {
+            content = content.trim_start();
+          }
+          if index == last {
+            content = content.trim_end();
+          }
+          self.mark(node.start());
+          self.output.push_str(&escape_mustache(content));
+        }
+        _ => self.print_inline(node),
+      }
+    }
+    self.output.push('\n');
+  }
+
+  /// 在当前行输出节点，文本原样输出
+  fn print_inline(&mut self, node: &Node) {
+    self.mark(node.start());
+    match node {
+      Node::Element { .. } => self.output.push_str(&generate_node(node, &self.codegen)),
+      Node::Text { content, .. } => self.output.push_str(&escape_mustache(content)),
+      Node::Comment { content, .. } => {
+        self.output.push_str("<!--");
+        self.output.push_str(content);
+        self.output.push_str("-->");
+      }
+      Node::Expression { content, .. } => {
+        self.output.push_str("{{ ");
+        self.output.push_str(content);
+        self.output.push_str(" }}");
+      }
+    }
+  }
+
+  fn print_element(&mut self, node: &Node, depth: usize) {
+    let Node::Element {
+      name,
+      attrs,
+      children,
+      self_closing,
+      first_attr_same_line,
+      ..
+    } = node
+    else {
+      return;
+    };
+
+    self.indent(depth);
+    self.mark(node.start());
+    self.output.push('<');
+    self.output.push_str(name);
+    // 作者将属性分行书写时保持每个属性一行
+    let multiline_attrs = !first_attr_same_line && !attrs.is_empty();
+    for attr in attrs {
+      if multiline_attrs {
+        self.output.push('\n');
+        self.indent(depth + 1);
+      } else {
+        self.output.push(' ');
+      }
+      self.mark(attr.start);
+      self
+        .output
+        .push_str(&generate_attribute(attr, &self.codegen));
+    }
+    if multiline_attrs {
+      self.output.push('\n');
+      self.indent(depth);
+    }
+
+    if *self_closing && children.is_empty() {
+      self
+        .output
+        .push_str(if multiline_attrs { "/>\n" } else { " />\n" });
+      return;
+    }
+    self.output.push('>');
+
+    if self.codegen.parser.is_raw_text_tag(name) {
+      // 原始文本标签（如 `<wxs>` 中的脚本）的内容不是标记，原样输出
+      for child in children {
+        if let Node::Text { content, .. } = child {
+          self.mark(child.start());
+          self.output.push_str(content);
+        }
+      }
+      self.output.push_str("</");
+      self.output.push_str(name);
+      self.output.push_str(">\n");
+      return;
+    }
+
+    // `<text>` 中的空白会被渲染，只包含文本、表达式与注释的元素也保持原样，以免改变渲染结果
+    let inline = name == "text"
+      || !children
+        .iter()
+        .any(|child| matches!(child, Node::Element { .. }));
+    if inline {
+      for child in children {
+        self.print_inline(child);
+      }
+    } else {
+      self.output.push('\n');
+      self.print_children(children, depth + 1);
+      self.indent(depth);
+    }
+    self.output.push_str("</");
+    self.output.push_str(name);
+    self.output.push_str(">\n");
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{options::WhitespaceMode, parser::Parser};
+
+  fn format_preserved(source: &str) -> String {
+    let options = ParserOptions {
+      whitespace: Some(WhitespaceMode::Preserve),
+      ..Default::default()
+    };
+    let root = Parser::with_options(source, options).parse_root().unwrap();
+    format(&root, &FormatOptions::default())
+  }
+
+  #[test]
+  fn keeps_significant_whitespace() {
+    let source = "<view>{{a}} {{b}}</view>
+<view>
+<text>
+    line one
+      line two
+  </text>
+    Hello <!-- c --> world
+        <image/>
+</view>";
+    let expected = "<view>{{ a }} {{ b }}</view>
+<view>
+  <text>
+    line one
+      line two
+  </text>
+  Hello <!-- c --> world
+  <image />
+</view>
+";
+    assert_eq!(format_preserved(source), expected);
+    assert_eq!(format_preserved(expected), expected);
+  }
+}
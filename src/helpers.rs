@@ -1,4 +1,8 @@
-/// 判断是否为模板中的空白字符
+use std::borrow::Cow;
+
+use memchr::memmem;
+
+// 判断是否为模板中的空白字符
 // pub(crate) fn is_template_whitespace(c: char) -> bool {
 //   match c {
 //     ' ' | '\t' | '\n' | '\r' => true,
@@ -21,3 +25,110 @@ pub(crate) fn is_tag_name_char(c: char) -> bool {
 pub(crate) fn is_attr_name_char(c: char) -> bool {
   !matches!(c, '"' | '\'' | '>' | '/' | '=') && !c.is_ascii_whitespace()
 }
+
+/// 判断 `<` 开头的 `source` 是否为标签、结束标签、注释或声明，否则 `<` 只是普通文本
+pub(crate) fn starts_markup(source: &str) -> bool {
+  let mut chars = source.chars().skip(1);
+  matches!(chars.next(), Some(ch) if is_tag_name_char(ch) || matches!(ch, '/' | '!' | '?'))
+}
+
+/// 查找表达式的结束位置，`source` 为 `{{` 之后的内容
+///
+/// 返回 `}}` 相对于 `source` 的字节偏移。扫描时跟踪花括号嵌套，因此
+/// `{{ {a: {b: 1}} }}` 这类对象字面量不会在内部的 `}}` 处被截断；同时跳过
+/// 单引号、双引号字符串（支持转义）以及模板字符串（含 `${}` 插值），
+/// 字符串中的 `}}`、`</` 不会被当作边界。花括号或字符串不完整时，回退到
+/// 遇到的第一个 `}}`。
+///
+/// 遇到结束标签 `</`、所在属性的引号 `quote` 或输入结尾时视为表达式未闭合。
+/// 属性引号即使出现在字符串中也会结束属性值，这与运行时的行为一致。
+/// 注意 `<` 本身是合法的比较运算符，不能作为边界
+pub(crate) fn find_expression_end(source: &str, quote: Option<char>) -> Option<usize> {
+  // 常见的简单表达式（如 `item.name`）中没有字符串、花括号与引号，直接按字节查找 `}}`
+  if let Some(index) = memmem::find(source.as_bytes(), b"}}") {
+    let simple = !source.as_bytes()[..index]
+      .iter()
+      .any(|&byte| matches!(byte, b'\'' | b'"' | b'`' | b'{' | b'}' | b'<'));
+    if simple {
+      return Some(index);
+    }
+  }
+
+  let mut depth = 0usize;
+  let mut first_close = None;
+  // 当前所在字符串的引号
+  let mut string: Option<char> = None;
+  let mut escaped = false;
+  // 模板字符串中 `${` 打开时的花括号深度
+  let mut templates: Vec<usize> = Vec::new();
+
+  for (index, c) in source.char_indices() {
+    let rest = &source[index..];
+    if Some(c) == quote {
+      break;
+    }
+    if rest.starts_with("}}") {
+      first_close.get_or_insert(index);
+    }
+    if let Some(delimiter) = string {
+      if escaped {
+        escaped = false;
+      } else if c == '\\' {
+        escaped = true;
+      } else if c == delimiter {
+        string = None;
+      } else if delimiter == '`' && rest.starts_with("${") {
+        templates.push(depth);
+        string = None;
+      }
+      continue;
+    }
+    if rest.starts_with("</") {
+      break;
+    }
+    match c {
+      '\'' | '"' | '`' => string = Some(c),
+      '{' => depth += 1,
+      '}' => {
+        if depth > 0 && templates.last() == Some(&(depth - 1)) {
+          // `${ ... }` 结束，回到模板字符串
+          templates.pop();
+          depth -= 1;
+          string = Some('`');
+          continue;
+        }
+        if depth == 0 && rest.starts_with("}}") {
+          return Some(index);
+        }
+        depth = depth.saturating_sub(1);
+      }
+      _ => {}
+    }
+  }
+  first_close
+}
+
+/// 识别转义花括号的插值，如 `{{'{{'}}`、`{{"{{ name }}"}}`
+///
+/// 表达式只是一个包含 `{{` 或 `}}` 的字符串字面量时，渲染结果就是字面量的内容，
+/// 返回去掉引号的内容，由解析器作为静态文本处理。字面量中有转义时内容与源码不一致，不作识别
+pub(crate) fn literal_mustache(expression: &str) -> Option<&str> {
+  let quote = expression
+    .chars()
+    .next()
+    .filter(|c| matches!(c, '\'' | '"'))?;
+  let content = expression[1..].strip_suffix(quote)?;
+  if content.contains([quote, '\\']) || !(content.contains("{{") || content.contains("}}")) {
+    return None;
+  }
+  Some(content)
+}
+
+/// 将静态文本中的 `{{` 转义为 `{{'{{'}}`，保证输出的文本不会被解析为插值
+pub(crate) fn escape_mustache(text: &str) -> Cow<'_, str> {
+  if text.contains("{{") {
+    Cow::Owned(text.replace("{{", "{{'{{'}}"))
+  } else {
+    Cow::Borrowed(text)
+  }
+}
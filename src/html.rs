@@ -0,0 +1,245 @@
+//! HTML → WXML 导入
+//!
+//! 把网页移植到小程序时，先用 [`Parser::parse_html`] 以容错的 HTML 模式解析页面，再由 [`html_to_wxml`]
+//! 把 HTML 元素映射为效果相近的内置组件，得到可以继续编辑的 WXML 模板：
+//!
+//! - 块级元素（`div`、`p`、`section`、`ul`、`li`、`h1` 等）与未知元素为 `view`，行内文本元素（`span`、`strong`、`em` 等）为 `text`
+//! - `img` 为 `image`，并设置 `mode="widthFix"` 保持原有的宽高比，`width`、`height` 属性合并到 `style` 中
+//! - `a` 为 `navigator`，`href` 改为 `url`；`input` 按 `type` 转换为 `checkbox`、`radio`、`slider` 或 `input`；`br` 为包含换行的 `text`
+//! - `html`、`body` 只保留其中的内容；`head`、`script`、`style`、`template`、`svg` 等无法直接对应的元素被移除
+//! - `onclick` 等内联事件处理属性被移除，其余属性原样保留
+//!
+//! 字符实体（如 `&nbsp;`）原样保留，需要时可改用 `<text decode>`
+
+use crate::{
+  ast::{Attribute, AttributeValue, Node, Position, Root},
+  parser::Parser,
+};
+
+/// HTML 中没有子节点与结束标签的空元素
+const VOID_ELEMENTS: &[&str] = &[
+  "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+  "wbr",
+];
+
+/// 内容按原始文本解析的 HTML 元素
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style"];
+
+/// 转换时移除的元素
+const REMOVED_ELEMENTS: &[&str] = &[
+  "head", "script", "style", "link", "meta", "title", "base", "noscript", "template", "svg",
+  "iframe", "object", "embed",
+];
+
+/// 转换为 `text` 的行内元素
+const TEXT_ELEMENTS: &[&str] = &[
+  "span", "strong", "b", "em", "i", "u", "s", "small", "code", "sub", "sup", "mark", "abbr",
+  "cite", "q", "time", "del", "ins", "kbd", "var",
+];
+
+/// 名称不变的元素，小程序有同名的内置组件
+const KEPT_ELEMENTS: &[&str] = &["button", "textarea", "form", "label", "video", "canvas"];
+
+pub(crate) fn is_void_element(name: &str) -> bool {
+  VOID_ELEMENTS
+    .iter()
+    .any(|void| void.eq_ignore_ascii_case(name))
+}
+
+impl Parser<'_> {
+  /// 以容错的 HTML 模式解析，适合从网页复制而来的内容
+  ///
+  /// 在片段模式（见 [`Parser::parse_fragment`]）的基础上，空元素没有结束标签，
+  /// `script`、`style` 的内容按原始文本解析，并允许不带引号的属性值
+  pub fn parse_html(&mut self) -> Vec<Node> {
+    self.html = true;
+    self.options.lenient = Some(true);
+    self
+      .options
+      .raw_text_tags
+      .get_or_insert_with(Vec::new)
+      .extend(RAW_TEXT_ELEMENTS.iter().map(|tag| tag.to_string()));
+    self.parse_fragment(None)
+  }
+}
+
+/// 解析 HTML 并转换为 WXML 文档；需要解析中遇到的问题时分别使用 [`Parser::parse_html`] 与 [`html_to_wxml`]
+pub fn import_html(source: &str) -> Root {
+  let nodes = Parser::new(source).parse_html();
+  Root {
+    children: html_to_wxml(nodes),
+    start: Position {
+      offset: 0,
+      line: 1,
+      column: 1,
+    },
+    end: Position {
+      offset: 0,
+      line: 1,
+      column: 1,
+    }
+    .advance(source),
+    stats: None,
+    comments: None,
+    errors: Vec::new(),
+  }
+}
+
+/// 把 HTML 节点转换为 WXML 节点，保留原有的位置
+pub fn html_to_wxml(nodes: Vec<Node>) -> Vec<Node> {
+  let mut out = Vec::new();
+  convert(nodes, &mut out);
+  out
+}
+
+fn convert(nodes: Vec<Node>, out: &mut Vec<Node>) {
+  for node in nodes {
+    let Node::Element {
+      name,
+      attrs,
+      children,
+      self_closing,
+      first_attr_same_line,
+      recovered,
+      close_start,
+      close_end,
+      start,
+      end,
+    } = node
+    else {
+      out.push(node);
+      continue;
+    };
+    let html_name = name.to_ascii_lowercase();
+    match html_name.as_str() {
+      "html" | "body" => {
+        convert(children, out);
+        continue;
+      }
+      "br" => {
+        out.push(Node::element("text").with_child(Node::text("\n")));
+        continue;
+      }
+      name if REMOVED_ELEMENTS.contains(&name) => continue,
+      _ => {}
+    }
+    let name = wxml_name(&html_name, &attrs);
+    let attrs = convert_attrs(&html_name, &name, attrs);
+    let mut converted = Vec::new();
+    convert(children, &mut converted);
+    out.push(Node::Element {
+      self_closing: self_closing || is_void_element(&html_name),
+      name,
+      attrs,
+      children: converted,
+      first_attr_same_line,
+      recovered,
+      close_start,
+      close_end,
+      start,
+      end,
+    });
+  }
+}
+
+fn wxml_name(html_name: &str, attrs: &[Attribute]) -> String {
+  let name = match html_name {
+    "img" => "image",
+    "a" => "navigator",
+    "input" => match input_type(attrs).as_deref() {
+      Some("checkbox") => "checkbox",
+      Some("radio") => "radio",
+      Some("range") => "slider",
+      _ => "input",
+    },
+    name if TEXT_ELEMENTS.contains(&name) => "text",
+    name if KEPT_ELEMENTS.contains(&name) => name,
+    _ => "view",
+  };
+  name.to_string()
+}
+
+fn input_type(attrs: &[Attribute]) -> Option<String> {
+  attrs
+    .iter()
+    .find(|attr| attr.name.eq_ignore_ascii_case("type"))
+    .and_then(Attribute::as_static_string)
+    .map(|value| value.trim().to_ascii_lowercase())
+}
+
+fn convert_attrs(html_name: &str, name: &str, attrs: Vec<Attribute>) -> Vec<Attribute> {
+  let mut converted = Vec::new();
+  let mut sizes = Vec::new();
+  for mut attr in attrs {
+    let attr_name = attr.name.to_ascii_lowercase();
+    match (html_name, attr_name.as_str()) {
+      (_, event) if event.starts_with("on") => {}
+      ("a", "href") => {
+        attr.rename("url");
+        converted.push(attr);
+      }
+      ("a", "target") | ("img", "alt") => {}
+      ("img", "width" | "height") => {
+        if let Some(value) = attr.as_static_string() {
+          let value = value.trim();
+          let unit = if value.chars().all(|c| c.is_ascii_digit()) {
+            "px"
+          } else {
+            ""
+          };
+          sizes.push(format!("{attr_name}: {value}{unit}"));
+        }
+      }
+      ("input", "type") => match input_type(std::slice::from_ref(&attr)).as_deref() {
+        Some("password") => converted.push(Attribute::new("password", None)),
+        Some("number") if name == "input" => converted.push(attr),
+        _ => {}
+      },
+      _ => converted.push(attr),
+    }
+  }
+  if html_name == "img" {
+    converted.push(Attribute::text("mode", "widthFix"));
+  }
+  if !sizes.is_empty() {
+    let style = converted.iter_mut().find(|attr| attr.name == "style");
+    match style {
+      Some(style) => {
+        let mut value = style.as_static_string().unwrap_or_default();
+        let len = value.trim_end().trim_end_matches(';').len();
+        value.truncate(len);
+        if !value.is_empty() {
+          value.push_str("; ");
+        }
+        value.push_str(&sizes.join("; "));
+        style.value = Some(vec![AttributeValue::text(value)]);
+      }
+      None => converted.push(Attribute::text("style", sizes.join("; "))),
+    }
+  }
+  converted
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::codegen::{generate, CodegenOptions};
+
+  #[test]
+  fn import_html_page() {
+    let source = r#"<!DOCTYPE html>
+<html><head><title>Demo</title><style>p > a { color: red }</style></head>
+<body>
+<div class="card" onclick="go()"><img src="a.png" alt="A" width="100"><p>Hello <strong>world</strong><br>
+<a href="/next" target="_blank">next</a></p>
+<input type=checkbox checked><input type="password" placeholder="pwd">
+<script>if (a < b) {}</script></div>
+</body></html>"#;
+    let root = import_html(source);
+    assert_eq!(
+      generate(&root, &CodegenOptions::default()),
+      r#"<view class="card"><image src="a.png" mode="widthFix" style="width: 100px" /><view>Hello <text>world</text><text>
+</text><navigator url="/next">next</navigator></view><checkbox checked /><input password placeholder="pwd" /></view>"#
+    );
+  }
+}
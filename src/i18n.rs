@@ -0,0 +1,154 @@
+//! 提取需要翻译的文本
+//!
+//! 收集模板中用户可见的静态文本，以及 `placeholder`、`title` 等属性的静态值，
+//! 连同源码位置一起返回，供本地化流程提取字符串并在翻译后按位置写回。
+//! 含插值的属性值、只包含数字或标点的文本不会被提取
+
+use serde::{Deserialize, Serialize};
+
+use crate::ast::{AttributeValue, Node, Position, Root};
+
+/// 值需要翻译的属性
+pub const TRANSLATABLE_ATTRS: &[&str] = &[
+  "alt",
+  "aria-label",
+  "cancel-text",
+  "confirm-text",
+  "placeholder",
+  "title",
+];
+
+/// 内容不会展示给用户的标签
+const NON_VISIBLE_TAGS: &[&str] = &["wxs", "import", "include"];
+
+/// 一段待翻译的文本
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextSegment {
+  /// 去除首尾空白后的文本
+  pub content: String,
+  /// 所在元素的标签名，位于顶层的文本为 `None`
+  pub element: Option<String>,
+  /// 来自属性值时为属性名，来自文本节点时为 `None`
+  pub attr: Option<String>,
+  /// `content` 在源码中的范围，写回译文时替换该范围即可保留原有的空白
+  pub start: Position,
+  pub end: Position,
+}
+
+/// 按出现顺序提取模板中所有待翻译的文本
+pub fn extract_text(root: &Root) -> Vec<TextSegment> {
+  let mut segments = Vec::new();
+  for node in &root.children {
+    extract_node(node, None, &mut segments);
+  }
+  segments
+}
+
+fn extract_node(node: &Node, parent: Option<&str>, segments: &mut Vec<TextSegment>) {
+  match node {
+    Node::Element {
+      name,
+      attrs,
+      children,
+      ..
+    } => {
+      if NON_VISIBLE_TAGS.contains(&name.as_str()) {
+        return;
+      }
+      for attr in attrs {
+        if !TRANSLATABLE_ATTRS.contains(&attr.name.as_str()) {
+          continue;
+        }
+        if let Some(
+          [AttributeValue::Text {
+            content,
+            start,
+            end,
+          }],
+        ) = attr.value.as_deref()
+        {
+          if let Some(segment) = segment(content, *start, *end) {
+            segments.push(TextSegment {
+              element: Some(name.clone()),
+              attr: Some(attr.name.clone()),
+              ..segment
+            });
+          }
+        }
+      }
+      for child in children {
+        extract_node(child, Some(name), segments);
+      }
+    }
+    Node::Text {
+      content,
+      start,
+      end,
+    } => {
+      if let Some(segment) = segment(content, *start, *end) {
+        segments.push(TextSegment {
+          element: parent.map(str::to_string),
+          ..segment
+        });
+      }
+    }
+    Node::Comment { .. } | Node::Expression { .. } => {}
+  }
+}
+
+/// 去除首尾空白并换算位置，不含任何文字的文本返回 `None`
+fn segment(content: &str, start: Position, end: Position) -> Option<TextSegment> {
+  if !content.chars().any(char::is_alphabetic) {
+    return None;
+  }
+  let trimmed_start = content.trim_start();
+  let leading = &content[..content.len() - trimmed_start.len()];
+  let trimmed = trimmed_start.trim_end();
+  let start = start.advance(leading);
+  let end = if trimmed.len() == trimmed_start.len() {
+    end
+  } else {
+    start.advance(trimmed)
+  };
+  Some(TextSegment {
+    content: trimmed.to_string(),
+    element: None,
+    attr: None,
+    start,
+    end,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::parser::Parser;
+
+  #[test]
+  fn extracts_static_text() {
+    let source = "<view title=\"Greeting\">\n  Hello, {{name}}!\n  <input placeholder=\"Your name\" value=\"{{v}}\"/>\n  <text>  42  </text><text>Bye</text>\n</view>";
+    let root = Parser::new(source).parse_root().unwrap();
+    let segments = extract_text(&root);
+    let contents: Vec<_> = segments
+      .iter()
+      .map(|segment| {
+        let range = segment.start.offset as usize..segment.end.offset as usize;
+        assert_eq!(&source[range], segment.content);
+        (segment.content.as_str(), segment.attr.as_deref())
+      })
+      .collect();
+    assert_eq!(
+      contents,
+      [
+        ("Greeting", Some("title")),
+        ("Hello,", None),
+        ("Your name", Some("placeholder")),
+        ("Bye", None),
+      ]
+    );
+    assert_eq!(segments[1].start.line, 2);
+    assert_eq!(segments[1].start.column, 3);
+    assert_eq!(segments[3].element.as_deref(), Some("text"));
+  }
+}
@@ -0,0 +1,276 @@
+//! 条件编译注释
+//!
+//! uni-app、Taro 等跨端框架使用注释标记只在部分平台生效的区域：
+//!
+//! ```text
+//! <!-- #ifdef MP-WEIXIN || MP-ALIPAY -->
+//! <ad unit-id="..." />
+//! <!-- #endif -->
+//! <!-- #ifndef H5 -->
+//! <button open-type="share">分享</button>
+//! <!-- #endif -->
+//! ```
+//!
+//! [`conditional_blocks`] 把成对的标记解析为区域的嵌套结构，供编辑器折叠、标记非当前平台的代码；
+//! [`strip_conditional_blocks`] 按平台删除不生效的区域以及所有标记注释。
+//! 区域必须在同一组兄弟节点中开始与结束，不能跨越元素的边界
+
+use std::{error::Error, fmt};
+
+use serde::{Deserialize, Serialize};
+
+use crate::ast::{Node, Position, Root};
+
+/// `#ifdef`/`#ifndef` 的条件
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IfdefCondition {
+  /// 是否为 `#ifndef`
+  pub negated: bool,
+  /// 以 `||` 分隔的平台
+  pub platforms: Vec<String>,
+}
+
+impl IfdefCondition {
+  /// 在启用了 `enabled` 中的平台时条件是否成立
+  ///
+  /// 平台名称不区分大小写；条件中的平台也匹配以它加 `-` 开头的平台，如 `MP` 匹配 `MP-WEIXIN`
+  pub fn matches(&self, enabled: &[&str]) -> bool {
+    let defined = self.platforms.iter().any(|platform| {
+      enabled.iter().any(|flag| {
+        flag.eq_ignore_ascii_case(platform)
+          || flag
+            .get(..platform.len() + 1)
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(&format!("{platform}-")))
+      })
+    });
+    defined != self.negated
+  }
+}
+
+/// 标记注释
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Marker {
+  Start(IfdefCondition),
+  End,
+}
+
+impl Marker {
+  /// 从注释内容解析标记
+  fn parse(content: &str) -> Option<Self> {
+    let content = content.trim();
+    let (keyword, rest) = content
+      .split_once(char::is_whitespace)
+      .unwrap_or((content, ""));
+    let negated = match keyword {
+      "#ifdef" => false,
+      "#ifndef" => true,
+      "#endif" => return Some(Marker::End),
+      _ => return None,
+    };
+    let platforms = rest
+      .split("||")
+      .map(str::trim)
+      .filter(|platform| !platform.is_empty())
+      .map(str::to_string)
+      .collect();
+    Some(Marker::Start(IfdefCondition { negated, platforms }))
+  }
+}
+
+/// 注释内容是否为 `#ifdef`、`#ifndef` 或 `#endif` 标记
+pub(crate) fn is_ifdef_marker(content: &str) -> bool {
+  Marker::parse(content).is_some()
+}
+
+/// 一个条件编译区域
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConditionalBlock {
+  pub condition: IfdefCondition,
+  /// 从 `#ifdef`/`#ifndef` 注释的开始到 `#endif` 注释的结束
+  pub start: Position,
+  pub end: Position,
+  /// 区域内（包括区域内元素的子节点中）嵌套的区域
+  pub children: Vec<ConditionalBlock>,
+}
+
+/// 标记不成对
+#[derive(Debug, Clone)]
+pub struct IfdefError {
+  pub message: String,
+  /// 出错的标记注释的位置
+  pub start: Position,
+  pub end: Position,
+}
+
+impl fmt::Display for IfdefError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{} at {}", self.message, self.start)
+  }
+}
+
+impl Error for IfdefError {}
+
+/// 解析文档中的条件编译区域
+pub fn conditional_blocks(root: &Root) -> Result<Vec<ConditionalBlock>, IfdefError> {
+  let mut blocks = Vec::new();
+  collect(&root.children, &mut blocks)?;
+  Ok(blocks)
+}
+
+fn collect(nodes: &[Node], out: &mut Vec<ConditionalBlock>) -> Result<(), IfdefError> {
+  // 尚未闭合的区域：条件、开始位置、已收集的嵌套区域
+  let mut open: Vec<(IfdefCondition, Position, Position, Vec<ConditionalBlock>)> = Vec::new();
+  for node in nodes {
+    match node {
+      Node::Comment {
+        content,
+        start,
+        end,
+      } => match Marker::parse(content) {
+        Some(Marker::Start(condition)) => open.push((condition, *start, *end, Vec::new())),
+        Some(Marker::End) => {
+          let Some((condition, block_start, _, children)) = open.pop() else {
+            return Err(IfdefError {
+              message: "`#endif` without a matching `#ifdef`".to_string(),
+              start: *start,
+              end: *end,
+            });
+          };
+          let block = ConditionalBlock {
+            condition,
+            start: block_start,
+            end: *end,
+            children,
+          };
+          match open.last_mut() {
+            Some((.., children)) => children.push(block),
+            None => out.push(block),
+          }
+        }
+        None => {}
+      },
+      Node::Element { children, .. } => match open.last_mut() {
+        Some((.., nested)) => collect(children, nested)?,
+        None => collect(children, out)?,
+      },
+      _ => {}
+    }
+  }
+  match open.pop() {
+    Some((_, start, end, _)) => Err(IfdefError {
+      message: "`#ifdef` is not closed by `#endif` among its siblings".to_string(),
+      start,
+      end,
+    }),
+    None => Ok(()),
+  }
+}
+
+/// 删除在 `enabled` 平台下不生效的区域以及所有标记注释，返回删除的区域个数
+///
+/// 标记不成对时返回错误且不修改文档
+pub fn strip_conditional_blocks(root: &mut Root, enabled: &[&str]) -> Result<usize, IfdefError> {
+  conditional_blocks(root)?;
+  Ok(strip(&mut root.children, enabled))
+}
+
+fn strip(nodes: &mut Vec<Node>, enabled: &[&str]) -> usize {
+  let mut removed = 0;
+  // 每一层区域是否生效，外层不生效时内层同样不生效
+  let mut active: Vec<bool> = Vec::new();
+  for mut node in std::mem::take(nodes) {
+    if let Node::Comment { content, .. } = &node {
+      match Marker::parse(content) {
+        Some(Marker::Start(condition)) => {
+          // 外层已被删除的区域不重复计数
+          let parent = active.last().copied().unwrap_or(true);
+          let keep = condition.matches(enabled);
+          removed += usize::from(parent && !keep);
+          active.push(parent && keep);
+          continue;
+        }
+        Some(Marker::End) => {
+          active.pop();
+          continue;
+        }
+        None => {}
+      }
+    }
+    if !active.last().copied().unwrap_or(true) {
+      continue;
+    }
+    if let Some(children) = node.children_mut() {
+      removed += strip(children, enabled);
+    }
+    nodes.push(node);
+  }
+  removed
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    codegen::{generate, CodegenOptions},
+    options::ParserOptions,
+    parser::Parser,
+  };
+
+  #[test]
+  fn ifdef_regions() {
+    let source = "<view>\
+<!-- #ifdef MP-WEIXIN || MP-ALIPAY --><ad/><!-- #ifndef MP-ALIPAY --><text>wx</text><!-- #endif --><!-- #endif -->\
+<!-- #ifdef H5 --><a/><!-- #endif -->\
+<!-- #ifndef MP --><b/><!-- #endif -->\
+</view>";
+    let mut root = Parser::new(source).parse_root().unwrap();
+    let blocks = conditional_blocks(&root).unwrap();
+    assert_eq!(blocks.len(), 3);
+    assert_eq!(blocks[0].condition.platforms, ["MP-WEIXIN", "MP-ALIPAY"]);
+    assert!(blocks[0].children[0].condition.negated);
+    assert_eq!(blocks[0].start.offset, 6);
+
+    let mut alipay = root.clone();
+    assert_eq!(
+      strip_conditional_blocks(&mut alipay, &["MP-ALIPAY"]).unwrap(),
+      3
+    );
+    assert_eq!(
+      generate(&alipay, &CodegenOptions::default()),
+      "<view><ad /></view>"
+    );
+    assert_eq!(strip_conditional_blocks(&mut root, &["h5"]).unwrap(), 1);
+    assert_eq!(
+      generate(&root, &CodegenOptions::default()),
+      "<view><a /><b /></view>"
+    );
+
+    let broken = Parser::new("<view><!-- #ifdef H5 --></view><!-- #endif -->")
+      .parse_root()
+      .unwrap();
+    let error = conditional_blocks(&broken).unwrap_err();
+    assert_eq!(error.start.offset, 6);
+  }
+
+  #[test]
+  fn markers_survive_comment_attachment() {
+    let source = "<view><!-- 广告 --><!-- #ifdef H5 --><a/><!-- #endif --><b/></view>";
+    let options = ParserOptions {
+      attach_comments: Some(true),
+      ..Default::default()
+    };
+    let mut root = Parser::with_options(source, options).parse_root().unwrap();
+    assert_eq!(root.comments.as_ref().unwrap().len(), 1);
+    assert_eq!(conditional_blocks(&root).unwrap().len(), 1);
+    assert_eq!(
+      strip_conditional_blocks(&mut root, &["MP-WEIXIN"]).unwrap(),
+      1
+    );
+    assert_eq!(
+      generate(&root, &CodegenOptions::default()),
+      "<view><b /></view>"
+    );
+  }
+}
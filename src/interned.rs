@@ -0,0 +1,441 @@
+//! 字符串驻留的 AST
+//!
+//! 结构与 [`ast`](crate::ast) 一一对应，但标签名、属性名与属性值为从 [`Interner`] 取得的 `Arc<str>`：
+//! 相同的字符串只保存一份，如大型项目中反复出现的 `view`、`class`、`bindtap`、`{{item.id}}`。
+//! 批量解析时在多个文件之间复用同一个 [`Interner`] 即可显著减少常驻内存；
+//! 文本、注释与插值表达式的内容很少重复，仍为 `String`。
+//! 需要序列化或使用其他接口时使用 `into_owned` 转换为 [`ast`](crate::ast) 中的类型。仅提供 Rust 接口
+
+use std::{collections::HashSet, sync::Arc};
+
+use crate::{
+  ast::{self, split_attr_name_str, AttributeQuote, Position},
+  parser::{Build, PResult, Parser},
+};
+
+/// 字符串驻留池，相同内容的字符串共享同一份 `Arc<str>`
+#[derive(Debug, Clone, Default)]
+pub struct Interner {
+  strings: HashSet<Arc<str>>,
+}
+
+impl Interner {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// 取得 `text` 对应的共享字符串，首次出现时加入驻留池
+  pub fn intern(&mut self, text: &str) -> Arc<str> {
+    if let Some(existing) = self.strings.get(text) {
+      return existing.clone();
+    }
+    let interned: Arc<str> = Arc::from(text);
+    self.strings.insert(interned.clone());
+    interned
+  }
+
+  /// 已驻留的字符串，不存在时返回 `None`
+  pub fn get(&self, text: &str) -> Option<&Arc<str>> {
+    self.strings.get(text)
+  }
+
+  /// 驻留的不同字符串的数量
+  pub fn len(&self) -> usize {
+    self.strings.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.strings.is_empty()
+  }
+
+  /// 驻留的字符串内容的总字节数
+  pub fn bytes(&self) -> usize {
+    self.strings.iter().map(|text| text.len()).sum()
+  }
+
+  /// 所有驻留的字符串，顺序不固定
+  pub fn iter(&self) -> impl Iterator<Item = &Arc<str>> {
+    self.strings.iter()
+  }
+
+  /// 移除不再被任何 AST 引用的字符串
+  pub fn shrink(&mut self) {
+    self.strings.retain(|text| Arc::strong_count(text) > 1);
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct Root {
+  pub children: Vec<Node>,
+  pub start: Position,
+  pub end: Position,
+}
+
+#[derive(Debug, Clone)]
+pub enum Node {
+  Element {
+    name: Arc<str>,
+    attrs: Vec<Attribute>,
+    children: Vec<Node>,
+    self_closing: bool,
+    first_attr_same_line: bool,
+    recovered: bool,
+    close_start: Option<Position>,
+    close_end: Option<Position>,
+    start: Position,
+    end: Position,
+  },
+  Text {
+    content: String,
+    start: Position,
+    end: Position,
+  },
+  Comment {
+    content: String,
+    start: Position,
+    end: Position,
+  },
+  Expression {
+    content: String,
+    start: Position,
+    end: Position,
+  },
+}
+
+#[derive(Debug, Clone)]
+pub struct Attribute {
+  pub name: Arc<str>,
+  pub prefix: Option<Arc<str>>,
+  pub local: Arc<str>,
+  pub value: Option<Vec<AttributeValue>>,
+  pub quote: AttributeQuote,
+  pub start: Position,
+  pub end: Position,
+}
+
+#[derive(Debug, Clone)]
+pub enum AttributeValue {
+  Text {
+    content: Arc<str>,
+    start: Position,
+    end: Position,
+  },
+  Expression {
+    content: Arc<str>,
+    start: Position,
+    end: Position,
+  },
+}
+
+impl<'s> Parser<'s> {
+  /// 解析为字符串驻留的 AST，见 [`interned`](crate::interned)
+  pub fn parse_root_interned(&mut self, interner: &mut Interner) -> PResult<Root> {
+    let (children, start, end) = self.parse_document(&mut Interned { interner })?;
+    Ok(Root {
+      children,
+      start,
+      end,
+    })
+  }
+}
+
+impl Root {
+  /// 深度优先（先序）遍历所有节点
+  pub fn walk<'a, F>(&'a self, f: &mut F)
+  where
+    F: FnMut(&'a Node),
+  {
+    for child in &self.children {
+      child.walk(f);
+    }
+  }
+
+  /// 转换为拥有所有权的 AST
+  pub fn into_owned(self) -> ast::Root {
+    ast::Root {
+      children: self.children.into_iter().map(Node::into_owned).collect(),
+      start: self.start,
+      end: self.end,
+      stats: None,
+      comments: None,
+      errors: Vec::new(),
+    }
+  }
+}
+
+impl Node {
+  /// 元素的标签名
+  pub fn name(&self) -> Option<&str> {
+    match self {
+      Node::Element { name, .. } => Some(name),
+      _ => None,
+    }
+  }
+
+  /// 元素的子节点
+  pub fn children(&self) -> Option<&[Node]> {
+    match self {
+      Node::Element { children, .. } => Some(children),
+      _ => None,
+    }
+  }
+
+  /// 深度优先（先序）遍历当前节点及其后代
+  pub fn walk<'a, F>(&'a self, f: &mut F)
+  where
+    F: FnMut(&'a Node),
+  {
+    f(self);
+    if let Node::Element { children, .. } = self {
+      for child in children {
+        child.walk(f);
+      }
+    }
+  }
+
+  /// 转换为拥有所有权的节点
+  pub fn into_owned(self) -> ast::Node {
+    match self {
+      Node::Element {
+        name,
+        attrs,
+        children,
+        self_closing,
+        first_attr_same_line,
+        recovered,
+        close_start,
+        close_end,
+        start,
+        end,
+      } => ast::Node::Element {
+        name: name.to_string(),
+        attrs: attrs.into_iter().map(Attribute::into_owned).collect(),
+        children: children.into_iter().map(Node::into_owned).collect(),
+        self_closing,
+        first_attr_same_line,
+        recovered,
+        close_start,
+        close_end,
+        start,
+        end,
+      },
+      Node::Text {
+        content,
+        start,
+        end,
+      } => ast::Node::Text {
+        content,
+        start,
+        end,
+      },
+      Node::Comment {
+        content,
+        start,
+        end,
+      } => ast::Node::Comment {
+        content,
+        start,
+        end,
+      },
+      Node::Expression {
+        content,
+        start,
+        end,
+      } => ast::Node::Expression {
+        content,
+        start,
+        end,
+      },
+    }
+  }
+}
+
+impl Attribute {
+  /// 转换为拥有所有权的属性
+  pub fn into_owned(self) -> ast::Attribute {
+    ast::Attribute {
+      name: self.name.to_string(),
+      prefix: self.prefix.map(|prefix| prefix.to_string()),
+      local: self.local.to_string(),
+      value: self
+        .value
+        .map(|values| values.into_iter().map(AttributeValue::into_owned).collect()),
+      quote: self.quote,
+      start: self.start,
+      end: self.end,
+    }
+  }
+}
+
+impl AttributeValue {
+  /// 转换为拥有所有权的属性值
+  pub fn into_owned(self) -> ast::AttributeValue {
+    match self {
+      AttributeValue::Text {
+        content,
+        start,
+        end,
+      } => ast::AttributeValue::Text {
+        content: content.to_string(),
+        start,
+        end,
+      },
+      AttributeValue::Expression {
+        content,
+        start,
+        end,
+      } => ast::AttributeValue::Expression {
+        content: content.to_string(),
+        start,
+        end,
+      },
+    }
+  }
+}
+
+/// 通过驻留池构造节点
+struct Interned<'i> {
+  interner: &'i mut Interner,
+}
+
+impl<'s> Build<'s> for Interned<'_> {
+  type Node = Node;
+  type Attribute = Attribute;
+  type AttributeValue = AttributeValue;
+
+  fn element(
+    &mut self,
+    name: &'s str,
+    attrs: Vec<Attribute>,
+    children: Vec<Node>,
+    self_closing: bool,
+    first_attr_same_line: bool,
+    recovered: bool,
+    close_start: Option<Position>,
+    close_end: Option<Position>,
+    start: Position,
+    end: Position,
+  ) -> Node {
+    Node::Element {
+      name: self.interner.intern(name),
+      attrs,
+      children,
+      self_closing,
+      first_attr_same_line,
+      recovered,
+      close_start,
+      close_end,
+      start,
+      end,
+    }
+  }
+
+  fn text(&mut self, content: &'s str, start: Position, end: Position) -> Node {
+    Node::Text {
+      content: content.to_string(),
+      start,
+      end,
+    }
+  }
+
+  fn comment(&mut self, content: &'s str, start: Position, end: Position) -> Node {
+    Node::Comment {
+      content: content.to_string(),
+      start,
+      end,
+    }
+  }
+
+  fn expression(&mut self, content: &'s str, start: Position, end: Position) -> Node {
+    Node::Expression {
+      content: content.to_string(),
+      start,
+      end,
+    }
+  }
+
+  fn attribute(
+    &mut self,
+    name: &'s str,
+    value: Option<Vec<AttributeValue>>,
+    quote: AttributeQuote,
+    start: Position,
+    end: Position,
+  ) -> Attribute {
+    let (prefix, local) = split_attr_name_str(name);
+    Attribute {
+      name: self.interner.intern(name),
+      prefix: prefix.map(|prefix| self.interner.intern(prefix)),
+      local: self.interner.intern(local),
+      value,
+      quote,
+      start,
+      end,
+    }
+  }
+
+  fn attribute_start(&self, attr: &Attribute) -> Position {
+    attr.start
+  }
+
+  fn text_value(&mut self, content: &'s str, start: Position, end: Position) -> AttributeValue {
+    AttributeValue::Text {
+      content: self.interner.intern(content),
+      start,
+      end,
+    }
+  }
+
+  fn expression_value(
+    &mut self,
+    content: &'s str,
+    start: Position,
+    end: Position,
+  ) -> AttributeValue {
+    AttributeValue::Expression {
+      content: self.interner.intern(content),
+      start,
+      end,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn shares_strings_across_files() {
+    let sources = [
+      r#"<view class="item" bindtap="tap"><text class="item">{{name}}</text></view>"#,
+      r#"<view class="item" wx:for="{{list}}"/>"#,
+    ];
+    let mut interner = Interner::new();
+    let roots: Vec<_> = sources
+      .iter()
+      .map(|source| {
+        Parser::new(source)
+          .parse_root_interned(&mut interner)
+          .unwrap()
+      })
+      .collect();
+
+    let class = |root: &Root| match &root.children[0] {
+      Node::Element { attrs, .. } => attrs[0].name.clone(),
+      _ => unreachable!(),
+    };
+    assert!(Arc::ptr_eq(&class(&roots[0]), &class(&roots[1])));
+    // view、text、class、item、bindtap、tap、wx:for、wx、for 以及表达式 list
+    assert_eq!(interner.len(), 10);
+
+    let owned = roots[0].clone().into_owned();
+    assert_eq!(
+      serde_json::to_value(owned).unwrap(),
+      serde_json::to_value(Parser::new(sources[0]).parse_root().unwrap()).unwrap()
+    );
+
+    drop(roots);
+    interner.shrink();
+    assert!(interner.is_empty());
+  }
+}
@@ -0,0 +1,584 @@
+//! WXML → JSX 转换
+//!
+//! 把页面或组件模板转换为 React 函数组件的源码，帮助团队把小程序页面迁移到 Taro 等基于 React 的技术栈：
+//!
+//! - 标签名转换为组件名，如 `scroll-view` 为 `ScrollView`，`<block>` 为 `Block`；属性名转换为驼峰形式，
+//!   `class` 为 `className`，`data-*` 与 `aria-*` 保持不变
+//! - `wx:if`/`wx:elif`/`wx:else` 转换为条件表达式，`wx:for` 转换为 `.map`，`wx:key` 转换为 `key`
+//! - 事件绑定转换为 `on` 开头的属性，`tap` 与 Taro 一致为 `onClick`；`catch` 阻止冒泡的语义需要在处理函数中补充
+//! - 模板定义转换为同一文件中的函数组件，`<template is>` 按名称调用；动态的模板名无法转换，输出为注释
+//! - 注释保留为 JSX 注释，`<wxs>`、`<import>`、`<include>` 不输出
+//!
+//! 表达式原样输出，模板中使用的外部变量与按名称绑定的事件处理函数作为组件的参数。自定义组件的导入需要根据 `usingComponents` 手动补充
+
+use std::collections::BTreeSet;
+
+use crate::{
+  ast::{Attribute, AttributeValue, Node, Root},
+  builtins::is_builtin_tag,
+  template_data::{parse_template_data, template_parameters},
+};
+
+/// JSX 转换选项
+#[derive(Debug, Clone)]
+pub struct JsxOptions {
+  /// 导出的组件名称
+  pub component_name: String,
+  /// 内置组件的导入来源，为 `None` 时不输出导入语句
+  pub import_source: Option<String>,
+  pub indent_width: usize,
+}
+
+impl Default for JsxOptions {
+  fn default() -> Self {
+    Self {
+      component_name: "Index".to_string(),
+      import_source: Some("@tarojs/components".to_string()),
+      indent_width: 2,
+    }
+  }
+}
+
+/// 将整个文档转换为 React 函数组件的源码
+pub fn to_jsx(root: &Root, options: &JsxOptions) -> String {
+  let mut printer = JsxPrinter {
+    options,
+    output: String::new(),
+    components: BTreeSet::new(),
+  };
+  let mut body = String::new();
+  let mut templates = Vec::new();
+  let mut page = Node::element("block");
+  for child in &root.children {
+    if child.name() == Some("template") && child.attr("name").is_some() {
+      templates.push(child);
+    } else {
+      page = page.with_child(child.clone());
+    }
+  }
+  for template in templates {
+    let name = template
+      .attr("name")
+      .and_then(Attribute::as_static_string)
+      .unwrap_or_default();
+    printer.print_component(&template_component(&name), template);
+    body.push_str(&std::mem::take(&mut printer.output));
+    body.push('\n');
+  }
+  printer.output.push_str("export default ");
+  printer.print_component(&options.component_name, &page);
+  body.push_str(&printer.output);
+
+  let mut output = String::new();
+  if let Some(source) = &options.import_source {
+    if !printer.components.is_empty() {
+      let components: Vec<_> = printer.components.into_iter().collect();
+      output.push_str(&format!(
+        "import {{ {} }} from {};\n\n",
+        components.join(", "),
+        js_string(source)
+      ));
+    }
+  }
+  output.push_str(&body);
+  output
+}
+
+struct JsxPrinter<'a> {
+  options: &'a JsxOptions,
+  output: String,
+  /// 使用到的内置组件
+  components: BTreeSet<String>,
+}
+
+impl JsxPrinter<'_> {
+  fn indent(&mut self, depth: usize) {
+    self
+      .output
+      .extend(std::iter::repeat_n(' ', depth * self.options.indent_width));
+  }
+
+  fn line(&mut self, depth: usize, text: &str) {
+    self.indent(depth);
+    self.output.push_str(text);
+    self.output.push('\n');
+  }
+
+  /// 输出以 `node` 的子节点为内容的函数组件，参数为其中使用的外部变量
+  fn print_component(&mut self, name: &str, node: &Node) {
+    let mut parameters = template_parameters(node);
+    // 以静态文本给出的事件处理函数输出为同名变量，同样需要从参数中取得
+    for child in node.children().unwrap_or_default() {
+      child.walk(&mut |node| {
+        parameters.extend(
+          node
+            .attrs()
+            .unwrap_or_default()
+            .iter()
+            .filter_map(handler_name)
+            .filter(|name| is_identifier(name)),
+        );
+      });
+    }
+    let parameters: Vec<_> = parameters.into_iter().collect();
+    let parameters = if parameters.is_empty() {
+      String::new()
+    } else {
+      format!("{{ {} }}", parameters.join(", "))
+    };
+    self
+      .output
+      .push_str(&format!("function {name}({parameters}) {{\n"));
+    let children = node.children().unwrap_or_default();
+    self.line(1, "return (");
+    match children {
+      // 只有一个不带指令的元素时不需要 Fragment
+      [child @ Node::Element { attrs, .. }]
+        if !attrs
+          .iter()
+          .any(|attr| attr.prefix.as_deref() == Some("wx")) =>
+      {
+        self.print_element(child, 2, None);
+      }
+      _ => {
+        self.line(2, "<>");
+        self.print_children(children, 3);
+        self.line(2, "</>");
+      }
+    }
+    self.line(1, ");");
+    self.output.push_str("}\n");
+  }
+
+  fn print_children(&mut self, nodes: &[Node], depth: usize) {
+    // 条件链：(条件, 节点)，`wx:else` 的条件为 `None`
+    let mut chain: Vec<(Option<String>, &Node)> = Vec::new();
+    for node in nodes {
+      match node {
+        Node::Element { .. } => {
+          if node.attr("wx:for").is_some() {
+            self.flush_chain(&mut chain, depth);
+            self.print_for(node, depth);
+          } else if let Some(attr) = node.attr("wx:if") {
+            self.flush_chain(&mut chain, depth);
+            chain.push((Some(expression(attr)), node));
+          } else if let Some(attr) = node.attr("wx:elif") {
+            chain.push((Some(expression(attr)), node));
+          } else if node.attr("wx:else").is_some() {
+            chain.push((None, node));
+            self.flush_chain(&mut chain, depth);
+          } else {
+            self.flush_chain(&mut chain, depth);
+            self.print_element(node, depth, None);
+          }
+        }
+        Node::Text { content, .. } => {
+          if content.trim().is_empty() {
+            continue;
+          }
+          self.flush_chain(&mut chain, depth);
+          for line in content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+          {
+            if line.contains(['{', '}', '<', '>']) {
+              self.line(depth, &format!("{{{}}}", js_string(line)));
+            } else {
+              self.line(depth, line);
+            }
+          }
+        }
+        Node::Expression { content, .. } => {
+          self.flush_chain(&mut chain, depth);
+          self.line(depth, &format!("{{{content}}}"));
+        }
+        // 注释不打断条件链
+        Node::Comment { content, .. } => {
+          let content = content.trim().replace("*/", "* /");
+          self.line(depth, &format!("{{/* {content} */}}"));
+        }
+      }
+    }
+    self.flush_chain(&mut chain, depth);
+  }
+
+  /// 条件链输出为 `{a && (...)}` 或 `{a ? (...) : b ? (...) : (...)}`
+  fn flush_chain(&mut self, chain: &mut Vec<(Option<String>, &Node)>, depth: usize) {
+    let branches = std::mem::take(chain);
+    match branches.as_slice() {
+      [] => {}
+      [(None, node)] => self.print_element(node, depth, None),
+      [(Some(condition), node)] => {
+        self.line(depth, &format!("{{{} && (", wrap(condition)));
+        self.print_element(node, depth + 1, None);
+        self.line(depth, ")}");
+      }
+      _ => {
+        for (index, (condition, node)) in branches.iter().enumerate() {
+          let open = match (index, condition) {
+            (0, Some(condition)) => format!("{{{} ? (", wrap(condition)),
+            (_, Some(condition)) => format!(") : {} ? (", wrap(condition)),
+            (_, None) => ") : (".to_string(),
+          };
+          self.line(depth, &open);
+          self.print_element(node, depth + 1, None);
+        }
+        let close = if branches
+          .last()
+          .is_some_and(|(condition, _)| condition.is_none())
+        {
+          ")}"
+        } else {
+          ") : null}"
+        };
+        self.line(depth, close);
+      }
+    }
+  }
+
+  /// `wx:for` 输出为 `{list.map((item, index) => (...))}`，同时带有 `wx:if` 时在回调中判断
+  fn print_for(&mut self, node: &Node, depth: usize) {
+    let list = expression(node.attr("wx:for").unwrap());
+    let item = static_attr(node, "wx:for-item").unwrap_or_else(|| "item".to_string());
+    let index = static_attr(node, "wx:for-index").unwrap_or_else(|| "index".to_string());
+    let key = static_attr(node, "wx:key").map(|key| match key.as_str() {
+      "*this" => item.clone(),
+      _ => format!("{item}.{key}"),
+    });
+    self.line(
+      depth,
+      &format!("{{{}.map(({item}, {index}) => (", wrap(&list)),
+    );
+    match node.attr("wx:if") {
+      Some(attr) => {
+        self.line(depth + 1, &format!("{} ? (", wrap(&expression(attr))));
+        self.print_element(node, depth + 2, key.as_deref());
+        self.line(depth + 1, ") : null");
+      }
+      None => self.print_element(node, depth + 1, key.as_deref()),
+    }
+    self.line(depth, "))}");
+  }
+
+  /// 输出不考虑条件与循环的元素
+  fn print_element(&mut self, node: &Node, depth: usize, key: Option<&str>) {
+    let Node::Element {
+      name,
+      attrs,
+      children,
+      ..
+    } = node
+    else {
+      return;
+    };
+    let mut props: Vec<String> = key
+      .map(|key| format!("key={{{key}}}"))
+      .into_iter()
+      .collect();
+    let component = match name.as_str() {
+      "import" | "include" | "wxs" => return,
+      "template" => {
+        let Some(is) = node.attr("is") else {
+          return;
+        };
+        let Some(template) = is.as_static_string() else {
+          let source = expression(is).replace("*/", "* /");
+          self.line(
+            depth,
+            &format!("{{/* TODO: template is={{{{{source}}}}} */}}"),
+          );
+          return;
+        };
+        props.extend(template_props(node));
+        template_component(&template)
+      }
+      _ => {
+        props.extend(
+          attrs
+            .iter()
+            .filter(|attr| attr.prefix.as_deref() != Some("wx"))
+            .map(prop),
+        );
+        let component = pascal_case(name);
+        if is_builtin_tag(name) {
+          self.components.insert(component.clone());
+        }
+        component
+      }
+    };
+    let open = if props.is_empty() {
+      format!("<{component}")
+    } else {
+      format!("<{component} {}", props.join(" "))
+    };
+    let has_children = children
+      .iter()
+      .any(|child| !child.is_whitespace() || matches!(child, Node::Comment { .. }));
+    if name == "template" || !has_children {
+      self.line(depth, &format!("{open} />"));
+      return;
+    }
+    // 只包含单行文本与表达式时在同一行输出，保留文本中的空格
+    let inline = children.iter().all(|child| match child {
+      Node::Text { content, .. } => !content.contains('\n'),
+      Node::Expression { .. } => true,
+      _ => false,
+    });
+    if inline {
+      let content: String = children
+        .iter()
+        .map(|child| match child {
+          Node::Text { content, .. } if content.contains(['{', '}', '<', '>']) => {
+            format!("{{{}}}", js_string(content))
+          }
+          Node::Text { content, .. } => content.clone(),
+          Node::Expression { content, .. } => format!("{{{content}}}"),
+          _ => unreachable!(),
+        })
+        .collect();
+      self.line(depth, &format!("{open}>{content}</{component}>"));
+      return;
+    }
+    self.line(depth, &format!("{open}>"));
+    self.print_children(children, depth + 1);
+    self.line(depth, &format!("</{component}>"));
+  }
+}
+
+/// `<template is>` 的 `data` 转换为属性，`...item` 为 `{...item}`，`index` 为 `index={index}`
+fn template_props(node: &Node) -> Vec<String> {
+  let Some([AttributeValue::Expression { content, .. }]) =
+    node.attr("data").and_then(|attr| attr.value.as_deref())
+  else {
+    return Vec::new();
+  };
+  let Ok(arguments) = parse_template_data(content) else {
+    return vec![format!("{{...{{{content}}}}}")];
+  };
+  arguments
+    .iter()
+    .map(|argument| {
+      let source = content[argument.start..argument.end].trim();
+      match &argument.key {
+        None => format!("{{{source}}}"),
+        Some(key) => {
+          let value = source
+            .split_once(':')
+            .map(|(_, value)| value.trim())
+            .unwrap_or(key);
+          format!("{key}={{{value}}}")
+        }
+      }
+    })
+    .collect()
+}
+
+/// 由多个单词组成的事件名对应的属性名，与 Taro 一致
+const EVENT_NAMES: &[(&str, &str)] = &[
+  ("tap", "onClick"),
+  ("longpress", "onLongPress"),
+  ("longtap", "onLongTap"),
+  ("touchstart", "onTouchStart"),
+  ("touchmove", "onTouchMove"),
+  ("touchend", "onTouchEnd"),
+  ("touchcancel", "onTouchCancel"),
+  ("transitionend", "onTransitionEnd"),
+  ("animationstart", "onAnimationStart"),
+  ("animationiteration", "onAnimationIteration"),
+  ("animationend", "onAnimationEnd"),
+  ("scrolltoupper", "onScrollToUpper"),
+  ("scrolltolower", "onScrollToLower"),
+  ("getuserinfo", "onGetUserInfo"),
+  ("getphonenumber", "onGetPhoneNumber"),
+];
+
+/// 属性转换为 JSX 属性
+fn prop(attr: &Attribute) -> String {
+  let name = prop_name(attr);
+  match &attr.value {
+    None => name,
+    Some(_) if attr.is_event_binding() => format!("{name}={{{}}}", handler(attr)),
+    Some(values) => match values.as_slice() {
+      [AttributeValue::Expression { content, .. }] => format!("{name}={{{content}}}"),
+      _ => match attr.as_static_string() {
+        Some(text) if !text.contains('"') => format!("{name}=\"{text}\""),
+        Some(text) => format!("{name}={{{}}}", js_string(&text)),
+        None => format!(
+          "{name}={{{}}}",
+          attr.to_template_string().unwrap_or_default()
+        ),
+      },
+    },
+  }
+}
+
+fn prop_name(attr: &Attribute) -> String {
+  if attr.is_event_binding() {
+    let event = match attr.prefix.as_deref() {
+      Some("bind" | "catch" | "capture-bind" | "capture-catch" | "mut-bind") => attr.local.as_str(),
+      _ => attr
+        .name
+        .strip_prefix("bind")
+        .or_else(|| attr.name.strip_prefix("catch"))
+        .unwrap_or(&attr.name),
+    };
+    return match EVENT_NAMES.iter().find(|(name, _)| *name == event) {
+      Some((_, prop)) => prop.to_string(),
+      None => format!("on{}", pascal_case(event)),
+    };
+  }
+  match attr.name.as_str() {
+    "class" => "className".to_string(),
+    name if name.starts_with("data-") || name.starts_with("aria-") => name.to_string(),
+    name => {
+      let pascal = pascal_case(name);
+      let mut chars = pascal.chars();
+      chars
+        .next()
+        .map(|first| first.to_lowercase().chain(chars).collect())
+        .unwrap_or_default()
+    }
+  }
+}
+
+/// 事件处理函数：静态文本为同名函数，不是合法标识符时为字符串，表达式原样输出
+fn handler(attr: &Attribute) -> String {
+  match handler_name(attr) {
+    Some(name) if is_identifier(&name) => name,
+    Some(name) => js_string(&name),
+    None => expression(attr),
+  }
+}
+
+/// 事件绑定中以静态文本给出的处理函数名
+fn handler_name(attr: &Attribute) -> Option<String> {
+  if !attr.is_event_binding() {
+    return None;
+  }
+  attr.as_static_string().map(|name| name.trim().to_string())
+}
+
+fn is_identifier(name: &str) -> bool {
+  let mut chars = name.chars();
+  chars
+    .next()
+    .is_some_and(|first| first.is_alphabetic() || matches!(first, '_' | '$'))
+    && chars.all(|c| c.is_alphanumeric() || matches!(c, '_' | '$'))
+}
+
+/// 指令或属性的值作为 JS 表达式：单个表达式原样输出，静态文本为字符串，其余为模板字符串
+fn expression(attr: &Attribute) -> String {
+  match attr.value.as_deref() {
+    None => "true".to_string(),
+    Some([AttributeValue::Expression { content, .. }]) => content.clone(),
+    Some(_) => match attr.as_static_string() {
+      Some(text) => js_string(&text),
+      None => attr.to_template_string().unwrap_or_default(),
+    },
+  }
+}
+
+/// 非简单成员访问的表达式加上括号
+fn wrap(expression: &str) -> String {
+  let simple = expression
+    .chars()
+    .all(|c| c.is_alphanumeric() || matches!(c, '_' | '$' | '.'));
+  if simple {
+    expression.to_string()
+  } else {
+    format!("({expression})")
+  }
+}
+
+fn template_component(name: &str) -> String {
+  format!("{}Template", pascal_case(name))
+}
+
+/// `scroll-view` 转换为 `ScrollView`
+fn pascal_case(name: &str) -> String {
+  name
+    .split(|c: char| !c.is_alphanumeric())
+    .filter(|part| !part.is_empty())
+    .map(|part| {
+      let mut chars = part.chars();
+      chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+        .unwrap_or_default()
+    })
+    .collect()
+}
+
+fn js_string(value: &str) -> String {
+  serde_json::to_string(value).unwrap()
+}
+
+fn static_attr(node: &Node, name: &str) -> Option<String> {
+  node.attr(name).and_then(Attribute::as_static_string)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::parser::Parser;
+
+  #[test]
+  fn convert_to_jsx() {
+    let source = r#"<template name="row"><text>{{index}}: {{name}}</text></template>
+<view class="list {{cls}}" hover-class="hover" bindtap="open" data-id="{{id}}">
+  <!-- 列表 -->
+  <block wx:for="{{users}}" wx:for-item="user" wx:key="id">
+    <template wx:if="{{user.active}}" is="row" data="{{...user, index}}"/>
+    <text wx:elif="{{user.age > 60}}" catch:longpress="{{onPress}}">senior</text>
+    <text wx:else>-</text>
+  </block>
+  <scroll-view wx:if="{{more}}" scroll-y/>
+</view>"#;
+    let root = Parser::new(source).parse_root().unwrap();
+    assert_eq!(
+      to_jsx(&root, &JsxOptions::default()),
+      r#"import { Block, ScrollView, Text, View } from "@tarojs/components";
+
+function RowTemplate({ index, name }) {
+  return (
+    <Text>{index}: {name}</Text>
+  );
+}
+
+export default function Index({ cls, id, more, onPress, open, users }) {
+  return (
+    <View className={`list ${cls}`} hoverClass="hover" onClick={open} data-id={id}>
+      {/* 列表 */}
+      {users.map((user, index) => (
+        <Block key={user.id}>
+          {user.active ? (
+            <RowTemplate {...user} index={index} />
+          ) : (user.age > 60) ? (
+            <Text onLongPress={onPress}>senior</Text>
+          ) : (
+            <Text>-</Text>
+          )}
+        </Block>
+      ))}
+      {more && (
+        <ScrollView scrollY />
+      )}
+    </View>
+  );
+}
+"#
+    );
+  }
+
+  #[test]
+  fn handlers_are_parameters() {
+    let source = r#"<view bindtap="onTap" catch:longpress="{{onPress}}" bind:input="on-input">{{b}}</view>
+<text wx:for="{{list}}" bindtap="onItemTap">{{item}}</text>"#;
+    let root = Parser::new(source).parse_root().unwrap();
+    let jsx = to_jsx(&root, &JsxOptions::default());
+    assert!(jsx.contains("function Index({ b, list, onItemTap, onPress, onTap })"));
+    assert!(jsx
+      .contains(r#"<View onClick={onTap} onLongPress={onPress} onInput={"on-input"}>{b}</View>"#));
+  }
+}
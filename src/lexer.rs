@@ -0,0 +1,657 @@
+//! 词法分析：按上下文把源码切分为带位置的词法单元
+//!
+//! WXML 的词法依赖上下文：同样的 `>` 在标签内是标签结束，在文本中只是普通字符；原始文本标签的内容不切分标签；
+//! Vue 兼容模式下绑定属性的值整体是表达式。因此词法分析器不预先切分整个源码，而是由解析器按所处的上下文
+//! （`LexMode`）逐个读取下一个词法单元。解析器只根据词法单元的种类、内容与位置构造节点，
+//! 不直接访问源码中的字符；未闭合的 `{{`、不构成标签的 `<` 等词法错误由词法分析器记录。
+//!
+//! 词法单元首尾相接，拼接后即为源码（解析失败时剩余内容为一个 [`TokenKind::Invalid`]）。
+//! [`Parser::tokenize`] 返回解析过程中读取的全部词法单元，用于语法高亮与调试解析过程。仅提供 Rust 接口
+
+use std::cell::Cell;
+
+use memchr::{memchr, memmem};
+use serde::Serialize;
+
+use crate::{
+  ast::{AttributeQuote, Position},
+  error::{OpenTag, SyntaxError, SyntaxErrorKind},
+  helpers::{find_expression_end, is_attr_name_char, is_tag_name_char, starts_markup},
+  options::ParserOptions,
+  parser::{Build, PResult, Parser},
+  state::{until_or_mustache, Checkpoint, ParseState},
+};
+
+/// 词法单元的种类
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TokenKind {
+  /// 开始标签的 `<`
+  TagOpen,
+  /// 结束标签的 `</`
+  EndTagOpen,
+  /// 开始或结束标签中的标签名
+  TagName,
+  /// 标签结尾的 `>`
+  TagClose,
+  /// 自闭合标签结尾的 `/>`
+  SelfClose,
+  AttrName,
+  /// 属性名与属性值之间的 `=`
+  Equals,
+  /// 属性值的引号
+  Quote,
+  /// 属性值中的静态文本
+  AttrText,
+  /// `{{`
+  MustacheStart,
+  /// `{{` 与 `}}` 之间的表达式源码，包括首尾空白
+  Expression,
+  /// `}}`
+  MustacheEnd,
+  /// 节点之间的文本
+  Text,
+  /// 原始文本标签（如 `<wxs>`）的内容
+  RawText,
+  /// 注释开头的 `<!--`
+  CommentStart,
+  /// 注释的内容
+  CommentText,
+  /// 注释结尾的 `-->`，或 `<!-->` 这类空注释的结尾
+  CommentEnd,
+  /// 被忽略的 `<!DOCTYPE ...>` 与 `<?xml ...?>` 声明
+  Declaration,
+  /// 标签内、节点之间的空白
+  Whitespace,
+  /// 错误恢复时跳过的内容，以及标签内不跟 `>` 的 `/`
+  Invalid,
+  /// 输入结尾，或不带引号的属性值的结尾；不包含任何源码，不会出现在 [`Parser::tokenize`] 的结果中
+  Eof,
+}
+
+/// 词法单元
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Token<'s> {
+  pub kind: TokenKind,
+  /// 对应的源码
+  pub text: &'s str,
+  pub start: Position,
+  pub end: Position,
+}
+
+/// 读取词法单元时所处的上下文
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LexMode<'a> {
+  /// 节点之间：文本、`{{`、标签与注释的开头、声明，以及节点之间只含空白的文本
+  Content,
+  /// `<` 或 `</` 之后的标签名
+  TagName,
+  /// 标签内：属性名、`=`、引号、`>` 与 `/>`
+  Tag,
+  /// 属性值的内容，参数为所在的引号；`None` 为宽松模式下不带引号的值，遇到空白、`>` 或 `/>` 时结束
+  AttrValue(Option<char>),
+  /// Vue 兼容模式下整体作为表达式的属性值，参数为所在的引号
+  WholeExpression(char),
+  /// `{{` 之后的表达式与 `}}`，参数为所在属性值的引号
+  Mustache(Option<char>),
+  /// `<!--` 之后的注释内容与结尾
+  Comment,
+  /// 原始文本标签的内容，直到对应的结束标签 `</name`
+  RawText(&'a str),
+  /// 任意字符串中的文本与 `{{`，见 [`Parser::parse_interpolation`]
+  Interpolation,
+  /// 跳过属性中无法解析的内容，直到空白或标签结束
+  SkipAttribute,
+  /// 跳过片段中多余的结束标签或一个无法解析的字符
+  SkipFragment,
+}
+
+impl LexMode<'_> {
+  /// 用于缓存扫描结果的上下文，原始文本的结束标签名不缓存
+  fn cache_key(self) -> Option<LexMode<'static>> {
+    Some(match self {
+      LexMode::Content => LexMode::Content,
+      LexMode::TagName => LexMode::TagName,
+      LexMode::Tag => LexMode::Tag,
+      LexMode::AttrValue(quote) => LexMode::AttrValue(quote),
+      LexMode::WholeExpression(quote) => LexMode::WholeExpression(quote),
+      LexMode::Mustache(quote) => LexMode::Mustache(quote),
+      LexMode::Comment => LexMode::Comment,
+      LexMode::RawText(_) => return None,
+      LexMode::Interpolation => LexMode::Interpolation,
+      LexMode::SkipAttribute => LexMode::SkipAttribute,
+      LexMode::SkipFragment => LexMode::SkipFragment,
+    })
+  }
+}
+
+/// 扫描的结果：词法单元的种类与字节长度，或在当前位置无法切分出词法单元的错误
+type Scan = Result<(TokenKind, usize), SyntaxErrorKind>;
+
+/// 词法分析器，由解析器按上下文逐个读取词法单元
+///
+/// [`peek`](Lexer::peek) 只扫描、不消费，[`next`](Lexer::next) 消费一个词法单元并记录其中的词法错误。
+/// 需要多个词法单元才能确定的结构（如结束标签的名称是否匹配）通过 [`checkpoint`](Lexer::checkpoint)
+/// 与 [`rewind`](Lexer::rewind) 回溯
+pub(crate) struct Lexer<'s> {
+  state: ParseState<'s>,
+  /// 记录切分出的词法单元，只在 [`tokenize`] 时开启
+  tokens: Option<Vec<Token<'s>>>,
+  /// 最近一次扫描的位置、上下文与结果；解析器通常先前瞻再读取，避免重复扫描较长的文本
+  scanned: Cell<Option<(usize, LexMode<'static>, Scan)>>,
+}
+
+/// 词法分析器的快照，见 [`Lexer::checkpoint`]
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct LexerCheckpoint {
+  state: Checkpoint,
+  tokens: usize,
+}
+
+impl<'s> Lexer<'s> {
+  pub(crate) fn new(source: &'s str) -> Self {
+    Self {
+      state: ParseState::new(source),
+      tokens: None,
+      scanned: Cell::new(None),
+    }
+  }
+
+  /// 下一个词法单元的种类，不消费源码；无法切分出词法单元时为 [`TokenKind::Invalid`]
+  pub(crate) fn peek(&self, mode: LexMode) -> TokenKind {
+    self.scan(mode).map_or(TokenKind::Invalid, |(kind, _)| kind)
+  }
+
+  /// 消费下一个词法单元；无法切分出词法单元时记录错误且不消费任何内容
+  pub(crate) fn next(&mut self, mode: LexMode) -> PResult<Token<'s>> {
+    let (kind, len) = self
+      .scan(mode)
+      .map_err(|kind| self.state.emit_error(kind))?;
+    let token = self.consume(kind, len);
+    // 未闭合的 `{{` 按文本恢复
+    if matches!(kind, TokenKind::Text | TokenKind::AttrText) && token.text.starts_with("{{") {
+      self.state.emit_error_at(
+        SyntaxErrorKind::ExpectMustacheInterpolation,
+        token.start,
+        None,
+      );
+    }
+    // 与 HTML 一致，后面不是标签的 `<` 作为普通文本，每个都记录一条 `StrayLessThan` 错误
+    if mode == LexMode::Content && kind == TokenKind::Text {
+      let mut position = token.start;
+      let mut from = 0;
+      for index in memchr::memchr_iter(b'<', token.text.as_bytes()) {
+        position = position.advance(&token.text[from..index]);
+        from = index;
+        self
+          .state
+          .emit_error_at(SyntaxErrorKind::StrayLessThan, position, None);
+      }
+    }
+    Ok(token)
+  }
+
+  /// 下一个词法单元为 `kind` 时消费它
+  pub(crate) fn next_if(&mut self, mode: LexMode, kind: TokenKind) -> Option<Token<'s>> {
+    if self.peek(mode) != kind {
+      return None;
+    }
+    self.next(mode).ok()
+  }
+
+  /// 保存当前位置、已记录的错误与词法单元，用于回溯
+  pub(crate) fn checkpoint(&self) -> LexerCheckpoint {
+    LexerCheckpoint {
+      state: self.state.checkpoint(),
+      tokens: self.tokens.as_ref().map_or(0, Vec::len),
+    }
+  }
+
+  /// 回到 `checkpoint`，丢弃之后记录的错误与词法单元
+  pub(crate) fn rewind(&mut self, checkpoint: LexerCheckpoint) {
+    self.state.rewind(checkpoint.state);
+    if let Some(tokens) = &mut self.tokens {
+      tokens.truncate(checkpoint.tokens);
+    }
+  }
+
+  /// 消费 `len` 个字节作为一个 `kind` 词法单元；没有消费任何内容时不记录
+  fn consume(&mut self, kind: TokenKind, len: usize) -> Token<'s> {
+    let start = self.state.position();
+    let text = self.state.next_bytes(len);
+    let token = Token {
+      kind,
+      text,
+      start,
+      end: self.state.position(),
+    };
+    if let Some(tokens) = &mut self.tokens {
+      if !text.is_empty() {
+        tokens.push(token);
+      }
+    }
+    token
+  }
+
+  /// 按上下文扫描下一个词法单元，不消费源码
+  fn scan(&self, mode: LexMode) -> Scan {
+    let index = self.state.index();
+    let key = mode.cache_key();
+    if let (Some((cached_index, cached_mode, scan)), Some(key)) = (self.scanned.get(), key) {
+      if cached_index == index && cached_mode == key {
+        return scan;
+      }
+    }
+    let scan = self.scan_uncached(mode);
+    if let Some(key) = key {
+      self.scanned.set(Some((index, key, scan)));
+    }
+    scan
+  }
+
+  fn scan_uncached(&self, mode: LexMode) -> Scan {
+    let rest = self.state.current_str();
+    if rest.is_empty() && mode != LexMode::Comment {
+      return Ok((TokenKind::Eof, 0));
+    }
+    match mode {
+      LexMode::Content => scan_content(rest),
+      LexMode::TagName => Ok((TokenKind::TagName, prefix_len(rest, is_tag_name_char))),
+      LexMode::Tag => Ok(scan_tag(rest)),
+      LexMode::AttrValue(Some(quote)) => Ok(scan_quoted_value(rest, quote)),
+      LexMode::AttrValue(None) => Ok(scan_unquoted_value(rest)),
+      LexMode::WholeExpression(quote) => Ok(if rest.starts_with(char::is_whitespace) {
+        (TokenKind::Whitespace, prefix_len(rest, char::is_whitespace))
+      } else if rest.starts_with(quote) {
+        (TokenKind::Quote, quote.len_utf8())
+      } else {
+        let len = memchr(quote as u8, rest.as_bytes()).unwrap_or(rest.len());
+        (TokenKind::Expression, len)
+      }),
+      LexMode::Mustache(quote) => {
+        if rest.starts_with("}}") {
+          return Ok((TokenKind::MustacheEnd, 2));
+        }
+        find_expression_end(rest, quote)
+          .map(|len| (TokenKind::Expression, len))
+          .ok_or(SyntaxErrorKind::ExpectMustacheInterpolation)
+      }
+      LexMode::Comment => scan_comment(rest),
+      LexMode::RawText(name) => {
+        let close = format!("</{name}");
+        let len = memmem::find(rest.as_bytes(), close.as_bytes()).unwrap_or(rest.len());
+        Ok((TokenKind::RawText, len))
+      }
+      LexMode::Interpolation => Ok(if starts_expression(rest, None) {
+        (TokenKind::MustacheStart, 2)
+      } else {
+        let from = if rest.starts_with("{{") { 2 } else { 0 };
+        let len =
+          memmem::find(&rest.as_bytes()[from..], b"{{").map_or(rest.len(), |len| from + len);
+        (TokenKind::Text, len)
+      }),
+      LexMode::SkipAttribute => {
+        let first = rest.chars().next().map_or(0, char::len_utf8);
+        let len = first
+          + prefix_len(&rest[first..], |c| {
+            !c.is_whitespace() && c != '>' && c != '/'
+          });
+        Ok((TokenKind::Invalid, len))
+      }
+      LexMode::SkipFragment => {
+        let len = if rest.starts_with("</") {
+          memchr(b'>', rest.as_bytes()).map_or(rest.len(), |len| len + 1)
+        } else {
+          rest.chars().next().map_or(0, char::len_utf8)
+        };
+        Ok((TokenKind::Invalid, len))
+      }
+    }
+  }
+
+  pub(crate) fn position(&self) -> Position {
+    self.state.position()
+  }
+
+  pub(crate) fn source(&self) -> &'s str {
+    self.state.source()
+  }
+
+  /// 尚未消费的字节数
+  pub(crate) fn remaining_len(&self) -> usize {
+    self.state.current_str().len()
+  }
+
+  pub(crate) fn is_end(&self) -> bool {
+    self.state.is_end()
+  }
+
+  pub(crate) fn errors(&self) -> &[SyntaxError] {
+    self.state.errors()
+  }
+
+  pub(crate) fn emit_error(&mut self, kind: SyntaxErrorKind) -> SyntaxError {
+    self.state.emit_error(kind)
+  }
+
+  pub(crate) fn emit_error_with_open_tag(
+    &mut self,
+    kind: SyntaxErrorKind,
+    open_tag: Option<OpenTag>,
+  ) -> SyntaxError {
+    self.state.emit_error_with_open_tag(kind, open_tag)
+  }
+
+  pub(crate) fn errors_mut(&mut self) -> &mut Vec<SyntaxError> {
+    self.state.errors_mut()
+  }
+
+  pub(crate) fn record_tokens(&mut self) {
+    self.tokens = Some(Vec::new());
+  }
+
+  /// 已记录的词法单元，剩余未消费的内容作为一个 [`TokenKind::Invalid`]
+  pub(crate) fn take_tokens(&mut self) -> Vec<Token<'s>> {
+    self.consume(TokenKind::Invalid, self.remaining_len());
+    self.tokens.take().unwrap_or_default()
+  }
+}
+
+/// 满足条件的前缀的字节长度
+fn prefix_len(source: &str, predicate: impl Fn(char) -> bool) -> usize {
+  source.find(|c| !predicate(c)).unwrap_or(source.len())
+}
+
+/// `source` 是否以闭合的 `{{ }}` 开头，`quote` 为所在属性值的引号
+fn starts_expression(source: &str, quote: Option<char>) -> bool {
+  source.starts_with("{{") && find_expression_end(&source[2..], quote).is_some()
+}
+
+fn scan_content(rest: &str) -> Scan {
+  let space = prefix_len(rest, char::is_whitespace);
+  if space > 0 {
+    // 节点之间只含空白的文本单独切分，由解析器按选项处理；包含其他字符时属于文本
+    let after = &rest[space..];
+    if after.is_empty() || after.starts_with('<') || after.starts_with("{{") {
+      return Ok((TokenKind::Whitespace, space));
+    }
+    return Ok((TokenKind::Text, text_len(rest)));
+  }
+  // 从 HTML、XML 文件复制而来的 `<!DOCTYPE ...>` 与 `<?xml ...?>` 声明
+  let terminator = if rest.starts_with("<?") {
+    Some("?>")
+  } else if rest
+    .get(..9)
+    .is_some_and(|prefix| prefix.eq_ignore_ascii_case("<!doctype"))
+  {
+    Some(">")
+  } else {
+    None
+  };
+  if let Some(terminator) = terminator {
+    return rest
+      .find(terminator)
+      .map(|len| (TokenKind::Declaration, len + terminator.len()))
+      .ok_or(SyntaxErrorKind::ExpectDoctype);
+  }
+  if rest.starts_with("<!--") {
+    return Ok((TokenKind::CommentStart, 4));
+  }
+  if rest.starts_with("<!") {
+    return Err(SyntaxErrorKind::ExpectComment);
+  }
+  if rest.starts_with("</") {
+    return Ok((TokenKind::EndTagOpen, 2));
+  }
+  if rest.starts_with('<') && rest[1..].starts_with(is_tag_name_char) {
+    return Ok((TokenKind::TagOpen, 1));
+  }
+  if starts_expression(rest, None) {
+    return Ok((TokenKind::MustacheStart, 2));
+  }
+  Ok((TokenKind::Text, text_len(rest)))
+}
+
+/// 文本的长度，直到标签、注释等标记或 `{{`；开头未闭合的 `{{` 与不构成标签的 `<` 属于文本
+fn text_len(rest: &str) -> usize {
+  let bytes = rest.as_bytes();
+  let mut len = if rest.starts_with("{{") { 2 } else { 0 };
+  loop {
+    len += until_or_mustache(&bytes[len..], b'<');
+    if bytes.get(len) != Some(&b'<') || starts_markup(&rest[len..]) {
+      return len;
+    }
+    len += 1;
+  }
+}
+
+fn scan_tag(rest: &str) -> (TokenKind, usize) {
+  match rest.chars().next() {
+    Some(c) if c.is_whitespace() => (TokenKind::Whitespace, prefix_len(rest, char::is_whitespace)),
+    Some('>') => (TokenKind::TagClose, 1),
+    Some('/') if rest.starts_with("/>") => (TokenKind::SelfClose, 2),
+    Some('/') => (TokenKind::Invalid, 1),
+    Some('=') => (TokenKind::Equals, 1),
+    Some('"' | '\'') => (TokenKind::Quote, 1),
+    Some(_) => (TokenKind::AttrName, prefix_len(rest, is_attr_name_char)),
+    None => (TokenKind::Eof, 0),
+  }
+}
+
+fn scan_quoted_value(rest: &str, quote: char) -> (TokenKind, usize) {
+  if rest.starts_with(quote) {
+    return (TokenKind::Quote, quote.len_utf8());
+  }
+  if starts_expression(rest, Some(quote)) {
+    return (TokenKind::MustacheStart, 2);
+  }
+  let from = if rest.starts_with("{{") { 2 } else { 0 };
+  let len = from + until_or_mustache(&rest.as_bytes()[from..], quote as u8);
+  (TokenKind::AttrText, len)
+}
+
+/// 宽松模式下不带引号的属性值，结尾处的空白、`>` 与 `/>` 按标签内的词法单元切分
+fn scan_unquoted_value(rest: &str) -> (TokenKind, usize) {
+  if rest.starts_with(|c: char| c.is_whitespace() || c == '>') || rest.starts_with("/>") {
+    return scan_tag(rest);
+  }
+  if starts_expression(rest, None) {
+    return (TokenKind::MustacheStart, 2);
+  }
+  let from = if rest.starts_with("{{") { 2 } else { 0 };
+  let len = rest[from..]
+    .char_indices()
+    .find(|&(index, c)| {
+      let s = &rest[from + index..];
+      s.starts_with("{{") || c.is_whitespace() || c == '>' || s.starts_with("/>")
+    })
+    .map_or(rest.len(), |(index, _)| from + index);
+  (TokenKind::AttrText, len)
+}
+
+fn scan_comment(rest: &str) -> Scan {
+  // 与 HTML 一致，`<!-->` 与 `<!--->` 是空注释
+  for abrupt in [">", "->"] {
+    if rest.starts_with(abrupt) {
+      return Ok((TokenKind::CommentEnd, abrupt.len()));
+    }
+  }
+  // 注释在第一个 `-->` 处结束，HTML 同样接受 `--!>`；中间的 `--` 与 `-- >` 属于注释内容
+  let (len, terminator) = ["-->", "--!>"]
+    .into_iter()
+    .filter_map(|terminator| {
+      memmem::find(rest.as_bytes(), terminator.as_bytes()).map(|len| (len, terminator))
+    })
+    .min()
+    .ok_or(SyntaxErrorKind::ExpectComment)?;
+  Ok(match len {
+    0 => (TokenKind::CommentEnd, terminator.len()),
+    len => (TokenKind::CommentText, len),
+  })
+}
+
+/// 只切分词法单元、不构造节点
+struct Discard;
+
+impl<'s> Build<'s> for Discard {
+  type Node = ();
+  type Attribute = Position;
+  type AttributeValue = ();
+
+  fn element(
+    &mut self,
+    _name: &'s str,
+    _attrs: Vec<Position>,
+    _children: Vec<()>,
+    _self_closing: bool,
+    _first_attr_same_line: bool,
+    _recovered: bool,
+    _close_start: Option<Position>,
+    _close_end: Option<Position>,
+    _start: Position,
+    _end: Position,
+  ) {
+  }
+
+  fn text(&mut self, _content: &'s str, _start: Position, _end: Position) {}
+
+  fn comment(&mut self, _content: &'s str, _start: Position, _end: Position) {}
+
+  fn expression(&mut self, _content: &'s str, _start: Position, _end: Position) {}
+
+  fn attribute(
+    &mut self,
+    _name: &'s str,
+    _value: Option<Vec<()>>,
+    _quote: AttributeQuote,
+    start: Position,
+    _end: Position,
+  ) -> Position {
+    start
+  }
+
+  fn attribute_start(&self, attr: &Position) -> Position {
+    *attr
+  }
+
+  fn text_value(&mut self, _content: &'s str, _start: Position, _end: Position) {}
+
+  fn expression_value(&mut self, _content: &'s str, _start: Position, _end: Position) {}
+}
+
+impl<'s> Parser<'s> {
+  /// 解析整个文档但不构造节点，返回解析过程中记录的词法单元，用于语法高亮与调试，见 [`lexer`](crate::lexer)
+  pub fn tokenize(&mut self) -> Vec<Token<'s>> {
+    self.lexer.record_tokens();
+    let _ = self.parse_document(&mut Discard);
+    self.lexer.take_tokens()
+  }
+}
+
+/// 使用默认选项切分词法单元
+pub fn tokenize(source: &str) -> Vec<Token<'_>> {
+  Parser::with_options(source, ParserOptions::default()).tokenize()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn tokens_cover_source() {
+    let source = "<!DOCTYPE html>\n<view class=\"a {{b}}\" hidden>\n  hi {{ name }}<!-- c --><wxs module=\"m\">var a = 1 < 2;</wxs>\n</view >";
+    let tokens = tokenize(source);
+    assert_eq!(
+      tokens.iter().map(|token| token.text).collect::<String>(),
+      source
+    );
+    let kinds: Vec<_> = tokens
+      .iter()
+      .filter(|token| token.kind != TokenKind::Whitespace)
+      .map(|token| (token.kind, token.text))
+      .collect();
+    assert_eq!(
+      kinds,
+      [
+        (TokenKind::Declaration, "<!DOCTYPE html>"),
+        (TokenKind::TagOpen, "<"),
+        (TokenKind::TagName, "view"),
+        (TokenKind::AttrName, "class"),
+        (TokenKind::Equals, "="),
+        (TokenKind::Quote, "\""),
+        (TokenKind::AttrText, "a "),
+        (TokenKind::MustacheStart, "{{"),
+        (TokenKind::Expression, "b"),
+        (TokenKind::MustacheEnd, "}}"),
+        (TokenKind::Quote, "\""),
+        (TokenKind::AttrName, "hidden"),
+        (TokenKind::TagClose, ">"),
+        (TokenKind::Text, "\n  hi "),
+        (TokenKind::MustacheStart, "{{"),
+        (TokenKind::Expression, " name "),
+        (TokenKind::MustacheEnd, "}}"),
+        (TokenKind::CommentStart, "<!--"),
+        (TokenKind::CommentText, " c "),
+        (TokenKind::CommentEnd, "-->"),
+        (TokenKind::TagOpen, "<"),
+        (TokenKind::TagName, "wxs"),
+        (TokenKind::AttrName, "module"),
+        (TokenKind::Equals, "="),
+        (TokenKind::Quote, "\""),
+        (TokenKind::AttrText, "m"),
+        (TokenKind::Quote, "\""),
+        (TokenKind::TagClose, ">"),
+        (TokenKind::RawText, "var a = 1 < 2;"),
+        (TokenKind::EndTagOpen, "</"),
+        (TokenKind::TagName, "wxs"),
+        (TokenKind::TagClose, ">"),
+        (TokenKind::EndTagOpen, "</"),
+        (TokenKind::TagName, "view"),
+        (TokenKind::TagClose, ">"),
+      ]
+    );
+
+    let tokens = tokenize("<view><text>");
+    assert_eq!(tokens.last().unwrap().kind, TokenKind::TagClose);
+    let tokens = tokenize("<view><!-- x");
+    assert_eq!(tokens.last().unwrap().kind, TokenKind::Invalid);
+  }
+
+  #[test]
+  fn lexes_by_mode() {
+    let mut lexer = Lexer::new("a < b{{c}}</view >");
+    assert_eq!(lexer.peek(LexMode::Content), TokenKind::Text);
+    let text = lexer.next(LexMode::Content).unwrap();
+    assert_eq!(text.text, "a < b");
+    assert!(matches!(
+      lexer.errors()[0].kind,
+      SyntaxErrorKind::StrayLessThan
+    ));
+    assert_eq!(lexer.errors()[0].offset, 2);
+    assert_eq!(
+      lexer.next(LexMode::Content).unwrap().kind,
+      TokenKind::MustacheStart
+    );
+    assert_eq!(lexer.next(LexMode::Mustache(None)).unwrap().text, "c");
+    assert_eq!(
+      lexer.next(LexMode::Mustache(None)).unwrap().kind,
+      TokenKind::MustacheEnd
+    );
+
+    let checkpoint = lexer.checkpoint();
+    assert_eq!(
+      lexer.next(LexMode::Content).unwrap().kind,
+      TokenKind::EndTagOpen
+    );
+    assert_eq!(lexer.next(LexMode::TagName).unwrap().text, "view");
+    lexer.rewind(checkpoint);
+    assert_eq!(lexer.position().offset, 10);
+    assert_eq!(lexer.peek(LexMode::Content), TokenKind::EndTagOpen);
+
+    let mut lexer = Lexer::new("<!-- x");
+    lexer.next(LexMode::Content).unwrap();
+    assert!(lexer.next(LexMode::Comment).is_err());
+    assert_eq!(lexer.position().offset, 4);
+  }
+}
@@ -2,44 +2,271 @@
 //!
 //! 此库实现了微信小程序 WXML 模板语法的解析器，可以将 WXML 模板转换为抽象语法树
 //! 支持标准 WXML 的常见功能和 {{ }} 表达式语法
+//!
+//! 默认启用的 `napi` 特性用于构建 Node.js 绑定。作为纯 Rust 库使用时可以关闭默认特性：
+//!
+//! ```toml
+//! miniprogram-template-parser = { version = "0.1", default-features = false }
+//! ```
+//!
+//! ```
+//! use miniprogram_template_parser::{parser::Parser, Node};
+//!
+//! let mut parser = Parser::new("<view class=\"title\">{{ msg }}</view>");
+//! let root = parser.parse_root().unwrap();
+//! assert_eq!(root.children[0].name(), Some("view"));
+//! assert!(matches!(
+//!   root.children[0].children().unwrap()[0],
+//!   Node::Expression { .. }
+//! ));
+//! ```
 
+pub mod arena;
+pub mod assets;
 pub mod ast;
+#[cfg(feature = "json-schema")]
+pub mod ast_schema;
+pub mod attr_map;
+pub mod batch;
+pub mod borrowed;
+pub mod builtins;
+pub mod cache;
+pub mod classes;
+pub mod codegen;
+pub mod comments;
+pub mod compile;
+pub mod completion;
+pub mod complexity;
+pub mod components;
+pub mod data_types;
+pub mod dataset;
+pub mod diagnostics;
+pub mod dialect;
+pub mod diff;
+pub mod dump;
+pub mod encoding;
+pub mod env;
 pub mod error;
+pub mod expr;
+pub mod fixtures;
+pub mod format;
 pub mod helpers;
+pub mod html;
+pub mod i18n;
+pub mod ifdef;
+pub mod interned;
+pub mod jsx;
+pub mod lexer;
+pub mod line_index;
+pub mod lint;
+pub mod matching;
+pub mod optimize;
+pub mod options;
 pub mod parser;
+pub mod patch;
+pub mod preview;
+pub mod project;
+pub mod references;
+pub mod render;
+pub mod schema;
+pub mod security;
+pub mod semantic;
+pub mod slots;
+pub mod sourcemap;
 pub mod state;
+pub mod stats;
+pub mod style;
+pub mod symbols;
+#[cfg(feature = "napi")]
+pub mod task;
+pub mod template_data;
+pub mod testing;
+pub mod tree;
+pub mod trivia;
+pub mod unist;
+pub mod vue;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+pub mod wxs;
 
+#[cfg(feature = "napi")]
 use napi_derive::napi;
 use parser::Parser;
 
-/// 将 WXML 模板字符串解析为抽象语法树
-#[napi]
-pub fn parse(source: String) -> ast::Root {
-  let mut parser = Parser::new(&source);
-  parser.parse_root().unwrap()
+/// 将 WXML 模板字符串解析为抽象语法树，超出 [`ParserOptions`] 中的资源限制等无法恢复的错误时返回错误，
+/// 已恢复的错误记录在 [`Root::errors`](ast::Root::errors) 中
+pub fn parse(source: String, options: Option<ParserOptions>) -> Result<ast::Root, SyntaxError> {
+  let mut parser = Parser::with_options(&source, options.unwrap_or_default());
+  parser.parse_root()
+}
+
+/// 将 WXML 模板字符串解析为抽象语法树，无法恢复的错误（如超出资源限制）抛出为 JS 异常，
+/// 已恢复的错误记录在 [`Root::errors`](ast::Root::errors) 中
+#[cfg(feature = "napi")]
+#[napi(js_name = "parse")]
+pub fn parse_js(source: String, options: Option<ParserOptions>) -> napi::Result<ast::Root> {
+  parse(source, options).map_err(|error| napi::Error::from_reason(error.to_string()))
+}
+
+/// 将任意字符串按 `{{ }}` 拆分为静态文本与表达式，与属性值使用相同的拆分规则，
+/// 可用于 JSON 配置等包含插值模板的内容
+#[cfg_attr(feature = "napi", napi)]
+pub fn parse_interpolation(source: String) -> Vec<ast::Value> {
+  Parser::new(&source).parse_interpolation()
+}
+
+/// 解析 `{{ }}` 中的表达式内容并输出为 ESTree 结构，供 eslint、babel 等 JS 工具直接使用
+#[cfg(feature = "napi")]
+#[napi(js_name = "parseExpressionEstree")]
+pub fn parse_expression_estree(content: String) -> napi::Result<serde_json::Value> {
+  expr::parse_expression(&content)
+    .map(|expr| expr.to_estree())
+    .map_err(|error| napi::Error::from_reason(error.to_string()))
+}
+
+/// 将 WXML 模板字符串解析为 unist / hast 兼容的语法树，见 [`unist`]
+#[cfg(feature = "napi")]
+#[napi(js_name = "parseUnist")]
+pub fn parse_unist(
+  source: String,
+  options: Option<ParserOptions>,
+) -> napi::Result<serde_json::Value> {
+  parse_js(source, options).map(|root| unist::to_unist(&root))
+}
+
+/// 使用 `data` 渲染模板并输出为可在浏览器中预览的 HTML，见 [`preview`]
+#[cfg(feature = "napi")]
+#[napi(js_name = "renderPreview")]
+pub fn render_preview(
+  source: String,
+  data: serde_json::Value,
+  options: Option<ParserOptions>,
+) -> napi::Result<String> {
+  let mut parser = Parser::with_options(&source, options.unwrap_or_default());
+  let root = parser
+    .parse_root()
+    .map_err(|error| napi::Error::from_reason(error.to_string()))?;
+  preview::render_preview(&root, &data).map_err(|error| napi::Error::from_reason(error.to_string()))
+}
+
+/// 提取文档大纲，供编辑器的大纲视图使用，见 [`symbols`]
+#[cfg(feature = "napi")]
+#[napi(js_name = "documentSymbols")]
+pub fn document_symbols(
+  source: String,
+  options: Option<ParserOptions>,
+) -> napi::Result<Vec<symbols::DocumentSymbol>> {
+  let mut parser = Parser::with_options(&source, options.unwrap_or_default());
+  let root = parser
+    .parse_root()
+    .map_err(|error| napi::Error::from_reason(error.to_string()))?;
+  Ok(symbols::document_symbols(&root))
+}
+
+/// 输出语义高亮片段，见 [`semantic`]
+#[cfg(feature = "napi")]
+#[napi(js_name = "semanticTokens")]
+pub fn semantic_tokens(
+  source: String,
+  options: Option<ParserOptions>,
+) -> napi::Result<Vec<semantic::SemanticToken>> {
+  let mut parser = Parser::with_options(&source, options.unwrap_or_default());
+  let root = parser
+    .parse_root()
+    .map_err(|error| napi::Error::from_reason(error.to_string()))?;
+  Ok(semantic::semantic_tokens(&root, &source))
+}
+
+/// 分析光标处正在输入的内容，供编辑器补全使用，见 [`completion`]
+///
+/// `offset` 为光标的字节偏移
+#[cfg(feature = "napi")]
+#[napi(js_name = "completionContext")]
+pub fn completion_context(source: String, offset: u32) -> completion::CompletionContext {
+  completion::completion_context(&source, offset)
+}
+
+/// 查找光标所在标签的配对标签，见 [`matching::matching_tag`]
+#[cfg(feature = "napi")]
+#[napi(js_name = "matchingTag")]
+pub fn matching_tag(
+  source: String,
+  offset: u32,
+  options: Option<ParserOptions>,
+) -> napi::Result<Option<matching::TagMatch>> {
+  let options = options.unwrap_or_default();
+  let encoding = options.position_encoding;
+  let mut parser = Parser::with_options(&source, options);
+  let root = parser
+    .parse_root()
+    .map_err(|error| napi::Error::from_reason(error.to_string()))?;
+  Ok(matching::matching_tag(&root, offset, encoding))
+}
+
+/// 建议在光标处插入的结束标签，见 [`matching::auto_close_hint`]
+#[cfg(feature = "napi")]
+#[napi(js_name = "autoCloseHint")]
+pub fn auto_close_hint(source: String, offset: u32) -> Option<String> {
+  matching::auto_close_hint(&source, offset)
+}
+
+/// 解析不完整的模板片段，如编辑器补全时的上下文内容
+///
+/// `context_tag` 为片段所在的父元素；元素可以不闭合，解析错误会被跳过，总是返回已解析出的节点
+pub fn parse_fragment(source: &str, context_tag: Option<&str>) -> Vec<ast::Node> {
+  Parser::new(source).parse_fragment(context_tag)
 }
 
 /// 暴露 AST 类型以方便使用
-pub use ast::{Attribute, AttributeValue, Node, Position, Root, Value};
+pub use ast::{Attribute, AttributeQuote, AttributeValue, Node, Position, Root, Value};
+
+/// 暴露批量解析接口以方便使用
+pub use batch::{lint_files, parse_file, parse_files, LintResult, ParseResult, SourceFile};
+
+/// 暴露解析选项以方便使用
+pub use dialect::Dialect;
+pub use options::{ParserOptions, WhitespaceMode};
+
+/// 暴露解析统计信息以方便使用
+pub use stats::ParseStats;
 
 /// 暴露错误类型以方便使用
 pub use error::{SyntaxError, SyntaxErrorKind};
 
 #[cfg(test)]
 mod tests {
-  use crate::{ast::Node, parse, AttributeValue, Position};
+  use crate::{
+    ast::Node, parse, parser::Parser, AttributeQuote, AttributeValue, ParserOptions, Position,
+    SyntaxErrorKind,
+  };
 
   #[test]
   fn basic() {
-    let ast = parse("<div></div>".to_string());
+    let ast = parse("<div></div>".to_string(), None).unwrap();
     assert_eq!(ast.children.len(), 1);
     assert_eq!(ast.start.offset, 0);
     assert_eq!(ast.end.offset, 11);
   }
 
+  #[test]
+  fn closing_tags() {
+    let ast = parse("<view><text>a</text></view>".to_string(), None).unwrap();
+    assert_eq!(ast.end.offset, 27);
+    if let Node::Element { children, .. } = &ast.children[0] {
+      assert_eq!(children.len(), 1);
+      assert!(matches!(&children[0], Node::Element { name, .. } if name == "text"));
+    } else {
+      panic!("Expected an Element node");
+    }
+  }
+
   #[test]
   fn attrs() {
-    let ast = parse("<view class=\"cls1\" bindtap=\"{{handleTap}}\"></view>".to_string());
+    let ast = parse(
+      "<view class=\"cls1\" bindtap=\"{{handleTap}}\"></view>".to_string(),
+      None,
+    )
+    .unwrap();
     if let Node::Element { attrs, .. } = &ast.children[0] {
       println!("attrs: {:?}", attrs);
       assert_eq!(attrs.len(), 2);
@@ -47,7 +274,7 @@ mod tests {
       let attr1 = &attrs[1];
       assert_eq!(attr0.name, "class");
       assert_eq!(attr1.name, "bindtap");
-      if let AttributeValue::Text { content, .. } = attr0.value.as_ref().unwrap().get(0).unwrap() {
+      if let AttributeValue::Text { content, .. } = attr0.value.as_ref().unwrap().first().unwrap() {
         assert_eq!(content, "cls1");
       } else {
         panic!("Expected a Text variant");
@@ -57,11 +284,38 @@ mod tests {
     }
   }
 
+  #[test]
+  fn attr_prefix() {
+    let ast = parse(
+      "<view wx:for-item=\"i\" capture-bind:tap=\"t\" model:value=\"{{v}}\" data-user-id=\"1\" bindtap=\"t\"/>"
+        .to_string(),
+      None,
+    ).unwrap();
+    let names: Vec<_> = ast.children[0]
+      .attrs()
+      .unwrap()
+      .iter()
+      .map(|attr| (attr.prefix.as_deref(), attr.local.as_str()))
+      .collect();
+    assert_eq!(
+      names,
+      [
+        (Some("wx"), "for-item"),
+        (Some("capture-bind"), "tap"),
+        (Some("model"), "value"),
+        (Some("data"), "user-id"),
+        (None, "bindtap"),
+      ]
+    );
+  }
+
   #[test]
   fn mixedattrs() {
     let ast = parse(
       "<view class=\"cls1 {{tst}} cls2\" bindtap=\"tap1 tap2 {{handleTap}}\"></view>".to_string(),
-    );
+      None,
+    )
+    .unwrap();
 
     if let Node::Element { attrs, .. } = &ast.children[0] {
       assert_eq!(attrs.len(), 2);
@@ -84,9 +338,659 @@ mod tests {
     }
   }
 
+  #[test]
+  fn synthetic_nodes() {
+    let mut ast = parse("<view></view>".to_string(), None).unwrap();
+    let inserted = Node::element("text")
+      .with_attr(crate::Attribute::text("class", "title"))
+      .with_child(Node::expression("name"));
+    assert!(inserted.is_synthetic());
+    assert!(!ast.children[0].is_synthetic());
+
+    ast.walk_mut(&mut |node| {
+      if node.name() == Some("view") {
+        node.children_mut().unwrap().push(inserted.clone());
+        node.invalidate_positions();
+      }
+    });
+    let view = &ast.children[0];
+    assert!(view.is_synthetic());
+    assert_eq!(view.children().unwrap()[0].name(), Some("text"));
+    assert_eq!(Position::SYNTHETIC.to_string(), "<synthetic>");
+  }
+
+  #[test]
+  fn attr_literals() {
+    let ast = parse("<view hidden=\"false\" disabled checked=\"{{false}}\" size=\"{{ 12 }}\" title=\"a {{b}}\"></view>"
+        .to_string(), None).unwrap();
+    let attrs = ast.children[0].attrs().unwrap();
+    assert_eq!(attrs[0].as_bool(), Some(true));
+    assert_eq!(attrs[0].as_static_string().as_deref(), Some("false"));
+    assert_eq!(attrs[1].as_bool(), Some(true));
+    assert_eq!(attrs[1].as_static_string(), None);
+    assert_eq!(attrs[2].as_bool(), Some(false));
+    assert_eq!(attrs[3].as_number(), Some(12.0));
+    assert_eq!(attrs[4].as_bool(), Some(true));
+    assert_eq!(attrs[4].as_number(), None);
+  }
+
+  #[test]
+  fn unquoted_attrs() {
+    let source = "<view wx:if={{ok}} data-index=1 class='a'/>".to_string();
+    let options = ParserOptions {
+      lenient: Some(true),
+      ..Default::default()
+    };
+    let ast = parse(source, Some(options)).unwrap();
+    let attrs = ast.children[0].attrs().unwrap();
+    assert_eq!(attrs.len(), 3);
+    assert_eq!(attrs[0].quote, AttributeQuote::None);
+    assert!(matches!(
+      attrs[0].value.as_deref(),
+      Some([AttributeValue::Expression { content, .. }]) if content == "ok"
+    ));
+    assert_eq!(attrs[1].as_number(), Some(1.0));
+    assert_eq!(attrs[2].quote, AttributeQuote::Single);
+    assert!(matches!(
+      ast.children[0],
+      Node::Element {
+        self_closing: true,
+        ..
+      }
+    ));
+  }
+
+  #[test]
+  fn raw_text_tags() {
+    let source = "<wxs module=\"m\">var a = 1 < 2 && '{{'; </wxs><markdown># <b>x</b></markdown>";
+    let options = ParserOptions {
+      raw_text_tags: Some(vec!["markdown".to_string()]),
+      ..Default::default()
+    };
+    let ast = parse(source.to_string(), Some(options)).unwrap();
+    let texts: Vec<_> = ast
+      .children
+      .iter()
+      .map(|node| match node.children().unwrap() {
+        [Node::Text { content, .. }] => content.as_str(),
+        other => panic!("unexpected children: {other:?}"),
+      })
+      .collect();
+    assert_eq!(texts, ["var a = 1 < 2 && '{{'; ", "# <b>x</b>"]);
+  }
+
+  #[test]
+  fn whitespace_mode() {
+    use crate::WhitespaceMode;
+
+    let source = "<text> a </text>\n<view>{{a}} {{b}}</view>";
+    let texts = |mode: Option<WhitespaceMode>| {
+      let options = ParserOptions {
+        whitespace: mode,
+        ..Default::default()
+      };
+      let mut texts = Vec::new();
+      parse(source.to_string(), Some(options))
+        .unwrap()
+        .walk(&mut |node| {
+          if let Node::Text { content, .. } = node {
+            texts.push(content.clone());
+          }
+        });
+      texts
+    };
+    assert_eq!(texts(None), [" a "]);
+    assert_eq!(texts(Some(WhitespaceMode::Keep)), [" a ", "\n", " "]);
+    assert_eq!(texts(Some(WhitespaceMode::Collapse)), [" a ", " ", " "]);
+  }
+
+  #[test]
+  fn preserve_whitespace() {
+    use crate::{
+      codegen::{generate, CodegenOptions},
+      WhitespaceMode,
+    };
+
+    let source = "\n<view>\n  <text> a </text>\n  {{a}} {{b}}\n</view>\n";
+    let options = ParserOptions {
+      whitespace: Some(WhitespaceMode::Preserve),
+      ..Default::default()
+    };
+    let ast = parse(source.to_string(), Some(options)).unwrap();
+    assert!(ast.children[0].is_whitespace());
+    assert_eq!(generate(&ast, &CodegenOptions::default()), source);
+  }
+
+  #[test]
+  fn deep_nesting() {
+    // 递归解析时这一深度会导致测试线程栈溢出
+    let depth = 10_000;
+    let source = "<view>".repeat(depth) + &"</view>".repeat(depth);
+    let mut parser = Parser::new(&source);
+    let root = parser.parse_root().unwrap();
+    let mut node = &root.children[0];
+    let mut levels = 1;
+    while let Some([child]) = node.children() {
+      node = child;
+      levels += 1;
+    }
+    assert_eq!(levels, depth);
+  }
+
+  #[test]
+  fn comments() {
+    use crate::parse_file;
+
+    let source = "<!----><!-- --><!-- a -- > b --><!--><!---><!-- c --!><view/>";
+    let root = Parser::new(source).parse_root().unwrap();
+    let contents: Vec<_> = root
+      .children
+      .iter()
+      .filter_map(|node| match node {
+        Node::Comment { content, .. } => Some(content.as_str()),
+        _ => None,
+      })
+      .collect();
+    assert_eq!(contents, ["", " ", " a -- > b ", "", "", " c "]);
+    assert_eq!(root.children.last().unwrap().name(), Some("view"));
+    assert_eq!(root.children[2].end().offset, 32);
+
+    let result = parse_file("a.wxml", "<view/><!-- open", &ParserOptions::default());
+    assert!(result.root.is_none());
+    assert!(matches!(
+      result.errors[0].kind,
+      SyntaxErrorKind::ExpectComment
+    ));
+  }
+
+  #[test]
+  fn stray_less_than() {
+    use crate::parse_file;
+
+    let source = "<text>1 < 2 <= 3</text><view>a <</view>";
+    let result = parse_file("a.wxml", source, &ParserOptions::default());
+    let root = result.root.unwrap();
+    let texts: Vec<_> = root
+      .children
+      .iter()
+      .map(|node| match node.children().unwrap() {
+        [Node::Text { content, .. }] => content.as_str(),
+        other => panic!("unexpected children: {other:?}"),
+      })
+      .collect();
+    assert_eq!(texts, ["1 < 2 <= 3", "a <"]);
+    let offsets: Vec<_> = result.errors.iter().map(|error| error.offset).collect();
+    assert_eq!(offsets, [8, 12, 31]);
+    assert!(matches!(
+      result.errors[0].kind,
+      SyntaxErrorKind::StrayLessThan
+    ));
+  }
+
+  #[test]
+  fn parse_keeps_recovered_errors() {
+    let root = parse("<view>1 < 2</view>".to_string(), None).unwrap();
+    let codes: Vec<_> = root.errors.iter().map(|e| e.code.as_str()).collect();
+    assert_eq!(codes, ["WXML1016"]);
+    assert!(parse("<view/>".to_string(), None)
+      .unwrap()
+      .errors
+      .is_empty());
+  }
+
+  #[test]
+  fn recovered_attrs() {
+    let source = r#"<view class="a" ="b" id="c"><text>ok</text></view>"#;
+    let mut parser = Parser::new(source);
+    let root = parser.parse_root().unwrap();
+    let codes: Vec<_> = parser.errors().iter().map(|e| e.code.as_str()).collect();
+    assert_eq!(codes, ["WXML1001"]);
+    let Node::Element {
+      attrs,
+      children,
+      recovered,
+      ..
+    } = &root.children[0]
+    else {
+      unreachable!()
+    };
+    assert!(recovered);
+    let names: Vec<_> = attrs.iter().map(|attr| attr.name.as_str()).collect();
+    assert_eq!(names, ["class", "id"]);
+    assert!(matches!(
+      children[0],
+      Node::Element {
+        recovered: false,
+        ..
+      }
+    ));
+  }
+
+  #[test]
+  fn close_tag_mismatch() {
+    use crate::parse_file;
+
+    let source = "<view>\n  <text class=\"a\">hi\n</view>";
+    let result = parse_file("a.wxml", source, &ParserOptions::default());
+    assert!(result.root.is_none());
+    let error = &result.errors[0];
+    assert!(matches!(error.kind, SyntaxErrorKind::ExpectCloseTag));
+    assert_eq!((error.line, error.column), (3, 1));
+    let open_tag = error.open_tag.as_ref().unwrap();
+    assert_eq!(open_tag.name, "text");
+    assert_eq!(
+      &source[open_tag.start.offset as usize..open_tag.end.offset as usize],
+      "<text class=\"a\">"
+    );
+
+    let result = parse_file("b.wxml", "<view/></x><text/>", &ParserOptions::default());
+    assert!(result.root.is_none());
+    assert!(result.errors[0].open_tag.is_none());
+    assert_eq!(result.errors[0].offset, 7);
+  }
+
+  #[test]
+  fn declarations() {
+    use crate::parse_file;
+
+    let source = "<?xml version=\"1.0\"?>\n<!DOCTYPE html>\n<view>ok</view>";
+    let result = parse_file("a.wxml", source, &ParserOptions::default());
+    let root = result.root.unwrap();
+    assert_eq!(root.children.len(), 1);
+    assert_eq!(root.children[0].name(), Some("view"));
+    let kinds: Vec<_> = result
+      .errors
+      .iter()
+      .map(|error| error.code.as_str())
+      .collect();
+    assert_eq!(kinds, ["WXML1015", "WXML1015"]);
+    assert_eq!(result.errors[1].line, 2);
+
+    let result = parse_file("b.wxml", "<!doctype html", &ParserOptions::default());
+    assert!(result.root.is_none());
+    assert!(matches!(
+      result.errors[0].kind,
+      SyntaxErrorKind::ExpectDoctype
+    ));
+  }
+
+  #[test]
+  fn resource_limits() {
+    use crate::{error::SyntaxErrorKind, parse_file};
+
+    let exceeded = |source: &str, options: ParserOptions| {
+      let result = parse_file("a.wxml", source, &options);
+      result.root.is_none()
+        && matches!(
+          result.errors.last().map(|error| error.kind),
+          Some(SyntaxErrorKind::ResourceLimitExceeded)
+        )
+    };
+    let nested = "<view>".repeat(4) + &"</view>".repeat(4);
+    let depth = |max| ParserOptions {
+      max_depth: Some(max),
+      ..Default::default()
+    };
+    assert!(exceeded(&nested, depth(3)));
+    assert!(!exceeded(&nested, depth(4)));
+    let attrs = ParserOptions {
+      max_attrs: Some(2),
+      ..Default::default()
+    };
+    assert!(exceeded(r#"<view a b c/>"#, attrs));
+    let length = ParserOptions {
+      max_source_length: Some(8),
+      ..Default::default()
+    };
+    assert!(exceeded("<view></view>", length));
+  }
+
+  #[test]
+  fn parse_returns_resource_limit_errors() {
+    use crate::error::SyntaxErrorKind;
+
+    let cases = [
+      (
+        "<view><view><view></view></view></view>",
+        ParserOptions {
+          max_depth: Some(2),
+          ..Default::default()
+        },
+      ),
+      (
+        "<view a b c/>",
+        ParserOptions {
+          max_attrs: Some(2),
+          ..Default::default()
+        },
+      ),
+      (
+        "<view></view>",
+        ParserOptions {
+          max_source_length: Some(8),
+          ..Default::default()
+        },
+      ),
+    ];
+    for (source, options) in cases {
+      let error = parse(source.to_string(), Some(options)).unwrap_err();
+      assert!(matches!(error.kind, SyntaxErrorKind::ResourceLimitExceeded));
+    }
+  }
+
+  #[test]
+  fn fragment() {
+    use crate::parse_fragment;
+
+    let nodes = parse_fragment(
+      "</a> <view class=\"x\"><text>hi</view> tail <button type=\"pri",
+      None,
+    );
+    let names: Vec<_> = nodes
+      .iter()
+      .map(|node| node.name().unwrap_or("#text"))
+      .collect();
+    assert_eq!(names, ["view", "#text", "button"]);
+    let view_children = nodes[0].children().unwrap();
+    assert_eq!(view_children[0].name(), Some("text"));
+    assert_eq!(view_children[0].children().unwrap().len(), 1);
+
+    let nodes = parse_fragment("var a = '<b>';", Some("wxs"));
+    assert!(matches!(&nodes[..], [Node::Text { content, .. }] if content == "var a = '<b>';"));
+  }
+
+  #[test]
+  fn interpolation() {
+    use crate::{parse_interpolation, Value};
+
+    let values = parse_interpolation("Hi {{ user.name }}, {{ '}}' }} left {{ open".to_string());
+    let parts: Vec<_> = values
+      .iter()
+      .map(|value| match value {
+        Value::Text { content, .. } => format!("text:{content}"),
+        Value::Expression { content, .. } => format!("expr:{content}"),
+      })
+      .collect();
+    assert_eq!(
+      parts,
+      [
+        "text:Hi ",
+        "expr:user.name",
+        "text:, ",
+        "expr:'}}'",
+        "text: left ",
+        "text:{{ open"
+      ]
+    );
+    assert!(matches!(values[1], Value::Expression { start, .. } if start.offset == 3));
+  }
+
+  #[test]
+  fn quote_style() {
+    use crate::codegen::{generate, CodegenOptions, QuoteStyle};
+
+    let ast = parse(
+      "<view class='a' title=\"{{ 'x' }}\" hidden><text>hi</text></view>".to_string(),
+      None,
+    )
+    .unwrap();
+    let preserve = CodegenOptions::default();
+    assert_eq!(
+      generate(&ast, &preserve),
+      "<view class='a' title=\"{{'x'}}\" hidden><text>hi</text></view>"
+    );
+    let single = CodegenOptions {
+      quote: QuoteStyle::Single,
+      ..Default::default()
+    };
+    assert_eq!(
+      generate(&ast, &single),
+      "<view class='a' title=\"{{'x'}}\" hidden><text>hi</text></view>"
+    );
+    let double = CodegenOptions {
+      quote: QuoteStyle::Double,
+      ..Default::default()
+    };
+    assert_eq!(
+      generate(&ast, &double),
+      "<view class=\"a\" title=\"{{'x'}}\" hidden><text>hi</text></view>"
+    );
+  }
+
+  #[test]
+  fn unclosed_expression() {
+    let mut parser = Parser::new("<text>{{name</text><view class=\"a {{b\"></view>");
+    let ast = parser.parse_root().unwrap();
+    assert!(matches!(
+      ast.children[0].children().unwrap(),
+      [Node::Text { content, .. }] if content == "{{name"
+    ));
+    assert!(matches!(
+      ast.children[1].attrs().unwrap()[0].value.as_deref(),
+      Some([AttributeValue::Text { content: a, .. }, AttributeValue::Text { content: b, .. }])
+        if a == "a " && b == "{{b"
+    ));
+    let errors = parser.errors();
+    assert_eq!(errors.len(), 2);
+    assert!(matches!(
+      errors[0].kind,
+      SyntaxErrorKind::ExpectMustacheInterpolation
+    ));
+    assert_eq!(errors[0].offset, 6);
+    assert_eq!(errors[0].code, "WXML1009");
+    assert!(errors[0]
+      .to_json()
+      .starts_with(r#"{"code":"WXML1009","column":7,"#));
+  }
+
+  #[test]
+  fn nested_braces() {
+    let ast = parse(
+      "<view data=\"{{ {a: 1, b: {c: 2}} }}\">{{ fn({x:1}) }}{{ {a: 1 }}</view>".to_string(),
+      None,
+    )
+    .unwrap();
+    let view = &ast.children[0];
+    assert!(matches!(
+      view.attrs().unwrap()[0].value.as_deref(),
+      Some([AttributeValue::Expression { content, .. }]) if content == "{a: 1, b: {c: 2}}"
+    ));
+    assert!(matches!(
+      view.children().unwrap(),
+      [Node::Expression { content: a, .. }, Node::Expression { content: b, .. }]
+        if a == "fn({x:1})" && b == "{a: 1"
+    ));
+  }
+
+  #[test]
+  fn string_literals_in_expressions() {
+    let ast = parse(
+      "<text>{{ cond ? '>>' : \"a}}b\" }}{{ `x${ {a: '}}'}.a }` }}</text><view class=\"{{ c ? 'x}}' : '</y>' }}\" />"
+        .to_string(),
+      None,
+    ).unwrap();
+    assert!(matches!(
+      ast.children[0].children().unwrap(),
+      [Node::Expression { content: a, .. }, Node::Expression { content: b, .. }]
+        if a == "cond ? '>>' : \"a}}b\"" && b == "`x${ {a: '}}'}.a }`"
+    ));
+    assert!(matches!(
+      ast.children[1].attrs().unwrap()[0].value.as_deref(),
+      Some([AttributeValue::Expression { content, .. }]) if content == "c ? 'x}}' : '</y>'"
+    ));
+  }
+
+  #[test]
+  fn escaped_mustache() {
+    use crate::codegen::{generate, CodegenOptions};
+
+    let ast = parse(
+      "<text>{{'{{'}} name {{\"}}\"}}</text><view title=\"{{ '{{ a }}' }}\" data=\"{{ a + '{{' }}\"/>"
+        .to_string(),
+      None,
+    ).unwrap();
+    assert!(matches!(
+      ast.children[0].children().unwrap(),
+      [Node::Text { content: a, start, end }, Node::Text { content: b, .. }, Node::Text { content: c, .. }]
+        if a == "{{" && b == " name " && c == "}}" && start.offset == 6 && end.offset == 14
+    ));
+    assert!(matches!(
+      ast.children[1].attrs().unwrap(),
+      [title, data]
+        if matches!(title.value.as_deref(), Some([AttributeValue::Text { content, .. }]) if content == "{{ a }}")
+          && matches!(data.value.as_deref(), Some([AttributeValue::Expression { .. }]))
+    ));
+    assert_eq!(
+      generate(&ast, &CodegenOptions::default()),
+      "<text>{{'{{'}} name }}</text><view title=\"{{'{{'}} a }}\" data=\"{{a + '{{'}}\" />"
+    );
+  }
+
+  #[test]
+  fn template_string() {
+    let ast = parse(
+      "<view class=\"cls1 {{tst}} cls2\" title=\"`a` ${b}{{c}}{{d}}\" hidden/>".to_string(),
+      None,
+    )
+    .unwrap();
+    let attrs = ast.children[0].attrs().unwrap();
+    let parts = attrs[0].template_parts().unwrap();
+    assert_eq!(parts.quasis, ["cls1 ", " cls2"]);
+    assert_eq!(parts.expressions, ["tst"]);
+    assert_eq!(
+      attrs[0].to_template_string().as_deref(),
+      Some("`cls1 ${tst} cls2`")
+    );
+    assert_eq!(
+      attrs[1].to_template_string().as_deref(),
+      Some("`\\`a\\` \\${b}${c}${d}`")
+    );
+    assert!(attrs[2].to_template_string().is_none());
+  }
+
+  #[test]
+  fn batch_parse() {
+    let files = (0..8)
+      .map(|i| {
+        (
+          format!("page{i}.wxml"),
+          format!("<view>{{{{ item{i} }}}}</view>"),
+        )
+      })
+      .chain(std::iter::once((
+        "broken.wxml".to_string(),
+        "<view></text>".to_string(),
+      )))
+      .collect();
+    let results = crate::parse_files(files, &ParserOptions::default());
+    assert_eq!(results.len(), 9);
+    assert_eq!(results[3].path, "page3.wxml");
+    assert!(results[3].root.is_some() && results[3].errors.is_empty());
+    assert!(results[8].root.is_none());
+    assert!(matches!(
+      results[8].errors[0].kind,
+      SyntaxErrorKind::ExpectCloseTag
+    ));
+  }
+
+  #[test]
+  fn serialize_shape() {
+    let ast = parse("<view hidden/>".to_string(), None).unwrap();
+    let json = serde_json::to_value(&ast).unwrap();
+    assert_eq!(json["children"][0]["type"], "Element");
+    assert_eq!(json["children"][0]["selfClosing"], true);
+    assert_eq!(json["children"][0]["attrs"][0]["quote"], "None");
+    assert_eq!(json["end"]["offset"], 14);
+  }
+
+  #[test]
+  fn lint_and_format() {
+    use crate::{
+      format::{format, FormatOptions},
+      lint::Linter,
+    };
+
+    let ast = parse(
+      "<view class=\"a\" class=\"b\"><text>Hi {{name}}</text><image src=\"{{}}\"/></view>"
+        .to_string(),
+      None,
+    )
+    .unwrap();
+    let diagnostics = Linter::default().lint(&ast);
+    let rules: Vec<_> = diagnostics.iter().map(|d| d.rule.as_str()).collect();
+    assert_eq!(rules, ["no-duplicate-attrs", "no-empty-expression"]);
+
+    let formatted = format(&ast, &FormatOptions::default());
+    assert_eq!(
+      formatted,
+      "<view class=\"a\" class=\"b\">\n  <text>Hi {{ name }}</text>\n  <image src=\"{{}}\" />\n</view>\n"
+    );
+    let reparsed = parse(formatted.clone(), None).unwrap();
+    assert_eq!(format(&reparsed, &FormatOptions::default()), formatted);
+  }
+
+  #[test]
+  fn raw_text_is_not_escaped() {
+    use crate::{
+      codegen::{generate, CodegenOptions},
+      format::{format, FormatOptions},
+    };
+
+    let source = r#"<wxs module="m">
+var a = "{{";
+</wxs>
+<view>{{'{{'}}</view>
+"#;
+    let ast = parse(source.to_string(), None).unwrap();
+    assert_eq!(
+      generate(&ast, &CodegenOptions::default()),
+      r#"<wxs module="m">
+var a = "{{";
+</wxs><view>{{'{{'}}</view>"#
+    );
+    let formatted = format(&ast, &FormatOptions::default());
+    assert_eq!(formatted, source);
+    assert_eq!(
+      format(
+        &parse(formatted.clone(), None).unwrap(),
+        &FormatOptions::default()
+      ),
+      formatted
+    );
+  }
+
+  #[test]
+  fn lint_files_with_config() {
+    use crate::lint::LintConfig;
+
+    let files = vec![
+      (
+        "a.wxml".to_string(),
+        "<image src=\"{{}}\"/><view class=\"a\" class=\"b\"/>".to_string(),
+      ),
+      ("b.wxml".to_string(), "<view></text>".to_string()),
+    ];
+    let config = LintConfig {
+      accessibility: Some(true),
+      stylistic: None,
+      disabled_rules: Some(vec!["no-duplicate-attrs".to_string()]),
+      parser: None,
+      ..Default::default()
+    };
+    let results = crate::lint_files(files, &config).unwrap();
+    let rules: Vec<_> = results[0]
+      .diagnostics
+      .iter()
+      .map(|d| d.rule.as_str())
+      .collect();
+    assert_eq!(rules, ["a11y-image-alt", "no-empty-expression"]);
+    assert!(results[1].diagnostics.is_empty());
+    assert_eq!(results[1].errors.len(), 1);
+  }
+
   #[test]
   fn expressions() {
-    let ast = parse("<text>Hello {{ world }}</text>".to_string());
+    let ast = parse("<text>Hello {{ world }}</text>".to_string(), None).unwrap();
     if let Node::Element { children, .. } = &ast.children[0] {
       println!("children: {:?}", children);
       assert_eq!(children.len(), 2);
@@ -0,0 +1,160 @@
+//! 行索引
+//!
+//! 记录源码中每一行开始的位置，在字节偏移与行列号之间换算。换算诊断位置、在编辑器与 AST 之间
+//! 对应范围时都需要它，解析器设置 [`position_encoding`](crate::options::ParserOptions::position_encoding)
+//! 时同样使用它换算位置。只有 `\n` 被视为换行，与解析器计算行号的方式一致
+
+use crate::{ast::Position, encoding::PositionEncoding};
+
+/// 一行开始之前的内容按各编码计算的长度
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct LineStart {
+  bytes: usize,
+  utf16: u32,
+  chars: u32,
+}
+
+impl LineStart {
+  fn units(&self, encoding: PositionEncoding) -> u32 {
+    match encoding {
+      PositionEncoding::Utf8 => self.bytes as u32,
+      PositionEncoding::Utf16 => self.utf16,
+      PositionEncoding::Utf32 => self.chars,
+    }
+  }
+}
+
+/// 源码的行索引，构建一次后可以反复换算
+#[derive(Debug, Clone)]
+pub struct LineIndex<'s> {
+  source: &'s str,
+  lines: Vec<LineStart>,
+}
+
+impl<'s> LineIndex<'s> {
+  pub fn new(source: &'s str) -> Self {
+    let mut lines = vec![LineStart {
+      bytes: 0,
+      utf16: 0,
+      chars: 0,
+    }];
+    let mut start = lines[0];
+    for (index, _) in source.match_indices('\n') {
+      let line = &source[start.bytes..=index];
+      start = LineStart {
+        bytes: index + 1,
+        utf16: start.utf16 + PositionEncoding::Utf16.len(line),
+        chars: start.chars + PositionEncoding::Utf32.len(line),
+      };
+      lines.push(start);
+    }
+    Self { source, lines }
+  }
+
+  pub fn source(&self) -> &'s str {
+    self.source
+  }
+
+  /// 行数，空文档也有一行
+  pub fn line_count(&self) -> u32 {
+    self.lines.len() as u32
+  }
+
+  /// 第 `line` 行（从 1 开始）开始的字节偏移
+  pub fn line_start(&self, line: u32) -> Option<usize> {
+    let index = (line as usize).checked_sub(1)?;
+    self.lines.get(index).map(|start| start.bytes)
+  }
+
+  /// 第 `line` 行（从 1 开始）的内容，不包含行尾的 `\n`
+  pub fn line_text(&self, line: u32) -> Option<&'s str> {
+    let start = self.line_start(line)?;
+    let end = self
+      .line_start(line + 1)
+      .map_or(self.source.len(), |next| next - 1);
+    Some(&self.source[start..end])
+  }
+
+  /// 字节偏移所在的行号，从 1 开始
+  pub fn line_of(&self, offset: usize) -> u32 {
+    self.lines.partition_point(|start| start.bytes <= offset) as u32
+  }
+
+  /// 把字节偏移换算为位置，`offset` 与 `column` 按 `encoding` 计算，列号从 1 开始
+  ///
+  /// 偏移超出源码或落在多字节字符中间时返回 `None`
+  pub fn position(&self, offset: usize, encoding: PositionEncoding) -> Option<Position> {
+    let line = self.line_of(offset);
+    let start = self.lines[line as usize - 1];
+    let units = encoding.len(self.source.get(start.bytes..offset)?);
+    Some(Position {
+      offset: start.units(encoding) + units,
+      line,
+      column: units + 1,
+    })
+  }
+
+  /// 把行列号换算为字节偏移，`column` 从 1 开始并按 `encoding` 计算
+  ///
+  /// 列号超出行尾或落在字符中间（如 UTF-16 代理对之间）时返回 `None`
+  pub fn offset(&self, line: u32, column: u32, encoding: PositionEncoding) -> Option<usize> {
+    let start = self.line_start(line)?;
+    let text = self.line_text(line)?;
+    let target = column.checked_sub(1)?;
+    let mut units = 0;
+    for (index, ch) in text.char_indices() {
+      if units == target {
+        return Some(start + index);
+      }
+      units += match encoding {
+        PositionEncoding::Utf8 => ch.len_utf8() as u32,
+        PositionEncoding::Utf16 => ch.len_utf16() as u32,
+        PositionEncoding::Utf32 => 1,
+      };
+      if units > target {
+        return None;
+      }
+    }
+    (units == target).then_some(start + text.len())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn line_index_conversions() {
+    let index = LineIndex::new("ab\n中😀c\n");
+    assert_eq!(index.line_count(), 3);
+    assert_eq!(index.line_text(2), Some("中😀c"));
+    assert_eq!(index.line_text(3), Some(""));
+    assert_eq!(index.line_of(3), 2);
+
+    let position = |offset, line, column| Position {
+      offset,
+      line,
+      column,
+    };
+    // `c` 位于第 10 个字节
+    assert_eq!(
+      index.position(10, PositionEncoding::Utf8),
+      Some(position(10, 2, 8))
+    );
+    assert_eq!(
+      index.position(10, PositionEncoding::Utf16),
+      Some(position(6, 2, 4))
+    );
+    assert_eq!(
+      index.position(10, PositionEncoding::Utf32),
+      Some(position(5, 2, 3))
+    );
+    assert_eq!(index.position(4, PositionEncoding::Utf8), None);
+
+    assert_eq!(index.offset(2, 4, PositionEncoding::Utf16), Some(10));
+    assert_eq!(index.offset(2, 3, PositionEncoding::Utf16), None);
+    assert_eq!(index.offset(2, 4, PositionEncoding::Utf32), Some(11));
+    assert_eq!(index.offset(1, 3, PositionEncoding::Utf8), Some(2));
+    assert_eq!(index.offset(1, 4, PositionEncoding::Utf8), None);
+  }
+}
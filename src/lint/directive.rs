@@ -0,0 +1,214 @@
+//! 注释指令
+//!
+//! 与 eslint 注释类似，通过注释控制检查规则的启用范围：
+//!
+//! * `<!-- wxml-lint-disable -->` / `<!-- wxml-lint-disable rule-a, rule-b -->`：从此处开始禁用
+//! * `<!-- wxml-lint-enable -->` / `<!-- wxml-lint-enable rule-a -->`：从此处开始重新启用
+//! * `<!-- wxml-lint-disable-line -->`：禁用注释所在行
+//! * `<!-- wxml-lint-disable-next-line -->`：禁用注释结束后的下一行
+//!
+//! 未指定规则名称时作用于所有规则
+
+use std::collections::HashSet;
+
+use crate::ast::{Node, Position, Root};
+
+use super::Diagnostic;
+
+/// 注释指令，`rules` 为空表示所有规则
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommentDirective {
+  Disable { rules: Vec<String> },
+  Enable { rules: Vec<String> },
+  DisableLine { rules: Vec<String> },
+  DisableNextLine { rules: Vec<String> },
+}
+
+impl CommentDirective {
+  /// 从注释内容（不含 `<!--` 与 `-->`）解析指令，不是指令时返回 `None`
+  pub fn parse(content: &str) -> Option<Self> {
+    let content = content.trim();
+    let (keyword, rest) = content
+      .split_once(char::is_whitespace)
+      .unwrap_or((content, ""));
+    let rules = rest
+      .split(|c: char| c == ',' || c.is_whitespace())
+      .filter(|rule| !rule.is_empty())
+      .map(str::to_string)
+      .collect();
+    match keyword {
+      "wxml-lint-disable" => Some(Self::Disable { rules }),
+      "wxml-lint-enable" => Some(Self::Enable { rules }),
+      "wxml-lint-disable-line" => Some(Self::DisableLine { rules }),
+      "wxml-lint-disable-next-line" => Some(Self::DisableNextLine { rules }),
+      _ => None,
+    }
+  }
+
+  /// 指令涉及的规则
+  pub fn rules(&self) -> &[String] {
+    match self {
+      Self::Disable { rules }
+      | Self::Enable { rules }
+      | Self::DisableLine { rules }
+      | Self::DisableNextLine { rules } => rules,
+    }
+  }
+
+  fn applies_to(&self, rule: &str) -> bool {
+    let rules = self.rules();
+    rules.is_empty() || rules.iter().any(|name| name == rule)
+  }
+}
+
+/// 收集文档中的所有指令及其注释位置，按出现顺序排列
+pub fn collect_directives(root: &Root) -> Vec<(CommentDirective, Position, Position)> {
+  let mut directives = Vec::new();
+  root.walk(&mut |node| {
+    if let Node::Comment {
+      content,
+      start,
+      end,
+    } = node
+    {
+      if let Some(directive) = CommentDirective::parse(content) {
+        directives.push((directive, *start, *end));
+      }
+    }
+  });
+  directives
+}
+
+/// 规则的禁用状态
+#[derive(Default)]
+struct DisableState<'a> {
+  /// 是否禁用了所有规则
+  all: bool,
+  /// 单独禁用的规则
+  rules: HashSet<&'a str>,
+  /// 禁用所有规则后又单独启用的规则
+  except: HashSet<&'a str>,
+}
+
+impl<'a> DisableState<'a> {
+  fn apply(&mut self, disable: bool, rules: &'a [String]) {
+    if rules.is_empty() {
+      *self = Self {
+        all: disable,
+        ..Self::default()
+      };
+      return;
+    }
+    for rule in rules {
+      if disable {
+        self.rules.insert(rule);
+        self.except.remove(rule.as_str());
+      } else {
+        self.rules.remove(rule.as_str());
+        if self.all {
+          self.except.insert(rule);
+        }
+      }
+    }
+  }
+
+  fn is_disabled(&self, rule: &str) -> bool {
+    (self.all && !self.except.contains(rule)) || self.rules.contains(rule)
+  }
+}
+
+/// 诊断信息是否被指令禁用
+fn is_suppressed(
+  diagnostic: &Diagnostic,
+  directives: &[(CommentDirective, Position, Position)],
+) -> bool {
+  let mut state = DisableState::default();
+  for (directive, start, end) in directives {
+    match directive {
+      CommentDirective::Disable { rules } if end.offset <= diagnostic.start.offset => {
+        state.apply(true, rules)
+      }
+      CommentDirective::Enable { rules } if end.offset <= diagnostic.start.offset => {
+        state.apply(false, rules)
+      }
+      CommentDirective::DisableLine { .. }
+        if start.line == diagnostic.start.line && directive.applies_to(&diagnostic.rule) =>
+      {
+        return true
+      }
+      CommentDirective::DisableNextLine { .. }
+        if end.line + 1 == diagnostic.start.line && directive.applies_to(&diagnostic.rule) =>
+      {
+        return true
+      }
+      _ => {}
+    }
+  }
+  state.is_disabled(&diagnostic.rule)
+}
+
+/// 移除被注释指令禁用的诊断信息
+pub(crate) fn filter_diagnostics(root: &Root, diagnostics: &mut Vec<Diagnostic>) {
+  let directives = collect_directives(root);
+  if directives.is_empty() {
+    return;
+  }
+  diagnostics.retain(|diagnostic| !is_suppressed(diagnostic, &directives));
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{lint::Linter, options::ParserOptions, parser::Parser};
+
+  #[test]
+  fn parse_directive() {
+    assert_eq!(
+      CommentDirective::parse(" wxml-lint-disable no-duplicate-attrs, a11y-image-alt "),
+      Some(CommentDirective::Disable {
+        rules: vec!["no-duplicate-attrs".into(), "a11y-image-alt".into()]
+      })
+    );
+    assert_eq!(
+      CommentDirective::parse("wxml-lint-enable"),
+      Some(CommentDirective::Enable { rules: vec![] })
+    );
+    assert_eq!(CommentDirective::parse(" just a comment "), None);
+  }
+
+  #[test]
+  fn suppress_diagnostics() {
+    let source = r#"<item a="1" a="2"/>
+<!-- wxml-lint-disable-next-line -->
+<item b="1" b="2"/>
+<item c="1" c="2"/><!-- wxml-lint-disable-line no-duplicate-attrs -->
+<!-- wxml-lint-disable -->
+<item d="1" d="2"/>
+<!-- wxml-lint-enable no-duplicate-attrs -->
+<item e="1" e="2">{{}}</item>
+<!-- wxml-lint-enable -->
+<item f="1" f="2"/>"#;
+    let root = Parser::new(source).parse_root().unwrap();
+    let lines: Vec<_> = Linter::default()
+      .lint(&root)
+      .into_iter()
+      .map(|diagnostic| (diagnostic.start.line, diagnostic.rule))
+      .collect();
+    assert_eq!(
+      lines,
+      [
+        (1, "no-duplicate-attrs".to_string()),
+        (8, "no-duplicate-attrs".to_string()),
+        (10, "no-duplicate-attrs".to_string()),
+      ]
+    );
+
+    let options = ParserOptions {
+      attach_comments: Some(true),
+      ..Default::default()
+    };
+    let root = Parser::with_options(source, options).parse_root().unwrap();
+    assert!(root.comments.as_ref().unwrap().is_empty());
+    assert_eq!(Linter::default().lint(&root).len(), 3);
+  }
+}
@@ -0,0 +1,126 @@
+//! 自动修复
+//!
+//! 规则通过 [`LintContext::report_with_fix`](super::LintContext::report_with_fix) 为诊断信息附带改动，
+//! 这里负责把多条诊断的修复合并应用到源码上。不同规则的修复可能改动同一段文本，
+//! 冲突的修复会被跳过，重新检查后再次修复即可，[`Linter::fix`] 会自动重复这一过程
+
+use super::{Diagnostic, Linter};
+use crate::{
+  options::ParserOptions,
+  parser::Parser,
+  patch::{apply_edits, Edit},
+};
+
+/// [`Linter::fix`] 最多重复检查、修复的次数，避免规则的修复互相影响导致无法收敛
+const MAX_FIX_PASSES: usize = 10;
+
+/// 应用修复的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixResult {
+  /// 修复后的源码
+  pub output: String,
+  /// 成功应用的修复数量
+  pub applied: usize,
+  /// 因与先前的修复冲突或范围无效而跳过的诊断，值为其在输入中的下标
+  pub skipped: Vec<usize>,
+}
+
+/// 按顺序应用诊断信息附带的修复
+///
+/// 每条诊断的改动作为整体应用；与已接受的改动重叠（包括在同一位置插入）时整条跳过
+pub fn apply_fixes(source: &str, diagnostics: &[Diagnostic]) -> FixResult {
+  let mut accepted: Vec<Edit> = Vec::new();
+  let mut applied = 0;
+  let mut skipped = Vec::new();
+  for (index, diagnostic) in diagnostics.iter().enumerate() {
+    if diagnostic.fix.is_empty() {
+      continue;
+    }
+    let conflict = apply_edits(source, &diagnostic.fix).is_err()
+      || diagnostic
+        .fix
+        .iter()
+        .any(|edit| accepted.iter().any(|other| overlaps(edit, other)));
+    if conflict {
+      skipped.push(index);
+    } else {
+      accepted.extend(diagnostic.fix.iter().cloned());
+      applied += 1;
+    }
+  }
+  let output = apply_edits(source, &accepted).expect("accepted fixes never overlap");
+  FixResult {
+    output,
+    applied,
+    skipped,
+  }
+}
+
+fn overlaps(a: &Edit, b: &Edit) -> bool {
+  (a.start < b.end && b.start < a.end)
+    || (a.start == b.start && (a.start == a.end || b.start == b.end))
+}
+
+impl Linter {
+  /// 反复检查并应用修复，直到没有可应用的修复，返回修复后的源码
+  ///
+  /// 源码存在语法错误时不做任何修改，避免在错误恢复后的 AST 上改写
+  pub fn fix(&self, source: &str, options: &ParserOptions) -> String {
+    let mut output = source.to_string();
+    for _ in 0..MAX_FIX_PASSES {
+      let mut parser = Parser::with_options(&output, options.clone());
+      let Ok(root) = parser.parse_root() else {
+        break;
+      };
+      if !parser.errors().is_empty() {
+        break;
+      }
+      let result = apply_fixes(&output, &self.lint_source(&root, &output));
+      if result.applied == 0 {
+        break;
+      }
+      output = result.output;
+    }
+    output
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{ast::Position, diagnostics::Severity, lint::rules::AttrOrder};
+
+  fn diagnostic(fix: Vec<Edit>) -> Diagnostic {
+    Diagnostic {
+      rule: "test".to_string(),
+      code: "WXML0000".to_string(),
+      message: String::new(),
+      severity: Severity::Warning,
+      start: Position::SYNTHETIC,
+      end: Position::SYNTHETIC,
+      fix,
+    }
+  }
+
+  #[test]
+  fn skips_conflicting_fixes() {
+    let diagnostics = [
+      diagnostic(vec![Edit::new(0, 3, "AAA")]),
+      diagnostic(Vec::new()),
+      diagnostic(vec![Edit::new(5, 6, "x"), Edit::new(2, 4, "y")]),
+      diagnostic(vec![Edit::insert(8, "!")]),
+      diagnostic(vec![Edit::insert(8, "?")]),
+      diagnostic(vec![Edit::new(3, 99, "")]),
+    ];
+    let result = apply_fixes("abcdefgh", &diagnostics);
+    assert_eq!(result.output, "AAAdefgh!");
+    assert_eq!(result.applied, 2);
+    assert_eq!(result.skipped, [2, 4, 5]);
+
+    let source = r#"<view class="a" wx:if="{{ok}}" bindtap="t"/>"#;
+    let fixed = Linter::empty()
+      .with_rule(AttrOrder::default())
+      .fix(source, &ParserOptions::default());
+    assert_eq!(fixed, r#"<view wx:if="{{ok}}" bindtap="t" class="a"/>"#);
+  }
+}
@@ -0,0 +1,322 @@
+//! 模板检查（lint）
+//!
+//! 检查基于解析后的 AST 进行，每条规则实现 [`Rule`]，由 [`Linter`] 统一遍历节点并收集诊断信息；
+//! 模板中的注释指令可以局部禁用规则，见 [`directive`]；规则附带的自动修复见 [`fix`]
+
+pub mod directive;
+pub mod fix;
+pub mod rules;
+
+#[cfg(feature = "napi")]
+use napi_derive::napi;
+use std::{error::Error, fmt};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  ast::{Node, Position, Root},
+  diagnostics::Severity,
+  options::ParserOptions,
+  patch::Edit,
+};
+
+/// 检查规则产生的诊断信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi", napi(object))]
+pub struct Diagnostic {
+  /// 规则名称，如 `no-duplicate-attrs`
+  pub rule: String,
+  /// 规则的稳定编号，如 `WXML2001`
+  pub code: String,
+  pub message: String,
+  /// 严重级别，缺省时为 [`Severity::Warning`]
+  #[serde(default)]
+  pub severity: Severity,
+  pub start: Position,
+  pub end: Position,
+  /// 自动修复需要的改动，规则不提供修复时为空
+  pub fix: Vec<Edit>,
+}
+
+impl Diagnostic {
+  /// 输出为 JSON 对象，便于编辑器和 CI 按编号处理
+  pub fn to_json(&self) -> String {
+    serde_json::to_string(self).unwrap()
+  }
+}
+
+/// 检查配置，供 Node.js 绑定等无法直接组装 [`Linter`] 的调用方使用
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+#[cfg_attr(feature = "napi", napi(object))]
+pub struct LintConfig {
+  /// 同时启用无障碍规则，默认只启用推荐规则
+  pub accessibility: Option<bool>,
+  /// 同时启用代码风格规则，如 `attr-order`
+  pub stylistic: Option<bool>,
+  /// 按名称禁用的规则
+  pub disabled_rules: Option<Vec<String>>,
+  /// 禁止使用的标签，设置后启用 `no-banned` 规则
+  pub banned_tags: Option<Vec<rules::BannedTag>>,
+  /// 禁止使用的属性，设置后启用 `no-banned` 规则
+  pub banned_attributes: Option<Vec<rules::BannedAttribute>>,
+  /// 要求文档只有一个根元素，设置后启用 `single-root` 规则，适用于组件模板
+  pub single_root: Option<bool>,
+  /// 项目支持的最低基础库版本，如 `2.10.0`，设置后启用 `min-version` 规则
+  pub target_version: Option<String>,
+  /// 表达式复杂度的限制，设置后启用 `no-complex-expression` 规则
+  pub expression_limits: Option<rules::ExpressionLimits>,
+  /// 解析选项
+  pub parser: Option<ParserOptions>,
+}
+
+/// [`LintConfig`] 中无法使用的配置项
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintConfigError {
+  /// 配置项名称，与 JSON 中的字段名一致，如 `targetVersion`
+  pub field: &'static str,
+  pub message: String,
+}
+
+impl fmt::Display for LintConfigError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "invalid lint config `{}`: {}", self.field, self.message)
+  }
+}
+
+impl Error for LintConfigError {}
+
+/// 规则检查的范围，用于 [`Linter::lint_range`] 判断需要重新执行的规则
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleScope {
+  /// 只通过 [`Rule::check_node`] 检查单个节点及其后代，修改只影响相交的节点
+  Node,
+  /// 通过 [`Rule::check_root`] 检查整个文档，依赖兄弟、祖先等上下文
+  Document,
+}
+
+/// 检查规则
+pub trait Rule: Send + Sync {
+  /// 规则名称，使用 kebab-case
+  fn name(&self) -> &'static str;
+
+  /// 规则的稳定编号，一经分配不再变更；内置规则使用 `WXML2xxx`，
+  /// 其中无障碍规则使用 `WXML21xx`，代码风格规则使用 `WXML22xx`，策略规则使用 `WXML23xx`，语法错误的编号见 [`SyntaxErrorKind::code`](crate::error::SyntaxErrorKind::code)
+  fn code(&self) -> &'static str;
+
+  /// 诊断信息的严重级别，默认为 [`Severity::Warning`]
+  fn severity(&self) -> Severity {
+    Severity::Warning
+  }
+
+  /// 检查的范围，实现了 [`Rule::check_root`] 的规则应返回 [`RuleScope::Document`]
+  fn scope(&self) -> RuleScope {
+    RuleScope::Node
+  }
+
+  /// 检查单个节点，遍历时对每个节点（含后代）调用一次
+  fn check_node(&self, _node: &Node, _ctx: &mut LintContext) {}
+
+  /// 检查整个文档，适用于需要上下文（如兄弟、祖先节点）的规则
+  fn check_root(&self, _root: &Root, _ctx: &mut LintContext) {}
+}
+
+/// 规则执行时的上下文，用于报告诊断信息
+pub struct LintContext<'s> {
+  rule: &'static str,
+  code: &'static str,
+  severity: Severity,
+  source: Option<&'s str>,
+  diagnostics: Vec<Diagnostic>,
+}
+
+impl<'s> LintContext<'s> {
+  fn new(rule: &dyn Rule, source: Option<&'s str>) -> Self {
+    Self {
+      rule: rule.name(),
+      code: rule.code(),
+      severity: rule.severity(),
+      source,
+      diagnostics: Vec::new(),
+    }
+  }
+
+  /// 文档的源码，仅通过 [`Linter::lint_source`] 检查时存在，
+  /// 供需要定位到节点内部（如去除空白前的表达式内容）的规则使用
+  pub fn source(&self) -> Option<&'s str> {
+    self.source
+  }
+
+  /// 报告一条诊断信息
+  pub fn report(&mut self, message: impl Into<String>, start: Position, end: Position) {
+    self.report_with_fix(message, start, end, Vec::new());
+  }
+
+  /// 报告一条附带自动修复的诊断信息
+  pub fn report_with_fix(
+    &mut self,
+    message: impl Into<String>,
+    start: Position,
+    end: Position,
+    fix: Vec<Edit>,
+  ) {
+    self.diagnostics.push(Diagnostic {
+      rule: self.rule.to_string(),
+      code: self.code.to_string(),
+      message: message.into(),
+      severity: self.severity,
+      start,
+      end,
+      fix,
+    });
+  }
+}
+
+/// 规则集合与执行器
+pub struct Linter {
+  rules: Vec<Box<dyn Rule>>,
+}
+
+impl Default for Linter {
+  /// 启用所有推荐规则
+  fn default() -> Self {
+    Self {
+      rules: rules::recommended(),
+    }
+  }
+}
+
+impl Linter {
+  /// 按配置创建执行器，配置项无效（如无法解析的 `targetVersion`）时返回错误，不会静默忽略对应的规则
+  pub fn from_config(config: &LintConfig) -> Result<Self, LintConfigError> {
+    let mut linter = Self::default();
+    if config.accessibility == Some(true) {
+      linter = linter.with_rules(rules::accessibility());
+    }
+    if config.stylistic == Some(true) {
+      linter = linter.with_rules(rules::stylistic());
+    }
+    let tags = config.banned_tags.clone().unwrap_or_default();
+    let attributes = config.banned_attributes.clone().unwrap_or_default();
+    if !tags.is_empty() || !attributes.is_empty() {
+      linter = linter.with_rule(rules::NoBanned { tags, attributes });
+    }
+    if config.single_root == Some(true) {
+      linter = linter.with_rule(rules::SingleRoot);
+    }
+    if let Some(version) = &config.target_version {
+      let target = version.parse().map_err(|message| LintConfigError {
+        field: "targetVersion",
+        message,
+      })?;
+      linter = linter.with_rule(rules::MinVersion { target });
+    }
+    if let Some(limits) = &config.expression_limits {
+      linter = linter.with_rule(rules::NoComplexExpression {
+        limits: limits.clone(),
+      });
+    }
+    for name in config.disabled_rules.iter().flatten() {
+      linter = linter.without_rule(name);
+    }
+    Ok(linter)
+  }
+
+  /// 创建不包含任何规则的执行器
+  pub fn empty() -> Self {
+    Self { rules: Vec::new() }
+  }
+
+  /// 添加规则
+  pub fn with_rule(mut self, rule: impl Rule + 'static) -> Self {
+    self.rules.push(Box::new(rule));
+    self
+  }
+
+  /// 批量添加规则，如 [`rules::accessibility`]
+  pub fn with_rules(mut self, rules: Vec<Box<dyn Rule>>) -> Self {
+    self.rules.extend(rules);
+    self
+  }
+
+  /// 按名称移除规则
+  pub fn without_rule(mut self, name: &str) -> Self {
+    self.rules.retain(|rule| rule.name() != name);
+    self
+  }
+
+  /// 已启用的规则名称
+  pub fn rule_names(&self) -> Vec<&'static str> {
+    self.rules.iter().map(|rule| rule.name()).collect()
+  }
+
+  /// 对整个文档执行所有规则，结果按位置排序，已移除被注释指令禁用的诊断信息
+  pub fn lint(&self, root: &Root) -> Vec<Diagnostic> {
+    self.lint_document(root, None)
+  }
+
+  /// 同 [`Linter::lint`]，规则可以通过 [`LintContext::source`] 读取 `root` 对应的源码，
+  /// 如 `no-complex-expression` 据此把诊断信息定位到表达式内部
+  pub fn lint_source(&self, root: &Root, source: &str) -> Vec<Diagnostic> {
+    self.lint_document(root, Some(source))
+  }
+
+  fn lint_document(&self, root: &Root, source: Option<&str>) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for rule in &self.rules {
+      let mut ctx = LintContext::new(rule.as_ref(), source);
+      rule.check_root(root, &mut ctx);
+      root.walk(&mut |node| rule.check_node(node, &mut ctx));
+      diagnostics.append(&mut ctx.diagnostics);
+    }
+    directive::filter_diagnostics(root, &mut diagnostics);
+    diagnostics.sort_by_key(|diagnostic| diagnostic.start.offset);
+    diagnostics
+  }
+
+  /// 只重新检查修改过的范围 `start..=end`（字节偏移），适合编辑器在每次输入后调用
+  ///
+  /// [`RuleScope::Node`] 规则只对与范围相交的节点（修改的节点及其祖先）执行，不遍历其他子树；
+  /// [`RuleScope::Document`] 规则对整个文档执行。返回起始位置在范围内的诊断信息，
+  /// 调用方用它替换上次结果中位于范围内的部分，范围外的诊断信息保持不变。
+  /// 检查后代并在祖先上报告的问题可能位于范围之外，下一次完整检查时更新。
+  /// 与 [`Linter::lint_source`] 一样，`source` 为 `root` 对应的源码
+  pub fn lint_range(&self, root: &Root, source: &str, start: u32, end: u32) -> Vec<Diagnostic> {
+    let mut intersecting = Vec::new();
+    collect_intersecting(&root.children, start, end, &mut intersecting);
+    let mut diagnostics = Vec::new();
+    for rule in &self.rules {
+      let mut ctx = LintContext::new(rule.as_ref(), Some(source));
+      match rule.scope() {
+        RuleScope::Node => {
+          for node in &intersecting {
+            rule.check_node(node, &mut ctx);
+          }
+        }
+        RuleScope::Document => {
+          rule.check_root(root, &mut ctx);
+          root.walk(&mut |node| rule.check_node(node, &mut ctx));
+        }
+      }
+      diagnostics.append(&mut ctx.diagnostics);
+    }
+    diagnostics.retain(|diagnostic| (start..=end).contains(&diagnostic.start.offset));
+    directive::filter_diagnostics(root, &mut diagnostics);
+    diagnostics.sort_by_key(|diagnostic| diagnostic.start.offset);
+    diagnostics
+  }
+}
+
+/// 与范围相交的节点，只进入相交节点的子节点
+fn collect_intersecting<'a>(nodes: &'a [Node], start: u32, end: u32, out: &mut Vec<&'a Node>) {
+  for node in nodes {
+    if node.start().offset > end || node.end().offset < start {
+      continue;
+    }
+    out.push(node);
+    if let Node::Element { children, .. } = node {
+      collect_intersecting(children, start, end, out);
+    }
+  }
+}
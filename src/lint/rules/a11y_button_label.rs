@@ -0,0 +1,42 @@
+use crate::{
+  ast::Node,
+  lint::{LintContext, Rule},
+};
+
+/// 仅包含图标、图片等非文本内容的按钮需要提供 `aria-label`，否则读屏软件无法朗读
+pub struct A11yButtonLabel;
+
+impl Rule for A11yButtonLabel {
+  fn name(&self) -> &'static str {
+    "a11y-button-label"
+  }
+
+  fn code(&self) -> &'static str {
+    "WXML2101"
+  }
+
+  fn check_node(&self, node: &Node, ctx: &mut LintContext) {
+    let Node::Element {
+      name, start, end, ..
+    } = node
+    else {
+      return;
+    };
+    if name != "button" || node.attr("aria-label").is_some() {
+      return;
+    }
+    let mut has_text = false;
+    node.walk(&mut |child| match child {
+      Node::Text { content, .. } => has_text |= !content.trim().is_empty(),
+      Node::Expression { .. } => has_text = true,
+      _ => {}
+    });
+    if !has_text {
+      ctx.report(
+        "button without text content needs `aria-label`",
+        *start,
+        *end,
+      );
+    }
+  }
+}
@@ -0,0 +1,41 @@
+use crate::{
+  ast::Node,
+  lint::{LintContext, Rule},
+};
+
+/// 图片需要通过 `alt` 或 `aria-label` 提供替代文本
+pub struct A11yImageAlt;
+
+impl Rule for A11yImageAlt {
+  fn name(&self) -> &'static str {
+    "a11y-image-alt"
+  }
+
+  fn code(&self) -> &'static str {
+    "WXML2102"
+  }
+
+  fn check_node(&self, node: &Node, ctx: &mut LintContext) {
+    let Node::Element {
+      name, start, end, ..
+    } = node
+    else {
+      return;
+    };
+    if !matches!(name.as_str(), "image" | "cover-image") {
+      return;
+    }
+    // 装饰性图片可通过 `aria-hidden` 显式忽略
+    if ["alt", "aria-label", "aria-hidden"]
+      .iter()
+      .any(|attr| node.attr(attr).is_some())
+    {
+      return;
+    }
+    ctx.report(
+      format!("<{name}> needs `alt` or `aria-label`"),
+      *start,
+      *end,
+    );
+  }
+}
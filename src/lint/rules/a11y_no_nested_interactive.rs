@@ -0,0 +1,68 @@
+use crate::{
+  ast::{Node, Root},
+  lint::{LintContext, Rule, RuleScope},
+};
+
+/// 可交互的内置组件
+const INTERACTIVE: &[&str] = &[
+  "button",
+  "navigator",
+  "input",
+  "textarea",
+  "picker",
+  "switch",
+  "slider",
+  "checkbox",
+  "radio",
+];
+
+/// 禁止在按钮、链接等可交互元素内部嵌套其他可交互元素
+pub struct A11yNoNestedInteractive;
+
+impl Rule for A11yNoNestedInteractive {
+  fn name(&self) -> &'static str {
+    "a11y-no-nested-interactive"
+  }
+
+  fn code(&self) -> &'static str {
+    "WXML2103"
+  }
+
+  fn scope(&self) -> RuleScope {
+    RuleScope::Document
+  }
+
+  fn check_root(&self, root: &Root, ctx: &mut LintContext) {
+    for node in &root.children {
+      check(node, None, ctx);
+    }
+  }
+}
+
+/// `outer` 为最近的可交互祖先元素名称
+fn check(node: &Node, outer: Option<&str>, ctx: &mut LintContext) {
+  let Node::Element {
+    name,
+    children,
+    start,
+    end,
+    ..
+  } = node
+  else {
+    return;
+  };
+  let mut outer = outer;
+  if INTERACTIVE.contains(&name.as_str()) {
+    if let Some(outer) = outer {
+      ctx.report(
+        format!("interactive <{name}> nested inside <{outer}>"),
+        *start,
+        *end,
+      );
+    }
+    outer = Some(name);
+  }
+  for child in children {
+    check(child, outer, ctx);
+  }
+}
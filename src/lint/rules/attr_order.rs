@@ -0,0 +1,128 @@
+use crate::{
+  ast::{Attribute, AttributeValue, Node},
+  codegen::{generate_attribute, CodegenOptions},
+  diagnostics::Severity,
+  dialect::Directive,
+  lint::{LintContext, Rule},
+  patch::Edit,
+};
+
+/// 属性分组，用于 [`AttrOrder`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttrGroup {
+  /// `wx:` 指令，如 `wx:if`、`wx:for`、`wx:key`，以及其他方言的条件与列表渲染指令
+  Directives,
+  /// 值包含 `{{}}` 表达式的数据绑定，以及 `model:` 双向绑定
+  Bindings,
+  /// 事件绑定，如 `bindtap`、`catch:touchstart`、`capture-bind:tap`
+  Events,
+  /// 其余静态属性
+  Plain,
+}
+
+impl AttrGroup {
+  fn of(attr: &Attribute) -> Self {
+    let prefix = attr.prefix.as_deref();
+    if prefix == Some("wx") || Directive::of(&attr.name).is_some() {
+      return AttrGroup::Directives;
+    }
+    if attr.is_event_binding() {
+      return AttrGroup::Events;
+    }
+    let has_expression = attr
+      .value
+      .iter()
+      .flatten()
+      .any(|value| matches!(value, AttributeValue::Expression { .. }));
+    if prefix == Some("model") || has_expression {
+      AttrGroup::Bindings
+    } else {
+      AttrGroup::Plain
+    }
+  }
+
+  fn label(&self) -> &'static str {
+    match self {
+      AttrGroup::Directives => "directives",
+      AttrGroup::Bindings => "bindings",
+      AttrGroup::Events => "events",
+      AttrGroup::Plain => "plain attributes",
+    }
+  }
+}
+
+/// 要求元素的属性按分组顺序排列，默认依次为指令、数据绑定、事件、静态属性；组内顺序不限
+///
+/// 附带的修复使用代码生成器重新输出全部属性，属性之间的换行与多余空白会被合并为一个空格
+pub struct AttrOrder {
+  /// 分组顺序，未列出的分组排在最后
+  pub order: Vec<AttrGroup>,
+}
+
+impl Default for AttrOrder {
+  fn default() -> Self {
+    Self {
+      order: vec![
+        AttrGroup::Directives,
+        AttrGroup::Bindings,
+        AttrGroup::Events,
+        AttrGroup::Plain,
+      ],
+    }
+  }
+}
+
+impl AttrOrder {
+  fn rank(&self, attr: &Attribute) -> usize {
+    let group = AttrGroup::of(attr);
+    self
+      .order
+      .iter()
+      .position(|item| *item == group)
+      .unwrap_or(self.order.len())
+  }
+}
+
+impl Rule for AttrOrder {
+  fn name(&self) -> &'static str {
+    "attr-order"
+  }
+
+  fn code(&self) -> &'static str {
+    "WXML2201"
+  }
+
+  fn severity(&self) -> Severity {
+    Severity::Hint
+  }
+
+  fn check_node(&self, node: &Node, ctx: &mut LintContext) {
+    let Some(attrs) = node.attrs() else {
+      return;
+    };
+    let Some(index) = (1..attrs.len()).find(|&i| self.rank(&attrs[i]) < self.rank(&attrs[i - 1]))
+    else {
+      return;
+    };
+    let mut sorted: Vec<_> = attrs.iter().collect();
+    sorted.sort_by_key(|attr| self.rank(attr));
+    let (first, last) = (&attrs[0], &attrs[attrs.len() - 1]);
+    let fix = (!first.is_synthetic() && !last.is_synthetic()).then(|| {
+      let replacement = sorted
+        .iter()
+        .map(|attr| generate_attribute(attr, &CodegenOptions::default()))
+        .collect::<Vec<_>>()
+        .join(" ");
+      Edit::new(first.start.offset, last.end.offset, replacement)
+    });
+    let attr = &attrs[index];
+    let message = format!(
+      "`{}` ({}) should come before `{}` ({})",
+      attr.name,
+      AttrGroup::of(attr).label(),
+      attrs[index - 1].name,
+      AttrGroup::of(&attrs[index - 1]).label(),
+    );
+    ctx.report_with_fix(message, attr.start, attr.end, fix.into_iter().collect());
+  }
+}
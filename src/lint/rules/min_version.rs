@@ -0,0 +1,62 @@
+use crate::{
+  ast::Node,
+  builtins::{attr_since, component_since, Version},
+  diagnostics::Severity,
+  lint::{LintContext, Rule},
+};
+
+/// 禁止使用目标基础库版本尚不支持的组件与属性语法，版本要求见 [`VERSION_REQUIREMENTS`](crate::builtins::VERSION_REQUIREMENTS)
+///
+/// 适用于需要兼容旧版本微信的项目，`target` 为项目设置的最低基础库版本
+#[derive(Debug, Clone, Copy)]
+pub struct MinVersion {
+  pub target: Version,
+}
+
+impl Rule for MinVersion {
+  fn name(&self) -> &'static str {
+    "min-version"
+  }
+
+  fn code(&self) -> &'static str {
+    "WXML2303"
+  }
+
+  fn severity(&self) -> Severity {
+    Severity::Error
+  }
+
+  fn check_node(&self, node: &Node, ctx: &mut LintContext) {
+    let Node::Element {
+      name, attrs, start, ..
+    } = node
+    else {
+      return;
+    };
+    if let Some(since) = component_since(name).filter(|since| *since > self.target) {
+      let name_start = start.advance("<");
+      ctx.report(
+        format!(
+          "<{name}> requires base library {since}, but the target version is {}",
+          self.target
+        ),
+        name_start,
+        name_start.advance(name),
+      );
+    }
+    for attr in attrs {
+      if let Some((feature, since)) =
+        attr_since(&attr.name).filter(|(_, since)| *since > self.target)
+      {
+        ctx.report(
+          format!(
+            "`{feature}` requires base library {since}, but the target version is {}",
+            self.target
+          ),
+          attr.start,
+          attr.end,
+        );
+      }
+    }
+  }
+}
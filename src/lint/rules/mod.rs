@@ -0,0 +1,379 @@
+//! 内置检查规则
+
+mod a11y_button_label;
+mod a11y_image_alt;
+mod a11y_no_nested_interactive;
+mod attr_order;
+mod min_version;
+mod no_banned;
+mod no_complex_expression;
+mod no_duplicate_attrs;
+mod no_empty_expression;
+mod single_root;
+mod valid_block_attrs;
+mod valid_builtin_attrs;
+mod valid_conditional_chain;
+
+pub use a11y_button_label::A11yButtonLabel;
+pub use a11y_image_alt::A11yImageAlt;
+pub use a11y_no_nested_interactive::A11yNoNestedInteractive;
+pub use attr_order::{AttrGroup, AttrOrder};
+pub use min_version::MinVersion;
+pub use no_banned::{BannedAttribute, BannedTag, NoBanned};
+pub use no_complex_expression::{ExpressionLimits, NoComplexExpression};
+pub use no_duplicate_attrs::NoDuplicateAttrs;
+pub use no_empty_expression::NoEmptyExpression;
+pub use single_root::SingleRoot;
+pub use valid_block_attrs::ValidBlockAttrs;
+pub use valid_builtin_attrs::ValidBuiltinAttrs;
+pub use valid_conditional_chain::ValidConditionalChain;
+
+use super::Rule;
+
+/// 推荐启用的规则
+pub fn recommended() -> Vec<Box<dyn Rule>> {
+  vec![
+    Box::new(NoDuplicateAttrs),
+    Box::new(NoEmptyExpression),
+    Box::new(ValidBlockAttrs),
+    Box::new(ValidBuiltinAttrs),
+    Box::new(ValidConditionalChain),
+  ]
+}
+
+/// 无障碍相关规则，默认不启用
+pub fn accessibility() -> Vec<Box<dyn Rule>> {
+  vec![
+    Box::new(A11yButtonLabel),
+    Box::new(A11yImageAlt),
+    Box::new(A11yNoNestedInteractive),
+  ]
+}
+
+/// 代码风格规则，默认不启用
+pub fn stylistic() -> Vec<Box<dyn Rule>> {
+  vec![Box::new(AttrOrder::default())]
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    builtins::Version,
+    lint::{LintConfig, Linter},
+    parser::Parser,
+  };
+
+  fn lint(rule: impl Rule + 'static, source: &str) -> Vec<String> {
+    let root = Parser::new(source).parse_root().unwrap();
+    Linter::empty()
+      .with_rule(rule)
+      .lint(&root)
+      .into_iter()
+      .map(|diagnostic| diagnostic.message)
+      .collect()
+  }
+
+  #[test]
+  fn rule_codes() {
+    let rules: Vec<_> = recommended()
+      .into_iter()
+      .chain(super::accessibility())
+      .chain(stylistic())
+      .chain([
+        Box::new(NoBanned::default()) as Box<dyn Rule>,
+        Box::new(SingleRoot),
+        Box::new(MinVersion {
+          target: Version::new(1, 0, 0),
+        }),
+        Box::new(NoComplexExpression::default()),
+      ])
+      .collect();
+    let mut codes: Vec<_> = rules.iter().map(|rule| rule.code()).collect();
+    assert!(codes.iter().all(|code| code.starts_with("WXML2")));
+    codes.sort_unstable();
+    codes.dedup();
+    assert_eq!(codes.len(), rules.len());
+
+    let root = Parser::new(r#"<view id="a" id="b"/>"#)
+      .parse_root()
+      .unwrap();
+    let diagnostics = Linter::default().lint(&root);
+    assert_eq!(diagnostics[0].code, "WXML2001");
+    assert!(diagnostics[0].to_json().contains(r#""code":"WXML2001""#));
+  }
+
+  #[test]
+  fn valid_builtin_attrs() {
+    let messages = lint(
+      ValidBuiltinAttrs,
+      r#"<button type="primery" size="{{s}}" open-type="share" bindtap="t" data-id="1" mark:id="1" colour="red"/><image mode="aspectFill" model:src="{{a}}"/><my-comp foo="bar"/>"#,
+    );
+    assert_eq!(
+      messages,
+      [
+        "invalid value `primery` for `type` on <button>, expected one of: primary, default, warn",
+        "unknown attribute `colour` on <button>",
+      ]
+    );
+  }
+
+  #[test]
+  fn valid_conditional_chain() {
+    let messages = lint(
+      ValidConditionalChain,
+      r#"<view wx:if="{{a}}"/>
+<!-- note -->
+<view wx:elif="{{b}}"/>
+<view wx:else/>
+<view wx:else/>
+<view wx:if="{{c}}"/>text<view wx:elif="{{d}}"/>
+<view><text wx:elif="{{e}}"/></view>
+<view wx:if="{{f}}" wx:else/>
+<view s-if="g"/><view s-elif="h"/>text<view tt:else/>"#,
+    );
+    assert_eq!(
+      messages,
+      [
+        "`wx:else` must directly follow an element with `wx:if` or `wx:elif`",
+        "`wx:elif` must directly follow an element with `wx:if` or `wx:elif`",
+        "`wx:elif` must directly follow an element with `wx:if` or `wx:elif`",
+        "`wx:else` cannot be used together with `wx:if`",
+        "`tt:else` must directly follow an element with `tt:if` or `tt:elif`",
+      ]
+    );
+  }
+
+  #[test]
+  fn accessibility() {
+    let source = r#"<button><icon type="search"/></button><button aria-label="搜索"><icon/></button><button>{{label}}</button><image src="a.png"/><image src="b.png" alt="logo"/><navigator url="/a"><button>Go</button></navigator>"#;
+    let root = Parser::new(source).parse_root().unwrap();
+    let diagnostics: Vec<_> = Linter::empty()
+      .with_rules(super::accessibility())
+      .lint(&root)
+      .into_iter()
+      .map(|diagnostic| diagnostic.rule)
+      .collect();
+    assert_eq!(
+      diagnostics,
+      [
+        "a11y-button-label",
+        "a11y-image-alt",
+        "a11y-no-nested-interactive",
+      ]
+    );
+  }
+
+  #[test]
+  fn attr_order() {
+    let source = "<view class=\"a\" bindtap=\"t\"\n  wx:if=\"{{ok}}\">x</view><text wx:if=\"{{b}}\" id=\"{{id}}\"/>";
+    let root = Parser::new(source).parse_root().unwrap();
+    let diagnostics = Linter::empty().with_rule(AttrOrder::default()).lint(&root);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+      diagnostics[0].message,
+      "`bindtap` (events) should come before `class` (plain attributes)"
+    );
+    let fixed = crate::patch::apply_edits(source, &diagnostics[0].fix).unwrap();
+    assert_eq!(
+      fixed,
+      "<view wx:if=\"{{ok}}\" bindtap=\"t\" class=\"a\">x</view><text wx:if=\"{{b}}\" id=\"{{id}}\"/>"
+    );
+  }
+
+  #[test]
+  fn no_banned() {
+    let rule = NoBanned {
+      tags: vec![BannedTag {
+        name: "web-view".to_string(),
+        message: None,
+      }],
+      attributes: vec![
+        BannedAttribute {
+          name: "open-type".to_string(),
+          value: Some("getUserInfo".to_string()),
+          tag: Some("button".to_string()),
+          message: Some("use `wx.getUserProfile` instead".to_string()),
+        },
+        BannedAttribute {
+          name: "catchtouchmove".to_string(),
+          ..Default::default()
+        },
+      ],
+    };
+    let source = r#"<web-view src="a"/><button open-type="getUserInfo"/><button open-type="share"/><view open-type="getUserInfo" catchtouchmove="m"/>"#;
+    let root = Parser::new(source).parse_root().unwrap();
+    let diagnostics = Linter::empty().with_rule(rule).lint(&root);
+    let found: Vec<_> = diagnostics
+      .iter()
+      .map(|d| (d.message.as_str(), d.start.offset, d.end.offset))
+      .collect();
+    assert_eq!(
+      found,
+      [
+        ("<web-view> is banned by policy", 1, 9),
+        ("use `wx.getUserProfile` instead", 27, 50),
+        ("attribute `catchtouchmove` is banned by policy", 109, 127),
+      ]
+    );
+  }
+
+  #[test]
+  fn single_root() {
+    let messages = lint(
+      SingleRoot,
+      r#"<import src="./a.wxml"/><wxs module="m" src="./m.wxs"/><template name="item"><text/></template>
+<view wx:if="{{a}}"/><view wx:else/><!-- note --><text/>"#,
+    );
+    assert_eq!(
+      messages,
+      ["template must have a single root element, found another <text>"]
+    );
+    assert!(lint(
+      SingleRoot,
+      "<view wx:if=\"{{a}}\"/><view wx:elif=\"{{b}}\"/>"
+    )
+    .is_empty());
+    assert_eq!(
+      lint(SingleRoot, "<block wx:if=\"{{a}}\"><view/><text/></block>").len(),
+      1
+    );
+  }
+
+  #[test]
+  fn valid_block_attrs() {
+    let messages = lint(
+      ValidBlockAttrs,
+      r#"<block wx:for="{{list}}" wx:key="id" class="row" s-if="a"><block slot="footer" bindtap="t"/></block>"#,
+    );
+    assert_eq!(
+      messages,
+      [
+        "attribute `class` has no effect on <block>, which renders nothing",
+        "attribute `bindtap` has no effect on <block>, which renders nothing",
+      ]
+    );
+  }
+
+  #[test]
+  fn lint_range() {
+    let source = r#"<view id="a" id="b"/>
+<view wx:else/>
+<view>
+  <text class="x" class="y">{{ }}</text>
+</view>"#;
+    let root = Parser::new(source).parse_root().unwrap();
+    let linter = Linter::default();
+    assert_eq!(linter.lint(&root).len(), 4);
+
+    let start = source.find("<text").unwrap() as u32;
+    let end = source.find("</text>").unwrap() as u32;
+    let codes: Vec<_> = linter
+      .lint_range(&root, source, start, end)
+      .into_iter()
+      .map(|diagnostic| (diagnostic.code, diagnostic.start.line))
+      .collect();
+    assert_eq!(
+      codes,
+      [("WXML2001".to_string(), 4), ("WXML2002".to_string(), 4)]
+    );
+  }
+
+  #[test]
+  fn min_version() {
+    let messages = lint(
+      MinVersion {
+        target: "2.8".parse().unwrap(),
+      },
+      r#"<page-meta/><view mut-bind:tap="a" bind:tap="b" model:value="{{v}}"><editor/></view>"#,
+    );
+    assert_eq!(
+      messages,
+      [
+        "<page-meta> requires base library 2.9.0, but the target version is 2.8.0",
+        "`mut-bind` requires base library 2.8.2, but the target version is 2.8.0",
+        "`model:` requires base library 2.9.3, but the target version is 2.8.0",
+      ]
+    );
+
+    let config = |version: &str| LintConfig {
+      target_version: Some(version.to_string()),
+      ..Default::default()
+    };
+    let linter = Linter::from_config(&config("2.8")).ok().unwrap();
+    assert!(linter.rule_names().contains(&"min-version"));
+    let error = Linter::from_config(&config("2.x")).err().unwrap();
+    assert_eq!(
+      error.to_string(),
+      "invalid lint config `targetVersion`: invalid version `2.x`"
+    );
+  }
+
+  #[test]
+  fn no_complex_expression() {
+    let source = r#"<wxs module="fmt" src="./fmt.wxs"/>
+<view class="{{ a && b || c ? 'x' : 'y' }}">{{ fmt.price(total) }} {{ format(date, user.name()) }}</view>
+<input value="{{count = 1   }}" bindinput="{{
+    n++
+  }}" data-d="{{ new Date() }}" data-e="{{ a === b }}" data-f="{{ `${a} = ${b}` }}"/>"#;
+    let root = Parser::new(source).parse_root().unwrap();
+    let rule = NoComplexExpression {
+      limits: ExpressionLimits {
+        max_operators: Some(2),
+        no_calls: Some(true),
+        no_assignment: Some(true),
+        no_new: Some(true),
+      },
+    };
+    let linter = Linter::empty().with_rule(rule);
+    let diagnostics = linter.lint_source(&root, source);
+    let increment = &diagnostics[4];
+    assert_eq!((increment.start.line, increment.start.column), (4, 6));
+    let diagnostics: Vec<_> = diagnostics
+      .into_iter()
+      .map(|diagnostic| {
+        let range = diagnostic.start.offset as usize..diagnostic.end.offset as usize;
+        (diagnostic.message, &source[range])
+      })
+      .collect();
+    assert_eq!(
+      diagnostics,
+      [
+        (
+          "expression has 3 operators, more than the maximum of 2".to_string(),
+          "?"
+        ),
+        (
+          "call to `format` is not allowed in bindings, only wxs module functions can be called"
+            .to_string(),
+          "format(date, user.name())"
+        ),
+        (
+          "call to `user.name` is not allowed in bindings, only wxs module functions can be called"
+            .to_string(),
+          "user.name()"
+        ),
+        (
+          "`=` modifies data and is not allowed in bindings".to_string(),
+          "="
+        ),
+        (
+          "`++` modifies data and is not allowed in bindings".to_string(),
+          "++"
+        ),
+        ("`new` is not allowed in bindings".to_string(), "new"),
+      ]
+    );
+
+    // 没有源码时定位到整个表达式
+    let diagnostic = &linter.lint(&root)[0];
+    let range = diagnostic.start.offset as usize..diagnostic.end.offset as usize;
+    assert_eq!(&source[range], "{{ a && b || c ? 'x' : 'y' }}");
+
+    // 增量检查同样定位到表达式内部
+    let start = source.find("<input").unwrap() as u32;
+    let diagnostic = &linter.lint_range(&root, source, start, source.len() as u32)[0];
+    let range = diagnostic.start.offset as usize..diagnostic.end.offset as usize;
+    assert_eq!(&source[range], "=");
+  }
+}
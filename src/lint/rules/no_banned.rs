@@ -0,0 +1,94 @@
+#[cfg(feature = "napi")]
+use napi_derive::napi;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  ast::Node,
+  lint::{LintContext, Rule},
+};
+
+/// 禁止使用的标签
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi", napi(object))]
+pub struct BannedTag {
+  /// 标签名，如 `web-view`
+  pub name: String,
+  /// 自定义的诊断信息，如说明替代方案
+  pub message: Option<String>,
+}
+
+/// 禁止使用的属性
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi", napi(object))]
+pub struct BannedAttribute {
+  /// 属性名，如 `open-type`
+  pub name: String,
+  /// 只禁止该静态值，如 `getUserInfo`；不设置时禁止整个属性
+  pub value: Option<String>,
+  /// 只检查该标签上的属性，不设置时检查所有元素
+  pub tag: Option<String>,
+  /// 自定义的诊断信息
+  pub message: Option<String>,
+}
+
+/// 按团队或企业的合规策略禁止指定的标签与属性
+///
+/// 限定了属性值时只比较静态值，动态值（含 `{{}}`）无法静态确定，不做检查
+#[derive(Debug, Clone, Default)]
+pub struct NoBanned {
+  pub tags: Vec<BannedTag>,
+  pub attributes: Vec<BannedAttribute>,
+}
+
+impl Rule for NoBanned {
+  fn name(&self) -> &'static str {
+    "no-banned"
+  }
+
+  fn code(&self) -> &'static str {
+    "WXML2301"
+  }
+
+  fn check_node(&self, node: &Node, ctx: &mut LintContext) {
+    let Node::Element {
+      name, attrs, start, ..
+    } = node
+    else {
+      return;
+    };
+    if let Some(tag) = self.tags.iter().find(|tag| tag.name == *name) {
+      let name_start = start.advance("<");
+      ctx.report(
+        tag
+          .message
+          .clone()
+          .unwrap_or_else(|| format!("<{name}> is banned by policy")),
+        name_start,
+        name_start.advance(name),
+      );
+    }
+    for attr in attrs {
+      let banned = self.attributes.iter().find(|banned| {
+        banned.name == attr.name
+          && banned.tag.as_ref().is_none_or(|tag| tag == name)
+          && banned
+            .value
+            .as_ref()
+            .is_none_or(|value| attr.as_static_string().as_ref() == Some(value))
+      });
+      let Some(banned) = banned else {
+        continue;
+      };
+      let message = banned
+        .message
+        .clone()
+        .unwrap_or_else(|| match &banned.value {
+          Some(value) => format!("`{}=\"{}\"` is banned by policy", attr.name, value),
+          None => format!("attribute `{}` is banned by policy", attr.name),
+        });
+      ctx.report(message, attr.start, attr.end);
+    }
+  }
+}
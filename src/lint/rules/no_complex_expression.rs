@@ -0,0 +1,298 @@
+use std::ops::Range;
+
+#[cfg(feature = "napi")]
+use napi_derive::napi;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  ast::{AttributeValue, Node, Position, Root},
+  dialect::Dialect,
+  expr::{parse_expression_with_spans, Expr, ObjectProperty},
+  lint::{LintContext, Rule, RuleScope},
+};
+
+/// 表达式复杂度的限制，未设置的项不检查
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+#[cfg_attr(feature = "napi", napi(object))]
+pub struct ExpressionLimits {
+  /// 单个表达式中运算符（一元、二元运算与三元运算）数量的上限
+  pub max_operators: Option<u32>,
+  /// 禁止函数调用，wxs 模块导出的函数除外
+  pub no_calls: Option<bool>,
+  /// 禁止赋值与自增、自减
+  pub no_assignment: Option<bool>,
+  /// 禁止 `new`
+  pub no_new: Option<bool>,
+}
+
+/// 限制绑定中的表达式复杂度，要求把逻辑放在页面代码或 wxs 模块中
+///
+/// 运算符与函数调用根据解析后的表达式检查；赋值、自增自减与 `new` 不属于表达式语法，按词法扫描检查。
+/// 语法树中的表达式内容已去除两侧空白，通过 [`Linter::lint_source`](crate::lint::Linter::lint_source)
+/// 检查时诊断信息定位到表达式内部，否则为整个表达式
+#[derive(Debug, Clone, Default)]
+pub struct NoComplexExpression {
+  pub limits: ExpressionLimits,
+}
+
+const CALL_HINT: &str = "only wxs module functions can be called";
+
+impl Rule for NoComplexExpression {
+  fn name(&self) -> &'static str {
+    "no-complex-expression"
+  }
+
+  fn code(&self) -> &'static str {
+    "WXML2304"
+  }
+
+  fn scope(&self) -> RuleScope {
+    RuleScope::Document
+  }
+
+  fn check_root(&self, root: &Root, ctx: &mut LintContext) {
+    let mut modules = Vec::new();
+    root.walk(&mut |node| {
+      if Dialect::ALL
+        .iter()
+        .any(|dialect| node.name() == Some(dialect.script_tag()))
+      {
+        if let Some(module) = node.attr("module").and_then(|attr| attr.as_static_string()) {
+          modules.push(module);
+        }
+      }
+    });
+    root.walk(&mut |node| match node {
+      Node::Expression {
+        content,
+        start,
+        end,
+      } => self.check_expression(content, *start, *end, &modules, ctx),
+      Node::Element { attrs, .. } => {
+        for value in attrs.iter().flat_map(|attr| attr.value.iter().flatten()) {
+          if let AttributeValue::Expression {
+            content,
+            start,
+            end,
+          } = value
+          {
+            self.check_expression(content, *start, *end, &modules, ctx);
+          }
+        }
+      }
+      _ => {}
+    });
+  }
+}
+
+impl NoComplexExpression {
+  fn check_expression(
+    &self,
+    content: &str,
+    start: Position,
+    end: Position,
+    modules: &[String],
+    ctx: &mut LintContext,
+  ) {
+    let base = ctx
+      .source()
+      .and_then(|source| content_start(source, content, start, end));
+    let mut report = |message: String, range: Range<usize>| match base {
+      Some(base) => {
+        let start = base.advance(&content[..range.start]);
+        ctx.report(message, start, start.advance(&content[range]));
+      }
+      None => ctx.report(message, start, end),
+    };
+    let limits = &self.limits;
+    for (syntax, range) in scan_syntax(content) {
+      match syntax {
+        Syntax::Assignment if limits.no_assignment == Some(true) => report(
+          format!(
+            "`{}` modifies data and is not allowed in bindings",
+            &content[range.clone()]
+          ),
+          range,
+        ),
+        Syntax::New if limits.no_new == Some(true) => {
+          report("`new` is not allowed in bindings".to_string(), range)
+        }
+        _ => {}
+      }
+    }
+    let Ok((expr, spans)) = parse_expression_with_spans(content) else {
+      return;
+    };
+    if let Some(max) = limits.max_operators {
+      if let Some(range) = spans.operators.get(max as usize) {
+        report(
+          format!(
+            "expression has {} operators, more than the maximum of {max}",
+            spans.operators.len()
+          ),
+          range.clone(),
+        );
+      }
+    }
+    if limits.no_calls == Some(true) {
+      let mut calls = Vec::new();
+      collect_calls(&expr, &mut calls);
+      for (callee, range) in calls.into_iter().zip(spans.calls) {
+        if root_ident(callee).is_some_and(|root| modules.iter().any(|module| module == root)) {
+          continue;
+        }
+        let message = match callee_path(callee) {
+          Some(path) => format!("call to `{path}` is not allowed in bindings, {CALL_HINT}"),
+          None => format!("function calls are not allowed in bindings, {CALL_HINT}"),
+        };
+        report(message, range);
+      }
+    }
+  }
+}
+
+/// 表达式内容在源码中的起始位置，`start..end` 包括 `{{ }}`，Vue 兼容模式下恰为内容本身；
+/// 源码与语法树不一致（如位置不按字节计算）时为 `None`
+fn content_start(source: &str, content: &str, start: Position, end: Position) -> Option<Position> {
+  let raw = source.get(start.offset as usize..end.offset as usize)?;
+  let (open, inner) = match raw
+    .strip_prefix("{{")
+    .and_then(|rest| rest.strip_suffix("}}"))
+  {
+    Some(inner) => (2, inner),
+    None => (0, raw),
+  };
+  if inner.trim() != content {
+    return None;
+  }
+  let leading = inner.len() - inner.trim_start().len();
+  Some(start.advance(&raw[..open + leading]))
+}
+
+/// 表达式语法之外、需要按词法识别的写法
+enum Syntax {
+  /// 赋值、复合赋值与自增自减
+  Assignment,
+  New,
+}
+
+/// 扫描表达式内容中的赋值与 `new`，跳过字符串字面量与模板字符串
+fn scan_syntax(content: &str) -> Vec<(Syntax, Range<usize>)> {
+  let bytes = content.as_bytes();
+  let mut found = Vec::new();
+  let mut pos = 0;
+  while pos < bytes.len() {
+    match bytes[pos] {
+      quote @ (b'"' | b'\'' | b'`') => {
+        pos += 1;
+        while pos < bytes.len() && bytes[pos] != quote {
+          pos += if bytes[pos] == b'\\' { 2 } else { 1 };
+        }
+        pos += 1;
+      }
+      b'=' => {
+        let run = bytes[pos..].iter().take_while(|b| **b == b'=').count();
+        let prev = pos.checked_sub(1).map(|index| bytes[index]);
+        // `==`、`===`、`!=`、`<=`、`>=` 与 `=>` 不是赋值
+        if run == 1
+          && bytes.get(pos + 1) != Some(&b'>')
+          && !matches!(prev, Some(b'!' | b'<' | b'>'))
+        {
+          let operator = bytes[..pos]
+            .iter()
+            .rev()
+            .take_while(|b| b"+-*/%&|?".contains(b))
+            .count();
+          found.push((Syntax::Assignment, pos - operator..pos + 1));
+        }
+        pos += run;
+      }
+      b'+' | b'-' if bytes.get(pos + 1) == Some(&bytes[pos]) => {
+        found.push((Syntax::Assignment, pos..pos + 2));
+        pos += 2;
+      }
+      byte if byte.is_ascii_alphanumeric() || byte == b'_' || byte == b'$' || byte >= 0x80 => {
+        let len = bytes[pos..]
+          .iter()
+          .take_while(|b| b.is_ascii_alphanumeric() || **b == b'_' || **b == b'$' || **b >= 0x80)
+          .count();
+        let is_property = content[..pos].trim_end().ends_with('.');
+        let next = content[pos + len..].trim_start().chars().next();
+        if &content[pos..pos + len] == "new"
+          && !is_property
+          && next.is_some_and(|ch| ch.is_alphabetic() || ch == '_' || ch == '$' || ch == '(')
+        {
+          found.push((Syntax::New, pos..pos + len));
+        }
+        pos += len;
+      }
+      _ => pos += 1,
+    }
+  }
+  found
+}
+
+/// 按后序遍历收集函数调用的被调用表达式，顺序与 [`ExprSpans::calls`](crate::expr::ExprSpans::calls) 一致
+fn collect_calls<'a>(expr: &'a Expr, out: &mut Vec<&'a Expr>) {
+  match expr {
+    Expr::Null
+    | Expr::Undefined
+    | Expr::Bool(_)
+    | Expr::Number(_)
+    | Expr::String(_)
+    | Expr::Ident(_) => {}
+    Expr::Array(items) => items.iter().for_each(|item| collect_calls(item, out)),
+    Expr::Object(properties) => {
+      for property in properties {
+        match property {
+          ObjectProperty::Property { value, .. } | ObjectProperty::Spread(value) => {
+            collect_calls(value, out)
+          }
+        }
+      }
+    }
+    Expr::Member { object, .. } => collect_calls(object, out),
+    Expr::Index { object, index } => {
+      collect_calls(object, out);
+      collect_calls(index, out);
+    }
+    Expr::Call { callee, args } => {
+      collect_calls(callee, out);
+      args.iter().for_each(|arg| collect_calls(arg, out));
+      out.push(callee);
+    }
+    Expr::Unary { argument, .. } | Expr::Spread(argument) => collect_calls(argument, out),
+    Expr::Binary { left, right, .. } => {
+      collect_calls(left, out);
+      collect_calls(right, out);
+    }
+    Expr::Conditional {
+      test,
+      consequent,
+      alternate,
+    } => {
+      collect_calls(test, out);
+      collect_calls(consequent, out);
+      collect_calls(alternate, out);
+    }
+  }
+}
+
+/// 成员访问链开头的变量
+fn root_ident(expr: &Expr) -> Option<&str> {
+  match expr {
+    Expr::Ident(name) => Some(name),
+    Expr::Member { object, .. } | Expr::Index { object, .. } => root_ident(object),
+    _ => None,
+  }
+}
+
+/// 变量与静态成员访问组成的路径，如 `utils.format`
+fn callee_path(expr: &Expr) -> Option<String> {
+  match expr {
+    Expr::Ident(name) => Some(name.clone()),
+    Expr::Member { object, property } => Some(format!("{}.{property}", callee_path(object)?)),
+    _ => None,
+  }
+}
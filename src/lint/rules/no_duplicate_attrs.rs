@@ -0,0 +1,35 @@
+use std::collections::HashSet;
+
+use crate::{
+  ast::Node,
+  lint::{LintContext, Rule},
+};
+
+/// 禁止同一元素上出现重复的属性
+pub struct NoDuplicateAttrs;
+
+impl Rule for NoDuplicateAttrs {
+  fn name(&self) -> &'static str {
+    "no-duplicate-attrs"
+  }
+
+  fn code(&self) -> &'static str {
+    "WXML2001"
+  }
+
+  fn check_node(&self, node: &Node, ctx: &mut LintContext) {
+    let Some(attrs) = node.attrs() else {
+      return;
+    };
+    let mut seen = HashSet::new();
+    for attr in attrs {
+      if !seen.insert(attr.name.as_str()) {
+        ctx.report(
+          format!("duplicate attribute `{}`", attr.name),
+          attr.start,
+          attr.end,
+        );
+      }
+    }
+  }
+}
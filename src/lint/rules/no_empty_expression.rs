@@ -0,0 +1,44 @@
+use crate::{
+  ast::{AttributeValue, Node},
+  lint::{LintContext, Rule},
+};
+
+/// 禁止空的插值表达式 `{{ }}`
+pub struct NoEmptyExpression;
+
+const MESSAGE: &str = "empty mustache expression";
+
+impl Rule for NoEmptyExpression {
+  fn name(&self) -> &'static str {
+    "no-empty-expression"
+  }
+
+  fn code(&self) -> &'static str {
+    "WXML2002"
+  }
+
+  fn check_node(&self, node: &Node, ctx: &mut LintContext) {
+    match node {
+      Node::Expression {
+        content,
+        start,
+        end,
+      } if content.is_empty() => ctx.report(MESSAGE, *start, *end),
+      Node::Element { attrs, .. } => {
+        for value in attrs.iter().flat_map(|attr| attr.value.iter().flatten()) {
+          if let AttributeValue::Expression {
+            content,
+            start,
+            end,
+          } = value
+          {
+            if content.is_empty() {
+              ctx.report(MESSAGE, *start, *end);
+            }
+          }
+        }
+      }
+      _ => {}
+    }
+  }
+}
@@ -0,0 +1,75 @@
+use crate::{
+  ast::{Node, Root},
+  dialect::{Dialect, Directive},
+  lint::{LintContext, Rule, RuleScope},
+};
+
+/// 要求文档只有一个根元素，适用于希望组件模板只有单个根节点的团队
+///
+/// 页面模板通常允许多个根节点，因此默认不启用。不渲染内容的 `<wxs>`、`<import>`、
+/// `<template name>` 不计入根元素；`wx:elif`、`wx:else` 与前面的 `wx:if` 只渲染其一，合计为一个根元素；
+/// `<block>` 本身不渲染，按其中的元素计算
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SingleRoot;
+
+impl Rule for SingleRoot {
+  fn name(&self) -> &'static str {
+    "single-root"
+  }
+
+  fn code(&self) -> &'static str {
+    "WXML2302"
+  }
+
+  fn scope(&self) -> RuleScope {
+    RuleScope::Document
+  }
+
+  fn check_root(&self, root: &Root, ctx: &mut LintContext) {
+    let mut roots = Vec::new();
+    collect_roots(&root.children, &mut roots);
+    for node in roots.into_iter().skip(1) {
+      let Node::Element { name, start, .. } = node else {
+        continue;
+      };
+      let name_start = start.advance("<");
+      ctx.report(
+        format!("template must have a single root element, found another <{name}>"),
+        name_start,
+        name_start.advance(name),
+      );
+    }
+  }
+}
+
+/// 收集会渲染的根元素，`<block>` 不渲染自身，按其中的元素计算
+fn collect_roots<'a>(nodes: &'a [Node], roots: &mut Vec<&'a Node>) {
+  for node in nodes {
+    let Node::Element { children, .. } = node else {
+      continue;
+    };
+    let branch = [Directive::Elif, Directive::Else]
+      .into_iter()
+      .any(|directive| node.directive(directive).is_some());
+    if branch || !renders(node) {
+      continue;
+    }
+    if node.is_block() {
+      collect_roots(children, roots);
+    } else {
+      roots.push(node);
+    }
+  }
+}
+
+/// 元素是否会渲染内容
+fn renders(node: &Node) -> bool {
+  match node.name() {
+    Some("template") => node.attr("name").is_none(),
+    Some("import") => false,
+    Some(name) => !Dialect::ALL
+      .iter()
+      .any(|dialect| dialect.script_tag() == name),
+    None => false,
+  }
+}
@@ -0,0 +1,42 @@
+use crate::{
+  ast::Node,
+  dialect::Directive,
+  lint::{LintContext, Rule},
+};
+
+/// `<block>` 本身不渲染任何内容，只有条件、循环等指令对其生效，其他属性（如 `class`、`bindtap`）会被忽略
+///
+/// 允许 `wx:` 开头的属性与其他方言的指令；`slot` 用于把一组节点整体放入具名插槽，同样允许
+pub struct ValidBlockAttrs;
+
+impl Rule for ValidBlockAttrs {
+  fn name(&self) -> &'static str {
+    "valid-block-attrs"
+  }
+
+  fn code(&self) -> &'static str {
+    "WXML2005"
+  }
+
+  fn check_node(&self, node: &Node, ctx: &mut LintContext) {
+    if !node.is_block() {
+      return;
+    }
+    for attr in node.attrs().unwrap_or_default() {
+      if attr.prefix.as_deref() == Some("wx")
+        || Directive::of(&attr.name).is_some()
+        || attr.name == "slot"
+      {
+        continue;
+      }
+      ctx.report(
+        format!(
+          "attribute `{}` has no effect on <block>, which renders nothing",
+          attr.name
+        ),
+        attr.start,
+        attr.end,
+      );
+    }
+  }
+}
@@ -0,0 +1,65 @@
+use crate::{
+  ast::Node,
+  builtins::{component_spec, is_common_attr},
+  lint::{LintContext, Rule},
+};
+
+/// 校验内置组件的属性名称以及静态属性值
+///
+/// 只检查目录中收录的组件；动态值（含 `{{}}`）无法静态确定，不做校验
+pub struct ValidBuiltinAttrs;
+
+impl Rule for ValidBuiltinAttrs {
+  fn name(&self) -> &'static str {
+    "valid-builtin-attrs"
+  }
+
+  fn code(&self) -> &'static str {
+    "WXML2003"
+  }
+
+  fn check_node(&self, node: &Node, ctx: &mut LintContext) {
+    let Node::Element { name, attrs, .. } = node else {
+      return;
+    };
+    let Some(spec) = component_spec(name) else {
+      return;
+    };
+    for attr in attrs {
+      if is_common_attr(&attr.name) {
+        continue;
+      }
+      // 简易双向绑定 `model:value` 校验其绑定的属性
+      let attr_name = match attr.prefix.as_deref() {
+        Some("model") => &attr.local,
+        _ => &attr.name,
+      };
+      let Some(attr_spec) = spec.attr(attr_name) else {
+        ctx.report(
+          format!("unknown attribute `{}` on <{}>", attr.name, name),
+          attr.start,
+          attr.end,
+        );
+        continue;
+      };
+      if attr_spec.values.is_empty() {
+        continue;
+      }
+      if let Some(value) = attr.as_static_string() {
+        if !attr_spec.values.contains(&value.as_str()) {
+          ctx.report(
+            format!(
+              "invalid value `{}` for `{}` on <{}>, expected one of: {}",
+              value,
+              attr.name,
+              name,
+              attr_spec.values.join(", ")
+            ),
+            attr.start,
+            attr.end,
+          );
+        }
+      }
+    }
+  }
+}
@@ -0,0 +1,68 @@
+use crate::{
+  ast::{Node, Root},
+  dialect::{Dialect, Directive},
+  lint::{LintContext, Rule, RuleScope},
+};
+
+/// `wx:elif`、`wx:else` 必须紧跟在带有 `wx:if` 或 `wx:elif` 的兄弟元素之后，
+/// 中间只允许出现空白与注释。其他方言的 `s-if`、`tt:if` 等同样适用
+pub struct ValidConditionalChain;
+
+impl Rule for ValidConditionalChain {
+  fn name(&self) -> &'static str {
+    "valid-conditional-chain"
+  }
+
+  fn code(&self) -> &'static str {
+    "WXML2004"
+  }
+
+  fn scope(&self) -> RuleScope {
+    RuleScope::Document
+  }
+
+  fn check_root(&self, root: &Root, ctx: &mut LintContext) {
+    check_siblings(&root.children, ctx);
+  }
+}
+
+fn check_siblings(nodes: &[Node], ctx: &mut LintContext) {
+  // 前一个有效兄弟节点是否可以接续 `wx:elif`/`wx:else`
+  let mut in_chain = false;
+  for node in nodes {
+    match node {
+      Node::Comment { .. } => continue,
+      Node::Text { content, .. } if content.trim().is_empty() => continue,
+      Node::Element { children, .. } => {
+        check_siblings(children, ctx);
+        let has_if = node.directive(Directive::If).is_some();
+        let branch = [Directive::Elif, Directive::Else]
+          .into_iter()
+          .find_map(|directive| node.directive(directive));
+        let dialect = branch
+          .and_then(|attr| Directive::of(&attr.name))
+          .map_or(Dialect::Wxml, |(dialect, _)| dialect);
+        let if_name = dialect.directive_name(Directive::If);
+        let elif_name = dialect.directive_name(Directive::Elif);
+        match branch {
+          Some(attr) if has_if => ctx.report(
+            format!("`{}` cannot be used together with `{if_name}`", attr.name),
+            attr.start,
+            attr.end,
+          ),
+          Some(attr) if !in_chain => ctx.report(
+            format!(
+              "`{}` must directly follow an element with `{if_name}` or `{elif_name}`",
+              attr.name
+            ),
+            attr.start,
+            attr.end,
+          ),
+          _ => {}
+        }
+        in_chain = has_if || branch.is_some_and(|attr| attr.name == elif_name);
+      }
+      _ => in_chain = false,
+    }
+  }
+}
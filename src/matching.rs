@@ -0,0 +1,181 @@
+//! 标签配对与自动闭合
+//!
+//! 编辑器插件常用的两个辅助功能：光标位于开始或结束标签上时，找到与之配对的另一个标签，
+//! 用于高亮配对标签与同步重命名；输入开始标签的 `>` 或结束标签的 `</` 时，给出需要插入的结束标签
+
+#[cfg(feature = "napi")]
+use napi_derive::napi;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  ast::{Node, Position, Root},
+  completion::{completion_context, CompletionKind},
+  encoding::PositionEncoding,
+  tree::TreeIndex,
+};
+
+/// 一个元素的开始标签与结束标签中标签名的位置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi", napi(object))]
+pub struct TagMatch {
+  pub name: String,
+  /// 开始标签中标签名的范围
+  pub open_start: Position,
+  pub open_end: Position,
+  /// 结束标签中标签名的范围，自闭合元素没有结束标签
+  pub close_start: Option<Position>,
+  pub close_end: Option<Position>,
+}
+
+/// 查找 `offset` 所在的开始或结束标签，返回该元素两个标签中标签名的位置
+///
+/// 光标位于 `<` 或 `</` 与标签名末尾之间时视为位于标签上，合成节点与不在标签上的位置返回 `None`。
+/// `encoding` 为解析 `root` 时使用的 [`ParserOptions::position_encoding`](crate::options::ParserOptions::position_encoding)，
+/// `offset` 与返回的位置按该编码计算
+pub fn matching_tag(
+  root: &Root,
+  offset: u32,
+  encoding: Option<PositionEncoding>,
+) -> Option<TagMatch> {
+  let tree = TreeIndex::new(root);
+  let node = tree.node(tree.node_at(offset)?);
+  let Node::Element {
+    name,
+    close_start,
+    close_end,
+    start,
+    ..
+  } = node
+  else {
+    return None;
+  };
+  let open_start = start.advance("<");
+  let open_end = match encoding {
+    None => open_start.advance(name),
+    // 标签名不含换行，按编码计算长度即可
+    Some(encoding) => Position {
+      offset: open_start.offset + encoding.len(name),
+      column: open_start.column + encoding.len(name),
+      ..open_start
+    },
+  };
+  let on_open = start.offset <= offset && offset <= open_end.offset;
+  let on_close = close_start
+    .zip(*close_end)
+    .is_some_and(|(close_start, close_end)| {
+      close_start.offset.saturating_sub(2) <= offset && offset <= close_end.offset
+    });
+  if !on_open && !on_close {
+    return None;
+  }
+  Some(TagMatch {
+    name: name.clone(),
+    open_start,
+    open_end,
+    close_start: *close_start,
+    close_end: *close_end,
+  })
+}
+
+/// 光标位于 `offset`（字节偏移）时建议在光标处插入的内容，光标应保持在插入内容之前
+///
+/// - 刚输入开始标签的 `>`，如 `<view class="a">|`：插入 `</view>`；之后已经是该元素的结束标签时不插入
+/// - 正在输入结束标签，如 `<view></|` 或 `<view></vi|`：补全最内层未闭合元素的标签名与 `>`
+pub fn auto_close_hint(source: &str, offset: u32) -> Option<String> {
+  let context = completion_context(source, offset);
+  let parent = context.parent?;
+  match context.kind {
+    CompletionKind::CloseTag => {
+      let rest = parent.strip_prefix(context.prefix.as_str())?;
+      Some(format!("{rest}>"))
+    }
+    CompletionKind::Text => {
+      // 光标前的 `>` 结束了一个开始标签，且该元素正是最内层的未闭合元素
+      let before = source.get(..context.start as usize)?.strip_suffix('>')?;
+      let tag = completion_context(before, before.len() as u32);
+      let element = match tag.kind {
+        CompletionKind::TagName => tag.prefix,
+        CompletionKind::AttributeName { element, .. }
+        | CompletionKind::AttributeValue { element, .. } => element,
+        _ => return None,
+      };
+      if element != parent || before.trim_end().ends_with('/') {
+        return None;
+      }
+      let close = format!("</{parent}");
+      let after = &source[context.start as usize..];
+      (!after.trim_start().starts_with(&close)).then(|| format!("{close}>"))
+    }
+    _ => None,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{options::ParserOptions, parser::Parser};
+
+  #[test]
+  fn tag_matching_and_auto_close() {
+    let source = "<view>\n  <text>a</text>\n  <image/>\n</view>";
+    let root = Parser::new(source).parse_root().unwrap();
+    let text = matching_tag(&root, 12, None).unwrap();
+    assert_eq!(text.name, "text");
+    assert_eq!((text.open_start.offset, text.open_end.offset), (10, 14));
+    assert_eq!(text.close_start.map(|p| p.offset), Some(18));
+    assert_eq!(matching_tag(&root, 10, None), matching_tag(&root, 20, None));
+    let view = matching_tag(&root, source.len() as u32 - 2, None).unwrap();
+    assert_eq!(view.open_start.offset, 1);
+    assert_eq!(view.close_start.map(|p| (p.line, p.column)), Some((4, 3)));
+    assert_eq!(matching_tag(&root, 29, None).unwrap().close_start, None);
+    assert_eq!(matching_tag(&root, 15, None), None);
+
+    let hint = |source: &str| auto_close_hint(source, source.len() as u32);
+    assert_eq!(
+      hint(r#"<view><button type="a>b">"#).as_deref(),
+      Some("</button>")
+    );
+    assert_eq!(hint("<view><text>").as_deref(), Some("</text>"));
+    assert_eq!(hint("<view><image/>"), None);
+    assert_eq!(hint("<view><text>a</text>"), None);
+    assert_eq!(hint("<view><text></te").as_deref(), Some("xt>"));
+    assert_eq!(hint("<view><text></").as_deref(), Some("text>"));
+    assert_eq!(auto_close_hint("<view><text></text></view>", 12), None);
+  }
+
+  #[test]
+  fn close_tag_with_whitespace_and_encoding() {
+    let source = "<view>\n  <text>a</text  >\n</view\n>";
+    let root = Parser::new(source).parse_root().unwrap();
+    let text = matching_tag(&root, 12, None).unwrap();
+    assert_eq!(
+      (
+        text.close_start.unwrap().offset,
+        text.close_end.unwrap().offset
+      ),
+      (18, 22)
+    );
+    let view = matching_tag(&root, 1, None).unwrap();
+    let close = view.close_start.unwrap();
+    assert_eq!((close.offset, close.line, close.column), (28, 3, 3));
+    assert_eq!(matching_tag(&root, 31, None).unwrap().name, "view");
+
+    // 按 UTF-16 计算位置时，`😀` 占两个码元
+    let source = "<view>😀</view>";
+    let options = ParserOptions {
+      position_encoding: Some(PositionEncoding::Utf16),
+      ..Default::default()
+    };
+    let root = Parser::with_options(source, options).parse_root().unwrap();
+    let view = matching_tag(&root, 11, Some(PositionEncoding::Utf16)).unwrap();
+    assert_eq!(view.open_end.offset, 5);
+    assert_eq!(
+      (
+        view.close_start.unwrap().offset,
+        view.close_end.unwrap().offset
+      ),
+      (10, 14)
+    );
+  }
+}
@@ -0,0 +1,340 @@
+//! 模板优化
+//!
+//! 作用于语法树的优化变换，供构建工具在输出模板前使用，减少运行时需要计算的绑定
+
+use serde_json::{Map, Value};
+
+use crate::{
+  ast::{Attribute, AttributeValue, Node, Root},
+  dialect::Directive,
+  expr::{parse_expression, to_display_string, truthy, Expr, ObjectProperty, Sandbox},
+};
+
+/// 常量折叠：把不引用任何变量的表达式（如 `{{ 1 + 2 }}`、`{{ 'a' + 'b' }}`）替换为静态文本，
+/// 返回折叠的表达式个数。折叠后的节点保留原表达式的位置
+///
+/// 只折叠不改变运行时语义的表达式：
+///
+/// - 文本中的表达式结果为字符串、数字、布尔值或 `null`/`undefined`（显示为空）时折叠
+/// - 属性值只有一个表达式时，结果会原样传给组件，`hidden="{{false}}"` 与 `hidden="false"` 并不等价，
+///   因此只折叠结果为字符串的表达式；与静态文本拼接的表达式结果总是字符串，均可折叠
+/// - 结果包含 `{{`、文本中包含 `<`，或属性值同时包含单双引号时不折叠，避免输出后被解析为其他内容
+pub fn fold_constants(root: &mut Root) -> usize {
+  let mut folded = 0;
+  for node in &mut root.children {
+    node.walk_mut(&mut |node| match node {
+      Node::Expression {
+        content,
+        start,
+        end,
+      } => {
+        if let Some(text) = fold_text(content) {
+          *node = Node::Text {
+            content: text,
+            start: *start,
+            end: *end,
+          };
+          folded += 1;
+        }
+      }
+      Node::Element { attrs, .. } => {
+        for attr in attrs {
+          let Some(values) = &mut attr.value else {
+            continue;
+          };
+          let single = values.len() == 1;
+          for value in values.iter_mut() {
+            let AttributeValue::Expression {
+              content,
+              start,
+              end,
+            } = value
+            else {
+              continue;
+            };
+            if let Some(text) = fold_attribute_part(content, single) {
+              *value = AttributeValue::Text {
+                content: text,
+                start: *start,
+                end: *end,
+              };
+              folded += 1;
+            }
+          }
+        }
+      }
+      _ => {}
+    });
+  }
+  folded
+}
+
+/// 文本中的 `{{ content }}` 可以折叠时返回显示的文本
+pub(crate) fn fold_text(content: &str) -> Option<String> {
+  let text = match constant_value(content)? {
+    Value::Array(_) | Value::Object(_) => return None,
+    value => to_display_string(&value),
+  };
+  (!text.contains("{{") && !text.contains('<')).then_some(text)
+}
+
+/// 属性值中的 `{{ content }}` 可以折叠时返回静态文本，`single` 表示属性值只有这一个表达式
+pub(crate) fn fold_attribute_part(content: &str, single: bool) -> Option<String> {
+  let text = match constant_value(content)? {
+    Value::String(text) => text,
+    Value::Array(_) | Value::Object(_) => return None,
+    _ if single => return None,
+    value => to_display_string(&value),
+  };
+  let mixed_quotes = text.contains('"') && text.contains('\'');
+  (!text.contains("{{") && !mixed_quotes).then_some(text)
+}
+
+/// 不引用任何变量的表达式的值，求值失败时返回 `None`
+pub(crate) fn constant_value(content: &str) -> Option<Value> {
+  let expr = parse_expression(content).ok()?;
+  // 常量表达式中的函数调用只能是常量上的内置方法，如 `'a,b'.split(',')`
+  if !only_references(&expr, &Map::new()) {
+    return None;
+  }
+  Sandbox::default().evaluate_with(&expr, &|_| None).ok()
+}
+
+/// 条件分支在编译期的取值
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Branch {
+  /// 条件恒为真，或为 `wx:else`
+  Taken,
+  /// 条件恒为假
+  Dead,
+  /// 依赖运行时数据
+  Unknown,
+}
+
+/// 兄弟节点中条件链的状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Chain {
+  /// 不在条件链中
+  Outside,
+  /// 在条件链中，`kept` 表示之前是否保留了条件未知的分支
+  Open { kept: bool },
+  /// 条件链中已有恒为真的分支，之后的分支都不会被执行
+  Resolved,
+}
+
+/// 死分支消除：使用编译期常量（如 `{"PLATFORM": "weapp"}`）计算 `wx:if`/`wx:elif` 的条件，
+/// 删除不会被执行的分支及其子树，返回删除的分支个数。其他方言的 `s-if`、`tt:if` 等同样适用
+///
+/// 只有条件中引用的变量全部是常量时才计算，否则保留分支。链中条件恒为真的分支去掉条件，
+/// 前面没有保留的分支时成为普通元素（没有其他属性的 `<block>` 直接展开为子节点），
+/// 否则改写为 `wx:else`；`wx:if` 被删除后，第一个保留的 `wx:elif` 改写为 `wx:if`
+pub fn eliminate_dead_branches(root: &mut Root, constants: &Map<String, Value>) -> usize {
+  prune(&mut root.children, constants)
+}
+
+fn prune(nodes: &mut Vec<Node>, constants: &Map<String, Value>) -> usize {
+  let mut removed = 0;
+  let mut chain = Chain::Outside;
+  for mut node in std::mem::take(nodes) {
+    let directive = [Directive::If, Directive::Elif, Directive::Else]
+      .into_iter()
+      .find(|directive| node.directive(*directive).is_some());
+    let Some(directive) = directive else {
+      // 空白与注释不会打断条件链
+      let blank = match &node {
+        Node::Comment { .. } => true,
+        Node::Text { content, .. } => content.trim().is_empty(),
+        _ => false,
+      };
+      if !blank {
+        chain = Chain::Outside;
+      }
+      removed += push(nodes, node, constants, false);
+      continue;
+    };
+    let kept = match (directive, chain) {
+      (Directive::If, _) => false,
+      (_, Chain::Open { kept }) => kept,
+      (_, Chain::Resolved) => {
+        removed += 1;
+        continue;
+      }
+      // 不在条件链中的 `wx:elif`/`wx:else` 保持原样
+      (_, Chain::Outside) => {
+        removed += push(nodes, node, constants, false);
+        continue;
+      }
+    };
+    match branch(&node, directive, constants) {
+      Branch::Dead => {
+        removed += 1;
+        chain = Chain::Open { kept };
+      }
+      Branch::Taken => {
+        let attrs = node.attrs_mut().unwrap();
+        let index = directive_position(attrs, directive);
+        if kept {
+          let (dialect, _) = Directive::of(&attrs[index].name).unwrap();
+          attrs[index].rename(dialect.directive_name(Directive::Else));
+          attrs[index].value = None;
+        } else {
+          attrs.remove(index);
+        }
+        removed += push(nodes, node, constants, !kept);
+        chain = Chain::Resolved;
+      }
+      Branch::Unknown => {
+        if !kept && directive == Directive::Elif {
+          let attrs = node.attrs_mut().unwrap();
+          let index = directive_position(attrs, directive);
+          let attr = &mut attrs[index];
+          let (dialect, _) = Directive::of(&attr.name).unwrap();
+          attr.rename(dialect.directive_name(Directive::If));
+        }
+        removed += push(nodes, node, constants, false);
+        chain = Chain::Open { kept: true };
+      }
+    }
+  }
+  removed
+}
+
+/// 元素中表示 `directive` 的属性的下标，调用方须确保属性存在
+fn directive_position(attrs: &[Attribute], directive: Directive) -> usize {
+  attrs
+    .iter()
+    .position(|attr| Directive::of(&attr.name).is_some_and(|(_, found)| found == directive))
+    .unwrap()
+}
+
+/// 处理子节点后加入列表，`unwrap` 为真时展开没有属性的 `<block>`
+fn push(
+  nodes: &mut Vec<Node>,
+  mut node: Node,
+  constants: &Map<String, Value>,
+  unwrap: bool,
+) -> usize {
+  let Node::Element {
+    name,
+    attrs,
+    children,
+    ..
+  } = &mut node
+  else {
+    nodes.push(node);
+    return 0;
+  };
+  let removed = prune(children, constants);
+  if unwrap && name == "block" && attrs.is_empty() {
+    nodes.append(children);
+  } else {
+    nodes.push(node);
+  }
+  removed
+}
+
+fn branch(node: &Node, directive: Directive, constants: &Map<String, Value>) -> Branch {
+  if directive == Directive::Else {
+    return Branch::Taken;
+  }
+  let Some(attr) = node.directive(directive) else {
+    return Branch::Unknown;
+  };
+  let value = match attr.value.as_deref() {
+    Some([AttributeValue::Expression { content, .. }]) => {
+      let Ok(expr) = parse_expression(content) else {
+        return Branch::Unknown;
+      };
+      if !only_references(&expr, constants) {
+        return Branch::Unknown;
+      }
+      match Sandbox::default().evaluate_with(&expr, &|name| constants.get(name).cloned()) {
+        Ok(value) => truthy(&value),
+        Err(_) => return Branch::Unknown,
+      }
+    }
+    Some(_) => match attr.as_static_string() {
+      Some(text) => !text.is_empty(),
+      None => return Branch::Unknown,
+    },
+    None => return Branch::Unknown,
+  };
+  if value {
+    Branch::Taken
+  } else {
+    Branch::Dead
+  }
+}
+
+/// 表达式中引用的变量是否都在 `constants` 中
+fn only_references(expr: &Expr, constants: &Map<String, Value>) -> bool {
+  let check = |expr| only_references(expr, constants);
+  match expr {
+    Expr::Null | Expr::Undefined | Expr::Bool(_) | Expr::Number(_) | Expr::String(_) => true,
+    Expr::Ident(name) => constants.contains_key(name),
+    Expr::Array(items) => items.iter().all(check),
+    Expr::Object(properties) => properties.iter().all(|property| match property {
+      ObjectProperty::Property { value, .. } | ObjectProperty::Spread(value) => check(value),
+    }),
+    Expr::Member { object, .. } => check(object),
+    Expr::Index { object, index } => check(object) && check(index),
+    Expr::Call { callee, args } => check(callee) && args.iter().all(check),
+    Expr::Unary { argument, .. } | Expr::Spread(argument) => check(argument),
+    Expr::Binary { left, right, .. } => check(left) && check(right),
+    Expr::Conditional {
+      test,
+      consequent,
+      alternate,
+    } => check(test) && check(consequent) && check(alternate),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    codegen::{generate, CodegenOptions},
+    parser::Parser,
+  };
+
+  #[test]
+  fn constant_folding() {
+    let source = r#"<view class="a-{{ 1 + 2 }} {{ on ? 'x' : '' }}" title="{{ 'a' + 'b' }}" hidden="{{ false }}" data-n="{{ 2 * 3 }}">{{ 'hi'.toUpperCase() }}-{{ n + 1 }}-{{ '<b>' }}{{ null }}</view>"#;
+    let mut root = Parser::new(source).parse_root().unwrap();
+    assert_eq!(fold_constants(&mut root), 4);
+    assert_eq!(
+      generate(&root, &CodegenOptions::default()),
+      r#"<view class="a-3 {{on ? 'x' : ''}}" title="ab" hidden="{{false}}" data-n="{{2 * 3}}">HI-{{n + 1}}-{{'<b>'}}</view>"#
+    );
+    let output = CodegenOptions {
+      fold_constants: true,
+      ..Default::default()
+    };
+    let root = Parser::new(source).parse_root().unwrap();
+    assert_eq!(
+      generate(&root, &output),
+      r#"<view class="a-3 {{on ? 'x' : ''}}" title="ab" hidden="{{false}}" data-n="{{2 * 3}}">HI-{{n + 1}}-{{'<b>'}}</view>"#
+    );
+  }
+
+  #[test]
+  fn dead_branches() {
+    let source = r#"<view wx:if="{{ PLATFORM === 'alipay' }}">a</view>
+<view wx:elif="{{ show }}">b</view>
+<!-- c -->
+<view wx:elif="{{ PLATFORM === 'weapp' }}" class="w">c</view>
+<view wx:else>d</view>
+<block s-if="{{ DEBUG }}"><text>debug</text></block>
+<block s-else><text tt:if="{{ !DEBUG }}">release</text><text tt:else>x</text></block>
+<view wx:if="{{ PLATFORM == 'swan' }}">e</view>
+<view wx:elif="{{ items.length }}">f</view>"#;
+    let mut root = Parser::new(source).parse_root().unwrap();
+    let constants = serde_json::json!({ "PLATFORM": "weapp", "DEBUG": false });
+    let removed = eliminate_dead_branches(&mut root, constants.as_object().unwrap());
+    assert_eq!(removed, 5);
+    assert_eq!(
+      generate(&root, &CodegenOptions::default()),
+      r#"<view wx:if="{{show}}">b</view><!-- c --><view wx:else class="w">c</view><text>release</text><view wx:if="{{items.length}}">f</view>"#
+    );
+  }
+}
@@ -0,0 +1,105 @@
+//! 解析选项
+
+#[cfg(feature = "napi")]
+use napi_derive::napi;
+use serde::{Deserialize, Serialize};
+
+use crate::{dialect::Dialect, encoding::PositionEncoding};
+
+/// 解析器选项，所有字段均为可选，未设置时使用默认行为
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+#[cfg_attr(feature = "napi", napi(object))]
+pub struct ParserOptions {
+  /// 宽松模式，容忍实际项目中常见的不规范写法，如不带引号的属性值 `data-index=1`
+  pub lenient: Option<bool>,
+  /// 模板方言，决定哪个内联脚本标签按原始文本解析，默认为 [`Dialect::Wxml`]
+  pub dialect: Option<Dialect>,
+  /// 额外的原始文本标签，其内容不作为标记解析，如 `["markdown"]`；
+  /// `wxs` 与方言的内联脚本标签始终是原始文本标签
+  pub raw_text_tags: Option<Vec<String>>,
+  /// Vue 兼容模式，`v-if`、`:prop`、`@event` 等属性带引号的值整体作为一个表达式，见 [`vue`](crate::vue)
+  pub vue: Option<bool>,
+  /// 节点之间只包含空白的文本如何表示，默认为 [`WhitespaceMode::Drop`]
+  pub whitespace: Option<WhitespaceMode>,
+  /// 元素的最大嵌套层数，超出时报告 `ResourceLimitExceeded` 错误，默认不限制
+  ///
+  /// 解析本身不会因嵌套过深而栈溢出，但遍历、格式化以及释放 AST 仍按层级递归，处理不可信输入时建议设置
+  pub max_depth: Option<u32>,
+  /// 单个元素的最大属性数量，默认不限制
+  pub max_attrs: Option<u32>,
+  /// 源码的最大长度（字节），默认不限制
+  pub max_source_length: Option<u32>,
+  /// 在 [`Root::stats`](crate::ast::Root::stats) 中附带节点数量、嵌套深度与解析耗时，默认关闭
+  pub stats: Option<bool>,
+  /// 位置的计算单位，默认 `offset` 按字节、`column` 按字符计算，见 [`encoding`](crate::encoding)
+  ///
+  /// 设置后 `offset` 不再是字节偏移，不能再用于切分 Rust 字符串；适合直接交给编辑器或 JS 使用
+  pub position_encoding: Option<PositionEncoding>,
+  /// 把注释从兄弟节点中移出，附着到相邻的节点上并记录在 [`Root::comments`](crate::ast::Root::comments) 中，
+  /// 注释指令与条件编译标记仍保留在树中，见 [`comments`](crate::comments)，默认关闭
+  pub attach_comments: Option<bool>,
+}
+
+/// 节点之间只含空白的文本的处理方式
+///
+/// 只影响后面紧跟标签、注释或 `{{` 的空白，如 `<view/>\n  <text/>`、`{{a}} {{b}}`；
+/// 除 [`WhitespaceMode::Preserve`] 外，结束标签与文件结尾之前的空白总是被丢弃。
+/// 包含非空白字符的文本总是原样保留，包括首尾空白
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "napi", napi(string_enum = "lowercase"))]
+#[serde(rename_all = "lowercase")]
+pub enum WhitespaceMode {
+  /// 丢弃，不产生节点
+  #[default]
+  Drop,
+  /// 原样保留为文本节点
+  Keep,
+  /// 保留为内容是单个空格的文本节点，与 WXML 渲染时空白折叠的效果一致
+  Collapse,
+  /// 原样保留所有空白，包括结束标签与文件结尾之前的空白，是否有意义由使用方决定
+  Preserve,
+}
+
+impl ParserOptions {
+  pub(crate) fn is_lenient(&self) -> bool {
+    self.lenient.unwrap_or(false)
+  }
+
+  pub(crate) fn is_vue(&self) -> bool {
+    self.vue.unwrap_or(false)
+  }
+
+  pub(crate) fn attaches_comments(&self) -> bool {
+    self.attach_comments.unwrap_or(false)
+  }
+
+  pub(crate) fn collects_stats(&self) -> bool {
+    self.stats.unwrap_or(false)
+  }
+
+  pub(crate) fn whitespace_mode(&self) -> WhitespaceMode {
+    self.whitespace.unwrap_or_default()
+  }
+
+  pub(crate) fn exceeds_depth(&self, depth: u32) -> bool {
+    self.max_depth.is_some_and(|max| depth > max)
+  }
+
+  pub(crate) fn exceeds_attrs(&self, count: usize) -> bool {
+    self.max_attrs.is_some_and(|max| count > max as usize)
+  }
+
+  pub(crate) fn exceeds_source_length(&self, len: usize) -> bool {
+    self.max_source_length.is_some_and(|max| len > max as usize)
+  }
+
+  pub(crate) fn is_raw_text_tag(&self, name: &str) -> bool {
+    name == "wxs"
+      || name == self.dialect.unwrap_or_default().script_tag()
+      || self
+        .raw_text_tags
+        .as_ref()
+        .is_some_and(|tags| tags.iter().any(|tag| tag == name))
+  }
+}
@@ -2,9 +2,15 @@ use std::vec;
 
 use crate::{
   ast::*,
-  error::{SyntaxError, SyntaxErrorKind},
+  comments::attach_comments,
+  encoding::Encoder,
+  error::{OpenTag, SyntaxError, SyntaxErrorKind},
   helpers::*,
-  state::ParseState,
+  html::is_void_element,
+  lexer::{LexMode, Lexer, Token, TokenKind},
+  options::{ParserOptions, WhitespaceMode},
+  stats::{measure, ParseStats},
+  vue::is_vue_attr,
 };
 
 pub type PResult<T> = Result<T, SyntaxError>;
@@ -12,364 +18,848 @@ pub type PResult<T> = Result<T, SyntaxError>;
 /// Parser结构体表示模板解析器的状态
 ///
 /// 字段说明：
-/// * `source` - 待解析的源代码字符串
-/// * `state` - 解析状态，包含字符迭代器和位置信息
+/// * `lexer` - 词法分析器，按上下文切分带位置的词法单元，见 [`lexer`](crate::lexer)
 pub struct Parser<'s> {
-  state: ParseState<'s>,
+  pub(crate) lexer: Lexer<'s>,
+  pub(crate) options: ParserOptions,
+  /// 片段模式：允许元素不闭合，见 [`Parser::parse_fragment`]
+  fragment: bool,
+  /// HTML 模式：空元素没有结束标签，见 [`Parser::parse_html`]
+  pub(crate) html: bool,
+}
+
+/// 解析器构造 AST 的方式
+///
+/// 解析逻辑只有一份，由实现者决定节点如何保存：[`Owned`] 将文本复制为 `String`，
+/// [`borrowed`](crate::borrowed) 直接引用源码，[`arena`](crate::arena) 将节点扁平存放在连续的数组中，
+/// [`interned`](crate::interned) 通过驻留池共享重复的名称与属性值
+pub(crate) trait Build<'s> {
+  type Node;
+  type Attribute;
+  type AttributeValue;
+
+  #[allow(clippy::too_many_arguments)]
+  fn element(
+    &mut self,
+    name: &'s str,
+    attrs: Vec<Self::Attribute>,
+    children: Vec<Self::Node>,
+    self_closing: bool,
+    first_attr_same_line: bool,
+    recovered: bool,
+    close_start: Option<Position>,
+    close_end: Option<Position>,
+    start: Position,
+    end: Position,
+  ) -> Self::Node;
+  fn text(&mut self, content: &'s str, start: Position, end: Position) -> Self::Node;
+  fn comment(&mut self, content: &'s str, start: Position, end: Position) -> Self::Node;
+  fn expression(&mut self, content: &'s str, start: Position, end: Position) -> Self::Node;
+  fn attribute(
+    &mut self,
+    name: &'s str,
+    value: Option<Vec<Self::AttributeValue>>,
+    quote: AttributeQuote,
+    start: Position,
+    end: Position,
+  ) -> Self::Attribute;
+  fn attribute_start(&self, attr: &Self::Attribute) -> Position;
+  fn text_value(
+    &mut self,
+    content: &'s str,
+    start: Position,
+    end: Position,
+  ) -> Self::AttributeValue;
+  fn expression_value(
+    &mut self,
+    content: &'s str,
+    start: Position,
+    end: Position,
+  ) -> Self::AttributeValue;
+}
+
+/// 构造拥有所有权的 [`ast`](crate::ast) 节点
+pub(crate) struct Owned;
+
+impl<'s> Build<'s> for Owned {
+  type Node = Node;
+  type Attribute = Attribute;
+  type AttributeValue = AttributeValue;
+
+  fn element(
+    &mut self,
+    name: &'s str,
+    attrs: Vec<Attribute>,
+    children: Vec<Node>,
+    self_closing: bool,
+    first_attr_same_line: bool,
+    recovered: bool,
+    close_start: Option<Position>,
+    close_end: Option<Position>,
+    start: Position,
+    end: Position,
+  ) -> Node {
+    Node::Element {
+      name: name.to_string(),
+      attrs,
+      children,
+      self_closing,
+      first_attr_same_line,
+      recovered,
+      close_start,
+      close_end,
+      start,
+      end,
+    }
+  }
+
+  fn text(&mut self, content: &'s str, start: Position, end: Position) -> Node {
+    Node::Text {
+      content: content.to_string(),
+      start,
+      end,
+    }
+  }
+
+  fn comment(&mut self, content: &'s str, start: Position, end: Position) -> Node {
+    Node::Comment {
+      content: content.to_string(),
+      start,
+      end,
+    }
+  }
+
+  fn expression(&mut self, content: &'s str, start: Position, end: Position) -> Node {
+    Node::Expression {
+      content: content.to_string(),
+      start,
+      end,
+    }
+  }
+
+  fn attribute(
+    &mut self,
+    name: &'s str,
+    value: Option<Vec<AttributeValue>>,
+    quote: AttributeQuote,
+    start: Position,
+    end: Position,
+  ) -> Attribute {
+    let (prefix, local) = split_attr_name(name);
+    Attribute {
+      name: name.to_string(),
+      prefix,
+      local,
+      value,
+      quote,
+      start,
+      end,
+    }
+  }
+
+  fn attribute_start(&self, attr: &Attribute) -> Position {
+    attr.start
+  }
+
+  fn text_value(&mut self, content: &'s str, start: Position, end: Position) -> AttributeValue {
+    AttributeValue::Text {
+      content: content.to_string(),
+      start,
+      end,
+    }
+  }
+
+  fn expression_value(
+    &mut self,
+    content: &'s str,
+    start: Position,
+    end: Position,
+  ) -> AttributeValue {
+    AttributeValue::Expression {
+      content: content.to_string(),
+      start,
+      end,
+    }
+  }
+}
+
+/// 已解析开始标签、尚未闭合的元素
+struct OpenElement<'s, B: Build<'s>> {
+  name: &'s str,
+  attrs: Vec<B::Attribute>,
+  first_attr_same_line: bool,
+  recovered: bool,
+  start: Position,
+  /// 开始标签的结束位置，即 `>` 之后
+  start_tag_end: Position,
+  children: Vec<B::Node>,
+}
+
+/// 节点的解析结果
+enum StartTag<'s, B: Build<'s>> {
+  /// 已完整解析的节点，包括自闭合元素与原始文本元素
+  Complete(B::Node),
+  /// 需要继续解析子节点与结束标签的元素
+  Open(OpenElement<'s, B>),
 }
 
 impl<'s> Parser<'s> {
   /// 创建一个新的解析器实例
   pub fn new(source: &'s str) -> Self {
+    Self::with_options(source, ParserOptions::default())
+  }
+
+  /// 使用指定选项创建解析器实例
+  pub fn with_options(source: &'s str, options: ParserOptions) -> Self {
     Self {
-      state: ParseState::new(source),
+      lexer: Lexer::new(source),
+      options,
+      fragment: false,
+      html: false,
     }
   }
 
+  /// 解析过程中收集的语法错误，包括已恢复的错误
+  pub fn errors(&self) -> &[SyntaxError] {
+    self.lexer.errors()
+  }
+
+  /// 解析整个文档，已恢复的错误同时记录在 [`Root::errors`] 中；设置了 [`ParserOptions::position_encoding`] 时，
+  /// 结果、返回的错误与 [`Parser::errors`] 中的位置均按指定编码换算
   pub fn parse_root(&mut self) -> PResult<Root> {
-    let start = self.state.position();
-    let children = self.parse_children(None)?;
-    let end = self.state.position();
-    Ok(Root {
+    let mut result = self.parse_root_bytes();
+    if let Some(encoding) = self.options.position_encoding {
+      let encoder = Encoder::new(self.lexer.source(), encoding);
+      for error in self.lexer.errors_mut() {
+        encoder.encode_error(error);
+      }
+      result = result
+        .map(|mut root| {
+          encoder.encode_root(&mut root);
+          root
+        })
+        .map_err(|mut error| {
+          encoder.encode_error(&mut error);
+          error
+        });
+    }
+    result.map(|mut root| {
+      root.errors = self.errors().to_vec();
+      root
+    })
+  }
+
+  fn parse_root_bytes(&mut self) -> PResult<Root> {
+    let (result, duration) = measure(|| self.parse_document(&mut Owned));
+    let (children, start, end) = result?;
+    let mut root = Root {
       children,
       start,
       end,
-    })
+      stats: None,
+      comments: None,
+      errors: Vec::new(),
+    };
+    if self.options.attaches_comments() {
+      attach_comments(&mut root);
+    }
+    if self.options.collects_stats() {
+      root.stats = Some(ParseStats {
+        duration,
+        ..ParseStats::of(&root)
+      });
+    }
+    Ok(root)
   }
 
-  /// 解析一系列节点，直到遇到结束标签或文件结束
-  fn parse_children(&mut self, parent_name: Option<&str>) -> PResult<Vec<Node>> {
-    let mut children = vec![];
-    while !self.state.is_end() {
-      if self.state.starts_with("</") {
-        if let Some(name) = parent_name {
-          if self.state.starts_with(name) {
-            self.state.next_n(name.len());
-            break;
-          } else {
-            return Err(self.state.emit_error(SyntaxErrorKind::ExpectCloseTag));
+  /// 解析整个文档，返回根节点的子节点与起止位置
+  pub(crate) fn parse_document<B: Build<'s>>(
+    &mut self,
+    b: &mut B,
+  ) -> PResult<(Vec<B::Node>, Position, Position)> {
+    self.check_source_length()?;
+    let start = self.lexer.position();
+    let children = self.parse_children(b)?;
+    // 顶层出现没有对应开始标签的结束标签
+    if !self.lexer.is_end() {
+      return Err(self.lexer.emit_error(SyntaxErrorKind::ExpectCloseTag));
+    }
+    let end = self.lexer.position();
+    Ok((children, start, end))
+  }
+
+  /// 解析不完整的模板片段，如编辑器中正在输入的内容
+  ///
+  /// `context_tag` 为片段所在的父元素，父元素是原始文本标签时整个片段作为文本。
+  /// 片段中的元素可以不闭合，多余的结束标签与无法解析的字符会被跳过，
+  /// 因此总能返回已解析出的节点，遇到的问题记录在 [`Parser::errors`] 中
+  pub fn parse_fragment(&mut self, context_tag: Option<&str>) -> Vec<Node> {
+    self.fragment = true;
+    if self.check_source_length().is_err() {
+      return Vec::new();
+    }
+    if let Some(tag) = context_tag {
+      if self.options.is_raw_text_tag(tag) {
+        return self.parse_raw_text(&mut Owned, tag);
+      }
+    }
+    let mut nodes = Vec::new();
+    loop {
+      let _ = self.parse_children_into(&mut Owned, &mut nodes);
+      if self.lexer.is_end() {
+        break;
+      }
+      // 跳过多余的结束标签或无法解析的字符
+      let _ = self.lexer.next(LexMode::SkipFragment);
+    }
+    nodes
+  }
+
+  /// 将任意字符串拆分为静态文本与 `{{ }}` 表达式，未闭合的 `{{` 按静态文本处理
+  pub fn parse_interpolation(&mut self) -> Vec<Value> {
+    let mut values = Vec::new();
+    while let Ok(token) = self.lexer.next(LexMode::Interpolation) {
+      match token.kind {
+        TokenKind::Eof => break,
+        TokenKind::MustacheStart => {
+          if let Ok((content, start, end)) = self.parse_expression(token, None) {
+            values.push(Value::Expression {
+              content: content.to_string(),
+              start,
+              end,
+            });
           }
         }
-        break;
+        _ => values.push(Value::Text {
+          content: token.text.to_string(),
+          start: token.start,
+          end: token.end,
+        }),
       }
-      children.push(self.parse_node()?);
     }
-    Ok(children)
+    values
+  }
+
+  fn check_source_length(&mut self) -> PResult<()> {
+    if self
+      .options
+      .exceeds_source_length(self.lexer.remaining_len())
+    {
+      return Err(
+        self
+          .lexer
+          .emit_error(SyntaxErrorKind::ResourceLimitExceeded),
+      );
+    }
+    Ok(())
   }
 
-  /// 解析单个节点
-  fn parse_node(&mut self) -> PResult<Node> {
-    self.state.skip_whitespace();
+  /// 解析一系列节点，直到遇到结束标签或文件结束
+  fn parse_children<B: Build<'s>>(&mut self, b: &mut B) -> PResult<Vec<B::Node>> {
+    let mut children = vec![];
+    self.parse_children_into(b, &mut children)?;
+    Ok(children)
+  }
 
-    // 根据下一个字符决定如何解析
-    match self.state.peek_n() {
-      // 注释 <!-- ... -->
-      Some(['<', '!']) => {
-        if let Some(['<', '!', '-', '-']) = self.state.peek_n() {
-          return self.parse_comment();
-        } else {
-          return Err(self.state.emit_error(SyntaxErrorKind::ExpectComment));
+  /// 解析一系列节点并追加到 `children`，直到遇到多余的结束标签或文件结束
+  ///
+  /// 嵌套元素使用显式的栈而不是递归解析，任意深度的文档都不会导致栈溢出
+  fn parse_children_into<B: Build<'s>>(
+    &mut self,
+    b: &mut B,
+    children: &mut Vec<B::Node>,
+  ) -> PResult<()> {
+    let mut stack: Vec<OpenElement<'s, B>> = Vec::new();
+    loop {
+      let siblings = match stack.last_mut() {
+        Some(element) => &mut element.children,
+        None => &mut *children,
+      };
+      match self.parse_next_child(b, siblings)? {
+        Some(StartTag::Complete(node)) => siblings.push(node),
+        Some(StartTag::Open(element)) => {
+          if self.options.exceeds_depth(stack.len() as u32 + 1) {
+            return Err(
+              self
+                .lexer
+                .emit_error(SyntaxErrorKind::ResourceLimitExceeded),
+            );
+          }
+          stack.push(element);
         }
-      }
-      // 开始标签 <tagName
-      Some(['<', ch]) => {
-        if is_tag_name_char(ch) {
-          // 正常的开始标签
-          return self.parse_element();
-        } else if ch == '/' {
-          // 错误的结束标签
-          return Err(self.state.emit_error(SyntaxErrorKind::ExpectElement));
-        } else {
-          // 错误的标签名
-          return Err(self.state.emit_error(SyntaxErrorKind::ExpectElement));
+        // 当前层级结束，闭合栈顶元素
+        None => {
+          let Some(element) = stack.pop() else {
+            return Ok(());
+          };
+          let node = self.close_element(b, element)?;
+          match stack.last_mut() {
+            Some(parent) => parent.children.push(node),
+            None => children.push(node),
+          }
         }
       }
-      // 表达式 {{ ... }}
-      Some(['{', '{']) => {
-        return self.parse_expression_node();
-      }
-      // 普通文本节点
-      Some(_) => {
-        return self.parse_text();
-      }
-      None => {
-        // 到达文件尾部
-        return Err(self.state.emit_error(SyntaxErrorKind::ExpectTextNode));
+    }
+  }
+
+  /// 解析下一个子节点，遇到结束标签或文件结束时返回 `None`
+  ///
+  /// 节点之间的空白文本直接追加到 `siblings`
+  fn parse_next_child<B: Build<'s>>(
+    &mut self,
+    b: &mut B,
+    siblings: &mut Vec<B::Node>,
+  ) -> PResult<Option<StartTag<'s, B>>> {
+    loop {
+      match self.lexer.peek(LexMode::Content) {
+        // 结束标签由 close_element 消费并校验名称
+        TokenKind::Eof | TokenKind::EndTagOpen => return Ok(None),
+        TokenKind::Whitespace => {
+          let token = self.lexer.next(LexMode::Content)?;
+          // 结束标签或文件结尾前的空白不构成节点，保留所有空白时除外
+          if matches!(
+            self.lexer.peek(LexMode::Content),
+            TokenKind::Eof | TokenKind::EndTagOpen
+          ) && self.options.whitespace_mode() != WhitespaceMode::Preserve
+          {
+            continue;
+          }
+          if let Some(node) = self.whitespace_node(b, token) {
+            siblings.push(node);
+          }
+        }
+        // 跳过从 HTML、XML 文件复制而来的 `<!DOCTYPE ...>` 与 `<?xml ...?>` 声明，并记录一条
+        // `IgnoredDeclaration` 错误，不中断解析；声明没有结束时由词法分析报告 `ExpectDoctype`
+        TokenKind::Declaration => {
+          self.lexer.emit_error(SyntaxErrorKind::IgnoredDeclaration);
+          self.lexer.next(LexMode::Content)?;
+        }
+        _ => return self.parse_node(b).map(Some),
       }
     }
   }
 
-  /// 解析元素节点
-  fn parse_element(&mut self) -> PResult<Node> {
-    let start = self.state.position();
-    // 消费 "<"
-    self.state.next();
+  /// 解析单个节点，元素只解析到开始标签
+  fn parse_node<B: Build<'s>>(&mut self, b: &mut B) -> PResult<StartTag<'s, B>> {
+    let token = self.lexer.next(LexMode::Content)?;
+    let node = match token.kind {
+      // 注释 <!-- ... -->
+      TokenKind::CommentStart => self.parse_comment(b, token),
+      // 开始标签 <tagName
+      TokenKind::TagOpen => return self.parse_element(b, token),
+      // 表达式 {{ ... }}
+      TokenKind::MustacheStart => self.parse_expression_node(b, token),
+      // 普通文本节点，包括不构成标签的 `<`，如 `1 < 2`
+      TokenKind::Text => Ok(b.text(token.text, token.start, token.end)),
+      // 到达文件尾部
+      _ => Err(self.lexer.emit_error(SyntaxErrorKind::ExpectTextNode)),
+    };
+    node.map(StartTag::Complete)
+  }
+
+  /// 解析元素的开始标签，自闭合元素与原始文本元素会被完整解析
+  fn parse_element<B: Build<'s>>(
+    &mut self,
+    b: &mut B,
+    open: Token<'s>,
+  ) -> PResult<StartTag<'s, B>> {
+    let start = open.start;
 
     // 解析标签名
     let name = self.parse_tag_name()?;
 
     // 解析属性
-    let (attrs, first_attr_same_line) = self.parse_attributes()?;
-
-    self.state.skip_whitespace();
-    // 检查是否是自闭合标签
-    let self_closing = self.state.next_if(|c, _| c == '/');
+    let (attrs, first_attr_same_line, recovered) = self.parse_attributes(b)?;
 
-    let mut children = Vec::new();
-
-    if !self_closing {
+    self.skip_whitespace();
+    let token = self.lexer.next(LexMode::Tag)?;
+    match token.kind {
       // 消费结束 >
-      if !self.state.next_if(|c, _| c == '>') {
-        return Err(self.state.emit_error(SyntaxErrorKind::ExpectElement));
+      TokenKind::TagClose => {}
+      // 自闭合标签
+      TokenKind::SelfClose => {
+        return Ok(StartTag::Complete(b.element(
+          name,
+          attrs,
+          Vec::new(),
+          true,
+          first_attr_same_line,
+          recovered,
+          None,
+          None,
+          start,
+          token.end,
+        )));
       }
-
-      // 解析子节点
-      children = self.parse_children(Some(name))?;
-
-      self.state.skip_whitespace();
-      // 解析结束标签
-      self.parse_closing_tag(&name)?;
+      // 片段在开始标签中途结束
+      TokenKind::Eof if self.fragment => {
+        self.lexer.emit_error(SyntaxErrorKind::ExpectElement);
+        return Ok(StartTag::Complete(b.element(
+          name,
+          attrs,
+          Vec::new(),
+          false,
+          first_attr_same_line,
+          true,
+          None,
+          None,
+          start,
+          token.end,
+        )));
+      }
+      // `/` 后面不是 `>`
+      TokenKind::Invalid => {
+        return Err(self.lexer.emit_error(SyntaxErrorKind::ExpectSelfCloseTag));
+      }
+      _ => return Err(self.lexer.emit_error(SyntaxErrorKind::ExpectElement)),
     }
-
-    // 获取结束位置
-    let end = self.state.position();
-
-    Ok(Node::Element {
-      name: name.to_string(),
+    // HTML 的空元素（如 `<img>`、`<br>`）没有子节点与结束标签
+    if self.html && is_void_element(name) {
+      return Ok(StartTag::Complete(b.element(
+        name,
+        attrs,
+        Vec::new(),
+        false,
+        first_attr_same_line,
+        recovered,
+        None,
+        None,
+        start,
+        token.end,
+      )));
+    }
+    let mut element = OpenElement {
+      name,
       attrs,
-      children,
-      self_closing,
       first_attr_same_line,
+      recovered,
       start,
-      end,
-    })
+      start_tag_end: token.end,
+      children: Vec::new(),
+    };
+    // 原始文本标签的内容整体作为一个文本节点
+    if self.options.is_raw_text_tag(name) {
+      element.children = self.parse_raw_text(b, name);
+      return self.close_element(b, element).map(StartTag::Complete);
+    }
+    Ok(StartTag::Open(element))
+  }
+
+  /// 子节点解析完成后，解析结束标签并生成元素节点
+  fn close_element<B: Build<'s>>(
+    &mut self,
+    b: &mut B,
+    element: OpenElement<'s, B>,
+  ) -> PResult<B::Node> {
+    let name = element.name;
+    let mut recovered = element.recovered;
+    let open_tag = || OpenTag {
+      name: name.to_string(),
+      start: element.start,
+      end: element.start_tag_end,
+    };
+    let close = if self.fragment && !self.is_closing_tag_of(name) {
+      // 片段中的元素允许不闭合，结束标签留给祖先元素
+      self
+        .lexer
+        .emit_error_with_open_tag(SyntaxErrorKind::ExpectCloseTag, Some(open_tag()));
+      recovered = true;
+      None
+    } else if let Some(close) = self.parse_closing_tag(name) {
+      Some(close)
+    } else {
+      // 结束标签缺失、名称不匹配或没有 `>`，错误位置为结束标签的开头（或文件结尾）
+      return Err(
+        self
+          .lexer
+          .emit_error_with_open_tag(SyntaxErrorKind::ExpectCloseTag, Some(open_tag())),
+      );
+    };
+
+    Ok(b.element(
+      name,
+      element.attrs,
+      element.children,
+      false,
+      element.first_attr_same_line,
+      recovered,
+      close.map(|close| close.start),
+      close.map(|close| close.end),
+      element.start,
+      self.lexer.position(),
+    ))
   }
 
   /// 解析标签名
   fn parse_tag_name(&mut self) -> PResult<&'s str> {
-    let name = self.state.next_while(|c, _| is_tag_name_char(c));
+    let name = self.lexer.next(LexMode::TagName)?.text;
     if name.is_empty() {
-      return Err(self.state.emit_error(SyntaxErrorKind::ExpectTagName));
+      return Err(self.lexer.emit_error(SyntaxErrorKind::ExpectTagName));
     }
     Ok(name)
   }
 
-  /// 解析属性列表
-  fn parse_attributes(&mut self) -> PResult<(Vec<Attribute>, bool)> {
+  /// 跳过标签内的空白
+  fn skip_whitespace(&mut self) {
+    self.lexer.next_if(LexMode::Tag, TokenKind::Whitespace);
+  }
+
+  /// 解析属性列表，返回属性、第一个属性是否与标签名同行，以及是否跳过了无法解析的内容
+  fn parse_attributes<B: Build<'s>>(
+    &mut self,
+    b: &mut B,
+  ) -> PResult<(Vec<B::Attribute>, bool, bool)> {
     let mut attrs = Vec::new();
-    let start = self.state.position();
-    let mut first_attr_same_line = true;
+    let mut recovered = false;
+    let start = self.lexer.position();
 
     // 解析剩余属性
     loop {
-      self.state.skip_whitespace();
+      self.skip_whitespace();
 
       // 检查是否到达标签结束
-      match self.state.peek() {
-        Some('>') | Some('/') => break,
-        None => break,
+      match self.lexer.peek(LexMode::Tag) {
+        TokenKind::TagClose | TokenKind::SelfClose | TokenKind::Invalid | TokenKind::Eof => break,
         _ => {
           // 尝试解析下一个属性
-          match self.parse_attribute() {
-            Ok(attr) => attrs.push(attr),
+          match self.parse_attribute(b) {
+            Ok(attr) => {
+              attrs.push(attr);
+              if self.options.exceeds_attrs(attrs.len()) {
+                return Err(
+                  self
+                    .lexer
+                    .emit_error(SyntaxErrorKind::ResourceLimitExceeded),
+                );
+              }
+            }
             Err(_) => {
-              // 属性解析错误，跳过这个字符
-              self.state.next();
+              // 错误已记录在错误列表中，跳过无法解析的内容直到下一个空白或标签结束，
+              // 避免同一段内容产生一连串错误，并标记元素不完整
+              let _ = self.lexer.next(LexMode::SkipAttribute);
+              recovered = true;
             }
           }
         }
       }
     }
     // 检查第一个属性是否在同一行
-    first_attr_same_line = if let Some(attr) = attrs.first() {
-      attr.start.line == start.line
+    let first_attr_same_line = if let Some(attr) = attrs.first() {
+      b.attribute_start(attr).line == start.line
     } else {
       true
     };
 
-    Ok((attrs, first_attr_same_line))
+    Ok((attrs, first_attr_same_line, recovered))
   }
 
   /// 解析单个属性
-  fn parse_attribute(&mut self) -> PResult<Attribute> {
-    let start = self.state.position();
-
+  fn parse_attribute<B: Build<'s>>(&mut self, b: &mut B) -> PResult<B::Attribute> {
     // 解析属性名
-    let name = self.state.next_while(|c, _| is_attr_name_char(c));
-
-    if name.is_empty() {
-      return Err(self.state.emit_error(SyntaxErrorKind::ExpectAttrName));
-    }
+    let Some(name) = self.lexer.next_if(LexMode::Tag, TokenKind::AttrName) else {
+      return Err(self.lexer.emit_error(SyntaxErrorKind::ExpectAttrName));
+    };
     // 检查是否有属性值
-    let value = if self.state.next_if(|c, _| c == '=') {
-      Some(self.parse_attribute_value()?)
+    let (value, quote) = if self
+      .lexer
+      .next_if(LexMode::Tag, TokenKind::Equals)
+      .is_some()
+    {
+      let whole_expression = self.options.is_vue() && is_vue_attr(name.text);
+      let (value, quote) = self.parse_attribute_value(b, whole_expression)?;
+      (Some(value), quote)
     } else {
-      None
+      (None, AttributeQuote::None)
     };
-    let end = self.state.position();
-    Ok(Attribute {
-      name: name.to_string(),
-      value,
-      start,
-      end,
-    })
+    let end = self.lexer.position();
+    Ok(b.attribute(name.text, value, quote, name.start, end))
   }
 
   /// 解析属性值
-  fn parse_attribute_value(&mut self) -> PResult<Vec<AttributeValue>> {
-    let quote = match self.state.peek() {
-      Some('"') | Some('\'') => {
-        let (_, q) = self.state.next().unwrap();
-        Some(q)
-      }
-      _ => None,
-    };
+  ///
+  /// `whole_expression` 为真时，带引号的值不按 `{{ }}` 拆分，去掉首尾空白后整体作为一个表达式
+  fn parse_attribute_value<B: Build<'s>>(
+    &mut self,
+    b: &mut B,
+    whole_expression: bool,
+  ) -> PResult<(Vec<B::AttributeValue>, AttributeQuote)> {
+    let quote = self
+      .lexer
+      .next_if(LexMode::Tag, TokenKind::Quote)
+      .and_then(|token| token.text.chars().next());
 
     let mut values = Vec::new();
-    // 如果有引号，解析引号内的内容
-    if let Some(quote) = quote {
-      loop {
-        if self.state.next_if(|c, _| c == quote) {
-          break;
+    // Vue 兼容模式下绑定属性的值整体作为表达式
+    if let (Some(quote), true) = (quote, whole_expression) {
+      let mode = LexMode::WholeExpression(quote);
+      self.lexer.next_if(mode, TokenKind::Whitespace);
+      let expression = self.lexer.next_if(mode, TokenKind::Expression);
+      if self.lexer.next_if(mode, TokenKind::Quote).is_none() {
+        return Err(self.lexer.emit_error(SyntaxErrorKind::ExpectAttrValue));
+      }
+      if let Some(token) = expression {
+        let content = token.text.trim_end();
+        if !content.is_empty() {
+          values.push(b.expression_value(content, token.start, token.start.advance(content)));
         }
-        match self.state.peek_n() {
-          Some(['{', '{']) => {
-            let exp = self.parse_expression();
-            match exp {
-              Ok(exp) => {
-                values.push(AttributeValue::Expression {
-                  content: exp.content,
-                  start: exp.start,
-                  end: exp.end,
-                });
-              }
-              Err(e) => {
-                return Err(e);
-              }
-            }
-          }
-          _ => {
-            let start = self.state.position();
-            let text = self
-              .state
-              .next_until(|c, s| c == quote || s.starts_with("{{"));
-            let end = self.state.position();
-            values.push(AttributeValue::Text {
-              content: text.to_string(),
-              start,
-              end,
-            });
-          }
+      }
+    } else if let Some(quote) = quote {
+      // 如果有引号，解析引号内的内容
+      let mode = LexMode::AttrValue(Some(quote));
+      loop {
+        let token = self.lexer.next(mode)?;
+        match token.kind {
+          TokenKind::Quote => break,
+          // 缺少结束引号
+          TokenKind::Eof => return Err(self.lexer.emit_error(SyntaxErrorKind::ExpectAttrValue)),
+          _ => values.push(self.parse_attribute_value_part(b, token, Some(quote))?),
         }
       }
+    } else if self.options.is_lenient() {
+      // 宽松模式下解析不带引号的值，直到空白、`>` 或 `/>`
+      let mode = LexMode::AttrValue(None);
+      while matches!(
+        self.lexer.peek(mode),
+        TokenKind::AttrText | TokenKind::MustacheStart
+      ) {
+        let token = self.lexer.next(mode)?;
+        values.push(self.parse_attribute_value_part(b, token, None)?);
+      }
+      if values.is_empty() {
+        return Err(self.lexer.emit_error(SyntaxErrorKind::ExpectAttrValue));
+      }
     } else {
-      return Err(self.state.emit_error(SyntaxErrorKind::ExpectAttrValue));
+      return Err(self.lexer.emit_error(SyntaxErrorKind::ExpectAttrValue));
     }
 
     if values.is_empty() {
-      let pos = self.state.position();
-      values.push(AttributeValue::Text {
-        content: "".to_string(),
-        start: pos,
-        end: pos,
-      });
+      let pos = self.lexer.position();
+      values.push(b.text_value("", pos, pos));
     }
 
-    Ok(values)
+    let quote = match quote {
+      Some('"') => AttributeQuote::Double,
+      Some(_) => AttributeQuote::Single,
+      None => AttributeQuote::None,
+    };
+    Ok((values, quote))
   }
 
-  /// 解析结束标签 </tagName>
-  fn parse_closing_tag(&mut self, expected_name: &str) -> PResult<()> {
-    if self.state.starts_with("</") {
-      self.state.next_n(2);
-    } else {
-      return Err(self.state.emit_error(SyntaxErrorKind::ExpectCloseTag));
+  /// 由属性值中的词法单元构造一段静态文本或表达式
+  ///
+  /// 未闭合的 `{{` 已由词法分析报告错误并作为静态文本；`{{'{{'}}` 转义的花括号也是静态文本
+  fn parse_attribute_value_part<B: Build<'s>>(
+    &mut self,
+    b: &mut B,
+    token: Token<'s>,
+    quote: Option<char>,
+  ) -> PResult<B::AttributeValue> {
+    if token.kind != TokenKind::MustacheStart {
+      return Ok(b.text_value(token.text, token.start, token.end));
     }
-    // 解析标签名
-    let name = self.parse_tag_name()?;
+    let (content, start, end) = self.parse_expression(token, quote)?;
+    Ok(match literal_mustache(content) {
+      Some(text) => b.text_value(text, start, end),
+      None => b.expression_value(content, start, end),
+    })
+  }
 
-    // 检查标签名是否匹配
-    if name != expected_name {
-      return Err(self.state.emit_error(SyntaxErrorKind::ExpectCloseTag));
-    }
+  /// 当前位置是否为指定元素的结束标签，不消费任何内容
+  fn is_closing_tag_of(&mut self, name: &str) -> bool {
+    let checkpoint = self.lexer.checkpoint();
+    let matched = self.lex_closing_tag_name(name).is_some();
+    self.lexer.rewind(checkpoint);
+    matched
+  }
 
-    // 跳过空格
-    self.state.skip_whitespace();
+  /// 消费结束标签的 `</` 与标签名，标签名为 `name` 时返回标签名的词法单元
+  fn lex_closing_tag_name(&mut self, name: &str) -> Option<Token<'s>> {
+    self
+      .lexer
+      .next_if(LexMode::Content, TokenKind::EndTagOpen)?;
+    self
+      .lexer
+      .next(LexMode::TagName)
+      .ok()
+      .filter(|token| token.text == name)
+  }
 
-    // 检查结束标签是否正确关闭
-    if !self.state.next_if(|c, _| c == '>') {
-      return Err(self.state.emit_error(SyntaxErrorKind::ExpectCloseTag));
+  /// 解析结束标签 </tagName>，返回其中标签名的词法单元；不匹配时不消费任何字符并返回 `None`
+  fn parse_closing_tag(&mut self, expected_name: &str) -> Option<Token<'s>> {
+    let checkpoint = self.lexer.checkpoint();
+    let matched = self.lex_closing_tag_name(expected_name).filter(|_| {
+      self.skip_whitespace();
+      self
+        .lexer
+        .next_if(LexMode::Tag, TokenKind::TagClose)
+        .is_some()
+    });
+    if matched.is_none() {
+      self.lexer.rewind(checkpoint);
     }
+    matched
+  }
 
-    Ok(())
+  /// 节点之间只含空白的文本按选项生成节点
+  fn whitespace_node<B: Build<'s>>(&mut self, b: &mut B, token: Token<'s>) -> Option<B::Node> {
+    let content = match self.options.whitespace_mode() {
+      WhitespaceMode::Drop => return None,
+      WhitespaceMode::Keep | WhitespaceMode::Preserve => token.text,
+      WhitespaceMode::Collapse => " ",
+    };
+    Some(b.text(content, token.start, token.end))
   }
 
-  /// 解析文本节点
-  fn parse_text(&mut self) -> PResult<Node> {
-    let start = self.state.position();
-    let str = self
-      .state
-      .next_until(|c, s| c == '<' || s.starts_with("{{"));
-    let content = str.to_string();
-    // 如果文本内容为空，返回错误
-    if content.is_empty() {
-      return Err(self.state.emit_error(SyntaxErrorKind::ExpectTextNode));
+  /// 解析原始文本标签的内容，直到对应的结束标签
+  fn parse_raw_text<B: Build<'s>>(&mut self, b: &mut B, name: &str) -> Vec<B::Node> {
+    let Ok(token) = self.lexer.next(LexMode::RawText(name)) else {
+      return Vec::new();
+    };
+    let preserve = self.options.whitespace_mode() == WhitespaceMode::Preserve;
+    if token.text.is_empty() || (!preserve && token.text.trim().is_empty()) {
+      return Vec::new();
     }
-    let end = self.state.position();
-    Ok(Node::Text {
-      content,
-      start,
-      end,
-    })
+    vec![b.text(token.text, token.start, token.end)]
   }
 
-  /// 解析注释节点 <!-- ... -->
-  fn parse_comment(&mut self) -> PResult<Node> {
-    let start = self.state.position();
-    // 消费 "<!--"
-    self.state.next_n(4);
-    let str = self.state.next_until(|_, s| s.starts_with("-->"));
-    let content = str.to_string();
-    // 如果文本内容为空，返回错误
-    if content.is_empty() {
-      return Err(self.state.emit_error(SyntaxErrorKind::ExpectTextNode));
-    }
-    // 消费 "-->"
-    self.state.next_n(3);
-    let end = self.state.position();
-    Ok(Node::Comment {
-      content,
-      start,
-      end,
-    })
+  /// 解析注释节点 <!-- ... -->，`open` 为已读取的 `<!--`
+  fn parse_comment<B: Build<'s>>(&mut self, b: &mut B, open: Token<'s>) -> PResult<B::Node> {
+    let token = self.lexer.next(LexMode::Comment)?;
+    let (content, end) = match token.kind {
+      // `<!-->` 这类空注释
+      TokenKind::CommentEnd => ("", token.end),
+      _ => (token.text, self.lexer.next(LexMode::Comment)?.end),
+    };
+    Ok(b.comment(content, open.start, end))
   }
 
-  /// 解析表达式节点 {{ ... }}
-  fn parse_expression(&mut self) -> PResult<Expression> {
-    let start = self.state.position();
-    // 消费 "{{"
-    self.state.next_n(2);
-    // 跳过表达式开始处的空白
-    self.state.skip_whitespace();
-    let str = self.state.next_until(|_, s| s.starts_with("}}"));
-    let content = str.trim().to_string();
-    // 消费 "}}"
-    self.state.next_n(2);
-    let end = self.state.position();
-
-    Ok(Expression {
-      content,
-      start,
-      end,
-    })
+  /// 解析 `{{` 之后的表达式与 `}}`，`open` 为已读取的 `{{`
+  ///
+  /// `quote` 为所在属性值的引号，表达式不能越过它；词法分析只在找到匹配的 `}}` 时切分出 `{{`
+  fn parse_expression(
+    &mut self,
+    open: Token<'s>,
+    quote: Option<char>,
+  ) -> PResult<(&'s str, Position, Position)> {
+    let mode = LexMode::Mustache(quote);
+    let content = self
+      .lexer
+      .next_if(mode, TokenKind::Expression)
+      .map_or("", |token| token.text.trim());
+    let end = self.lexer.next(mode)?.end;
+    Ok((content, open.start, end))
   }
 
-  fn parse_expression_node(&mut self) -> PResult<Node> {
-    let expr = self.parse_expression()?;
-    Ok(Node::Expression {
-      content: expr.content,
-      start: expr.start,
-      end: expr.end,
+  fn parse_expression_node<B: Build<'s>>(
+    &mut self,
+    b: &mut B,
+    open: Token<'s>,
+  ) -> PResult<B::Node> {
+    let (content, start, end) = self.parse_expression(open, None)?;
+    // `{{'{{'}}` 转义的花括号作为文本
+    Ok(match literal_mustache(content) {
+      Some(text) => b.text(text, start, end),
+      None => b.expression(content, start, end),
     })
   }
 }
@@ -0,0 +1,174 @@
+//! 按源码范围改写模板
+//!
+//! 重构工具通常只需改动模板的一小部分。与修改 AST 后重新生成整个文件不同，
+//! 这里根据节点、属性的源码范围构造 [`Edit`]，直接替换原始文本，其余部分（包括格式和注释）保持不变
+
+use std::{error::Error, fmt};
+
+#[cfg(feature = "napi")]
+use napi_derive::napi;
+use serde::{Deserialize, Serialize};
+
+use crate::ast::{Attribute, Node, Position};
+
+/// 一次文本替换：将源码中 `start..end` 字节范围替换为 `replacement`，范围为空时即为插入
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "napi", napi(object))]
+pub struct Edit {
+  pub start: u32,
+  pub end: u32,
+  pub replacement: String,
+}
+
+impl Edit {
+  pub fn new(start: u32, end: u32, replacement: impl Into<String>) -> Self {
+    Self {
+      start,
+      end,
+      replacement: replacement.into(),
+    }
+  }
+
+  /// 在指定位置插入文本
+  pub fn insert(offset: u32, text: impl Into<String>) -> Self {
+    Self::new(offset, offset, text)
+  }
+
+  /// 替换整个节点，合成节点没有源码范围，返回 `None`
+  pub fn replace_node(node: &Node, replacement: impl Into<String>) -> Option<Self> {
+    span(node.start(), node.end()).map(|(start, end)| Self::new(start, end, replacement))
+  }
+
+  /// 删除整个节点
+  pub fn remove_node(node: &Node) -> Option<Self> {
+    Self::replace_node(node, "")
+  }
+
+  /// 在节点之前插入文本
+  pub fn insert_before(node: &Node, text: impl Into<String>) -> Option<Self> {
+    span(node.start(), node.end()).map(|(start, _)| Self::insert(start, text))
+  }
+
+  /// 在节点之后插入文本
+  pub fn insert_after(node: &Node, text: impl Into<String>) -> Option<Self> {
+    span(node.start(), node.end()).map(|(_, end)| Self::insert(end, text))
+  }
+
+  /// 替换整个属性（含属性名与值）
+  pub fn replace_attr(attr: &Attribute, replacement: impl Into<String>) -> Option<Self> {
+    span(attr.start, attr.end).map(|(start, end)| Self::new(start, end, replacement))
+  }
+}
+
+fn span(start: Position, end: Position) -> Option<(u32, u32)> {
+  if start.is_synthetic() || end.is_synthetic() {
+    None
+  } else {
+    Some((start.offset, end.offset))
+  }
+}
+
+/// 改动无法应用到源码上时的错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatchError {
+  /// 改动的范围起止颠倒、超出源码或不在字符边界上
+  InvalidRange { index: usize },
+  /// 两个改动修改了同一段文本，下标为改动在传入列表中的位置
+  Overlap { first: usize, second: usize },
+}
+
+impl fmt::Display for PatchError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      PatchError::InvalidRange { index } => write!(f, "edit #{index} has an invalid range"),
+      PatchError::Overlap { first, second } => {
+        write!(f, "edit #{first} overlaps with edit #{second}")
+      }
+    }
+  }
+}
+
+impl Error for PatchError {}
+
+/// 将所有改动应用到源码上，返回改写后的文本
+///
+/// 改动之间不能重叠；相邻的改动（一个的结束位置等于另一个的开始位置）是允许的，
+/// 同一位置的多次插入按传入顺序排列
+pub fn apply_edits(source: &str, edits: &[Edit]) -> Result<String, PatchError> {
+  let mut order: Vec<usize> = (0..edits.len()).collect();
+  for &index in &order {
+    let Edit { start, end, .. } = edits[index];
+    let (start, end) = (start as usize, end as usize);
+    if start > end
+      || end > source.len()
+      || !source.is_char_boundary(start)
+      || !source.is_char_boundary(end)
+    {
+      return Err(PatchError::InvalidRange { index });
+    }
+  }
+  order.sort_by_key(|&index| (edits[index].start, edits[index].end));
+  for pair in order.windows(2) {
+    let (previous, next) = (&edits[pair[0]], &edits[pair[1]]);
+    // 插入位于另一处替换范围的起点时不算重叠
+    let touches_start = next.start == previous.start && previous.start == previous.end;
+    if next.start < previous.end && !touches_start {
+      let (first, second) = (pair[0].min(pair[1]), pair[0].max(pair[1]));
+      return Err(PatchError::Overlap { first, second });
+    }
+  }
+
+  let mut result = String::with_capacity(source.len());
+  let mut copied = 0;
+  for index in order {
+    let edit = &edits[index];
+    result.push_str(&source[copied..edit.start as usize]);
+    result.push_str(&edit.replacement);
+    copied = edit.end as usize;
+  }
+  result.push_str(&source[copied..]);
+  Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::parser::Parser;
+
+  #[test]
+  fn applies_edits_from_nodes() {
+    let source = "<view>\n  <text class=\"a\">hi</text>\n  <!-- todo -->\n</view>";
+    let root = Parser::new(source).parse_root().unwrap();
+    let children = root.children[0].children().unwrap();
+    let text = children
+      .iter()
+      .find(|node| node.name() == Some("text"))
+      .unwrap();
+    let comment = children
+      .iter()
+      .find(|node| matches!(node, Node::Comment { .. }))
+      .unwrap();
+    let edits = [
+      Edit::replace_attr(text.attr("class").unwrap(), "class=\"b\"").unwrap(),
+      Edit::remove_node(comment).unwrap(),
+      Edit::insert_before(text, "<icon/>").unwrap(),
+    ];
+    assert_eq!(
+      apply_edits(source, &edits).unwrap(),
+      "<view>\n  <icon/><text class=\"b\">hi</text>\n  \n</view>"
+    );
+
+    let overlapping = [Edit::replace_node(text, "").unwrap(), edits[0].clone()];
+    assert_eq!(
+      apply_edits(source, &overlapping),
+      Err(PatchError::Overlap {
+        first: 0,
+        second: 1
+      })
+    );
+    assert_eq!(
+      apply_edits(source, &[Edit::new(3, 1, "")]),
+      Err(PatchError::InvalidRange { index: 0 })
+    );
+  }
+}
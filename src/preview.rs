@@ -0,0 +1,170 @@
+//! WXML → HTML 预览
+//!
+//! 先用 [`render`] 根据数据对模板求值，再把内置组件映射为效果相近的 HTML 元素，
+//! 供文档站点在浏览器中直接展示模板的预览效果：
+//!
+//! - 容器类组件（`view`、`swiper`、`scroll-view` 等）输出为 `<div>`，`text` 为 `<span>`，`image` 为 `<img>`
+//! - 表单组件输出为对应的 `<input>`、`<button>`、`<textarea>`，`navigator` 为 `<a>`，`url` 改为 `href`
+//! - 自定义组件输出为 `<div data-component="名称">`，不展开组件自身的模板
+//! - 事件绑定（`bindtap`、`catch:tap` 等）被移除；`hidden`、`disabled`、`checked` 的值为 `false` 或空时移除
+//!
+//! 文本与属性值均经过 HTML 转义，数据中的内容不会被当作标记插入页面
+
+use serde_json::Value;
+
+use crate::{
+  ast::{Attribute, Node, Root},
+  builtins::{is_builtin_tag, TEMPLATE_TAGS},
+  render::{escape_html, render, RenderError},
+};
+
+/// 内置组件对应的 HTML 标签与附加属性
+fn html_element(name: &str) -> (&'static str, &'static [(&'static str, &'static str)]) {
+  match name {
+    "text" => ("span", &[]),
+    "image" | "cover-image" => ("img", &[]),
+    "button" => ("button", &[("type", "button")]),
+    "input" => ("input", &[]),
+    "textarea" => ("textarea", &[]),
+    "checkbox" => ("input", &[("type", "checkbox")]),
+    "radio" => ("input", &[("type", "radio")]),
+    "switch" => ("input", &[("type", "checkbox"), ("role", "switch")]),
+    "slider" => ("input", &[("type", "range")]),
+    "label" => ("label", &[]),
+    "form" => ("form", &[]),
+    "progress" => ("progress", &[("max", "100")]),
+    "navigator" => ("a", &[]),
+    "icon" => ("i", &[]),
+    "video" => ("video", &[]),
+    "audio" => ("audio", &[]),
+    "canvas" => ("canvas", &[]),
+    "web-view" => ("iframe", &[]),
+    "scroll-view" => ("div", &[("style", "overflow:auto")]),
+    _ => ("div", &[]),
+  }
+}
+
+/// 属性名在 HTML 中的对应名称，返回 `None` 表示不输出
+fn html_attr_name<'a>(tag: &str, attr: &'a Attribute) -> Option<&'a str> {
+  let prefix = attr.prefix.as_deref();
+  if attr.is_event_binding() || matches!(prefix, Some("wx" | "generic")) {
+    return None;
+  }
+  if prefix == Some("model") {
+    return Some(&attr.local);
+  }
+  Some(match (tag, attr.name.as_str()) {
+    ("navigator", "url") => "href",
+    ("progress", "percent") => "value",
+    (_, name) => name,
+  })
+}
+
+const BOOLEAN_ATTRS: &[&str] = &["hidden", "disabled", "checked"];
+
+const VOID_ELEMENTS: &[&str] = &["img", "input"];
+
+/// 使用 `data` 渲染模板并输出为可在浏览器中预览的 HTML
+pub fn render_preview(root: &Root, data: &Value) -> Result<String, RenderError> {
+  let rendered = render(root, data)?;
+  let mut out = String::new();
+  for node in &rendered.children {
+    print_node(&mut out, node);
+  }
+  Ok(out)
+}
+
+fn print_node(out: &mut String, node: &Node) {
+  match node {
+    Node::Element {
+      name,
+      attrs,
+      children,
+      ..
+    } => {
+      // 渲染后仍然存在的 `<slot>`、`<template>` 等语法标签只输出内容
+      if TEMPLATE_TAGS.contains(&name.as_str()) {
+        for child in children {
+          print_node(out, child);
+        }
+        return;
+      }
+      let (tag, extra) = html_element(name);
+      out.push('<');
+      out.push_str(tag);
+      if !is_builtin_tag(name) {
+        push_attr(out, "data-component", Some(name));
+      }
+      for (attr_name, value) in extra {
+        if !attrs.iter().any(|attr| attr.name == *attr_name) {
+          push_attr(out, attr_name, Some(value));
+        }
+      }
+      for attr in attrs {
+        let Some(attr_name) = html_attr_name(name, attr) else {
+          continue;
+        };
+        let value = attr.as_static_string();
+        if BOOLEAN_ATTRS.contains(&attr_name) {
+          if !matches!(value.as_deref(), Some("false" | "")) {
+            push_attr(out, attr_name, None);
+          }
+          continue;
+        }
+        push_attr(out, attr_name, value.as_deref());
+      }
+      out.push('>');
+      if VOID_ELEMENTS.contains(&tag) {
+        return;
+      }
+      for child in children {
+        print_node(out, child);
+      }
+      out.push_str("</");
+      out.push_str(tag);
+      out.push('>');
+    }
+    Node::Text { content, .. } => escape_html(out, content, false),
+    // 渲染结果中只包含元素与文本
+    Node::Comment { .. } | Node::Expression { .. } => {}
+  }
+}
+
+fn push_attr(out: &mut String, name: &str, value: Option<&str>) {
+  out.push(' ');
+  out.push_str(name);
+  if let Some(value) = value {
+    out.push_str("=\"");
+    escape_html(out, value, true);
+    out.push('"');
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+  use crate::parser::Parser;
+
+  #[test]
+  fn html_preview() {
+    let root = Parser::new(
+      r#"<view class="list" bindtap="open"><text wx:for="{{items}}">{{item}}</text></view>
+<image src="{{src}}" mode="aspectFit"/><navigator url="/a" hidden="{{hide}}">go</navigator>
+<my-card title="{{title}}"/>"#,
+    )
+    .parse_root()
+    .unwrap();
+    let data = json!({
+      "items": ["a", "<b>"],
+      "src": "x.png",
+      "hide": false,
+      "title": "say \"hi\"",
+    });
+    assert_eq!(
+      render_preview(&root, &data).unwrap(),
+      r#"<div class="list"><span>a</span><span>&lt;b&gt;</span></div><img src="x.png" mode="aspectFit"><a href="/a">go</a><div data-component="my-card" title="say &quot;hi&quot;"></div>"#
+    );
+  }
+}
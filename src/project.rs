@@ -0,0 +1,402 @@
+//! 跨文件的模板组合关系
+//!
+//! 解析项目中的所有模板，收集 `<import>`、`<include>`、`<wxs src>` 以及页面 JSON 中
+//! `usingComponents` 声明且在模板中使用到的组件，构建依赖图并检测循环引用
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  ast::{Node, Position, Root},
+  batch::{parse_file, parse_files, ParseResult},
+  options::ParserOptions,
+};
+
+/// 依赖类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DependencyKind {
+  /// `<import src="...">`
+  Import,
+  /// `<include src="...">`
+  Include,
+  /// `<wxs src="...">`
+  Wxs,
+  /// 页面 JSON 中声明并在模板中使用的自定义组件
+  Component,
+}
+
+/// 一条依赖边
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Dependency {
+  pub kind: DependencyKind,
+  /// 引用方文件
+  pub from: String,
+  /// 解析后的被引用文件路径
+  pub to: String,
+  /// 源码中书写的原始路径
+  pub request: String,
+  /// 引用所在元素的位置
+  pub start: Position,
+  pub end: Position,
+}
+
+/// 项目中的源文件集合
+#[derive(Debug, Clone, Default)]
+pub struct Project {
+  sources: BTreeMap<String, String>,
+}
+
+/// 项目依赖图
+#[derive(Debug, Clone)]
+pub struct ProjectGraph {
+  /// 所有已知文件的路径，包括 wxs 与 json
+  pub files: BTreeSet<String>,
+  /// 各模板的解析结果，按路径排序
+  pub templates: BTreeMap<String, ParseResult>,
+  pub dependencies: Vec<Dependency>,
+  /// 由 import/include 构成的循环，每个循环以起点结尾，如 `[a, b, a]`
+  pub cycles: Vec<Vec<String>>,
+}
+
+impl Project {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// 添加文件，路径使用 `/` 分隔，以项目根目录为基准
+  pub fn add_file(&mut self, path: impl Into<String>, source: impl Into<String>) {
+    self.sources.insert(normalize(&path.into()), source.into());
+  }
+
+  /// 已添加的文件内容
+  pub fn file(&self, path: &str) -> Option<&str> {
+    self.sources.get(&normalize(path)).map(String::as_str)
+  }
+
+  /// 所有文件的路径与内容，按路径排序
+  pub fn files(&self) -> impl Iterator<Item = (&str, &str)> {
+    self
+      .sources
+      .iter()
+      .map(|(path, source)| (path.as_str(), source.as_str()))
+  }
+
+  /// 从入口文件出发，通过加载回调按需读取依赖的文件
+  ///
+  /// 回调返回 `None` 表示文件不存在，这类依赖会保留在图中并可通过
+  /// [`ProjectGraph::missing`] 查询
+  pub fn load<F>(entries: &[&str], mut loader: F, options: &ParserOptions) -> Self
+  where
+    F: FnMut(&str) -> Option<String>,
+  {
+    let mut project = Self::new();
+    let mut pending: Vec<String> = entries.iter().map(|entry| normalize(entry)).collect();
+    let mut visited = BTreeSet::new();
+    while let Some(path) = pending.pop() {
+      if !visited.insert(path.clone()) {
+        continue;
+      }
+      let Some(source) = loader(&path) else {
+        continue;
+      };
+      if path.ends_with(".wxml") {
+        let json_path = sibling_json(&path);
+        if let Some(json) = loader(&json_path) {
+          visited.insert(json_path.clone());
+          project.sources.insert(json_path, json);
+        }
+        let result = parse_file(&path, &source, options);
+        let json = project.sources.get(&sibling_json(&path));
+        if let Some(root) = &result.root {
+          for dependency in collect_dependencies(&path, root, json.map(String::as_str)) {
+            pending.push(dependency.to);
+          }
+        }
+      }
+      project.sources.insert(path, source);
+    }
+    project
+  }
+
+  /// 解析所有模板并构建依赖图
+  pub fn build(&self, options: &ParserOptions) -> ProjectGraph {
+    let inputs = self
+      .sources
+      .iter()
+      .filter(|(path, _)| path.ends_with(".wxml"))
+      .map(|(path, source)| (path.clone(), source.clone()))
+      .collect();
+    let templates: BTreeMap<String, ParseResult> = parse_files(inputs, options)
+      .into_iter()
+      .map(|result| (result.path.clone(), result))
+      .collect();
+
+    let mut dependencies = Vec::new();
+    for (path, result) in &templates {
+      if let Some(root) = &result.root {
+        let json = self.sources.get(&sibling_json(path)).map(String::as_str);
+        dependencies.extend(collect_dependencies(path, root, json));
+      }
+    }
+    let cycles = find_cycles(&dependencies);
+    ProjectGraph {
+      files: self.sources.keys().cloned().collect(),
+      templates,
+      dependencies,
+      cycles,
+    }
+  }
+}
+
+impl ProjectGraph {
+  /// 指定文件直接依赖的文件
+  pub fn dependencies_of<'a>(&'a self, path: &'a str) -> impl Iterator<Item = &'a Dependency> {
+    self.dependencies.iter().filter(move |dep| dep.from == path)
+  }
+
+  /// 直接依赖指定文件的文件
+  pub fn dependents_of<'a>(&'a self, path: &'a str) -> impl Iterator<Item = &'a Dependency> {
+    self.dependencies.iter().filter(move |dep| dep.to == path)
+  }
+
+  /// 目标文件不在项目中的依赖
+  pub fn missing(&self) -> impl Iterator<Item = &Dependency> {
+    self
+      .dependencies
+      .iter()
+      .filter(|dep| !self.files.contains(&dep.to))
+  }
+}
+
+/// 收集单个模板的依赖
+fn collect_dependencies(path: &str, root: &Root, json: Option<&str>) -> Vec<Dependency> {
+  let components = json.map(using_components).unwrap_or_default();
+  let mut used_components = BTreeSet::new();
+  let mut dependencies = Vec::new();
+  root.walk(&mut |node| {
+    let Node::Element {
+      name, start, end, ..
+    } = node
+    else {
+      return;
+    };
+    let kind = match name.as_str() {
+      "import" => Some(DependencyKind::Import),
+      "include" => Some(DependencyKind::Include),
+      "wxs" => Some(DependencyKind::Wxs),
+      _ => None,
+    };
+    if let Some(kind) = kind {
+      if let Some(request) = node.attr("src").and_then(|attr| attr.as_static_string()) {
+        let mut to = resolve(path, &request);
+        if kind != DependencyKind::Wxs && !to.ends_with(".wxml") {
+          to.push_str(".wxml");
+        }
+        dependencies.push(Dependency {
+          kind,
+          from: path.to_string(),
+          to,
+          request,
+          start: *start,
+          end: *end,
+        });
+      }
+    } else {
+      // 标签本身与 `generic:*` 指定的抽象节点实现都算作对组件的使用
+      let generics = node
+        .attrs()
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|attr| attr.as_generic_binding()?.component);
+      for component in std::iter::once(name.clone()).chain(generics) {
+        let Some(request) = components.get(&component) else {
+          continue;
+        };
+        // 同一组件只记录第一次使用的位置
+        if is_local_request(request) && used_components.insert(component) {
+          dependencies.push(Dependency {
+            kind: DependencyKind::Component,
+            from: path.to_string(),
+            to: format!("{}.wxml", resolve(path, request)),
+            request: request.clone(),
+            start: *start,
+            end: *end,
+          });
+        }
+      }
+    }
+  });
+  dependencies
+}
+
+/// 读取页面 JSON 中的 `usingComponents`
+pub(crate) fn using_components(json: &str) -> HashMap<String, String> {
+  let Ok(value) = serde_json::from_str::<serde_json::Value>(json) else {
+    return HashMap::new();
+  };
+  value
+    .get("usingComponents")
+    .and_then(|components| components.as_object())
+    .map(|components| {
+      components
+        .iter()
+        .filter_map(|(name, path)| Some((name.clone(), path.as_str()?.to_string())))
+        .collect()
+    })
+    .unwrap_or_default()
+}
+
+/// 相对路径或以项目根目录开头的绝对路径，排除 npm 包与插件
+fn is_local_request(request: &str) -> bool {
+  request.starts_with('.') || request.starts_with('/')
+}
+
+/// 与模板同名的页面配置文件
+pub(crate) fn sibling_json(path: &str) -> String {
+  match path.strip_suffix(".wxml") {
+    Some(stem) => format!("{stem}.json"),
+    None => format!("{path}.json"),
+  }
+}
+
+/// 以引用方文件所在目录为基准解析路径
+pub(crate) fn resolve(from: &str, request: &str) -> String {
+  if let Some(absolute) = request.strip_prefix('/') {
+    return normalize(absolute);
+  }
+  let dir = from.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("");
+  normalize(&format!("{dir}/{request}"))
+}
+
+/// 规范化路径，去除 `.`、`..` 和多余的分隔符
+pub(crate) fn normalize(path: &str) -> String {
+  let mut parts: Vec<&str> = Vec::new();
+  for part in path.split(['/', '\\']) {
+    match part {
+      "" | "." => {}
+      ".." => {
+        parts.pop();
+      }
+      _ => parts.push(part),
+    }
+  }
+  parts.join("/")
+}
+
+/// 在 import/include 构成的图中查找循环
+fn find_cycles(dependencies: &[Dependency]) -> Vec<Vec<String>> {
+  let mut graph: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+  for dep in dependencies {
+    if matches!(dep.kind, DependencyKind::Import | DependencyKind::Include) {
+      graph.entry(&dep.from).or_default().push(&dep.to);
+    }
+  }
+
+  #[derive(Clone, Copy, PartialEq)]
+  enum Mark {
+    Visiting,
+    Done,
+  }
+
+  fn visit<'a>(
+    node: &'a str,
+    graph: &BTreeMap<&'a str, Vec<&'a str>>,
+    marks: &mut HashMap<&'a str, Mark>,
+    stack: &mut Vec<&'a str>,
+    cycles: &mut Vec<Vec<String>>,
+  ) {
+    marks.insert(node, Mark::Visiting);
+    stack.push(node);
+    for &next in graph.get(node).into_iter().flatten() {
+      match marks.get(next) {
+        Some(Mark::Visiting) => {
+          let begin = stack.iter().position(|&item| item == next).unwrap();
+          let mut cycle: Vec<String> = stack[begin..].iter().map(|s| s.to_string()).collect();
+          cycle.push(next.to_string());
+          cycles.push(cycle);
+        }
+        Some(Mark::Done) => {}
+        None => visit(next, graph, marks, stack, cycles),
+      }
+    }
+    stack.pop();
+    marks.insert(node, Mark::Done);
+  }
+
+  let mut marks = HashMap::new();
+  let mut cycles = Vec::new();
+  for &node in graph.keys() {
+    if !marks.contains_key(node) {
+      visit(node, &graph, &mut marks, &mut Vec::new(), &mut cycles);
+    }
+  }
+  cycles
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn graph_and_cycles() {
+    let mut project = Project::new();
+    project.add_file(
+      "pages/index/index.wxml",
+      r#"<import src="../../common/a.wxml"/><wxs src="./util.wxs" module="u"/><my-card generic:footer="my-footer"/>"#,
+    );
+    project.add_file(
+      "pages/index/index.json",
+      r#"{"usingComponents": {"my-card": "/components/card/card", "my-footer": "/components/footer/footer", "van-button": "@vant/weapp/button"}}"#,
+    );
+    project.add_file("common/a.wxml", r#"<include src="b"/>"#);
+    project.add_file("common/b.wxml", r#"<include src="/common/a.wxml"/>"#);
+
+    let graph = project.build(&ParserOptions::default());
+    let deps: Vec<_> = graph
+      .dependencies_of("pages/index/index.wxml")
+      .map(|dep| (dep.kind, dep.to.as_str()))
+      .collect();
+    assert_eq!(
+      deps,
+      [
+        (DependencyKind::Import, "common/a.wxml"),
+        (DependencyKind::Wxs, "pages/index/util.wxs"),
+        (DependencyKind::Component, "components/card/card.wxml"),
+        (DependencyKind::Component, "components/footer/footer.wxml"),
+      ]
+    );
+    assert_eq!(
+      graph.cycles,
+      [["common/a.wxml", "common/b.wxml", "common/a.wxml"]]
+    );
+    let missing: Vec<_> = graph.missing().map(|dep| dep.to.as_str()).collect();
+    assert_eq!(
+      missing,
+      [
+        "pages/index/util.wxs",
+        "components/card/card.wxml",
+        "components/footer/footer.wxml"
+      ]
+    );
+  }
+
+  #[test]
+  fn load_with_callback() {
+    let files: HashMap<&str, &str> = [
+      ("index.wxml", r#"<import src="./a.wxml"/>"#),
+      ("a.wxml", r#"<template name="a"><view/></template>"#),
+      ("unused.wxml", "<view/>"),
+    ]
+    .into_iter()
+    .collect();
+    let project = Project::load(
+      &["index.wxml"],
+      |path| files.get(path).map(|s| s.to_string()),
+      &ParserOptions::default(),
+    );
+    let graph = project.build(&ParserOptions::default());
+    assert_eq!(graph.templates.len(), 2);
+    assert!(graph.missing().next().is_none());
+  }
+}
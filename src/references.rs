@@ -0,0 +1,166 @@
+//! 模板名与 wxs 模块名的引用查找
+//!
+//! 在项目的所有模板中查找某个名称的定义与使用，返回的范围恰好覆盖名称本身，可直接用于重命名：
+//!
+//! - 模板：`<template name="x">` 为定义，`<template is="x">` 以及 `is` 表达式中的字符串字面量
+//!   （如 `is="{{ a ? 'x' : 'y' }}"`）为使用。模板通过 `<import>` 跨文件可见，因此在整个项目中按名称匹配
+//! - wxs 模块：`<wxs module="x">` 为定义，表达式中的变量 `x`（如 `{{ x.format(a) }}`）为使用。
+//!   模块只在声明它的文件中可见，因此只在包含定义的文件中查找使用
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  ast::{AttributeValue, Node, Position, Root},
+  batch::parse_files,
+  options::ParserOptions,
+  project::Project,
+  semantic::{semantic_tokens, SemanticTokenKind},
+  symbols::SymbolKind,
+};
+
+/// 一处定义或使用
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Reference {
+  /// 所在的文件
+  pub path: String,
+  /// [`SymbolKind::Template`] 或 [`SymbolKind::Module`]
+  pub kind: SymbolKind,
+  /// 是否为定义，即 `<template name>` 或 `<wxs module>` 中的名称
+  pub definition: bool,
+  /// 名称本身的范围，不包括引号
+  pub start: Position,
+  pub end: Position,
+}
+
+/// 在项目的所有模板中查找名为 `name` 的模板与 wxs 模块，结果按文件路径与位置排序
+pub fn find_references(project: &Project, name: &str, options: &ParserOptions) -> Vec<Reference> {
+  let inputs = project
+    .files()
+    .filter(|(path, _)| path.ends_with(".wxml"))
+    .map(|(path, source)| (path.to_string(), source.to_string()))
+    .collect();
+  let mut references = Vec::new();
+  for result in parse_files(inputs, options) {
+    let (Some(root), Some(source)) = (&result.root, project.file(&result.path)) else {
+      continue;
+    };
+    file_references(&result.path, root, source, name, &mut references);
+  }
+  references
+}
+
+fn file_references(path: &str, root: &Root, source: &str, name: &str, out: &mut Vec<Reference>) {
+  let mut found = Vec::new();
+  let mut is_ranges = Vec::new();
+  let mut defines_module = false;
+  root.walk(&mut |node: &Node| {
+    let (kind, attr) = match node.name() {
+      Some("template") => (SymbolKind::Template, "name"),
+      Some("wxs") => (SymbolKind::Module, "module"),
+      _ => return,
+    };
+    if let Some((start, end)) = node
+      .attr(attr)
+      .and_then(|attr| static_span(&attr.value, name))
+    {
+      defines_module |= kind == SymbolKind::Module;
+      found.push((kind, true, start, end));
+    }
+    if let Some(attr) = node.attr("is").filter(|_| kind == SymbolKind::Template) {
+      is_ranges.push(attr.start.offset..attr.end.offset);
+    }
+  });
+
+  for token in semantic_tokens(root, source) {
+    let (start, end) = (token.start.offset as usize, token.end.offset as usize);
+    let text = &source[start..end];
+    match token.kind {
+      SemanticTokenKind::Variable if defines_module && text == name => {
+        found.push((SymbolKind::Module, false, token.start, token.end));
+      }
+      SemanticTokenKind::String
+        if is_ranges
+          .iter()
+          .any(|range| range.contains(&token.start.offset)) =>
+      {
+        if text == name {
+          found.push((SymbolKind::Template, false, token.start, token.end));
+        } else if text.len() == name.len() + 2 && &text[1..text.len() - 1] == name {
+          // 表达式中带引号的字符串字面量
+          let start = token.start.advance(&text[..1]);
+          found.push((SymbolKind::Template, false, start, start.advance(name)));
+        }
+      }
+      _ => {}
+    }
+  }
+
+  found.sort_by_key(|(_, _, start, _)| start.offset);
+  out.extend(
+    found
+      .into_iter()
+      .map(|(kind, definition, start, end)| Reference {
+        path: path.to_string(),
+        kind,
+        definition,
+        start,
+        end,
+      }),
+  );
+}
+
+/// 属性值为静态文本 `name` 时返回其范围
+fn static_span(value: &Option<Vec<AttributeValue>>, name: &str) -> Option<(Position, Position)> {
+  match value.as_deref()? {
+    [AttributeValue::Text {
+      content,
+      start,
+      end,
+    }] if content == name && !start.is_synthetic() => Some((*start, *end)),
+    _ => None,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn template_and_module_references() {
+    let mut project = Project::new();
+    project.add_file(
+      "a.wxml",
+      r#"<wxs module="fmt" src="./fmt.wxs"/><template name="item">{{ fmt.price(p) }}</template>"#,
+    );
+    project.add_file(
+      "b.wxml",
+      r#"<import src="a.wxml"/><template is="item"/><template is="{{ big ? 'item' : 'row' }}"/>{{ fmt }}"#,
+    );
+    let references: Vec<_> = find_references(&project, "item", &ParserOptions::default())
+      .into_iter()
+      .map(|r| (r.path, r.kind, r.definition, r.start.offset, r.end.offset))
+      .collect();
+    assert_eq!(
+      references,
+      [
+        ("a.wxml".to_string(), SymbolKind::Template, true, 51, 55),
+        ("b.wxml".to_string(), SymbolKind::Template, false, 36, 40),
+        ("b.wxml".to_string(), SymbolKind::Template, false, 67, 71),
+      ]
+    );
+
+    let modules: Vec<_> = find_references(&project, "fmt", &ParserOptions::default())
+      .into_iter()
+      .map(|r| (r.path, r.definition, r.start.offset))
+      .collect();
+    // b.wxml 中没有声明 fmt 模块，其中的 `fmt` 不是对模块的引用
+    assert_eq!(
+      modules,
+      [
+        ("a.wxml".to_string(), true, 13),
+        ("a.wxml".to_string(), false, 60)
+      ]
+    );
+  }
+}
@@ -0,0 +1,506 @@
+//! 模板渲染
+//!
+//! 根据 JSON 数据对模板求值，处理 `{{ }}` 插值、`wx:if`/`wx:elif`/`wx:else`、`wx:for`
+//! 以及 `<template is>`，输出渲染后的虚拟节点树或 HTML 字符串，便于在不启动开发者工具的情况下
+//! 对组件模板做快照测试。
+//!
+//! 渲染结果中只包含元素与文本节点：`<block>` 会被展开，注释、`<wxs>`、`<import>`、`<include>`
+//! 与模板定义会被移除，只含空白的文本节点会被忽略，`wx:` 开头的属性会被去掉，其余属性值均求值为静态文本
+
+use std::{collections::HashMap, error::Error, fmt, mem};
+
+use serde_json::{Map, Value};
+
+use crate::{
+  ast::{Attribute, AttributeValue, Node, Position, Root},
+  expr::{parse_expression, parse_object_body, to_display_string, truthy, Expr, Sandbox},
+};
+
+/// `<template is>` 的最大嵌套深度，防止模板递归引用自身
+const MAX_TEMPLATE_DEPTH: usize = 64;
+
+/// 渲染错误
+#[derive(Debug, Clone)]
+pub struct RenderError {
+  pub message: String,
+  /// 出错的节点或属性的位置
+  pub start: Position,
+  pub end: Position,
+}
+
+impl fmt::Display for RenderError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{} at {}", self.message, self.start)
+  }
+}
+
+impl Error for RenderError {}
+
+/// 使用 `data` 渲染模板，返回渲染后的节点树；表达式中允许调用内置方法
+pub fn render(root: &Root, data: &Value) -> Result<Root, RenderError> {
+  render_with(root, data, &Sandbox::default())
+}
+
+/// 使用指定的求值器渲染模板，可通过 [`Sandbox`] 控制表达式中允许调用的函数
+pub fn render_with(root: &Root, data: &Value, sandbox: &Sandbox) -> Result<Root, RenderError> {
+  let mut templates = HashMap::new();
+  root.walk(&mut |node| {
+    if node.name() == Some("template") {
+      if let Some(name) = node.attr("name").and_then(Attribute::as_static_string) {
+        templates.entry(name).or_insert(node);
+      }
+    }
+  });
+  let mut renderer = Renderer {
+    templates,
+    scopes: vec![match data {
+      Value::Object(data) => data.clone(),
+      _ => Map::new(),
+    }],
+    sandbox,
+    depth: 0,
+  };
+  let mut children = Vec::new();
+  renderer.render_children(&root.children, &mut children)?;
+  Ok(Root {
+    children,
+    start: root.start,
+    end: root.end,
+    stats: None,
+    comments: None,
+    errors: Vec::new(),
+  })
+}
+
+/// 使用 `data` 渲染模板并输出为 HTML 字符串
+///
+/// 标签与属性名保持模板中的写法，文本与属性值经过 HTML 转义，数据中的内容不会被当作标记；
+/// 需要在浏览器中展示时使用 [`render_preview`](crate::preview::render_preview)
+pub fn render_to_html(root: &Root, data: &Value) -> Result<String, RenderError> {
+  let rendered = render(root, data)?;
+  let mut out = String::new();
+  for node in &rendered.children {
+    print_html(&mut out, node);
+  }
+  Ok(out)
+}
+
+fn print_html(out: &mut String, node: &Node) {
+  match node {
+    Node::Element {
+      name,
+      attrs,
+      children,
+      self_closing,
+      ..
+    } => {
+      out.push('<');
+      out.push_str(name);
+      for attr in attrs {
+        out.push(' ');
+        out.push_str(&attr.name);
+        if let Some(value) = attr.as_static_string() {
+          out.push_str("=\"");
+          escape_html(out, &value, true);
+          out.push('"');
+        }
+      }
+      if *self_closing {
+        out.push_str("/>");
+        return;
+      }
+      out.push('>');
+      for child in children {
+        print_html(out, child);
+      }
+      out.push_str("</");
+      out.push_str(name);
+      out.push('>');
+    }
+    Node::Text { content, .. } => escape_html(out, content, false),
+    // 渲染结果中只包含元素与文本
+    Node::Comment { .. } | Node::Expression { .. } => {}
+  }
+}
+
+/// 转义 HTML 文本，`attribute` 为真时同时转义双引号
+pub(crate) fn escape_html(out: &mut String, text: &str, attribute: bool) {
+  for ch in text.chars() {
+    match ch {
+      '&' => out.push_str("&amp;"),
+      '<' => out.push_str("&lt;"),
+      '>' => out.push_str("&gt;"),
+      '"' if attribute => out.push_str("&quot;"),
+      _ => out.push(ch),
+    }
+  }
+}
+
+struct Renderer<'a> {
+  /// 按名称索引的模板定义
+  templates: HashMap<String, &'a Node>,
+  /// 变量作用域，栈底为传入的数据
+  scopes: Vec<Map<String, Value>>,
+  sandbox: &'a Sandbox,
+  depth: usize,
+}
+
+/// 条件分支链的状态
+#[derive(Clone, Copy, PartialEq)]
+enum Chain {
+  /// 不在条件链中
+  None,
+  /// 链中已有分支被渲染
+  Matched,
+  /// 链中之前的分支均未渲染
+  Pending,
+}
+
+impl Chain {
+  /// 条件分支求值后的结果与条件链状态
+  fn branch(matched: bool) -> (bool, Chain) {
+    let next = if matched {
+      Chain::Matched
+    } else {
+      Chain::Pending
+    };
+    (matched, next)
+  }
+}
+
+impl<'a> Renderer<'a> {
+  fn lookup(&self, name: &str) -> Option<Value> {
+    self
+      .scopes
+      .iter()
+      .rev()
+      .find_map(|scope| scope.get(name).cloned())
+  }
+
+  fn eval(&self, content: &str, start: Position, end: Position) -> Result<Value, RenderError> {
+    let error = |error: crate::expr::ExprError| RenderError {
+      message: format!("invalid expression `{content}`: {}", error.message),
+      start,
+      end,
+    };
+    let expr = parse_expression(content).map_err(error)?;
+    self
+      .sandbox
+      .evaluate_with(&expr, &|name| self.lookup(name))
+      .map_err(error)
+  }
+
+  /// 属性值求值：只包含单个表达式时返回表达式的值，否则拼接为字符串
+  fn eval_attr(&self, attr: &Attribute) -> Result<Value, RenderError> {
+    let Some(values) = &attr.value else {
+      return Ok(Value::Bool(true));
+    };
+    if let [AttributeValue::Expression {
+      content,
+      start,
+      end,
+    }] = values.as_slice()
+    {
+      return self.eval(content, *start, *end);
+    }
+    let mut result = String::new();
+    for value in values {
+      match value {
+        AttributeValue::Text { content, .. } => result.push_str(content),
+        AttributeValue::Expression {
+          content,
+          start,
+          end,
+        } => result.push_str(&to_display_string(&self.eval(content, *start, *end)?)),
+      }
+    }
+    Ok(Value::String(result))
+  }
+
+  fn render_children(&mut self, nodes: &'a [Node], out: &mut Vec<Node>) -> Result<(), RenderError> {
+    let mut chain = Chain::None;
+    for node in nodes {
+      match node {
+        Node::Element { .. } => chain = self.render_element(node, chain, out)?,
+        Node::Text { content, .. } => {
+          if !content.trim().is_empty() {
+            chain = Chain::None;
+            out.push(node.clone());
+          }
+        }
+        Node::Expression {
+          content,
+          start,
+          end,
+        } => {
+          chain = Chain::None;
+          let value = self.eval(content, *start, *end)?;
+          out.push(Node::Text {
+            content: to_display_string(&value),
+            start: *start,
+            end: *end,
+          });
+        }
+        Node::Comment { .. } => {}
+      }
+    }
+    Ok(())
+  }
+
+  /// 渲染元素并处理条件与循环，返回之后的条件链状态
+  fn render_element(
+    &mut self,
+    node: &'a Node,
+    chain: Chain,
+    out: &mut Vec<Node>,
+  ) -> Result<Chain, RenderError> {
+    // wx:for 的优先级高于 wx:if，条件在每次迭代中分别求值
+    if let Some(attr) = node.attr("wx:for") {
+      let items = self.eval_attr(attr)?;
+      let item_name = static_attr(node, "wx:for-item").unwrap_or_else(|| "item".to_string());
+      let index_name = static_attr(node, "wx:for-index").unwrap_or_else(|| "index".to_string());
+      for (item, index) in iterate(items) {
+        self.scopes.push(Map::from_iter([
+          (item_name.clone(), item),
+          (index_name.clone(), index),
+        ]));
+        let result = self.render_conditional(node, Chain::None, out);
+        self.scopes.pop();
+        result?;
+      }
+      return Ok(Chain::None);
+    }
+    self.render_conditional(node, chain, out)
+  }
+
+  fn render_conditional(
+    &mut self,
+    node: &'a Node,
+    chain: Chain,
+    out: &mut Vec<Node>,
+  ) -> Result<Chain, RenderError> {
+    let (condition, next) = if let Some(attr) = node.attr("wx:if") {
+      Chain::branch(truthy(&self.eval_attr(attr)?))
+    } else if let Some(attr) = node.attr("wx:elif") {
+      if chain == Chain::Matched {
+        return Ok(Chain::Matched);
+      }
+      Chain::branch(truthy(&self.eval_attr(attr)?))
+    } else if node.attr("wx:else").is_some() {
+      (chain != Chain::Matched, Chain::None)
+    } else {
+      (true, Chain::None)
+    };
+    if condition {
+      self.render_node(node, out)?;
+    }
+    Ok(next)
+  }
+
+  fn render_node(&mut self, node: &'a Node, out: &mut Vec<Node>) -> Result<(), RenderError> {
+    let Node::Element {
+      name,
+      attrs,
+      children,
+      self_closing,
+      first_attr_same_line,
+      recovered,
+      close_start,
+      close_end,
+      start,
+      end,
+    } = node
+    else {
+      return Ok(());
+    };
+    match name.as_str() {
+      "block" => self.render_children(children, out),
+      "template" => match node.attr("is") {
+        Some(attr) => self.render_template(node, attr, out),
+        // 模板定义不输出
+        None => Ok(()),
+      },
+      "import" | "include" | "wxs" => Ok(()),
+      _ => {
+        let mut rendered_attrs = Vec::new();
+        for attr in attrs {
+          if attr.prefix.as_deref() == Some("wx") {
+            continue;
+          }
+          let value = match &attr.value {
+            Some(_) => Some(vec![AttributeValue::Text {
+              content: to_display_string(&self.eval_attr(attr)?),
+              start: attr.start,
+              end: attr.end,
+            }]),
+            None => None,
+          };
+          rendered_attrs.push(Attribute {
+            value,
+            ..attr.clone()
+          });
+        }
+        let mut rendered_children = Vec::new();
+        self.render_children(children, &mut rendered_children)?;
+        out.push(Node::Element {
+          name: name.clone(),
+          attrs: rendered_attrs,
+          children: rendered_children,
+          self_closing: *self_closing,
+          first_attr_same_line: *first_attr_same_line,
+          recovered: *recovered,
+          close_start: *close_start,
+          close_end: *close_end,
+          start: *start,
+          end: *end,
+        });
+        Ok(())
+      }
+    }
+  }
+
+  fn render_template(
+    &mut self,
+    node: &'a Node,
+    is: &Attribute,
+    out: &mut Vec<Node>,
+  ) -> Result<(), RenderError> {
+    let error = |message: String| RenderError {
+      message,
+      start: is.start,
+      end: is.end,
+    };
+    let name = to_display_string(&self.eval_attr(is)?);
+    let template = *self
+      .templates
+      .get(&name)
+      .ok_or_else(|| error(format!("template `{name}` is not defined")))?;
+    if self.depth >= MAX_TEMPLATE_DEPTH {
+      return Err(error(format!("template `{name}` is nested too deeply")));
+    }
+
+    // `data="{{ ...item, index }}"` 的内容是省略了花括号的对象字面量
+    let data = match node.attr("data") {
+      Some(attr) => match attr.value.as_deref() {
+        Some(
+          [AttributeValue::Expression {
+            content,
+            start,
+            end,
+          }],
+        ) => {
+          let properties = parse_object_body(content).map_err(|e| RenderError {
+            message: format!("invalid template data `{content}`: {}", e.message),
+            start: *start,
+            end: *end,
+          })?;
+          match self.eval_expr(&Expr::Object(properties), *start, *end)? {
+            Value::Object(data) => data,
+            _ => Map::new(),
+          }
+        }
+        _ => Map::new(),
+      },
+      None => Map::new(),
+    };
+
+    // 模板只能访问传入的数据
+    let scopes = mem::replace(&mut self.scopes, vec![data]);
+    self.depth += 1;
+    let result = self.render_children(template.children().unwrap_or_default(), out);
+    self.depth -= 1;
+    self.scopes = scopes;
+    result
+  }
+
+  fn eval_expr(&self, expr: &Expr, start: Position, end: Position) -> Result<Value, RenderError> {
+    self
+      .sandbox
+      .evaluate_with(expr, &|name| self.lookup(name))
+      .map_err(|error| RenderError {
+        message: error.message,
+        start,
+        end,
+      })
+  }
+}
+
+fn static_attr(node: &Node, name: &str) -> Option<String> {
+  node.attr(name).and_then(Attribute::as_static_string)
+}
+
+/// `wx:for` 可遍历的值：数组、对象（以键为索引）、字符串与数字
+fn iterate(items: Value) -> Vec<(Value, Value)> {
+  match items {
+    Value::Array(items) => items
+      .into_iter()
+      .enumerate()
+      .map(|(index, item)| (item, Value::from(index)))
+      .collect(),
+    Value::Object(items) => items
+      .into_iter()
+      .map(|(key, item)| (item, Value::String(key)))
+      .collect(),
+    Value::String(items) => items
+      .chars()
+      .enumerate()
+      .map(|(index, ch)| (Value::String(ch.to_string()), Value::from(index)))
+      .collect(),
+    Value::Number(count) => (0..count.as_u64().unwrap_or(0))
+      .map(|index| (Value::from(index), Value::from(index)))
+      .collect(),
+    _ => Vec::new(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::parser::Parser;
+  use serde_json::json;
+
+  #[test]
+  fn render_template() {
+    let source = r#"<template name="row"><text>{{index}}:{{name}}</text></template>
+<view class="list {{cls}}">
+  <block wx:for="{{users}}" wx:for-item="user" wx:key="id">
+    <template wx:if="{{user.active}}" is="row" data="{{...user, index}}"/>
+    <text wx:elif="{{user.age > 60}}">senior</text>
+    <text wx:else>-</text>
+  </block>
+</view>
+<!-- note -->
+<view wx:if="{{users.length === 0}}">empty</view>"#;
+    let root = Parser::new(source).parse_root().unwrap();
+    let data = json!({
+      "cls": "dark",
+      "users": [
+        { "id": 1, "name": "Ann", "active": true },
+        { "id": 2, "name": "Bob", "age": 70 },
+        { "id": 3, "name": "Cid" },
+      ]
+    });
+    let html = render_to_html(&root, &data).unwrap();
+    assert_eq!(
+      html,
+      "<view class=\"list dark\"><text>0:Ann</text><text>senior</text><text>-</text></view>"
+    );
+  }
+
+  #[test]
+  fn html_escapes_data() {
+    let root =
+      Parser::new(r#"<view title="{{title}}" hidden>{{text}}</view><image src="{{src}}"/>"#)
+        .parse_root()
+        .unwrap();
+    let data = json!({
+      "title": "\"a\" & <b>",
+      "text": "<y> & {{x}}",
+      "src": "a.png?x=1&y=2",
+    });
+    assert_eq!(
+      render_to_html(&root, &data).unwrap(),
+      "<view title=\"&quot;a&quot; &amp; &lt;b&gt;\" hidden>&lt;y&gt; &amp; {{x}}</view><image src=\"a.png?x=1&amp;y=2\"/>"
+    );
+  }
+}
@@ -0,0 +1,363 @@
+//! 组件属性约束校验
+//!
+//! 组件库作者以 JSON 描述组件可接受的属性，模板按该约束校验，作为机器可检查的使用约定：
+//!
+//! ```json
+//! {
+//!   "my-button": {
+//!     "attributes": {
+//!       "type": { "type": "string", "enum": ["primary", "default"] },
+//!       "size": { "type": ["string", "number"] },
+//!       "label": { "type": "string", "required": true }
+//!     },
+//!     "additionalAttributes": false
+//!   }
+//! }
+//! ```
+//!
+//! 属性值按以下方式确定类型与取值，无法静态确定的值不做检查：
+//!
+//! - 无值属性为布尔值 `true`
+//! - 静态文本为字符串，可以转换为数字时同时满足 `number`
+//! - 只有一个不引用变量的表达式时为表达式的值，如 `{{ 12 }}`、`{{ [1, 2] }}`
+//! - 静态文本与表达式拼接时为字符串，取值无法确定
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+
+use crate::{
+  ast::{Attribute, AttributeValue, Node, Position, Root},
+  builtins::is_common_attr,
+  optimize::constant_value,
+};
+
+/// 属性值的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValueType {
+  String,
+  Number,
+  Boolean,
+  Object,
+  Array,
+  Null,
+}
+
+impl ValueType {
+  fn of(value: &Value) -> Self {
+    match value {
+      Value::Null => ValueType::Null,
+      Value::Bool(_) => ValueType::Boolean,
+      Value::Number(_) => ValueType::Number,
+      Value::String(_) => ValueType::String,
+      Value::Array(_) => ValueType::Array,
+      Value::Object(_) => ValueType::Object,
+    }
+  }
+
+  fn as_str(&self) -> &'static str {
+    match self {
+      ValueType::String => "string",
+      ValueType::Number => "number",
+      ValueType::Boolean => "boolean",
+      ValueType::Object => "object",
+      ValueType::Array => "array",
+      ValueType::Null => "null",
+    }
+  }
+}
+
+/// 单个属性的约束
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AttributeSchema {
+  /// 允许的类型，JSON 中可以写为单个类型或数组；为空表示不限制
+  #[serde(rename = "type", deserialize_with = "one_or_many")]
+  pub types: Vec<ValueType>,
+  /// 允许的取值；为空表示不限制
+  #[serde(rename = "enum")]
+  pub values: Vec<Value>,
+  /// 是否必须设置
+  pub required: bool,
+}
+
+/// 单个组件的约束
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ComponentSchema {
+  pub attributes: BTreeMap<String, AttributeSchema>,
+  /// 是否允许未声明的属性，默认允许；通用属性、事件绑定与指令总是允许
+  pub additional_attributes: bool,
+}
+
+impl Default for ComponentSchema {
+  fn default() -> Self {
+    Self {
+      attributes: BTreeMap::new(),
+      additional_attributes: true,
+    }
+  }
+}
+
+/// 按标签名索引的组件约束
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ComponentSchemas {
+  pub components: BTreeMap<String, ComponentSchema>,
+}
+
+impl ComponentSchemas {
+  /// 从 JSON 配置读取，格式见[模块文档](self)
+  pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+    serde_json::from_str(json)
+  }
+}
+
+fn one_or_many<'de, D>(deserializer: D) -> Result<Vec<ValueType>, D::Error>
+where
+  D: Deserializer<'de>,
+{
+  #[derive(Deserialize)]
+  #[serde(untagged)]
+  enum OneOrMany {
+    One(ValueType),
+    Many(Vec<ValueType>),
+  }
+  Ok(match OneOrMany::deserialize(deserializer)? {
+    OneOrMany::One(value) => vec![value],
+    OneOrMany::Many(values) => values,
+  })
+}
+
+/// 违反约束的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SchemaViolationKind {
+  /// 缺少必需的属性
+  MissingAttribute,
+  /// 不允许未声明的属性时使用了未声明的属性
+  UnknownAttribute,
+  /// 属性值的类型不在允许的类型中
+  InvalidType,
+  /// 属性值不在允许的取值中
+  InvalidValue,
+}
+
+/// 一处违反约束的用法
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaViolation {
+  pub kind: SchemaViolationKind,
+  /// 组件标签名
+  pub component: String,
+  pub attribute: String,
+  pub message: String,
+  /// 属性的位置；缺少属性时为开始标签中标签名的位置
+  pub start: Position,
+  pub end: Position,
+}
+
+/// 属性值静态可确定的部分
+enum Resolved {
+  /// 静态文本
+  Text(String),
+  /// 无值属性或常量表达式的值
+  Constant(Value),
+  /// 拼接得到的字符串，取值无法确定
+  String,
+  /// 无法确定
+  Unknown,
+}
+
+impl Resolved {
+  fn of(attr: &Attribute) -> Self {
+    let Some(values) = attr.value.as_deref() else {
+      return Resolved::Constant(Value::Bool(true));
+    };
+    if let Some(text) = attr.as_static_string() {
+      return Resolved::Text(text);
+    }
+    match values {
+      [AttributeValue::Expression { content, .. }] => match constant_value(content) {
+        Some(value) => Resolved::Constant(value),
+        None => Resolved::Unknown,
+      },
+      _ => Resolved::String,
+    }
+  }
+
+  fn has_type(&self, ty: ValueType) -> bool {
+    match self {
+      Resolved::Text(text) => {
+        ty == ValueType::String || (ty == ValueType::Number && text.trim().parse::<f64>().is_ok())
+      }
+      Resolved::Constant(value) => ValueType::of(value) == ty,
+      Resolved::String => ty == ValueType::String,
+      Resolved::Unknown => true,
+    }
+  }
+
+  fn is_one_of(&self, values: &[Value]) -> bool {
+    match self {
+      Resolved::Text(text) => values.iter().any(|value| match value {
+        Value::String(value) => value == text,
+        Value::Number(value) => text.trim().parse::<f64>().ok() == value.as_f64(),
+        _ => false,
+      }),
+      Resolved::Constant(constant) => values.iter().any(|value| match (value, constant) {
+        (Value::Number(left), Value::Number(right)) => left.as_f64() == right.as_f64(),
+        _ => value == constant,
+      }),
+      Resolved::String | Resolved::Unknown => true,
+    }
+  }
+}
+
+/// 按组件约束校验模板，按文档顺序返回违反约束的用法
+pub fn validate_schema(root: &Root, schemas: &ComponentSchemas) -> Vec<SchemaViolation> {
+  let mut violations = Vec::new();
+  root.walk(&mut |node| {
+    let Node::Element {
+      name, attrs, start, ..
+    } = node
+    else {
+      return;
+    };
+    let Some(schema) = schemas.components.get(name) else {
+      return;
+    };
+    let mut violation = |kind, attribute: &str, message: String, start, end| {
+      violations.push(SchemaViolation {
+        kind,
+        component: name.clone(),
+        attribute: attribute.to_string(),
+        message,
+        start,
+        end,
+      })
+    };
+    for attr in attrs {
+      // 简易双向绑定 `model:value` 按其绑定的属性校验
+      let attr_name = match attr.prefix.as_deref() {
+        Some("model") => &attr.local,
+        _ => &attr.name,
+      };
+      let Some(attr_schema) = schema.attributes.get(attr_name) else {
+        if !schema.additional_attributes && !is_common_attr(&attr.name) {
+          violation(
+            SchemaViolationKind::UnknownAttribute,
+            attr_name,
+            format!("unknown attribute `{}` on <{}>", attr.name, name),
+            attr.start,
+            attr.end,
+          );
+        }
+        continue;
+      };
+      let resolved = Resolved::of(attr);
+      if !attr_schema.types.is_empty() && !attr_schema.types.iter().any(|ty| resolved.has_type(*ty))
+      {
+        let expected: Vec<_> = attr_schema.types.iter().map(ValueType::as_str).collect();
+        violation(
+          SchemaViolationKind::InvalidType,
+          attr_name,
+          format!(
+            "`{}` on <{}> expects {}",
+            attr.name,
+            name,
+            expected.join(" or ")
+          ),
+          attr.start,
+          attr.end,
+        );
+      } else if !attr_schema.values.is_empty() && !resolved.is_one_of(&attr_schema.values) {
+        let expected: Vec<_> = attr_schema.values.iter().map(Value::to_string).collect();
+        violation(
+          SchemaViolationKind::InvalidValue,
+          attr_name,
+          format!(
+            "invalid value for `{}` on <{}>, expected one of: {}",
+            attr.name,
+            name,
+            expected.join(", ")
+          ),
+          attr.start,
+          attr.end,
+        );
+      }
+    }
+    let name_start = start.advance("<");
+    for (attr_name, attr_schema) in &schema.attributes {
+      let present = attrs.iter().any(|attr| {
+        attr.name == *attr_name
+          || (attr.prefix.as_deref() == Some("model") && attr.local == *attr_name)
+      });
+      if attr_schema.required && !present {
+        violation(
+          SchemaViolationKind::MissingAttribute,
+          attr_name,
+          format!("<{name}> requires attribute `{attr_name}`"),
+          name_start,
+          name_start.advance(name),
+        );
+      }
+    }
+  });
+  violations
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::parser::Parser;
+
+  #[test]
+  fn schema_validation() {
+    let schemas = ComponentSchemas::from_json(
+      r#"{
+        "my-button": {
+          "attributes": {
+            "type": { "type": "string", "enum": ["primary", "default"] },
+            "size": { "type": ["string", "number"], "enum": ["small", 24] },
+            "label": { "type": "string", "required": true },
+            "disabled": { "type": "boolean" }
+          },
+          "additionalAttributes": false
+        }
+      }"#,
+    )
+    .unwrap();
+    let source = r#"<my-button type="primary" size="{{24}}" label="{{a}}" disabled bindtap="t"/>
+<my-button type="warn" size="{{[1]}}" disabled="{{1}}" colour="red" label="x-{{a}}"/>
+<my-button type="{{t}}" size="24"/>"#;
+    let root = Parser::new(source).parse_root().unwrap();
+    let found: Vec<_> = validate_schema(&root, &schemas)
+      .into_iter()
+      .map(|violation| (violation.kind, violation.attribute, violation.start.offset))
+      .collect();
+    assert_eq!(
+      found,
+      [
+        (SchemaViolationKind::InvalidValue, "type".to_string(), 88),
+        (SchemaViolationKind::InvalidType, "size".to_string(), 100),
+        (
+          SchemaViolationKind::InvalidType,
+          "disabled".to_string(),
+          115
+        ),
+        (
+          SchemaViolationKind::UnknownAttribute,
+          "colour".to_string(),
+          132
+        ),
+        (
+          SchemaViolationKind::MissingAttribute,
+          "label".to_string(),
+          164
+        ),
+      ]
+    );
+  }
+}
@@ -0,0 +1,229 @@
+//! 动态内容安全审计
+//!
+//! 查找模板中把运行时数据用于敏感位置的写法，生成供安全审计使用的报告：
+//!
+//! - `<web-view src>` 中包含 `{{ }}`：可能加载任意页面。地址的协议与域名为静态文本时风险较低
+//! - `<rich-text nodes>` 直接绑定数据而不经过函数处理：可能渲染未过滤的富文本
+//! - 内联 `<wxs>`（及其他方言的脚本标签）中以非字面量调用 `getRegExp`：用户数据构造的正则表达式可能导致 ReDoS
+//!
+//! 审计基于模板写法的启发式规则，结果需要人工确认
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  ast::{AttributeValue, Node, Position, Root},
+  dialect::Dialect,
+  expr::{parse_expression, Expr},
+};
+
+/// 风险类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SecurityRisk {
+  DynamicWebViewSrc,
+  UnfilteredRichText,
+  DynamicRegExp,
+}
+
+/// 风险等级
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RiskLevel {
+  Low,
+  Medium,
+  High,
+}
+
+/// 一处风险
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecurityFinding {
+  pub risk: SecurityRisk,
+  pub level: RiskLevel,
+  pub message: String,
+  /// 有风险的属性，或 `getRegExp` 调用的位置
+  pub start: Position,
+  pub end: Position,
+}
+
+/// 安全审计报告
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecurityReport {
+  /// 按出现顺序排列的风险
+  pub findings: Vec<SecurityFinding>,
+}
+
+impl SecurityReport {
+  /// 风险等级不低于 `level` 的风险
+  pub fn at_least(&self, level: RiskLevel) -> impl Iterator<Item = &SecurityFinding> {
+    self
+      .findings
+      .iter()
+      .filter(move |finding| finding.level >= level)
+  }
+}
+
+/// 审计模板中的动态内容，见[模块文档](self)
+pub fn audit_security(root: &Root) -> SecurityReport {
+  let mut report = SecurityReport::default();
+  root.walk(&mut |node| {
+    let Node::Element { name, children, .. } = node else {
+      return;
+    };
+    match name.as_str() {
+      "web-view" => check_web_view(node, &mut report),
+      "rich-text" => check_rich_text(node, &mut report),
+      _ if Dialect::ALL
+        .iter()
+        .any(|dialect| dialect.script_tag() == name) =>
+      {
+        for child in children {
+          if let Node::Text { content, start, .. } = child {
+            check_script(content, *start, &mut report);
+          }
+        }
+      }
+      _ => {}
+    }
+  });
+  report
+}
+
+fn check_web_view(node: &Node, report: &mut SecurityReport) {
+  let Some(attr) = node.attr("src") else {
+    return;
+  };
+  let values = attr.value.as_deref().unwrap_or_default();
+  let Some(first) = values
+    .iter()
+    .position(|value| matches!(value, AttributeValue::Expression { .. }))
+  else {
+    return;
+  };
+  // 表达式之前的静态文本已经包含完整的协议与域名，如 `https://example.com/{{path}}`
+  let prefix: String = values[..first]
+    .iter()
+    .map(|value| match value {
+      AttributeValue::Text { content, .. } => content.as_str(),
+      AttributeValue::Expression { .. } => "",
+    })
+    .collect();
+  let fixed_origin = prefix
+    .strip_prefix("https://")
+    .is_some_and(|rest| rest.find('/').is_some_and(|slash| slash > 0));
+  let (level, message) = if fixed_origin {
+    (
+      RiskLevel::Medium,
+      "`<web-view src>` contains dynamic path segments, make sure they cannot change the page",
+    )
+  } else {
+    (
+      RiskLevel::High,
+      "`<web-view src>` is built from runtime data and may load an arbitrary page",
+    )
+  };
+  report.findings.push(SecurityFinding {
+    risk: SecurityRisk::DynamicWebViewSrc,
+    level,
+    message: message.to_string(),
+    start: attr.start,
+    end: attr.end,
+  });
+}
+
+fn check_rich_text(node: &Node, report: &mut SecurityReport) {
+  let Some(attr) = node.attr("nodes") else {
+    return;
+  };
+  let Some([AttributeValue::Expression { content, .. }]) = attr.value.as_deref() else {
+    return;
+  };
+  // 经过函数（如 wxs 模块中的过滤函数）处理的数据视为已过滤
+  if matches!(parse_expression(content), Ok(Expr::Call { .. })) {
+    return;
+  }
+  report.findings.push(SecurityFinding {
+    risk: SecurityRisk::UnfilteredRichText,
+    level: RiskLevel::High,
+    message: format!("`<rich-text nodes>` is bound to `{content}` without filtering"),
+    start: attr.start,
+    end: attr.end,
+  });
+}
+
+fn check_script(source: &str, start: Position, report: &mut SecurityReport) {
+  const CALL: &str = "getRegExp(";
+  let mut index = 0;
+  while let Some(found) = source[index..].find(CALL) {
+    let call = index + found;
+    index = call + CALL.len();
+    if is_string_literal_argument(&source[index..]) {
+      continue;
+    }
+    let call_start = start.advance(&source[..call]);
+    report.findings.push(SecurityFinding {
+      risk: SecurityRisk::DynamicRegExp,
+      level: RiskLevel::Medium,
+      message:
+        "`getRegExp` is called with a non-literal pattern, which may be built from user data"
+          .to_string(),
+      start: call_start,
+      end: call_start.advance(CALL),
+    });
+  }
+}
+
+/// 第一个参数是否为单个字符串字面量，`arguments` 为 `(` 之后的内容
+fn is_string_literal_argument(arguments: &str) -> bool {
+  let arguments = arguments.trim_start();
+  let Some(quote) = arguments.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+    return false;
+  };
+  let mut escaped = false;
+  for (index, ch) in arguments.char_indices().skip(1) {
+    match ch {
+      _ if escaped => escaped = false,
+      '\\' => escaped = true,
+      _ if ch == quote => {
+        let rest = arguments[index + 1..].trim_start();
+        return rest.starts_with(',') || rest.starts_with(')');
+      }
+      _ => {}
+    }
+  }
+  false
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::parser::Parser;
+
+  #[test]
+  fn security_audit() {
+    let root = Parser::new(
+      r#"<web-view src="{{url}}"/><web-view src="https://example.com/page?id={{id}}"/><web-view src="https://example.com"/>
+<rich-text nodes="{{article.html}}"/><rich-text nodes="{{safe.filter(article.html)}}"/>
+<wxs module="m">var a = getRegExp('^\\d+$', 'g'); var b = getRegExp(input + '.*');</wxs>"#,
+    )
+    .parse_root()
+    .unwrap();
+    let report = audit_security(&root);
+    let findings: Vec<_> = report
+      .findings
+      .iter()
+      .map(|finding| (finding.risk, finding.level, finding.start.offset))
+      .collect();
+    assert_eq!(
+      findings,
+      [
+        (SecurityRisk::DynamicWebViewSrc, RiskLevel::High, 10),
+        (SecurityRisk::DynamicWebViewSrc, RiskLevel::Medium, 35),
+        (SecurityRisk::UnfilteredRichText, RiskLevel::High, 126),
+        (SecurityRisk::DynamicRegExp, RiskLevel::Medium, 261),
+      ]
+    );
+    assert_eq!(report.at_least(RiskLevel::High).count(), 2);
+  }
+}
@@ -0,0 +1,367 @@
+//! 语义高亮
+//!
+//! 将文档中的标签名、属性名、属性值、注释以及 `{{ }}` 中的变量、字面量、运算符划分为带种类的片段，
+//! 对应 LSP 的 semantic tokens，编辑器据此对 WXML 做比正则语法更准确的高亮。
+//!
+//! 语法树中的表达式内容已去掉首尾空白，因此需要同时传入源码以定位表达式中的每个片段。
+//! 表达式按词法扫描而不是完整解析，输入过程中不完整的表达式同样可以得到高亮
+
+#[cfg(feature = "napi")]
+use napi_derive::napi;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  ast::{Attribute, AttributeValue, Node, Position, Root},
+  dialect::Directive,
+  expr::PUNCTUATORS,
+  vue::{classify_vue_attr, VueAttrKind},
+};
+
+/// 片段种类
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "napi", napi(string_enum = "lowercase"))]
+#[serde(rename_all = "lowercase")]
+pub enum SemanticTokenKind {
+  /// 开始与结束标签中的标签名
+  Tag,
+  /// 普通属性名
+  Attribute,
+  /// 指令属性名，如 `wx:if`、`s-for`
+  Directive,
+  /// 事件绑定属性名，如 `bindtap`、`catch:tap`
+  Event,
+  /// 属性值中的静态文本以及表达式中的字符串字面量
+  String,
+  Comment,
+  /// 插值的 `{{`、`}}`
+  Delimiter,
+  Variable,
+  /// 成员访问 `a.b` 的 `b` 与对象字面量的键
+  Property,
+  /// 被调用的函数或方法
+  Function,
+  /// `true`、`false`、`null`、`undefined`
+  Keyword,
+  Number,
+  Operator,
+}
+
+impl SemanticTokenKind {
+  /// 所有种类，可直接作为 LSP 的 token legend，下标即为编码时使用的类型编号
+  pub const ALL: [SemanticTokenKind; 13] = [
+    SemanticTokenKind::Tag,
+    SemanticTokenKind::Attribute,
+    SemanticTokenKind::Directive,
+    SemanticTokenKind::Event,
+    SemanticTokenKind::String,
+    SemanticTokenKind::Comment,
+    SemanticTokenKind::Delimiter,
+    SemanticTokenKind::Variable,
+    SemanticTokenKind::Property,
+    SemanticTokenKind::Function,
+    SemanticTokenKind::Keyword,
+    SemanticTokenKind::Number,
+    SemanticTokenKind::Operator,
+  ];
+
+  /// 最接近的 LSP 标准 token 类型，供不支持自定义类型的客户端使用
+  pub fn lsp_type(&self) -> &'static str {
+    match self {
+      SemanticTokenKind::Tag => "type",
+      SemanticTokenKind::Attribute => "parameter",
+      SemanticTokenKind::Directive => "macro",
+      SemanticTokenKind::Event => "event",
+      SemanticTokenKind::String => "string",
+      SemanticTokenKind::Comment => "comment",
+      SemanticTokenKind::Delimiter | SemanticTokenKind::Operator => "operator",
+      SemanticTokenKind::Variable => "variable",
+      SemanticTokenKind::Property => "property",
+      SemanticTokenKind::Function => "function",
+      SemanticTokenKind::Keyword => "keyword",
+      SemanticTokenKind::Number => "number",
+    }
+  }
+}
+
+/// 一个高亮片段，注释等片段可能跨越多行
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi", napi(object))]
+pub struct SemanticToken {
+  pub kind: SemanticTokenKind,
+  pub start: Position,
+  pub end: Position,
+}
+
+/// 按文档顺序输出高亮片段，`source` 必须是解析得到 `root` 的源码；合成节点不输出片段
+pub fn semantic_tokens(root: &Root, source: &str) -> Vec<SemanticToken> {
+  let mut collector = Collector {
+    source,
+    tokens: Vec::new(),
+  };
+  for node in &root.children {
+    collector.node(node);
+  }
+  collector.tokens
+}
+
+struct Collector<'s> {
+  source: &'s str,
+  tokens: Vec<SemanticToken>,
+}
+
+impl<'s> Collector<'s> {
+  fn push(&mut self, kind: SemanticTokenKind, start: Position, end: Position) {
+    if !start.is_synthetic() && start.offset < end.offset {
+      self.tokens.push(SemanticToken { kind, start, end });
+    }
+  }
+
+  fn slice(&self, start: Position, end: Position) -> Option<&'s str> {
+    if start.is_synthetic() {
+      return None;
+    }
+    self.source.get(start.offset as usize..end.offset as usize)
+  }
+
+  fn node(&mut self, node: &Node) {
+    match node {
+      Node::Element {
+        name,
+        attrs,
+        children,
+        self_closing,
+        start,
+        end,
+        ..
+      } => {
+        let name_start = start.advance("<");
+        self.push(SemanticTokenKind::Tag, name_start, name_start.advance(name));
+        for attr in attrs {
+          self.attribute(attr);
+        }
+        for child in children {
+          self.node(child);
+        }
+        if !self_closing {
+          self.close_tag(name, *start, *end);
+        }
+      }
+      Node::Comment { start, end, .. } => self.push(SemanticTokenKind::Comment, *start, *end),
+      Node::Expression {
+        content,
+        start,
+        end,
+      } => self.expression(content, *start, *end),
+      Node::Text { .. } => {}
+    }
+  }
+
+  /// 元素以 `</name>` 结尾时输出结束标签中的标签名，未闭合的元素没有结束标签
+  fn close_tag(&mut self, name: &str, start: Position, end: Position) {
+    let Some(raw) = self.slice(start, end) else {
+      return;
+    };
+    let Some(index) = raw.rfind("</") else {
+      return;
+    };
+    let rest = &raw[index + 2..];
+    let closed = rest
+      .strip_prefix(name)
+      .is_some_and(|rest| rest.trim_start() == ">");
+    if closed {
+      let name_start = start.advance(&raw[..index + 2]);
+      self.push(SemanticTokenKind::Tag, name_start, name_start.advance(name));
+    }
+  }
+
+  fn attribute(&mut self, attr: &Attribute) {
+    let kind = if attr.prefix.as_deref() == Some("wx") || Directive::of(&attr.name).is_some() {
+      SemanticTokenKind::Directive
+    } else if attr.is_event_binding() {
+      SemanticTokenKind::Event
+    } else {
+      match classify_vue_attr(&attr.name) {
+        Some(vue) if vue.kind == VueAttrKind::On => SemanticTokenKind::Event,
+        Some(_) => SemanticTokenKind::Directive,
+        None => SemanticTokenKind::Attribute,
+      }
+    };
+    self.push(kind, attr.start, attr.start.advance(&attr.name));
+    for value in attr.value.iter().flatten() {
+      match value {
+        AttributeValue::Text { start, end, .. } => {
+          self.push(SemanticTokenKind::String, *start, *end)
+        }
+        AttributeValue::Expression {
+          content,
+          start,
+          end,
+        } => self.expression(content, *start, *end),
+      }
+    }
+  }
+
+  /// `start..end` 为包括 `{{ }}` 的范围；Vue 兼容模式下整个属性值为表达式，没有 `{{ }}`
+  fn expression(&mut self, content: &str, start: Position, end: Position) {
+    let Some(raw) = self.slice(start, end) else {
+      return;
+    };
+    let (open, inner) = match raw
+      .strip_prefix("{{")
+      .and_then(|rest| rest.strip_suffix("}}"))
+    {
+      Some(inner) => (2, inner),
+      None => (0, raw),
+    };
+    // 源码与语法树不一致时不输出表达式内部的片段
+    if inner.trim() != content {
+      return;
+    }
+    if open > 0 {
+      self.push(SemanticTokenKind::Delimiter, start, start.advance("{{"));
+    }
+    let leading = inner.len() - inner.trim_start().len();
+    self.scan(content, start.advance(&raw[..open + leading]));
+    if open > 0 {
+      let close = start.advance(&raw[..raw.len() - 2]);
+      self.push(SemanticTokenKind::Delimiter, close, end);
+    }
+  }
+
+  /// 按词法扫描表达式，`base` 为 `text` 的起始位置
+  fn scan(&mut self, text: &str, base: Position) {
+    let bytes = text.as_bytes();
+    // 上一个位置及其下标，按顺序推进以避免重复计算行列
+    let mut cursor = (0, base);
+    let mut at = |index: usize| {
+      cursor.1 = cursor.1.advance(&text[cursor.0..index]);
+      cursor.0 = index;
+      cursor.1
+    };
+    let mut prev_punct: Option<&str> = None;
+    let mut pos = 0;
+    while pos < text.len() {
+      let ch = text[pos..].chars().next().unwrap();
+      let start = pos;
+      let kind = if ch.is_whitespace() {
+        pos += ch.len_utf8();
+        continue;
+      } else if ch.is_ascii_digit()
+        || (ch == '.' && bytes.get(pos + 1).is_some_and(u8::is_ascii_digit))
+      {
+        while pos < text.len() && (bytes[pos].is_ascii_alphanumeric() || bytes[pos] == b'.') {
+          pos += 1;
+        }
+        SemanticTokenKind::Number
+      } else if ch == '"' || ch == '\'' {
+        pos += string_len(&text[pos..]);
+        SemanticTokenKind::String
+      } else if ch.is_alphabetic() || ch == '_' || ch == '$' {
+        pos += text[pos..]
+          .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+          .unwrap_or(text.len() - pos);
+        let next = text[pos..].trim_start();
+        if next.starts_with('(') {
+          SemanticTokenKind::Function
+        } else if prev_punct == Some(".")
+          || (matches!(prev_punct, Some("{" | ",")) && next.starts_with(':'))
+        {
+          SemanticTokenKind::Property
+        } else if matches!(&text[start..pos], "true" | "false" | "null" | "undefined") {
+          SemanticTokenKind::Keyword
+        } else {
+          SemanticTokenKind::Variable
+        }
+      } else if let Some(punct) = PUNCTUATORS
+        .iter()
+        .find(|punct| text[pos..].starts_with(**punct))
+      {
+        pos += punct.len();
+        prev_punct = Some(punct);
+        // 括号、逗号与成员访问的 `.` 不输出片段
+        if matches!(*punct, "(" | ")" | "[" | "]" | "{" | "}" | "," | ".") {
+          continue;
+        }
+        let (start, end) = (at(start), at(pos));
+        self.push(SemanticTokenKind::Operator, start, end);
+        continue;
+      } else {
+        pos += ch.len_utf8();
+        continue;
+      };
+      prev_punct = None;
+      let (start, end) = (at(start), at(pos));
+      self.push(kind, start, end);
+    }
+  }
+}
+
+/// 以引号开头的字符串字面量的字节长度，未闭合时到文本末尾
+fn string_len(text: &str) -> usize {
+  let mut chars = text.char_indices();
+  let Some((_, quote)) = chars.next() else {
+    return 0;
+  };
+  while let Some((index, ch)) = chars.next() {
+    if ch == '\\' {
+      chars.next();
+    } else if ch == quote {
+      return index + 1;
+    }
+  }
+  text.len()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::parser::Parser;
+
+  #[test]
+  fn classify_tokens() {
+    let source = r#"<view wx:if="{{ user.name(1) }}" class="a {{x}}" bindtap="go">
+  <!-- c -->{{ ok ? {key: 'v'} : null }}
+</view>"#;
+    let root = Parser::new(source).parse_root().unwrap();
+    let tokens: Vec<_> = semantic_tokens(&root, source)
+      .into_iter()
+      .map(|token| {
+        let text = &source[token.start.offset as usize..token.end.offset as usize];
+        format!("{:?} {text}", token.kind)
+      })
+      .collect();
+    assert_eq!(
+      tokens,
+      [
+        "Tag view",
+        "Directive wx:if",
+        "Delimiter {{",
+        "Variable user",
+        "Function name",
+        "Number 1",
+        "Delimiter }}",
+        "Attribute class",
+        "String a ",
+        "Delimiter {{",
+        "Variable x",
+        "Delimiter }}",
+        "Event bindtap",
+        "String go",
+        "Comment <!-- c -->",
+        "Delimiter {{",
+        "Variable ok",
+        "Operator ?",
+        "Property key",
+        "Operator :",
+        "String 'v'",
+        "Operator :",
+        "Keyword null",
+        "Delimiter }}",
+        "Tag view",
+      ]
+    );
+    let close = semantic_tokens(&root, source).last().copied().unwrap();
+    assert_eq!((close.start.line, close.start.column), (3, 3));
+  }
+}
@@ -0,0 +1,165 @@
+//! 插槽（slot）分析
+//!
+//! 组件模板通过 `<slot name="...">` 声明插槽，使用方通过子节点上的 `slot="..."` 指定内容放入哪个插槽。
+//! 这里分别收集两侧的信息，并检查使用方是否用到了组件未声明的插槽
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  ast::{Attribute, Node, Position, Root},
+  builtins::is_builtin_tag,
+};
+
+/// 组件模板中的一个 `<slot>` 声明
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlotDeclaration {
+  /// 插槽名称，默认插槽为 `None`
+  pub name: Option<String>,
+  /// 名称包含表达式，无法静态确定
+  pub dynamic: bool,
+  pub start: Position,
+  pub end: Position,
+}
+
+/// 使用组件时放入插槽的一个子节点
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlotUsage {
+  /// 目标插槽名称，放入默认插槽时为 `None`
+  pub name: Option<String>,
+  /// `slot` 属性包含表达式，无法静态确定
+  pub dynamic: bool,
+  pub start: Position,
+  pub end: Position,
+}
+
+/// 收集组件模板中的所有插槽声明，按出现顺序排列
+pub fn slot_declarations(root: &Root) -> Vec<SlotDeclaration> {
+  let mut declarations = Vec::new();
+  root.walk(&mut |node| {
+    if node.name() == Some("slot") {
+      let (name, dynamic) = slot_name(node.attr("name"));
+      declarations.push(SlotDeclaration {
+        name,
+        dynamic,
+        start: node.start(),
+        end: node.end(),
+      });
+    }
+  });
+  declarations
+}
+
+/// 收集模板中自定义组件的插槽使用情况，按组件标签名分组
+///
+/// 只统计组件的直接子节点，`<block>` 会被展开；注释与空白文本不算作插槽内容
+pub fn slot_usages(root: &Root) -> BTreeMap<String, Vec<SlotUsage>> {
+  let mut usages: BTreeMap<String, Vec<SlotUsage>> = BTreeMap::new();
+  root.walk(&mut |node| {
+    let Node::Element { name, children, .. } = node else {
+      return;
+    };
+    if is_builtin_tag(name) {
+      return;
+    }
+    let mut slot_contents = Vec::new();
+    collect_slot_contents(children, &mut slot_contents);
+    if !slot_contents.is_empty() {
+      usages
+        .entry(name.clone())
+        .or_default()
+        .append(&mut slot_contents);
+    }
+  });
+  usages
+}
+
+fn collect_slot_contents(children: &[Node], usages: &mut Vec<SlotUsage>) {
+  for child in children {
+    match child {
+      Node::Element { name, children, .. } if name == "block" && child.attr("slot").is_none() => {
+        collect_slot_contents(children, usages)
+      }
+      Node::Comment { .. } => {}
+      _ if child.is_whitespace() => {}
+      _ => {
+        let (name, dynamic) = slot_name(child.attr("slot"));
+        usages.push(SlotUsage {
+          name,
+          dynamic,
+          start: child.start(),
+          end: child.end(),
+        });
+      }
+    }
+  }
+}
+
+/// 读取 `name` 或 `slot` 属性，空值视为默认插槽
+fn slot_name(attr: Option<&Attribute>) -> (Option<String>, bool) {
+  let Some(attr) = attr else {
+    return (None, false);
+  };
+  match attr.as_static_string() {
+    Some(name) if name.trim().is_empty() => (None, false),
+    Some(name) => (Some(name.trim().to_string()), false),
+    None => (None, attr.value.is_some()),
+  }
+}
+
+/// 找出使用了组件未声明插槽的内容
+///
+/// 组件存在动态名称的插槽时无法判断，不报告任何问题；动态的 `slot` 属性同样跳过
+pub fn undeclared_slot_usages<'a>(
+  usages: &'a [SlotUsage],
+  declarations: &[SlotDeclaration],
+) -> Vec<&'a SlotUsage> {
+  if declarations.iter().any(|declaration| declaration.dynamic) {
+    return Vec::new();
+  }
+  usages
+    .iter()
+    .filter(|usage| {
+      !usage.dynamic
+        && !declarations
+          .iter()
+          .any(|declaration| declaration.name == usage.name)
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::parser::Parser;
+
+  #[test]
+  fn checks_slot_contract() {
+    let component =
+      Parser::new(r#"<view><slot/><view class="footer"><slot name="footer"/></view></view>"#)
+        .parse_root()
+        .unwrap();
+    let declarations = slot_declarations(&component);
+    let names: Vec<_> = declarations.iter().map(|d| d.name.as_deref()).collect();
+    assert_eq!(names, [None, Some("footer")]);
+
+    let page = Parser::new(
+      r#"<my-card>
+  <text>body</text>
+  <block><view slot="footer">ok</view></block>
+  <view slot="header">missing</view>
+</my-card>"#,
+    )
+    .parse_root()
+    .unwrap();
+    let usages = slot_usages(&page);
+    let card = &usages["my-card"];
+    assert_eq!(card.len(), 3);
+    let undeclared = undeclared_slot_usages(card, &declarations);
+    assert_eq!(undeclared.len(), 1);
+    assert_eq!(undeclared[0].name.as_deref(), Some("header"));
+  }
+}
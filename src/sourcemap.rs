@@ -0,0 +1,173 @@
+//! 输出位置到源码位置的映射
+//!
+//! [`codegen`](crate::codegen) 与 [`format`](crate::format) 重写模板时记录每个节点、属性在输出中的位置，
+//! 以便调试工具和错误报告将输出中的位置换算回源码位置。合成节点没有源码位置，不会产生映射
+
+use crate::ast::Position;
+
+/// 一条映射：输出中的位置对应源码中的位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mapping {
+  /// 输出中的位置，`offset` 为字节偏移，行列号从 1 开始
+  pub generated: Position,
+  pub original: Position,
+}
+
+/// 位置映射表，按输出位置排序
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceMap {
+  pub mappings: Vec<Mapping>,
+}
+
+impl SourceMap {
+  /// 查找输出中某个字节偏移对应的源码位置，即该偏移之前最近的一条映射
+  pub fn original_position_for(&self, offset: u32) -> Option<Position> {
+    let index = self
+      .mappings
+      .partition_point(|mapping| mapping.generated.offset <= offset);
+    index
+      .checked_sub(1)
+      .map(|index| self.mappings[index].original)
+  }
+
+  /// 输出为 Source Map v3 格式的 JSON，`source` 为源文件路径，`file` 为输出文件路径
+  pub fn to_json(&self, source: &str, file: Option<&str>) -> String {
+    let mut json = serde_json::json!({
+      "version": 3,
+      "sources": [source],
+      "names": [],
+      "mappings": self.encode_mappings(),
+    });
+    if let Some(file) = file {
+      json["file"] = file.into();
+    }
+    json.to_string()
+  }
+
+  /// 按 Source Map v3 规范编码 `mappings` 字段，行列号均转换为从 0 开始
+  fn encode_mappings(&self) -> String {
+    let mut result = String::new();
+    let mut line = 1;
+    let mut previous_column = 0;
+    let mut previous_original_line = 0;
+    let mut previous_original_column = 0;
+    for mapping in &self.mappings {
+      let generated = mapping.generated;
+      if generated.line != line {
+        for _ in line..generated.line {
+          result.push(';');
+        }
+        line = generated.line;
+        previous_column = 0;
+      } else if !result.is_empty() && !result.ends_with(';') {
+        result.push(',');
+      }
+      let column = generated.column as i64 - 1;
+      let original_line = mapping.original.line as i64 - 1;
+      let original_column = mapping.original.column as i64 - 1;
+      encode_vlq(&mut result, column - previous_column);
+      encode_vlq(&mut result, 0);
+      encode_vlq(&mut result, original_line - previous_original_line);
+      encode_vlq(&mut result, original_column - previous_original_column);
+      previous_column = column;
+      previous_original_line = original_line;
+      previous_original_column = original_column;
+    }
+    result
+  }
+}
+
+fn encode_vlq(output: &mut String, value: i64) {
+  const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+  let mut vlq = if value < 0 {
+    ((-value) << 1) | 1
+  } else {
+    value << 1
+  };
+  loop {
+    let mut digit = vlq & 0b11111;
+    vlq >>= 5;
+    if vlq > 0 {
+      digit |= 0b100000;
+    }
+    output.push(BASE64[digit as usize] as char);
+    if vlq == 0 {
+      break;
+    }
+  }
+}
+
+/// 在生成输出的过程中记录映射
+#[derive(Debug, Default)]
+pub(crate) struct SourceMapBuilder {
+  /// 输出中的字节偏移与对应的源码位置
+  marks: Vec<(usize, Position)>,
+}
+
+impl SourceMapBuilder {
+  pub(crate) fn mark(&mut self, output_offset: usize, original: Position) {
+    if !original.is_synthetic() {
+      self.marks.push((output_offset, original));
+    }
+  }
+
+  /// 根据最终输出计算每条映射的行列号
+  pub(crate) fn build(mut self, output: &str) -> SourceMap {
+    self.marks.sort_by_key(|(offset, _)| *offset);
+    let mut mappings = Vec::with_capacity(self.marks.len());
+    let (mut line, mut column, mut scanned) = (1, 1, 0);
+    for (offset, original) in self.marks {
+      for ch in output[scanned..offset].chars() {
+        if ch == '\n' {
+          line += 1;
+          column = 1;
+        } else {
+          column += 1;
+        }
+      }
+      scanned = offset;
+      mappings.push(Mapping {
+        generated: Position {
+          offset: offset as u32,
+          line,
+          column,
+        },
+        original,
+      });
+    }
+    SourceMap { mappings }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    codegen::{generate_with_source_map, CodegenOptions},
+    format::{format_with_source_map, FormatOptions},
+    parser::Parser,
+  };
+
+  #[test]
+  fn map_formatted_output() {
+    let source = "<view><text   class=\"a\">{{msg}}</text></view>";
+    let root = Parser::new(source).parse_root().unwrap();
+
+    let (output, map) = format_with_source_map(&root, &FormatOptions::default());
+    assert_eq!(
+      output,
+      "<view>\n  <text class=\"a\">{{ msg }}</text>\n</view>\n"
+    );
+    let class_offset = output.find("class").unwrap() as u32;
+    let original = map.original_position_for(class_offset).unwrap();
+    assert_eq!(&source[original.offset as usize..][..5], "class");
+    assert_eq!(
+      map.to_json("index.wxml", None),
+      r#"{"mappings":"AAAA;EAAM,MAAQ,UAAU","names":[],"sources":["index.wxml"],"version":3}"#
+    );
+
+    let (output, map) = generate_with_source_map(&root, &CodegenOptions::default());
+    assert_eq!(output, "<view><text class=\"a\">{{msg}}</text></view>");
+    let original = map.original_position_for(output.find("{{").unwrap() as u32);
+    assert_eq!(original.map(|position| position.offset), Some(24));
+  }
+}
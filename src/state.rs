@@ -1,21 +1,20 @@
-use std::{iter::Peekable, str::CharIndices};
+use memchr::{memchr, memchr2, memchr_iter, memmem, memrchr};
 
 use crate::{
   ast::Position,
-  error::{SyntaxError, SyntaxErrorKind},
+  error::{OpenTag, SyntaxError, SyntaxErrorKind},
 };
 
 /// 解析过程中的状态信息
+///
+/// [`Lexer`](crate::lexer) 扫描出词法单元的长度后通过 [`next_bytes`](ParseState::next_bytes) 消费，
+/// 按字节批量更新行列号；逐字符的方法（如 [`next_while`](ParseState::next_while)）供其他按字符扫描的场景使用
 #[derive(Clone)]
 pub struct ParseState<'s> {
   /// 源码引用
   source: &'s str,
-  /// 源码字符迭代器
-  chars: Peekable<CharIndices<'s>>,
-  /// 当前 char 的索引
+  /// 当前字节索引
   index: usize,
-  /// 当前 chars 的偏移量
-  offset: usize,
   /// 当前行号
   line: usize,
   /// 当前列号
@@ -24,14 +23,21 @@ pub struct ParseState<'s> {
   errors: Vec<SyntaxError>,
 }
 
+/// 解析状态的快照，见 [`ParseState::checkpoint`]
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Checkpoint {
+  index: usize,
+  line: usize,
+  column: usize,
+  errors: usize,
+}
+
 impl<'s> ParseState<'s> {
   /// 创建新的解析状态
   pub fn new(source: &'s str) -> Self {
     Self {
       source,
-      chars: source.char_indices().peekable(),
       index: 0,
-      offset: 0,
       line: 1,
       column: 1,
       errors: Vec::new(),
@@ -39,12 +45,32 @@ impl<'s> ParseState<'s> {
   }
 
   pub fn emit_error(&mut self, kind: SyntaxErrorKind) -> SyntaxError {
-    let position = self.position();
+    self.emit_error_with_open_tag(kind, None)
+  }
+
+  /// 记录一条错误，并附带与之相关的开始标签
+  pub fn emit_error_with_open_tag(
+    &mut self,
+    kind: SyntaxErrorKind,
+    open_tag: Option<OpenTag>,
+  ) -> SyntaxError {
+    self.emit_error_at(kind, self.position(), open_tag)
+  }
+
+  /// 在已消费的位置 `position` 记录一条错误
+  pub(crate) fn emit_error_at(
+    &mut self,
+    kind: SyntaxErrorKind,
+    position: Position,
+    open_tag: Option<OpenTag>,
+  ) -> SyntaxError {
     let error = SyntaxError {
       kind,
+      code: kind.code().to_string(),
       offset: position.offset,
       line: position.line,
       column: position.column,
+      open_tag,
     };
     self.errors.push(error.clone());
     error
@@ -54,57 +80,91 @@ impl<'s> ParseState<'s> {
     &self.errors
   }
 
+  pub(crate) fn errors_mut(&mut self) -> &mut Vec<SyntaxError> {
+    &mut self.errors
+  }
+
+  /// 保存当前位置与错误数量，用于回溯
+  pub(crate) fn checkpoint(&self) -> Checkpoint {
+    Checkpoint {
+      index: self.index,
+      line: self.line,
+      column: self.column,
+      errors: self.errors.len(),
+    }
+  }
+
+  /// 回到 `checkpoint` 的位置，并丢弃之后记录的错误
+  pub(crate) fn rewind(&mut self, checkpoint: Checkpoint) {
+    self.index = checkpoint.index;
+    self.line = checkpoint.line;
+    self.column = checkpoint.column;
+    self.errors.truncate(checkpoint.errors);
+  }
+
+  /// 完整的源码
+  pub fn source(&self) -> &'s str {
+    self.source
+  }
+
   /// 获取当前位置信息
   pub fn position(&self) -> Position {
     Position {
-      offset: self.offset as u32,
+      offset: self.index as u32,
       line: self.line as u32,
       column: self.column as u32,
     }
   }
 
+  /// 当前字节索引
+  pub fn index(&self) -> usize {
+    self.index
+  }
+
+  /// 从指定字节索引到当前位置的源码片段
+  pub fn slice_from(&self, start: usize) -> &'s str {
+    &self.source[start..self.index]
+  }
+
   pub fn current_str(&self) -> &'s str {
     &self.source[self.index..]
   }
 
   /// 检查是否到达输入末尾
-  pub fn is_end(&mut self) -> bool {
+  pub fn is_end(&self) -> bool {
     self.peek().is_none()
   }
 
-  pub fn peek(&mut self) -> Option<char> {
-    self.chars.peek().map(|(_, c)| *c)
+  pub fn peek(&self) -> Option<char> {
+    self.current_str().chars().next()
   }
 
-  pub fn peek_n<const N: usize>(&mut self) -> Option<[char; N]> {
-    let mut chars = self.chars.clone();
+  pub fn peek_n<const N: usize>(&self) -> Option<[char; N]> {
+    let mut chars = self.current_str().chars();
     let mut result = ['\x00'; N];
-    for i in 0..N {
-      result[i] = chars.next()?.1;
+    for slot in result.iter_mut() {
+      *slot = chars.next()?;
     }
     Some(result)
   }
 
-  pub fn starts_with(&mut self, s: &str) -> bool {
+  pub fn starts_with(&self, s: &str) -> bool {
     self.current_str().starts_with(s)
   }
 
   /// 消费下一个字符并返回
+  #[allow(clippy::should_implement_trait)]
   pub fn next(&mut self) -> Option<(usize, char)> {
-    match self.chars.next() {
-      Some((offset, ch)) => {
-        self.offset = offset + 1;
-        self.index += ch.len_utf8();
-        if ch == '\n' {
-          self.line += 1;
-          self.column = 1;
-        } else {
-          self.column += 1;
-        }
-        Some((offset, ch))
-      }
-      None => None,
+    let offset = self.index;
+    let ch = self.peek()?;
+    self.index += ch.len_utf8();
+    if ch == '\n' {
+      self.line += 1;
+      self.column = 1;
+    } else {
+      self.column += 1;
     }
+    Some((offset, ch))
   }
 
   /// 判断是否可以匹配指定的字符，如果能则消费它
@@ -129,22 +189,54 @@ impl<'s> ParseState<'s> {
     &self.source[start..self.index]
   }
 
+  /// 消费 `len` 个字节，不在字符边界时延伸到下一个字符边界
+  pub fn next_bytes(&mut self, len: usize) -> &'s str {
+    let start = self.index;
+    let mut end = (start + len).min(self.source.len());
+    while !self.source.is_char_boundary(end) {
+      end += 1;
+    }
+    let text = &self.source[start..end];
+    let bytes = text.as_bytes();
+    match memrchr(b'\n', bytes) {
+      Some(last) => {
+        self.line += memchr_iter(b'\n', bytes).count();
+        self.column = text[last + 1..].chars().count() + 1;
+      }
+      None => self.column += text.chars().count(),
+    }
+    self.index = end;
+    text
+  }
+
+  /// 消费字节直到遇到 `byte`（ASCII 字符），找不到时消费到末尾
+  pub fn next_until_byte(&mut self, byte: u8) -> &'s str {
+    let rest = self.current_str().as_bytes();
+    self.next_bytes(memchr(byte, rest).unwrap_or(rest.len()))
+  }
+
+  /// 消费字节直到遇到 `needle`，找不到时消费到末尾
+  pub fn next_until_str(&mut self, needle: &str) -> &'s str {
+    let rest = self.current_str().as_bytes();
+    self.next_bytes(memmem::find(rest, needle.as_bytes()).unwrap_or(rest.len()))
+  }
+
+  /// 消费字节直到遇到 `stop`（ASCII 字符）或 `{{`，找不到时消费到末尾
+  pub fn next_until_or_mustache(&mut self, stop: u8) -> &'s str {
+    self.next_bytes(until_or_mustache(self.current_str().as_bytes(), stop))
+  }
+
   /// 消费字符直到不满足条件
   pub fn next_while<F>(&mut self, predicate: F) -> &'s str
   where
     F: Fn(char, &str) -> bool,
   {
     let start = self.index;
-    loop {
-      if let Some(ch) = self.peek() {
-        if predicate(ch, self.current_str()) {
-          self.next();
-        } else {
-          break;
-        }
-      } else {
+    while let Some(ch) = self.peek() {
+      if !predicate(ch, self.current_str()) {
         break;
       }
+      self.next();
     }
     &self.source[start..self.index]
   }
@@ -167,3 +259,42 @@ impl<'s> ParseState<'s> {
     }
   }
 }
+
+/// `bytes` 中第一个 `stop`（ASCII 字符）或 `{{` 的位置，找不到时为 `bytes` 的长度
+pub(crate) fn until_or_mustache(bytes: &[u8], stop: u8) -> usize {
+  let mut from = 0;
+  loop {
+    let Some(found) = memchr2(stop, b'{', &bytes[from..]) else {
+      return bytes.len();
+    };
+    let index = from + found;
+    if bytes[index] == stop || bytes.get(index + 1) == Some(&b'{') {
+      return index;
+    }
+    from = index + 1;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn byte_scans_track_positions() {
+    let mut state = ParseState::new("中文 {a}\n第二行 {{x}}<b>");
+    assert_eq!(state.next_until_or_mustache(b'<'), "中文 {a}\n第二行 ");
+    assert_eq!(
+      state.position(),
+      Position {
+        offset: 21,
+        line: 2,
+        column: 5,
+      }
+    );
+    assert_eq!(state.next_until_str("}}"), "{{x");
+    assert_eq!(state.next_until_byte(b'>'), "}}<b");
+    assert_eq!(state.position().column, 12);
+    assert_eq!(state.next_until_str("</b"), ">");
+    assert!(state.is_end());
+  }
+}
@@ -0,0 +1,99 @@
+//! 解析统计信息
+//!
+//! 设置 [`ParserOptions::stats`](crate::options::ParserOptions::stats) 后，
+//! [`Parser::parse_root`](crate::parser::Parser::parse_root) 会在 [`Root::stats`] 中附带节点数量、
+//! 嵌套深度与解析耗时，构建工具可以据此报告模板的复杂度，找出解析缓慢或结构异常的文件
+
+#[cfg(feature = "napi")]
+use napi_derive::napi;
+use serde::{Deserialize, Serialize};
+
+use crate::ast::{AttributeValue, Node, Root};
+
+/// 单个模板的解析统计信息
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi", napi(object))]
+pub struct ParseStats {
+  /// 节点总数，包括元素、文本、注释与插值表达式
+  pub node_count: u32,
+  pub element_count: u32,
+  /// `{{ }}` 表达式总数，包括文本中的插值与属性值中的表达式
+  pub expression_count: u32,
+  /// 元素的最大嵌套层数，顶层元素为 1
+  pub max_depth: u32,
+  /// 解析耗时（毫秒），不包括统计本身；wasm32 上没有可用的时钟，始终为 0
+  pub duration: f64,
+}
+
+impl ParseStats {
+  /// 统计已解析的语法树，`duration` 为 0
+  pub fn of(root: &Root) -> Self {
+    let mut stats = ParseStats::default();
+    for child in &root.children {
+      stats.visit(child, 1);
+    }
+    stats
+  }
+
+  fn visit(&mut self, node: &Node, depth: u32) {
+    self.node_count += 1;
+    match node {
+      Node::Element {
+        attrs, children, ..
+      } => {
+        self.element_count += 1;
+        self.max_depth = self.max_depth.max(depth);
+        self.expression_count += attrs
+          .iter()
+          .flat_map(|attr| attr.value.iter().flatten())
+          .filter(|value| matches!(value, AttributeValue::Expression { .. }))
+          .count() as u32;
+        for child in children {
+          self.visit(child, depth + 1);
+        }
+      }
+      Node::Expression { .. } => self.expression_count += 1,
+      Node::Text { .. } | Node::Comment { .. } => {}
+    }
+  }
+}
+
+/// 执行 `f` 并返回其结果与耗时（毫秒）
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn measure<T>(f: impl FnOnce() -> T) -> (T, f64) {
+  let start = std::time::Instant::now();
+  let value = f();
+  (value, start.elapsed().as_secs_f64() * 1000.0)
+}
+
+/// wasm32-unknown-unknown 上调用 `Instant::now` 会 panic，不计时
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn measure<T>(f: impl FnOnce() -> T) -> (T, f64) {
+  (f(), 0.0)
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{parse, ParserOptions};
+
+  #[test]
+  fn parse_stats() {
+    let source = "<view class=\"{{a}} b\"><text>{{x}} and {{y}}</text><!-- c --></view><image/>";
+    assert!(parse(source.to_string(), None).unwrap().stats.is_none());
+    let options = ParserOptions {
+      stats: Some(true),
+      ..Default::default()
+    };
+    let stats = parse(source.to_string(), Some(options))
+      .unwrap()
+      .stats
+      .unwrap();
+    assert_eq!(stats.node_count, 7);
+    assert_eq!(stats.element_count, 3);
+    assert_eq!(stats.expression_count, 3);
+    assert_eq!(stats.max_depth, 2);
+    assert!(stats.duration >= 0.0);
+  }
+}
@@ -0,0 +1,221 @@
+//! 内联样式解析
+//!
+//! 将 `style` 属性值拆分为 `属性: 值` 形式的声明列表，值中的 `{{}}` 表达式原样保留，
+//! 便于工具分析动态样式（如主题变量）。只做轻量的拆分，不校验 CSS 属性和值是否合法
+
+use serde::{Deserialize, Serialize};
+
+use crate::ast::{AttributeValue, Position};
+
+/// 一条样式声明
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StyleDeclaration {
+  /// 属性名，表达式按 `{{...}}` 形式拼接；整条声明由表达式给出（如 `style="{{css}}"`）时为 `None`
+  pub property: Option<String>,
+  /// 去除首尾空白后的值
+  pub value: Vec<AttributeValue>,
+  pub start: Position,
+  pub end: Position,
+}
+
+impl StyleDeclaration {
+  /// 值是否包含表达式
+  pub fn is_dynamic(&self) -> bool {
+    self
+      .value
+      .iter()
+      .any(|value| matches!(value, AttributeValue::Expression { .. }))
+  }
+
+  /// 值不包含表达式时返回静态文本
+  pub fn static_value(&self) -> Option<String> {
+    let mut result = String::new();
+    for value in &self.value {
+      match value {
+        AttributeValue::Text { content, .. } => result.push_str(content),
+        AttributeValue::Expression { .. } => return None,
+      }
+    }
+    Some(result)
+  }
+}
+
+/// 将 `style` 属性值拆分为声明列表
+///
+/// 分号和冒号只在括号与引号之外生效，因此 `url(data:image/png;base64,...)` 不会被拆开
+pub fn parse_style(values: &[AttributeValue]) -> Vec<StyleDeclaration> {
+  let mut builder = Builder::default();
+  for value in values {
+    match value {
+      AttributeValue::Text { content, start, .. } => builder.text(content, *start),
+      AttributeValue::Expression { .. } => builder.push(value.clone()),
+    }
+  }
+  builder.finish();
+  builder.declarations
+}
+
+#[derive(Default)]
+struct Builder {
+  declarations: Vec<StyleDeclaration>,
+  property: Vec<AttributeValue>,
+  value: Vec<AttributeValue>,
+  /// 当前声明是否已经出现冒号
+  colon: bool,
+  depth: u32,
+  quote: Option<char>,
+}
+
+impl Builder {
+  fn text(&mut self, content: &str, start: Position) {
+    let mut piece = 0;
+    for (index, ch) in content.char_indices() {
+      if let Some(quote) = self.quote {
+        if ch == quote {
+          self.quote = None;
+        }
+        continue;
+      }
+      match ch {
+        '"' | '\'' => self.quote = Some(ch),
+        '(' => self.depth += 1,
+        ')' => self.depth = self.depth.saturating_sub(1),
+        ':' if self.depth == 0 && !self.colon => {
+          self.push_text(&content[piece..index], start.advance(&content[..piece]));
+          self.colon = true;
+          piece = index + 1;
+        }
+        ';' if self.depth == 0 => {
+          self.push_text(&content[piece..index], start.advance(&content[..piece]));
+          self.finish();
+          piece = index + 1;
+        }
+        _ => {}
+      }
+    }
+    self.push_text(&content[piece..], start.advance(&content[..piece]));
+  }
+
+  fn push_text(&mut self, content: &str, start: Position) {
+    if !content.is_empty() {
+      self.push(AttributeValue::Text {
+        content: content.to_string(),
+        start,
+        end: start.advance(content),
+      });
+    }
+  }
+
+  fn push(&mut self, value: AttributeValue) {
+    if self.colon {
+      self.value.push(value);
+    } else {
+      self.property.push(value);
+    }
+  }
+
+  /// 结束当前声明
+  fn finish(&mut self) {
+    let mut property = std::mem::take(&mut self.property);
+    let mut value = std::mem::take(&mut self.value);
+    let colon = std::mem::replace(&mut self.colon, false);
+    trim(&mut property);
+    trim(&mut value);
+    let declaration = if colon {
+      let Some(first) = property.first().or(value.first()) else {
+        return;
+      };
+      let start = first.start();
+      let end = value.last().or(property.last()).unwrap().end();
+      let name = property
+        .iter()
+        .map(|part| match part {
+          AttributeValue::Text { content, .. } => content.clone(),
+          AttributeValue::Expression { content, .. } => format!("{{{{{content}}}}}"),
+        })
+        .collect();
+      StyleDeclaration {
+        property: Some(name),
+        value,
+        start,
+        end,
+      }
+    } else {
+      let (Some(first), Some(last)) = (property.first(), property.last()) else {
+        return;
+      };
+      StyleDeclaration {
+        property: None,
+        start: first.start(),
+        end: last.end(),
+        value: property,
+      }
+    };
+    self.declarations.push(declaration);
+  }
+}
+
+/// 去除首尾的空白文本并同步调整位置
+fn trim(parts: &mut Vec<AttributeValue>) {
+  parts.retain(
+    |part| !matches!(part, AttributeValue::Text { content, .. } if content.trim().is_empty()),
+  );
+  if let Some(AttributeValue::Text { content, start, .. }) = parts.first_mut() {
+    let trimmed = content.trim_start();
+    *start = start.advance(&content[..content.len() - trimmed.len()]);
+    *content = trimmed.to_string();
+  }
+  if let Some(AttributeValue::Text {
+    content,
+    start,
+    end,
+  }) = parts.last_mut()
+  {
+    let trimmed = content.trim_end();
+    if trimmed.len() != content.len() {
+      *content = trimmed.to_string();
+      *end = start.advance(content);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{ast::Node, parser::Parser};
+
+  #[test]
+  fn splits_declarations() {
+    let source = r#"<view style="color: red; background: url(a;b.png) ;width:{{w}}px;{{extra}}; --theme-color : {{dark ? '#000' : '#fff'}}"/>"#;
+    let root = Parser::new(source).parse_root().unwrap();
+    let Node::Element { attrs, .. } = &root.children[0] else {
+      unreachable!()
+    };
+    let declarations = attrs[0].style_declarations();
+    let summary: Vec<_> = declarations
+      .iter()
+      .map(|declaration| {
+        (
+          declaration.property.as_deref(),
+          declaration.static_value(),
+          declaration.is_dynamic(),
+        )
+      })
+      .collect();
+    assert_eq!(
+      summary,
+      [
+        (Some("color"), Some("red".to_string()), false),
+        (Some("background"), Some("url(a;b.png)".to_string()), false),
+        (Some("width"), None, true),
+        (None, None, true),
+        (Some("--theme-color"), None, true),
+      ]
+    );
+    let background = &declarations[1];
+    assert_eq!(
+      &source[background.start.offset as usize..background.end.offset as usize],
+      "background: url(a;b.png)"
+    );
+  }
+}
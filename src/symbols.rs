@@ -0,0 +1,206 @@
+//! 文档大纲
+//!
+//! 提取模板中的 `<template name>`、`<wxs module>`、具名 `<slot>`，以及带有 `id` 或 `class` 的顶层元素，
+//! 按嵌套关系组织为树形结构，字段与 LSP 的 `DocumentSymbol` 一一对应，供编辑器的大纲视图使用。
+//! 顶层元素指文档或模板定义的直接子元素；模板、模块与插槽在任意深度都会被收集，
+//! 并挂在最近的一个作为符号的祖先之下
+
+#[cfg(feature = "napi")]
+use napi_derive::napi;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  ast::{Attribute, Node, Position, Root},
+  classes::ClassToken,
+};
+
+/// 符号种类
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "napi", napi(string_enum = "lowercase"))]
+#[serde(rename_all = "lowercase")]
+pub enum SymbolKind {
+  /// `<template name="...">`
+  Template,
+  /// `<wxs module="...">`
+  Module,
+  /// `<slot name="...">`
+  Slot,
+  /// 带有 `id` 或 `class` 的顶层元素
+  Element,
+}
+
+impl SymbolKind {
+  /// 对应的 LSP `SymbolKind` 数值
+  pub fn lsp_kind(&self) -> u32 {
+    match self {
+      SymbolKind::Template => 5,
+      SymbolKind::Module => 2,
+      SymbolKind::Slot => 8,
+      SymbolKind::Element => 19,
+    }
+  }
+}
+
+/// 大纲中的一个符号
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "napi", napi(object))]
+pub struct DocumentSymbol {
+  /// 显示名称：模板、模块、插槽为其名称，元素为 `view#id.class` 形式
+  pub name: String,
+  /// 补充信息，如 `<wxs src>` 的路径、元素的标签名
+  pub detail: Option<String>,
+  pub kind: SymbolKind,
+  /// 整个元素的范围
+  pub start: Position,
+  pub end: Position,
+  /// 名称所在的范围，如 `name` 属性；元素为整个元素的范围
+  pub selection_start: Position,
+  pub selection_end: Position,
+  pub children: Vec<DocumentSymbol>,
+}
+
+/// 提取文档大纲，见[模块文档](self)
+pub fn document_symbols(root: &Root) -> Vec<DocumentSymbol> {
+  let mut symbols = Vec::new();
+  collect(&root.children, true, &mut symbols);
+  symbols
+}
+
+fn collect(nodes: &[Node], top_level: bool, out: &mut Vec<DocumentSymbol>) {
+  for node in nodes {
+    let Node::Element { children, .. } = node else {
+      continue;
+    };
+    match symbol(node, top_level) {
+      Some(mut symbol) => {
+        // 模板定义的直接子元素同样视为顶层元素
+        let nested_top_level = symbol.kind == SymbolKind::Template;
+        collect(children, nested_top_level, &mut symbol.children);
+        out.push(symbol);
+      }
+      None => collect(children, false, out),
+    }
+  }
+}
+
+fn symbol(node: &Node, top_level: bool) -> Option<DocumentSymbol> {
+  let tag = node.name()?;
+  let named = |kind, attr: &str, detail: Option<String>| {
+    let attr = node.attr(attr)?;
+    let name = attr
+      .as_static_string()
+      .unwrap_or_else(|| "<dynamic>".to_string());
+    Some(new_symbol(node, name, detail, kind, Some(attr)))
+  };
+  match tag {
+    "template" => named(SymbolKind::Template, "name", None),
+    "wxs" => {
+      let src = node.attr("src").and_then(Attribute::as_static_string);
+      named(SymbolKind::Module, "module", src)
+    }
+    "slot" => named(SymbolKind::Slot, "name", None),
+    _ if top_level => {
+      let id = node.attr("id").and_then(Attribute::as_static_string);
+      let classes: Vec<_> = node
+        .attr("class")
+        .map(|attr| {
+          attr
+            .class_list()
+            .into_iter()
+            .filter_map(|token| match token {
+              ClassToken::Static { name, .. } => Some(name),
+              ClassToken::Dynamic { .. } => None,
+            })
+            .collect()
+        })
+        .unwrap_or_default();
+      if id.is_none() && classes.is_empty() {
+        return None;
+      }
+      let mut name = tag.to_string();
+      if let Some(id) = id {
+        name.push('#');
+        name.push_str(&id);
+      }
+      for class in classes {
+        name.push('.');
+        name.push_str(&class);
+      }
+      Some(new_symbol(
+        node,
+        name,
+        Some(tag.to_string()),
+        SymbolKind::Element,
+        None,
+      ))
+    }
+    _ => None,
+  }
+}
+
+fn new_symbol(
+  node: &Node,
+  name: String,
+  detail: Option<String>,
+  kind: SymbolKind,
+  selection: Option<&Attribute>,
+) -> DocumentSymbol {
+  DocumentSymbol {
+    name,
+    detail,
+    kind,
+    start: node.start(),
+    end: node.end(),
+    selection_start: selection.map_or(node.start(), |attr| attr.start),
+    selection_end: selection.map_or(node.end(), |attr| attr.end),
+    children: Vec::new(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::parser::Parser;
+
+  fn outline(symbols: &[DocumentSymbol], depth: usize, out: &mut Vec<String>) {
+    for symbol in symbols {
+      out.push(format!(
+        "{}{:?} {}",
+        "  ".repeat(depth),
+        symbol.kind,
+        symbol.name
+      ));
+      outline(&symbol.children, depth + 1, out);
+    }
+  }
+
+  #[test]
+  fn symbols() {
+    let root = Parser::new(
+      r#"<wxs module="fmt" src="./fmt.wxs"/>
+<view id="page" class="container {{theme}}">
+  <view class="inner"><slot name="header"/></view>
+</view>
+<template name="item"><view class="row"><text class="cell"/></view></template>
+<text>plain</text>"#,
+    )
+    .parse_root()
+    .unwrap();
+    let symbols = document_symbols(&root);
+    let mut lines = Vec::new();
+    outline(&symbols, 0, &mut lines);
+    assert_eq!(
+      lines,
+      [
+        "Module fmt",
+        "Element view#page.container",
+        "  Slot header",
+        "Template item",
+        "  Element view.row",
+      ]
+    );
+    assert_eq!(symbols[0].detail.as_deref(), Some("./fmt.wxs"));
+    assert_eq!(symbols[0].selection_start.offset, 5);
+  }
+}
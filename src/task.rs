@@ -0,0 +1,114 @@
+//! 在 libuv 线程池中执行的异步解析任务，避免阻塞 Node 事件循环
+
+use std::collections::HashMap;
+
+use napi::{bindgen_prelude::*, Task};
+use napi_derive::napi;
+
+use crate::{
+  ast::Root,
+  batch::{lint_files, parse_files, LintResult, ParseResult, SourceFile},
+  lint::LintConfig,
+  options::ParserOptions,
+  parser::Parser,
+};
+
+/// 在后台线程中解析 WXML 模板，适用于体积较大的模板，返回 Promise
+#[napi(js_name = "parseAsync")]
+pub fn parse_async(source: String, options: Option<ParserOptions>) -> AsyncTask<ParseTask> {
+  AsyncTask::new(ParseTask {
+    source,
+    options: options.unwrap_or_default(),
+  })
+}
+
+/// 在后台线程中并行解析多个模板文件，返回 Promise
+#[napi(js_name = "parseFiles")]
+pub fn parse_files_async(
+  files: Vec<SourceFile>,
+  options: Option<ParserOptions>,
+) -> AsyncTask<ParseFilesTask> {
+  AsyncTask::new(ParseFilesTask {
+    files,
+    options: options.unwrap_or_default(),
+  })
+}
+
+/// 在后台线程中并行解析并检查多个模板文件，键为文件路径，值为源码；
+/// 结果按文件分组并按路径排序，返回 Promise；配置无效（如无法解析的 `targetVersion`）时 Promise 被拒绝
+#[napi(js_name = "lintProject")]
+pub fn lint_project(
+  files: HashMap<String, String>,
+  config: Option<LintConfig>,
+) -> AsyncTask<LintProjectTask> {
+  AsyncTask::new(LintProjectTask {
+    files,
+    config: config.unwrap_or_default(),
+  })
+}
+
+/// 单个模板的异步解析任务
+pub struct ParseTask {
+  pub(crate) source: String,
+  pub(crate) options: ParserOptions,
+}
+
+impl Task for ParseTask {
+  type Output = Root;
+  type JsValue = Root;
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    let mut parser = Parser::with_options(&self.source, self.options.clone());
+    parser
+      .parse_root()
+      .map_err(|error| Error::from_reason(error.to_string()))
+  }
+
+  fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+    Ok(output)
+  }
+}
+
+/// 批量解析任务
+pub struct ParseFilesTask {
+  pub(crate) files: Vec<SourceFile>,
+  pub(crate) options: ParserOptions,
+}
+
+impl Task for ParseFilesTask {
+  type Output = Vec<ParseResult>;
+  type JsValue = Vec<ParseResult>;
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    let files = std::mem::take(&mut self.files)
+      .into_iter()
+      .map(|file| (file.path, file.source))
+      .collect();
+    Ok(parse_files(files, &self.options))
+  }
+
+  fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+    Ok(output)
+  }
+}
+
+/// 批量检查任务
+pub struct LintProjectTask {
+  pub(crate) files: HashMap<String, String>,
+  pub(crate) config: LintConfig,
+}
+
+impl Task for LintProjectTask {
+  type Output = Vec<LintResult>;
+  type JsValue = Vec<LintResult>;
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    let mut files: Vec<_> = std::mem::take(&mut self.files).into_iter().collect();
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+    lint_files(files, &self.config).map_err(|error| Error::from_reason(error.to_string()))
+  }
+
+  fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+    Ok(output)
+  }
+}
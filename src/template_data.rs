@@ -0,0 +1,429 @@
+//! 模板调用参数
+//!
+//! `<template is="item" data="{{ ...item, index, title: name }}"/>` 的 `data` 是省略了花括号的对象字面量。
+//! [`parse_template_data`] 把它拆分为带位置的键值对；[`template_parameters`] 找出模板定义中使用的变量，
+//! 二者比对即可检查调用时遗漏或多余的参数，见 [`check_template_calls`]
+
+use std::collections::{BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  ast::{AttributeValue, Node, Position, Root},
+  dialect::{Dialect, Directive},
+  expr::{parse_expression, Expr, ExprError, ObjectProperty},
+};
+
+/// `data` 中的一项
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateArgument {
+  /// 键名，展开语法 `...item` 为 `None`
+  pub key: Option<String>,
+  /// 值；简写 `{ index }` 的值为同名变量
+  pub value: Expr,
+  /// 整项在表达式内容中的字节范围
+  pub start: usize,
+  pub end: usize,
+}
+
+/// 解析 `data` 属性中 `{{ }}` 的内容
+///
+/// 与 [`parse_object_body`](crate::expr::parse_object_body) 接受相同的语法，额外给出每一项的位置
+pub fn parse_template_data(content: &str) -> Result<Vec<TemplateArgument>, ExprError> {
+  let mut arguments = Vec::new();
+  for (start, end) in split_top_level(content)? {
+    let item = &content[start..end];
+    // 错误位置换算为相对于整个内容，`skip` 为值在该项中的偏移
+    let shift = |skip: usize| {
+      move |error: ExprError| ExprError::new(error.message, error.offset + start + skip)
+    };
+    if let Some(spread) = item.strip_prefix("...") {
+      arguments.push(TemplateArgument {
+        key: None,
+        value: parse_expression(spread).map_err(shift(3))?,
+        start,
+        end,
+      });
+      continue;
+    }
+    // 引号中的 `:` 属于键名，如 `'a:b': 1`
+    let key_end = match item.chars().next() {
+      Some(quote @ ('"' | '\'')) => item[1..]
+        .find(quote)
+        .and_then(|close| item[close + 2..].find(':').map(|colon| close + 2 + colon)),
+      _ => item.find(':'),
+    }
+    .unwrap_or(item.len());
+    let raw_key = item[..key_end].trim_end();
+    let key = match parse_expression(raw_key) {
+      Ok(Expr::Ident(name) | Expr::String(name)) => name,
+      Ok(Expr::Number(number)) if key_end < item.len() => number.to_string(),
+      _ => return Err(ExprError::new("expected property name", start)),
+    };
+    let value = if key_end < item.len() {
+      let value = &item[key_end + 1..];
+      parse_expression(value).map_err(shift(key_end + 1))?
+    } else if raw_key.starts_with(['"', '\'']) {
+      return Err(ExprError::new("expected `:`", end));
+    } else {
+      Expr::Ident(key.clone())
+    };
+    arguments.push(TemplateArgument {
+      key: Some(key),
+      value,
+      start,
+      end,
+    });
+  }
+  Ok(arguments)
+}
+
+/// 按括号与引号之外的 `,` 拆分，返回去除首尾空白后每一项的范围，忽略末尾多余的 `,`
+fn split_top_level(content: &str) -> Result<Vec<(usize, usize)>, ExprError> {
+  let mut items = Vec::new();
+  let mut depth = 0usize;
+  let mut quote = None;
+  let mut escaped = false;
+  let mut item_start = 0;
+  let mut push = |start: usize, end: usize, last: bool| {
+    let item = &content[start..end];
+    let trimmed = item.trim();
+    if trimmed.is_empty() {
+      return if last && !items.is_empty() {
+        Ok(())
+      } else {
+        Err(ExprError::new("expected property name", start))
+      };
+    }
+    let offset = start + (item.len() - item.trim_start().len());
+    items.push((offset, offset + trimmed.len()));
+    Ok(())
+  };
+  for (index, ch) in content.char_indices() {
+    match quote {
+      Some(_) if escaped => escaped = false,
+      Some(_) if ch == '\\' => escaped = true,
+      Some(open) if ch == open => quote = None,
+      Some(_) => {}
+      None => match ch {
+        '"' | '\'' => quote = Some(ch),
+        '(' | '[' | '{' => depth += 1,
+        ')' | ']' | '}' => {
+          depth = depth
+            .checked_sub(1)
+            .ok_or_else(|| ExprError::new(format!("unexpected `{ch}`"), index))?
+        }
+        ',' if depth == 0 => {
+          push(item_start, index, false)?;
+          item_start = index + 1;
+        }
+        _ => {}
+      },
+    }
+  }
+  if content.trim().is_empty() {
+    return Ok(Vec::new());
+  }
+  push(item_start, content.len(), true)?;
+  Ok(items)
+}
+
+/// 模板定义中使用的外部变量，即调用时需要通过 `data` 传入的参数
+///
+/// 列表渲染的 `item`、`index`（或 `wx:for-item`、`wx:for-index` 指定的名称）以及模板内定义的 wxs 模块不计入
+pub fn template_parameters(template: &Node) -> BTreeSet<String> {
+  let mut locals = Vec::new();
+  for child in template.children().unwrap_or_default() {
+    child.walk(&mut |node| {
+      if Dialect::ALL
+        .iter()
+        .any(|dialect| node.name() == Some(dialect.script_tag()))
+      {
+        if let Some(module) = node.attr("module").and_then(|attr| attr.as_static_string()) {
+          locals.push(module);
+        }
+      }
+    });
+  }
+  let mut parameters = BTreeSet::new();
+  for child in template.children().unwrap_or_default() {
+    collect_parameters(child, &mut locals, &mut parameters);
+  }
+  parameters
+}
+
+fn collect_parameters(node: &Node, locals: &mut Vec<String>, out: &mut BTreeSet<String>) {
+  match node {
+    Node::Expression { content, .. } => add_references(content, locals, out),
+    Node::Element {
+      name,
+      attrs,
+      children,
+      ..
+    } => {
+      // 列表指令在外层作用域中求值，其余属性与子节点可以访问列表项
+      let scope = locals.len();
+      if let Some(attr) = node.directive(Directive::For) {
+        for value in attr.value.iter().flatten() {
+          if let AttributeValue::Expression { content, .. } = value {
+            add_references(content, locals, out);
+          }
+        }
+        let local = |directive, default: &str| {
+          node
+            .directive(directive)
+            .and_then(|attr| attr.as_static_string())
+            .unwrap_or_else(|| default.to_string())
+        };
+        locals.push(local(Directive::ForItem, "item"));
+        locals.push(local(Directive::ForIndex, "index"));
+      }
+      let call_data = name == "template" && node.attr("is").is_some();
+      for attr in attrs {
+        if Directive::of(&attr.name).is_some_and(|(_, directive)| directive == Directive::For) {
+          continue;
+        }
+        for value in attr.value.iter().flatten() {
+          let AttributeValue::Expression { content, .. } = value else {
+            continue;
+          };
+          if call_data && attr.name == "data" {
+            for argument in parse_template_data(content).unwrap_or_default() {
+              add_expr_references(&argument.value, locals, out);
+            }
+          } else {
+            add_references(content, locals, out);
+          }
+        }
+      }
+      for child in children {
+        collect_parameters(child, locals, out);
+      }
+      locals.truncate(scope);
+    }
+    _ => {}
+  }
+}
+
+fn add_references(content: &str, locals: &[String], out: &mut BTreeSet<String>) {
+  if let Ok(expr) = parse_expression(content) {
+    add_expr_references(&expr, locals, out);
+  }
+}
+
+fn add_expr_references(expr: &Expr, locals: &[String], out: &mut BTreeSet<String>) {
+  let mut visit = |expr: &Expr| add_expr_references(expr, locals, out);
+  match expr {
+    Expr::Ident(name) => {
+      if !locals.contains(name) {
+        out.insert(name.clone());
+      }
+    }
+    Expr::Null | Expr::Undefined | Expr::Bool(_) | Expr::Number(_) | Expr::String(_) => {}
+    Expr::Array(items) => items.iter().for_each(visit),
+    Expr::Object(properties) => {
+      for property in properties {
+        match property {
+          ObjectProperty::Property { value, .. } | ObjectProperty::Spread(value) => visit(value),
+        }
+      }
+    }
+    Expr::Member { object, .. } => visit(object),
+    Expr::Index { object, index } => {
+      visit(object);
+      visit(index);
+    }
+    Expr::Call { callee, args } => {
+      visit(callee);
+      args.iter().for_each(visit);
+    }
+    Expr::Unary { argument, .. } | Expr::Spread(argument) => visit(argument),
+    Expr::Binary { left, right, .. } => {
+      visit(left);
+      visit(right);
+    }
+    Expr::Conditional {
+      test,
+      consequent,
+      alternate,
+    } => {
+      visit(test);
+      visit(consequent);
+      visit(alternate);
+    }
+  }
+}
+
+/// 模板调用参数的问题类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TemplateArgumentIssueKind {
+  /// 模板使用了调用时没有传入的参数；`data` 中包含展开语法时无法确定，不做检查
+  Missing,
+  /// 传入了模板没有使用的参数
+  Unused,
+  /// `data` 无法解析
+  Invalid,
+}
+
+/// 一处模板调用参数的问题
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateArgumentIssue {
+  pub kind: TemplateArgumentIssueKind,
+  /// 被调用的模板名
+  pub template: String,
+  /// 参数名，`data` 无法解析时为空
+  pub name: String,
+  pub message: String,
+  /// `data` 属性的位置，没有 `data` 属性时为调用元素的位置
+  pub start: Position,
+  pub end: Position,
+}
+
+/// 检查文档中以静态模板名调用的 `<template is>`，与同一文档中的 `<template name>` 定义比对参数
+pub fn check_template_calls(root: &Root) -> Vec<TemplateArgumentIssue> {
+  let mut definitions = HashMap::new();
+  root.walk(&mut |node| {
+    if node.name() == Some("template") {
+      if let Some(name) = node.attr("name").and_then(|attr| attr.as_static_string()) {
+        definitions
+          .entry(name)
+          .or_insert_with(|| template_parameters(node));
+      }
+    }
+  });
+  let mut issues = Vec::new();
+  root.walk(&mut |node| {
+    if node.name() != Some("template") {
+      return;
+    }
+    let Some(template) = node.attr("is").and_then(|attr| attr.as_static_string()) else {
+      return;
+    };
+    let Some(parameters) = definitions.get(&template) else {
+      return;
+    };
+    let data = node.attr("data");
+    let (start, end) = data.map_or((node.start(), node.end()), |attr| (attr.start, attr.end));
+    let mut issue = |kind, name: &str, message: String| {
+      issues.push(TemplateArgumentIssue {
+        kind,
+        template: template.clone(),
+        name: name.to_string(),
+        message,
+        start,
+        end,
+      })
+    };
+    let arguments = match data.and_then(|attr| attr.value.as_deref()) {
+      None => Vec::new(),
+      Some([AttributeValue::Expression { content, .. }]) => match parse_template_data(content) {
+        Ok(arguments) => arguments,
+        Err(error) => {
+          issue(
+            TemplateArgumentIssueKind::Invalid,
+            "",
+            format!("invalid template data `{content}`: {}", error.message),
+          );
+          return;
+        }
+      },
+      Some(_) => {
+        issue(
+          TemplateArgumentIssueKind::Invalid,
+          "",
+          "template data must be a single `{{ }}` expression".to_string(),
+        );
+        return;
+      }
+    };
+    let keys: Vec<_> = arguments
+      .iter()
+      .filter_map(|argument| argument.key.as_deref())
+      .collect();
+    for key in &keys {
+      if !parameters.contains(*key) {
+        issue(
+          TemplateArgumentIssueKind::Unused,
+          key,
+          format!("template `{template}` does not use `{key}`"),
+        );
+      }
+    }
+    if arguments.iter().any(|argument| argument.key.is_none()) {
+      return;
+    }
+    for parameter in parameters {
+      if !keys.contains(&parameter.as_str()) {
+        issue(
+          TemplateArgumentIssueKind::Missing,
+          parameter,
+          format!("template `{template}` uses `{parameter}` but it is not passed in `data`"),
+        );
+      }
+    }
+  });
+  issues
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::parser::Parser;
+
+  #[test]
+  fn template_data_arguments() {
+    let content = "...item, index, title: a ? b : 'x, y', 'full:name': fn(c, d),";
+    let arguments = parse_template_data(content).unwrap();
+    let found: Vec<_> = arguments
+      .iter()
+      .map(|argument| {
+        (
+          argument.key.as_deref(),
+          &content[argument.start..argument.end],
+        )
+      })
+      .collect();
+    assert_eq!(
+      found,
+      [
+        (None, "...item"),
+        (Some("index"), "index"),
+        (Some("title"), "title: a ? b : 'x, y'"),
+        (Some("full:name"), "'full:name': fn(c, d)"),
+      ]
+    );
+    assert_eq!(arguments[1].value, Expr::Ident("index".to_string()));
+    assert_eq!(parse_template_data("a, b: ").unwrap_err().offset, 5);
+    assert_eq!(parse_template_data("a,, b").unwrap_err().offset, 2);
+
+    let source = r#"<template name="row">
+  <wxs module="m">module.exports = {};</wxs>
+  <view wx:for="{{list}}" wx:for-item="row">{{ m.f(row) }} {{ index }} {{ title }}</view>
+  <template is="cell" data="{{ value: total }}"/>
+</template>
+<template is="row" data="{{ list, extra: 1 }}"/>
+<template is="row" data="{{ ...all }}"/>
+<template is="row" data="{{ list: [ }}"/>"#;
+    let root = Parser::new(source).parse_root().unwrap();
+    let parameters = template_parameters(&root.children[0]);
+    assert_eq!(
+      parameters.into_iter().collect::<Vec<_>>(),
+      ["list", "title", "total"]
+    );
+    let issues: Vec<_> = check_template_calls(&root)
+      .into_iter()
+      .map(|issue| (issue.kind, issue.name))
+      .collect();
+    assert_eq!(
+      issues,
+      [
+        (TemplateArgumentIssueKind::Unused, "extra".to_string()),
+        (TemplateArgumentIssueKind::Missing, "title".to_string()),
+        (TemplateArgumentIssueKind::Missing, "total".to_string()),
+        (TemplateArgumentIssueKind::Invalid, String::new()),
+      ]
+    );
+  }
+}
@@ -0,0 +1,149 @@
+//! 快照测试工具
+//!
+//! 供编写检查规则、代码转换的下游项目对解析结果做黄金测试。快照的内容为 [`Root::dump_tree`]
+//! 的输出，解析过程中的语法错误按 `error[编号]@偏移量: 信息` 的格式逐行追加在树之后。
+//!
+//! 目录约定：每个 `.wxml` 文件旁边放一个同名的 `.tree` 文件保存期望的快照，如
+//! `tests/fixtures/list/basic.wxml` 与 `tests/fixtures/list/basic.tree`，子目录会被递归扫描。
+//! 快照缺失时 [`assert_fixture_snapshots`] 会写入当前输出并失败，检查无误后重新运行即可；
+//! 设置环境变量 `WXML_UPDATE_SNAPSHOTS=1` 会用当前输出覆盖所有快照
+//!
+//! ```
+//! use miniprogram_template_parser::testing::assert_parse_snapshot;
+//!
+//! assert_parse_snapshot(
+//!   "<view>{{ a }}</view>",
+//!   r#"
+//! Root@0..20
+//!   Element@0..20 <view>
+//!     Expression@6..13 "a"
+//! "#,
+//! );
+//! ```
+
+use std::{env, fmt::Write, fs, path::Path};
+
+use crate::{ast::Root, fixtures::load_fixtures, options::ParserOptions, parser::Parser};
+
+/// 设置后用当前输出覆盖快照文件
+pub const UPDATE_ENV: &str = "WXML_UPDATE_SNAPSHOTS";
+
+/// 快照文件的扩展名
+pub const SNAPSHOT_EXTENSION: &str = "tree";
+
+/// 解析模板并生成快照文本
+pub fn parse_snapshot(source: &str, options: &ParserOptions) -> String {
+  let mut parser = Parser::with_options(source, options.clone());
+  let mut out = parser
+    .parse_root()
+    .as_ref()
+    .map(Root::dump_tree)
+    .unwrap_or_default();
+  for error in parser.errors() {
+    writeln!(
+      out,
+      "error[{}]@{}: {}",
+      error.code, error.offset, error.kind
+    )
+    .unwrap();
+  }
+  out
+}
+
+/// 断言模板的解析结果与期望的快照一致，比较时忽略首尾的空行
+///
+/// 不一致时 panic 并输出完整的实际快照，便于直接复制到测试中
+#[track_caller]
+pub fn assert_parse_snapshot(source: &str, expected: &str) {
+  let actual = parse_snapshot(source, &ParserOptions::default());
+  if normalize(&actual) != normalize(expected) {
+    panic!(
+      "parse snapshot mismatch for {source:?}\n--- expected\n{}\n--- actual\n{}",
+      normalize(expected),
+      normalize(&actual)
+    );
+  }
+}
+
+/// 检查目录下所有 `.wxml` 文件与同名 `.tree` 快照是否一致，见[模块文档](self)
+#[track_caller]
+pub fn assert_fixture_snapshots(dir: impl AsRef<Path>, options: &ParserOptions) {
+  let dir = dir.as_ref();
+  let update = env::var_os(UPDATE_ENV).is_some_and(|value| !value.is_empty() && value != "0");
+  let fixtures = load_fixtures(dir)
+    .unwrap_or_else(|error| panic!("failed to load fixtures from {}: {error}", dir.display()));
+  let mut failures = Vec::new();
+  for fixture in fixtures {
+    let path = dir.join(&fixture.name).with_extension(SNAPSHOT_EXTENSION);
+    let actual = parse_snapshot(&fixture.source, options);
+    let expected = fs::read_to_string(&path).ok();
+    if !update && expected.as_deref().map(normalize) == Some(normalize(&actual)) {
+      continue;
+    }
+    if update || expected.is_none() {
+      fs::write(&path, &actual)
+        .unwrap_or_else(|error| panic!("failed to write {}: {error}", path.display()));
+    }
+    if expected.is_none() {
+      failures.push(format!(
+        "{}: new snapshot written, review it and re-run",
+        fixture.name
+      ));
+    } else if !update {
+      failures.push(format!(
+        "{}: snapshot mismatch\n--- expected\n{}\n--- actual\n{}",
+        fixture.name,
+        normalize(expected.as_deref().unwrap_or_default()),
+        normalize(&actual)
+      ));
+    }
+  }
+  if !failures.is_empty() {
+    panic!(
+      "{} fixture snapshot(s) failed, set {UPDATE_ENV}=1 to accept the current output\n\n{}",
+      failures.len(),
+      failures.join("\n\n")
+    );
+  }
+}
+
+/// 去掉首尾的空行与每行末尾的空白，统一换行符
+fn normalize(snapshot: &str) -> String {
+  snapshot
+    .lines()
+    .map(str::trim_end)
+    .collect::<Vec<_>>()
+    .join("\n")
+    .trim_matches('\n')
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+  use std::panic;
+
+  use super::*;
+
+  #[test]
+  fn snapshots() {
+    assert_parse_snapshot(
+      "<view><text>",
+      r#"
+error[WXML1003]@12: expected close tag
+"#,
+    );
+    assert!(panic::catch_unwind(|| assert_parse_snapshot("<view/>", "Root@0..7")).is_err());
+
+    let dir = env::temp_dir().join(format!("wxml-snapshots-{}", std::process::id()));
+    fs::create_dir_all(dir.join("nested")).unwrap();
+    fs::write(dir.join("nested/a.wxml"), "<view/>").unwrap();
+    let options = ParserOptions::default();
+    assert!(panic::catch_unwind(|| assert_fixture_snapshots(&dir, &options)).is_err());
+    assert_eq!(
+      fs::read_to_string(dir.join("nested/a.tree")).unwrap(),
+      "Root@0..7\n  Element@0..7 <view> self-closing\n"
+    );
+    assert_fixture_snapshots(&dir, &options);
+    fs::remove_dir_all(&dir).unwrap();
+  }
+}
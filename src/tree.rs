@@ -0,0 +1,204 @@
+//! 带父节点索引的树视图
+//!
+//! [`Node`] 只保存子节点，无法从节点回到父节点。[`TreeIndex`] 为已解析的文档建立索引：
+//! 每个节点按文档顺序（先序）获得一个 [`TreeId`]，可以查询父节点、兄弟节点与祖先，
+//! 供选择器匹配、需要引用祖先的诊断等场景使用。同一棵树多次建立索引得到的编号相同
+//!
+//! ```
+//! use miniprogram_template_parser::{parser::Parser, tree::TreeIndex};
+//!
+//! let root = Parser::new("<view><text>hi</text></view>").parse_root().unwrap();
+//! let tree = TreeIndex::new(&root);
+//! let text = tree.find(|node| node.name() == Some("text")).unwrap();
+//! let view = tree.parent(text).unwrap();
+//! assert_eq!(tree.node(view).name(), Some("view"));
+//! ```
+
+use std::ops::Range;
+
+use crate::ast::{Node, Root};
+
+/// 节点在 [`TreeIndex`] 中的编号，按文档顺序（先序）从 0 开始
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TreeId(u32);
+
+impl TreeId {
+  /// 编号对应的下标
+  pub fn index(&self) -> usize {
+    self.0 as usize
+  }
+}
+
+struct Entry<'a> {
+  node: &'a Node,
+  parent: Option<TreeId>,
+  /// 在父节点（或顶层节点）中的位置
+  position: u32,
+  depth: u32,
+  /// 子节点在 `TreeIndex::child_ids` 中的范围
+  children: Range<u32>,
+}
+
+/// 文档的索引视图，借用原始的语法树
+pub struct TreeIndex<'a> {
+  entries: Vec<Entry<'a>>,
+  child_ids: Vec<TreeId>,
+  roots: Vec<TreeId>,
+}
+
+impl<'a> TreeIndex<'a> {
+  pub fn new(root: &'a Root) -> Self {
+    let mut index = TreeIndex {
+      entries: Vec::new(),
+      child_ids: Vec::new(),
+      roots: Vec::new(),
+    };
+    index.roots = index.add_children(&root.children, None, 0);
+    index
+  }
+
+  fn add_children(&mut self, nodes: &'a [Node], parent: Option<TreeId>, depth: u32) -> Vec<TreeId> {
+    let mut ids = Vec::with_capacity(nodes.len());
+    for (position, node) in nodes.iter().enumerate() {
+      let id = TreeId(self.entries.len() as u32);
+      self.entries.push(Entry {
+        node,
+        parent,
+        position: position as u32,
+        depth,
+        children: 0..0,
+      });
+      let children = self.add_children(node.children().unwrap_or_default(), Some(id), depth + 1);
+      let start = self.child_ids.len() as u32;
+      self.child_ids.extend(children);
+      self.entries[id.index()].children = start..self.child_ids.len() as u32;
+      ids.push(id);
+    }
+    ids
+  }
+
+  /// 节点总数
+  pub fn len(&self) -> usize {
+    self.entries.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.entries.is_empty()
+  }
+
+  /// 顶层节点
+  pub fn roots(&self) -> &[TreeId] {
+    &self.roots
+  }
+
+  pub fn node(&self, id: TreeId) -> &'a Node {
+    self.entries[id.index()].node
+  }
+
+  /// 父元素，顶层节点为 `None`
+  pub fn parent(&self, id: TreeId) -> Option<TreeId> {
+    self.entries[id.index()].parent
+  }
+
+  /// 子节点，非元素节点返回空切片
+  pub fn children(&self, id: TreeId) -> &[TreeId] {
+    let range = &self.entries[id.index()].children;
+    &self.child_ids[range.start as usize..range.end as usize]
+  }
+
+  /// 与节点同属一个父节点的所有节点（包括自身），顶层节点返回所有顶层节点
+  pub fn siblings(&self, id: TreeId) -> &[TreeId] {
+    match self.parent(id) {
+      Some(parent) => self.children(parent),
+      None => &self.roots,
+    }
+  }
+
+  /// 前一个兄弟节点
+  pub fn prev_sibling(&self, id: TreeId) -> Option<TreeId> {
+    let position = self.entries[id.index()].position as usize;
+    position
+      .checked_sub(1)
+      .map(|position| self.siblings(id)[position])
+  }
+
+  /// 后一个兄弟节点
+  pub fn next_sibling(&self, id: TreeId) -> Option<TreeId> {
+    let position = self.entries[id.index()].position as usize;
+    self.siblings(id).get(position + 1).copied()
+  }
+
+  /// 嵌套层数，顶层节点为 0
+  pub fn depth(&self, id: TreeId) -> u32 {
+    self.entries[id.index()].depth
+  }
+
+  /// 从父节点开始向上的所有祖先
+  pub fn ancestors(&self, id: TreeId) -> impl Iterator<Item = TreeId> + '_ {
+    std::iter::successors(self.parent(id), |id| self.parent(*id))
+  }
+
+  /// 所有节点的编号，按文档顺序
+  pub fn ids(&self) -> impl Iterator<Item = TreeId> {
+    (0..self.entries.len() as u32).map(TreeId)
+  }
+
+  /// 按文档顺序查找第一个满足条件的节点
+  pub fn find(&self, mut predicate: impl FnMut(&Node) -> bool) -> Option<TreeId> {
+    self.ids().find(|id| predicate(self.node(*id)))
+  }
+
+  /// 查找节点的编号，`node` 必须是建立索引时所用语法树中的节点
+  pub fn id_of(&self, node: &Node) -> Option<TreeId> {
+    self.find(|other| std::ptr::eq(other, node))
+  }
+
+  /// 包含 `offset`（字节偏移）的最内层节点，节点的结束位置不包括在内
+  pub fn node_at(&self, offset: u32) -> Option<TreeId> {
+    let contains = |id: &TreeId| {
+      let node = self.node(*id);
+      !node.is_synthetic() && node.start().offset <= offset && offset < node.end().offset
+    };
+    let mut found = self.roots.iter().copied().find(contains)?;
+    while let Some(child) = self.children(found).iter().copied().find(contains) {
+      found = child;
+    }
+    Some(found)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::parser::Parser;
+
+  #[test]
+  fn tree_index() {
+    let root = Parser::new("<view><text>a</text><image/></view><button>b</button>")
+      .parse_root()
+      .unwrap();
+    let tree = TreeIndex::new(&root);
+    let names: Vec<_> = tree
+      .ids()
+      .map(|id| tree.node(id).name().unwrap_or("#text"))
+      .collect();
+    assert_eq!(names, ["view", "text", "#text", "image", "button", "#text"]);
+
+    let image = tree.find(|node| node.name() == Some("image")).unwrap();
+    let text = tree.prev_sibling(image).unwrap();
+    assert_eq!(tree.node(text).name(), Some("text"));
+    assert_eq!(tree.next_sibling(image), None);
+    assert_eq!(tree.prev_sibling(text), None);
+    let view = tree.parent(image).unwrap();
+    assert_eq!(tree.next_sibling(view), Some(tree.roots()[1]));
+    assert_eq!(tree.children(view), [text, image]);
+
+    let leaf = tree.children(text)[0];
+    assert_eq!(tree.ancestors(leaf).collect::<Vec<_>>(), [text, view]);
+    assert_eq!(tree.depth(leaf), 2);
+    assert_eq!(tree.node_at(12), Some(leaf));
+    assert_eq!(tree.node_at(5), Some(view));
+    assert_eq!(tree.node_at(99), None);
+    assert_eq!(tree.id_of(tree.node(image)), Some(image));
+  }
+}
@@ -0,0 +1,133 @@
+//! 格式化琐碎信息
+//!
+//! AST 不保留开始标签中的空白：属性之间的空白、`>` 之前的换行、行尾多余的空格等。
+//! 格式化工具实现“保留原有换行”一类的选项时需要这些信息，因此按需从源码中提取，
+//! 记录在以节点为键的附表中，不增加 AST 本身的体积
+//!
+//! 节点以起始偏移作为标识，提取时 AST 的位置必须是字节偏移，即解析时没有设置
+//! [`position_encoding`](crate::options::ParserOptions::position_encoding)，且未经过改写
+
+use std::collections::HashMap;
+
+use crate::ast::{Node, Root};
+
+/// 一个元素开始标签中的空白
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ElementTrivia {
+  /// 每个属性之前的空白，即标签名或前一个属性与该属性之间的内容
+  pub before_attrs: Vec<String>,
+  /// 最后一个属性（没有属性时为标签名）与 `>` 或 `/>` 之间的空白
+  pub before_close: String,
+}
+
+impl ElementTrivia {
+  /// 第 `index` 个属性是否另起一行书写
+  pub fn breaks_before_attr(&self, index: usize) -> bool {
+    self
+      .before_attrs
+      .get(index)
+      .is_some_and(|space| space.contains('\n'))
+  }
+
+  /// `>` 或 `/>` 是否另起一行书写
+  pub fn breaks_before_close(&self) -> bool {
+    self.before_close.contains('\n')
+  }
+
+  /// 开始标签中是否有行尾多余的空格或制表符
+  pub fn has_trailing_whitespace(&self) -> bool {
+    self
+      .before_attrs
+      .iter()
+      .chain([&self.before_close])
+      .any(|space| {
+        space
+          .split('\n')
+          .rev()
+          .skip(1)
+          .any(|line| line.trim_end_matches('\r').ends_with([' ', '\t']))
+      })
+  }
+}
+
+/// 文档中所有元素的琐碎信息
+#[derive(Debug, Clone, Default)]
+pub struct Trivia {
+  elements: HashMap<u32, ElementTrivia>,
+}
+
+impl Trivia {
+  /// 元素的琐碎信息，其他节点与合成节点返回 `None`
+  pub fn element(&self, node: &Node) -> Option<&ElementTrivia> {
+    match node {
+      Node::Element { .. } if !node.is_synthetic() => self.elements.get(&node.start().offset),
+      _ => None,
+    }
+  }
+
+  pub fn len(&self) -> usize {
+    self.elements.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.elements.is_empty()
+  }
+}
+
+/// 从源码中提取所有元素开始标签中的空白，`root` 必须由 `source` 解析得到
+pub fn collect_trivia(source: &str, root: &Root) -> Trivia {
+  let mut trivia = Trivia::default();
+  root.walk(&mut |node| {
+    let Node::Element {
+      name, attrs, start, ..
+    } = node
+    else {
+      return;
+    };
+    if node.is_synthetic() {
+      return;
+    }
+    let mut cursor = start.offset as usize + 1 + name.len();
+    let mut element = ElementTrivia::default();
+    for attr in attrs {
+      let end = attr.start.offset as usize;
+      element
+        .before_attrs
+        .push(source.get(cursor..end).unwrap_or_default().to_string());
+      cursor = attr.end.offset as usize;
+    }
+    let rest = source.get(cursor..).unwrap_or_default();
+    let close = rest.len() - rest.trim_start().len();
+    element.before_close = rest[..close].to_string();
+    trivia.elements.insert(start.offset, element);
+  });
+  trivia
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::parser::Parser;
+
+  #[test]
+  fn element_trivia() {
+    let source = "<view  class=\"a\" \n    id=\"b\"\n>\n  <text wx:if=\"{{c}}\"/>\n</view>";
+    let root = Parser::new(source).parse_root().unwrap();
+    let trivia = collect_trivia(source, &root);
+    assert_eq!(trivia.len(), 2);
+
+    let view = trivia.element(&root.children[0]).unwrap();
+    assert_eq!(view.before_attrs, ["  ", " \n    "]);
+    assert_eq!(view.before_close, "\n");
+    assert!(!view.breaks_before_attr(0));
+    assert!(view.breaks_before_attr(1));
+    assert!(view.breaks_before_close());
+    assert!(view.has_trailing_whitespace());
+
+    let text = &root.children[0].children().unwrap()[0];
+    let text = trivia.element(text).unwrap();
+    assert_eq!(text.before_attrs, [" "]);
+    assert_eq!(text.before_close, "");
+    assert!(!text.has_trailing_whitespace());
+  }
+}
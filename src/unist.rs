@@ -0,0 +1,120 @@
+//! unist / hast 兼容的语法树输出
+//!
+//! 将 AST 转换为 [unist](https://github.com/syntax-tree/unist) 结构的 JSON：每个节点包含 `type`，
+//! 父节点包含 `children`，节点位置为 `position: { start, end }`，其中 `line`、`column` 从 1 开始，
+//! `offset` 与 [`Position::offset`] 一致。元素、文本与注释沿用 [hast](https://github.com/syntax-tree/hast)
+//! 的 `element`、`text`、`comment`，因此 `unist-util-visit` 等工具可以直接处理解析结果。
+//!
+//! hast 没有对应插值表达式的节点，`{{ }}` 输出为 `{ type: "expression", value }`；
+//! 属性按原始名称放入 `properties`，无值属性为 `true`，包含表达式的值按 `{{...}}` 形式拼接为字符串
+
+use serde_json::{json, Map, Value};
+
+use crate::ast::{Attribute, AttributeValue, Node, Position, Root};
+
+/// 将整个文档转换为 unist 结构
+pub fn to_unist(root: &Root) -> Value {
+  node_with_position(
+    json!({
+      "type": "root",
+      "children": root.children.iter().map(node_to_unist).collect::<Vec<_>>(),
+    }),
+    root.start,
+    root.end,
+  )
+}
+
+/// 将单个节点（含后代）转换为 unist 结构
+pub fn node_to_unist(node: &Node) -> Value {
+  let value = match node {
+    Node::Element {
+      name,
+      attrs,
+      children,
+      self_closing,
+      ..
+    } => json!({
+      "type": "element",
+      "tagName": name,
+      "properties": properties(attrs),
+      "children": children.iter().map(node_to_unist).collect::<Vec<_>>(),
+      "data": { "selfClosing": self_closing },
+    }),
+    Node::Text { content, .. } => json!({ "type": "text", "value": content }),
+    Node::Comment { content, .. } => json!({ "type": "comment", "value": content }),
+    Node::Expression { content, .. } => json!({ "type": "expression", "value": content }),
+  };
+  node_with_position(value, node.start(), node.end())
+}
+
+/// 合成节点没有源码位置，按 unist 的约定省略 `position`
+fn node_with_position(mut value: Value, start: Position, end: Position) -> Value {
+  if !start.is_synthetic() {
+    value["position"] = json!({ "start": point(start), "end": point(end) });
+  }
+  value
+}
+
+fn point(position: Position) -> Value {
+  json!({
+    "line": position.line,
+    "column": position.column,
+    "offset": position.offset,
+  })
+}
+
+fn properties(attrs: &[Attribute]) -> Map<String, Value> {
+  attrs
+    .iter()
+    .map(|attr| {
+      let value = match &attr.value {
+        None => Value::Bool(true),
+        Some(values) => Value::String(
+          values
+            .iter()
+            .map(|value| match value {
+              AttributeValue::Text { content, .. } => content.clone(),
+              AttributeValue::Expression { content, .. } => format!("{{{{{content}}}}}"),
+            })
+            .collect(),
+        ),
+      };
+      (attr.name.clone(), value)
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+  use crate::parser::Parser;
+
+  #[test]
+  fn unist_shape() {
+    let root = Parser::new("<view hidden class=\"a {{b}}\">hi {{name}}<!-- c --></view>")
+      .parse_root()
+      .unwrap();
+    let tree = to_unist(&root);
+    assert_eq!(tree["type"], "root");
+    let view = &tree["children"][0];
+    assert_eq!(view["tagName"], "view");
+    assert_eq!(
+      view["properties"],
+      json!({ "hidden": true, "class": "a {{b}}" })
+    );
+    let types: Vec<_> = view["children"]
+      .as_array()
+      .unwrap()
+      .iter()
+      .map(|child| child["type"].as_str().unwrap())
+      .collect();
+    assert_eq!(types, ["text", "expression", "comment"]);
+    assert_eq!(
+      view["position"]["start"],
+      json!({ "line": 1, "column": 1, "offset": 0 })
+    );
+    assert!(node_to_unist(&Node::text("x")).get("position").is_none());
+  }
+}
@@ -0,0 +1,301 @@
+//! Vue / uni-app 模板兼容
+//!
+//! 把 Vue 模板迁移到 WXML 的工具需要用同一个解析器读取两种语法。属性名本身已经允许 `@`、`:`、`#`，
+//! 这里负责识别 `v-if`、`:prop`、`@tap`、`#default` 等写法；开启
+//! [`ParserOptions::vue`](crate::options::ParserOptions::vue) 后，这些属性带引号的值不再按 `{{ }}` 拆分，
+//! 而是整体作为一个表达式值，例如 `:class="active"` 的值为表达式 `active`。
+//! 把属性改名为 `class` 再用 [`codegen`](crate::codegen) 输出即可得到 `class="{{active}}"`
+//!
+//! `v-for="item in list"`、`#item="{ row }"` 等值并不是合法的 JS 表达式，同样按原文保存，由使用方处理
+//!
+//! 反方向的迁移使用 [`to_vue_template`] 把 WXML 转换为 Vue 模板
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  ast::{Attribute, AttributeValue, Node, Root},
+  dialect::Directive,
+  format::{format, FormatOptions},
+};
+
+/// Vue 属性的种类
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum VueAttrKind {
+  /// `v-bind:prop` 或简写 `:prop`
+  Bind,
+  /// `v-on:event` 或简写 `@event`
+  On,
+  /// `v-slot:name` 或简写 `#name`
+  Slot,
+  /// 其他指令，如 `v-if`、`v-for`、`v-model`、自定义指令 `v-focus`
+  Directive,
+}
+
+/// 拆分后的 Vue 属性，如 `@click.stop.prevent` 为 `On`、参数 `click`、修饰符 `stop`、`prevent`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VueAttr {
+  pub kind: VueAttrKind,
+  /// 指令名称，不含 `v-`，如 `bind`、`on`、`if`、`model`
+  pub directive: String,
+  /// 指令参数，如 `v-bind:id` 的 `id`；动态参数 `:[key]` 保留方括号
+  pub arg: Option<String>,
+  pub modifiers: Vec<String>,
+}
+
+/// 识别 Vue 的指令与简写属性，普通属性返回 `None`
+pub fn classify_vue_attr(name: &str) -> Option<VueAttr> {
+  let (directive, rest) = if let Some(rest) = name.strip_prefix(':') {
+    ("bind", rest)
+  } else if let Some(rest) = name.strip_prefix('@') {
+    ("on", rest)
+  } else if let Some(rest) = name.strip_prefix('#') {
+    ("slot", rest)
+  } else {
+    let rest = name.strip_prefix("v-").filter(|rest| !rest.is_empty())?;
+    // 参数与修饰符之前的部分为指令名称
+    let end = rest.find([':', '.']).unwrap_or(rest.len());
+    let directive = &rest[..end];
+    let rest = &rest[end..];
+    (directive, rest.strip_prefix(':').unwrap_or(rest))
+  };
+  let mut parts = split_modifiers(rest);
+  let arg = parts.remove(0);
+  let kind = match directive {
+    "bind" => VueAttrKind::Bind,
+    "on" => VueAttrKind::On,
+    "slot" => VueAttrKind::Slot,
+    _ => VueAttrKind::Directive,
+  };
+  Some(VueAttr {
+    kind,
+    directive: directive.to_string(),
+    arg: (!arg.is_empty()).then(|| arg.to_string()),
+    modifiers: parts.into_iter().map(str::to_string).collect(),
+  })
+}
+
+/// 是否为 Vue 的指令或简写属性
+pub fn is_vue_attr(name: &str) -> bool {
+  classify_vue_attr(name).is_some()
+}
+
+/// 按 `.` 拆分参数与修饰符，动态参数 `[a.b]` 内部的 `.` 不拆分
+fn split_modifiers(source: &str) -> Vec<&str> {
+  let arg_end = match source.strip_prefix('[') {
+    Some(rest) => rest.find(']').map_or(source.len(), |index| index + 2),
+    None => 0,
+  };
+  let mut parts = Vec::new();
+  let mut start = 0;
+  for (index, ch) in source
+    .char_indices()
+    .skip_while(|(index, _)| *index < arg_end)
+  {
+    if ch == '.' {
+      parts.push(&source[start..index]);
+      start = index + 1;
+    }
+  }
+  parts.push(&source[start..]);
+  parts
+}
+
+/// 将 WXML 模板转换为 Vue 单文件组件的 `<template>` 块，用于从小程序迁移到 Vue / uni-app
+///
+/// 指令按 [`Directive::of`] 识别，因此同样接受 Swan、TTML 的写法：
+///
+/// - `wx:if`、`wx:elif`、`wx:else` 转换为 `v-if`、`v-else-if`、`v-else`
+/// - `wx:for` 与 `wx:for-item`、`wx:for-index` 合并为 `v-for="(item, index) in list"`，`wx:key` 转换为 `:key`
+/// - 事件绑定转换为 `@event`，`catch` 与 `capture` 分别对应修饰符 `.stop` 与 `.capture`
+/// - 包含表达式的属性转换为 `:prop`，静态文本与 `{{ }}` 混合时为模板字符串；`<block>` 转换为 `<template>`
+///
+/// 模板定义、`<template is>` 与 `<wxs>` 在 Vue 中没有对应写法，原样保留，需要手动改写为组件或方法
+pub fn to_vue_template(root: &Root, options: &FormatOptions) -> String {
+  let mut root = root.clone();
+  root.walk_mut(&mut |node| {
+    let Node::Element { name, attrs, .. } = node else {
+      return;
+    };
+    if name == "block" {
+      *name = "template".to_string();
+    }
+    *attrs = convert_attrs(attrs);
+  });
+  let indent = " ".repeat(options.indent_width);
+  let mut output = String::from("<template>\n");
+  for line in format(&root, options).lines() {
+    if !line.is_empty() {
+      output.push_str(&indent);
+    }
+    output.push_str(line);
+    output.push('\n');
+  }
+  output.push_str("</template>\n");
+  output
+}
+
+fn convert_attrs(attrs: &[Attribute]) -> Vec<Attribute> {
+  let directive = |target: Directive| {
+    attrs
+      .iter()
+      .find(|attr| Directive::of(&attr.name).is_some_and(|(_, found)| found == target))
+  };
+  let static_value = |target: Directive, default: &str| {
+    directive(target)
+      .and_then(Attribute::as_static_string)
+      .unwrap_or_else(|| default.to_string())
+  };
+  let mut converted = Vec::new();
+  for attr in attrs {
+    let Some((_, found)) = Directive::of(&attr.name) else {
+      converted.push(convert_attr(attr));
+      continue;
+    };
+    match found {
+      Directive::If => converted.push(Attribute::text("v-if", expression(attr))),
+      Directive::Elif => converted.push(Attribute::text("v-else-if", expression(attr))),
+      Directive::Else => converted.push(Attribute::new("v-else", None)),
+      Directive::For => {
+        let item = static_value(Directive::ForItem, "item");
+        let index = static_value(Directive::ForIndex, "index");
+        // Swan 的 `s-for="item in list trackBy item.id"` 已经是 Vue 的写法
+        let (source, track_by) = match attr.as_static_string() {
+          Some(text) if text.contains(" in ") => match text.split_once(" trackBy ") {
+            Some((source, key)) => (source.trim().to_string(), Some(key.trim().to_string())),
+            None => (text.trim().to_string(), None),
+          },
+          _ => (format!("({item}, {index}) in {}", expression(attr)), None),
+        };
+        converted.push(Attribute::text("v-for", source));
+        let key = track_by.or_else(|| {
+          directive(Directive::Key)
+            .and_then(Attribute::as_static_string)
+            .map(|key| match key.trim() {
+              "*this" => item.clone(),
+              key => format!("{item}.{key}"),
+            })
+        });
+        if let Some(key) = key {
+          converted.push(Attribute::text(":key", key));
+        }
+      }
+      Directive::ForItem | Directive::ForIndex | Directive::Key => {}
+    }
+  }
+  converted
+}
+
+/// 普通属性：事件绑定转换为 `@event`，包含表达式的值转换为 `:prop`
+fn convert_attr(attr: &Attribute) -> Attribute {
+  if attr.is_event_binding() {
+    let (event, modifiers) = match attr.prefix.as_deref() {
+      Some("bind" | "mut-bind") => (attr.local.as_str(), ""),
+      Some("catch") => (attr.local.as_str(), ".stop"),
+      Some("capture-bind") => (attr.local.as_str(), ".capture"),
+      Some("capture-catch") => (attr.local.as_str(), ".capture.stop"),
+      _ => match attr.name.strip_prefix("bind") {
+        Some(event) => (event, ""),
+        None => (attr.name.strip_prefix("catch").unwrap_or_default(), ".stop"),
+      },
+    };
+    let handler = attr
+      .as_static_string()
+      .map(|handler| handler.trim().to_string())
+      .unwrap_or_else(|| expression(attr));
+    return Attribute::text(format!("@{event}{modifiers}"), handler);
+  }
+  match &attr.value {
+    Some(_) if attr.as_static_string().is_none() => {
+      Attribute::text(format!(":{}", attr.name), expression(attr))
+    }
+    _ => attr.clone(),
+  }
+}
+
+/// 属性值作为 Vue 表达式：单个表达式原样输出，其余为模板字符串
+fn expression(attr: &Attribute) -> String {
+  match attr.value.as_deref() {
+    Some([AttributeValue::Expression { content, .. }]) => content.clone(),
+    _ => attr.to_template_string().unwrap_or_default(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{parse, AttributeValue, ParserOptions};
+
+  #[test]
+  fn vue_attrs() {
+    let attr = classify_vue_attr("@click.stop.prevent").unwrap();
+    assert_eq!(attr.kind, VueAttrKind::On);
+    assert_eq!(attr.arg.as_deref(), Some("click"));
+    assert_eq!(attr.modifiers, ["stop", "prevent"]);
+    let attr = classify_vue_attr("v-model.trim").unwrap();
+    assert_eq!((attr.directive.as_str(), attr.arg), ("model", None));
+    assert_eq!(attr.modifiers, ["trim"]);
+    let attr = classify_vue_attr(":[a.b].sync").unwrap();
+    assert_eq!(attr.arg.as_deref(), Some("[a.b]"));
+    assert_eq!(
+      classify_vue_attr("v-slot:footer").unwrap().kind,
+      VueAttrKind::Slot
+    );
+    assert_eq!(
+      classify_vue_attr("v-if").unwrap().kind,
+      VueAttrKind::Directive
+    );
+    assert!(classify_vue_attr("class").is_none());
+    assert!(classify_vue_attr("wx:if").is_none());
+
+    let source = r#"<view v-if="ok" :class=" a ? 'b' : c " @tap="go" title="{{t}}" v-else/>"#;
+    let options = ParserOptions {
+      vue: Some(true),
+      ..Default::default()
+    };
+    let root = parse(source.to_string(), Some(options)).unwrap();
+    let values: Vec<_> = root.children[0]
+      .attrs()
+      .unwrap()
+      .iter()
+      .map(|attr| match attr.value.as_deref() {
+        Some([AttributeValue::Expression { content, .. }]) => format!("expr {content}"),
+        Some([AttributeValue::Text { content, .. }]) => format!("text {content}"),
+        Some(values) => format!("{} parts", values.len()),
+        None => "none".to_string(),
+      })
+      .collect();
+    assert_eq!(
+      values,
+      ["expr ok", "expr a ? 'b' : c", "expr go", "expr t", "none"]
+    );
+    let class = &root.children[0].attrs().unwrap()[1];
+    assert_eq!(
+      (class.prefix.as_deref(), class.local.as_str()),
+      (Some(":"), "class")
+    );
+  }
+
+  #[test]
+  fn wxml_to_vue() {
+    let source = r#"<view class="list {{cls}}" hidden="{{!show}}" bindtap="open" catch:longpress="{{onPress}}">
+<block wx:for="{{users}}" wx:for-item="user" wx:key="id"><text wx:if="{{user.active}}">{{user.name}}</text><text wx:else>-</text></block>
+<view s-for="item in list trackBy item.id" capture-bind:touchstart="start"/>
+</view>"#;
+    let root = parse(source.to_string(), None).unwrap();
+    assert_eq!(
+      to_vue_template(&root, &FormatOptions::default()),
+      r#"<template>
+  <view :class="`list ${cls}`" :hidden="!show" @tap="open" @longpress.stop="onPress">
+    <template v-for="(user, index) in users" :key="user.id">
+      <text v-if="user.active">{{ user.name }}</text>
+      <text v-else>-</text>
+    </template>
+    <view v-for="item in list" :key="item.id" @touchstart.capture="start" />
+  </view>
+</template>
+"#
+    );
+  }
+}
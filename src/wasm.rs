@@ -0,0 +1,67 @@
+//! 基于 wasm-bindgen 的浏览器端接口
+//!
+//! 通过 `wasm-pack build --target web -- --no-default-features --features wasm` 构建，
+//! 输出结构与 NAPI 版本一致，枚举值以名称字符串表示
+
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+  expr::parse_expression, options::ParserOptions, parser::Parser, preview::render_preview,
+  unist::to_unist,
+};
+
+fn to_js_error(error: impl std::fmt::Display) -> JsError {
+  JsError::new(&error.to_string())
+}
+
+fn parser_options(options: JsValue) -> Result<ParserOptions, JsError> {
+  if options.is_undefined() || options.is_null() {
+    Ok(ParserOptions::default())
+  } else {
+    serde_wasm_bindgen::from_value(options).map_err(to_js_error)
+  }
+}
+
+/// 将 WXML 模板字符串解析为抽象语法树
+#[wasm_bindgen(js_name = parse)]
+pub fn parse_wasm(source: &str, options: JsValue) -> Result<JsValue, JsError> {
+  let mut parser = Parser::with_options(source, parser_options(options)?);
+  let root = parser.parse_root().map_err(to_js_error)?;
+  let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+  root.serialize(&serializer).map_err(to_js_error)
+}
+
+/// 将 WXML 模板字符串解析为 unist / hast 兼容的语法树
+#[wasm_bindgen(js_name = parseUnist)]
+pub fn parse_unist_wasm(source: &str, options: JsValue) -> Result<JsValue, JsError> {
+  let mut parser = Parser::with_options(source, parser_options(options)?);
+  let root = parser.parse_root().map_err(to_js_error)?;
+  let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+  to_unist(&root).serialize(&serializer).map_err(to_js_error)
+}
+
+/// 解析 `{{ }}` 中的表达式内容并输出为 ESTree 结构
+#[wasm_bindgen(js_name = parseExpressionEstree)]
+pub fn parse_expression_estree_wasm(content: &str) -> Result<JsValue, JsError> {
+  let expr = parse_expression(content).map_err(to_js_error)?;
+  let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+  expr.to_estree().serialize(&serializer).map_err(to_js_error)
+}
+
+/// 使用 `data` 渲染模板并输出为可在浏览器中预览的 HTML
+#[wasm_bindgen(js_name = renderPreview)]
+pub fn render_preview_wasm(
+  source: &str,
+  data: JsValue,
+  options: JsValue,
+) -> Result<String, JsError> {
+  let mut parser = Parser::with_options(source, parser_options(options)?);
+  let root = parser.parse_root().map_err(to_js_error)?;
+  let data: serde_json::Value = if data.is_undefined() || data.is_null() {
+    serde_json::Value::Null
+  } else {
+    serde_wasm_bindgen::from_value(data).map_err(to_js_error)?
+  };
+  render_preview(&root, &data).map_err(to_js_error)
+}
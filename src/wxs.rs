@@ -0,0 +1,448 @@
+//! 内联 wxs 模块的静态分析
+//!
+//! 从内联 `<wxs module>`（及其他方言的脚本标签）中找出 `module.exports` 导出的成员，
+//! 与模板表达式中对模块成员的访问（如 `{{ fmt.price(p) }}`）交叉比对，报告未被使用的导出函数，
+//! 以及访问了未导出成员的表达式
+//!
+//! 支持的导出写法：
+//!
+//! ```js
+//! module.exports = { price: price, format: function (v) { ... }, unit };
+//! module.exports.round = round;
+//! module.exports['ceil'] = function (v) { ... };
+//! ```
+//!
+//! 使用 `src` 引用外部文件的模块无法确定导出，不做检查；模块整体作为值传递（如 `{{ fmt }}`）
+//! 或以非字面量下标访问（如 `{{ fmt[name] }}`）时视为使用了全部导出
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  ast::{AttributeValue, Node, Position, Root},
+  dialect::Dialect,
+  expr::{parse_expression, Expr, ObjectProperty},
+};
+
+/// 模块导出的一个成员
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WxsExport {
+  pub name: String,
+  /// 导出的值是否为函数，即 `function` 表达式或模块中以 `function` 声明的同名函数
+  pub function: bool,
+  /// 导出名称在源码中的位置
+  pub start: Position,
+  pub end: Position,
+}
+
+/// 一个内联模块
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WxsModule {
+  /// 模块名，即 `module` 属性的值
+  pub name: String,
+  /// 按出现顺序排列的导出成员
+  pub exports: Vec<WxsExport>,
+  /// 脚本标签的位置
+  pub start: Position,
+  pub end: Position,
+}
+
+/// 问题类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WxsIssueKind {
+  /// 导出的函数没有在模板中使用
+  UnusedExport,
+  /// 模板访问了模块没有导出的成员
+  MissingMember,
+}
+
+/// 一处问题
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WxsIssue {
+  pub kind: WxsIssueKind,
+  pub module: String,
+  pub member: String,
+  pub message: String,
+  /// 未使用的导出为导出名称的位置，缺失的成员为访问它的 `{{ }}` 的位置
+  pub start: Position,
+  pub end: Position,
+}
+
+/// 收集文档中的内联模块
+pub fn inline_wxs_modules(root: &Root) -> Vec<WxsModule> {
+  let mut modules = Vec::new();
+  root.walk(&mut |node| {
+    let Node::Element {
+      name,
+      children,
+      start,
+      end,
+      ..
+    } = node
+    else {
+      return;
+    };
+    if !Dialect::ALL
+      .iter()
+      .any(|dialect| dialect.script_tag() == name)
+      || node.attr("src").is_some()
+    {
+      return;
+    }
+    let Some(module) = node.attr("module").and_then(|attr| attr.as_static_string()) else {
+      return;
+    };
+    let mut exports = Vec::new();
+    for child in children {
+      if let Node::Text { content, start, .. } = child {
+        collect_exports(content, *start, &mut exports);
+      }
+    }
+    modules.push(WxsModule {
+      name: module,
+      exports,
+      start: *start,
+      end: *end,
+    });
+  });
+  modules
+}
+
+/// 交叉比对内联模块的导出与模板中的使用，按位置返回问题
+pub fn analyze_wxs(root: &Root) -> Vec<WxsIssue> {
+  let modules = inline_wxs_modules(root);
+  let names: HashSet<&str> = modules.iter().map(|module| module.name.as_str()).collect();
+  let mut accesses = Vec::new();
+  let mut visit = |content: &str, start: Position, end: Position| {
+    if let Ok(expr) = parse_expression(content) {
+      collect_accesses(&expr, &names, &mut |module, member| {
+        accesses.push((module.to_string(), member, start, end));
+      });
+    }
+  };
+  root.walk(&mut |node| match node {
+    Node::Expression {
+      content,
+      start,
+      end,
+    } => visit(content, *start, *end),
+    Node::Element { attrs, .. } => {
+      for value in attrs.iter().flat_map(|attr| attr.value.iter().flatten()) {
+        if let AttributeValue::Expression {
+          content,
+          start,
+          end,
+        } = value
+        {
+          visit(content, *start, *end);
+        }
+      }
+    }
+    _ => {}
+  });
+
+  let mut issues = Vec::new();
+  for module in &modules {
+    let uses: Vec<_> = accesses
+      .iter()
+      .filter(|(name, ..)| *name == module.name)
+      .collect();
+    let uses_all = uses.iter().any(|(_, member, ..)| member.is_none());
+    for export in &module.exports {
+      let used = uses_all
+        || uses
+          .iter()
+          .any(|(_, member, ..)| member.as_deref() == Some(export.name.as_str()));
+      if export.function && !used {
+        issues.push(WxsIssue {
+          kind: WxsIssueKind::UnusedExport,
+          module: module.name.clone(),
+          member: export.name.clone(),
+          message: format!(
+            "`{}.{}` is exported but never used in the template",
+            module.name, export.name
+          ),
+          start: export.start,
+          end: export.end,
+        });
+      }
+    }
+    for (_, member, start, end) in &uses {
+      let Some(member) = member else {
+        continue;
+      };
+      if module.exports.iter().all(|export| export.name != *member) {
+        issues.push(WxsIssue {
+          kind: WxsIssueKind::MissingMember,
+          module: module.name.clone(),
+          member: member.clone(),
+          message: format!("`{}` does not export `{}`", module.name, member),
+          start: *start,
+          end: *end,
+        });
+      }
+    }
+  }
+  issues.sort_by_key(|issue| issue.start.offset);
+  issues
+}
+
+/// 查找对模块的访问，`member` 为 `None` 表示无法确定访问的成员
+fn collect_accesses(expr: &Expr, modules: &HashSet<&str>, f: &mut dyn FnMut(&str, Option<String>)) {
+  let access = match expr {
+    Expr::Ident(name) => Some((name, None)),
+    Expr::Member { object, property } => match object.as_ref() {
+      Expr::Ident(name) => Some((name, Some(property))),
+      _ => None,
+    },
+    Expr::Index { object, index } => match (object.as_ref(), index.as_ref()) {
+      (Expr::Ident(name), Expr::String(member)) => Some((name, Some(member))),
+      _ => None,
+    },
+    _ => None,
+  };
+  if let Some((name, member)) = access {
+    if modules.contains(name.as_str()) {
+      f(name, member.cloned());
+    }
+    return;
+  }
+  let mut visit = |expr: &Expr| collect_accesses(expr, modules, f);
+  match expr {
+    Expr::Member { object, .. } => visit(object),
+    Expr::Index { object, index } => {
+      visit(object);
+      visit(index);
+    }
+    Expr::Null
+    | Expr::Undefined
+    | Expr::Bool(_)
+    | Expr::Number(_)
+    | Expr::String(_)
+    | Expr::Ident(_) => {}
+    Expr::Array(items) => items.iter().for_each(visit),
+    Expr::Object(properties) => {
+      for property in properties {
+        match property {
+          ObjectProperty::Property { value, .. } | ObjectProperty::Spread(value) => visit(value),
+        }
+      }
+    }
+    Expr::Call { callee, args } => {
+      visit(callee);
+      args.iter().for_each(visit);
+    }
+    Expr::Unary { argument, .. } | Expr::Spread(argument) => visit(argument),
+    Expr::Binary { left, right, .. } => {
+      visit(left);
+      visit(right);
+    }
+    Expr::Conditional {
+      test,
+      consequent,
+      alternate,
+    } => {
+      visit(test);
+      visit(consequent);
+      visit(alternate);
+    }
+  }
+}
+
+/// 脚本中的词法单元，注释已跳过
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token<'a> {
+  Ident(&'a str),
+  /// 字符串字面量的内容（不含引号，不处理转义）
+  String(&'a str),
+  Punct(char),
+}
+
+/// 切分脚本，返回词法单元与其字节偏移。wxs 不支持正则表达式字面量，`/` 只可能是除号或注释
+fn tokenize(source: &str) -> Vec<(Token<'_>, usize)> {
+  let bytes = source.as_bytes();
+  let mut tokens = Vec::new();
+  let mut index = 0;
+  while index < bytes.len() {
+    let byte = bytes[index];
+    let rest = &source[index..];
+    if byte.is_ascii_whitespace() {
+      index += 1;
+    } else if rest.starts_with("//") {
+      index += rest.find('\n').unwrap_or(rest.len());
+    } else if let Some(comment) = rest.strip_prefix("/*") {
+      index += comment.find("*/").map_or(rest.len(), |end| end + 4);
+    } else if byte == b'"' || byte == b'\'' {
+      let mut end = index + 1;
+      while end < bytes.len() && bytes[end] != byte {
+        end += if bytes[end] == b'\\' { 2 } else { 1 };
+      }
+      let end = end.min(bytes.len());
+      tokens.push((Token::String(&source[index + 1..end]), index));
+      index = end + 1;
+    } else if byte == b'_' || byte == b'$' || byte.is_ascii_alphanumeric() || byte >= 0x80 {
+      let len = rest
+        .find(|c: char| !(c == '_' || c == '$' || c.is_alphanumeric()))
+        .unwrap_or(rest.len());
+      tokens.push((Token::Ident(&rest[..len]), index));
+      index += len;
+    } else {
+      let ch = rest.chars().next().unwrap_or_default();
+      tokens.push((Token::Punct(ch), index));
+      index += ch.len_utf8();
+    }
+  }
+  tokens
+}
+
+fn collect_exports(source: &str, start: Position, out: &mut Vec<WxsExport>) {
+  let tokens = tokenize(source);
+  let functions = declared_functions(&tokens);
+  let is_function = |value: Option<&(Token, usize)>, next: Option<&(Token, usize)>| match value {
+    Some((Token::Ident("function"), _)) => true,
+    Some((Token::Ident(name), _)) => {
+      functions.contains(name) && matches!(next, None | Some((Token::Punct(',' | '}' | ';'), _)))
+    }
+    _ => false,
+  };
+  let mut push = |name: &str, offset: usize, function| {
+    let name_start = start.advance(&source[..offset]);
+    out.push(WxsExport {
+      name: name.to_string(),
+      function,
+      start: name_start,
+      end: name_start.advance(&source[offset..offset + name.len()]),
+    });
+  };
+  let mut index = 0;
+  while index + 2 < tokens.len() {
+    let matched = matches!(
+      tokens[index..index + 3],
+      [
+        (Token::Ident("module"), _),
+        (Token::Punct('.'), _),
+        (Token::Ident("exports"), _)
+      ]
+    );
+    index += 1;
+    if !matched {
+      continue;
+    }
+    let rest = &tokens[index + 2..];
+    match rest {
+      // module.exports.name = value
+      [(Token::Punct('.'), _), (Token::Ident(name), offset), (Token::Punct('='), _), tail @ ..] => {
+        push(name, *offset, is_function(tail.first(), tail.get(1)))
+      }
+      // module.exports['name'] = value
+      [(Token::Punct('['), _), (Token::String(name), offset), (Token::Punct(']'), _), (Token::Punct('='), _), tail @ ..] => {
+        push(name, offset + 1, is_function(tail.first(), tail.get(1)))
+      }
+      // module.exports = { ... }
+      [(Token::Punct('='), _), (Token::Punct('{'), _), tail @ ..] => {
+        let mut depth = 0;
+        // 当前是否位于属性名的位置
+        let mut at_key = true;
+        for (position, (token, offset)) in tail.iter().enumerate() {
+          match token {
+            Token::Punct('{' | '(' | '[') => depth += 1,
+            Token::Punct('}') if depth == 0 => break,
+            Token::Punct('}' | ')' | ']') => depth -= 1,
+            Token::Punct(',') if depth == 0 => at_key = true,
+            Token::Ident(name) | Token::String(name) if depth == 0 && at_key => {
+              at_key = false;
+              let offset = if matches!(token, Token::String(_)) {
+                offset + 1
+              } else {
+                *offset
+              };
+              let function = match tail.get(position + 1) {
+                Some((Token::Punct(':'), _)) => {
+                  is_function(tail.get(position + 2), tail.get(position + 3))
+                }
+                // 简写 `{ name }`
+                next => is_function(Some(&tail[position]), next),
+              };
+              push(name, offset, function);
+            }
+            _ => {}
+          }
+        }
+      }
+      _ => {}
+    }
+  }
+}
+
+/// 模块中以 `function name()` 或 `var name = function` 声明的函数
+fn declared_functions<'a>(tokens: &[(Token<'a>, usize)]) -> HashSet<&'a str> {
+  let mut functions = HashSet::new();
+  for window in tokens.windows(4) {
+    match window {
+      [(Token::Ident("function"), _), (Token::Ident(name), _), (Token::Punct('('), _), _]
+      | [(Token::Ident("var"), _), (Token::Ident(name), _), (Token::Punct('='), _), (Token::Ident("function"), _)] =>
+      {
+        functions.insert(*name);
+      }
+      _ => {}
+    }
+  }
+  functions
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::parser::Parser;
+
+  #[test]
+  fn wxs_exports_and_usages() {
+    let source = r#"<wxs module="fmt">
+function price(v) { return '$' + v; }
+var unit = function () { return 'cm'; };
+// module.exports.commented = price;
+module.exports = { price: price, unit, 'round': function (v) { return Math.round(v); }, version: '1.0' };
+module.exports.trim = function (s) { return s.trim(); };
+</wxs>
+<wxs module="ext" src="./ext.wxs"/>
+<text>{{ fmt.price(p) }}{{ fmt['round'](n) }}{{ fmt.ceil(n) }} {{ ext.any(a) }}</text>"#;
+    let root = Parser::new(source).parse_root().unwrap();
+    let modules = inline_wxs_modules(&root);
+    assert_eq!(modules.len(), 1);
+    let exports: Vec<_> = modules[0]
+      .exports
+      .iter()
+      .map(|export| (export.name.as_str(), export.function))
+      .collect();
+    assert_eq!(
+      exports,
+      [
+        ("price", true),
+        ("unit", true),
+        ("round", true),
+        ("version", false),
+        ("trim", true)
+      ]
+    );
+    let round = &modules[0].exports[2];
+    assert_eq!((round.start.line, round.start.column), (5, 41));
+
+    let issues: Vec<_> = analyze_wxs(&root)
+      .into_iter()
+      .map(|issue| (issue.kind, issue.member))
+      .collect();
+    assert_eq!(
+      issues,
+      [
+        (WxsIssueKind::UnusedExport, "unit".to_string()),
+        (WxsIssueKind::UnusedExport, "trim".to_string()),
+        (WxsIssueKind::MissingMember, "ceil".to_string()),
+      ]
+    );
+  }
+}
@@ -1,5 +1,5 @@
 import { describe, it, expect } from "vitest";
-import { Attribute, parse } from "../";
+import { Attribute, parse, parseAsync } from "../";
 
 describe("parse", () => {
   it("toBeDefined", () => {
@@ -43,12 +43,18 @@ describe("parse", () => {
   //   // console.log(result);
   //   expect(result1.children[0].endTag).toEqual(null);
   // });
+  it("errors", () => {
+    const result = parse(`<view>1 < 2</view>`);
+    expect(result.errors.map((error) => error.code)).toEqual(["WXML1016"]);
+    expect(parse(`<view/>`).errors).toEqual([]);
+  });
   it("attrs", () => {
     const wxml = `<view class="cls1" bindtap="{{handleTap}}"></view>`;
     const result = parse(wxml);
     const attrs = result.children[0].attrs as Attribute[];
     expect(attrs[0]).toEqual({
       name: "class",
+      local: "class",
       value: [
         {
           type: "Text",
@@ -57,11 +63,13 @@ describe("parse", () => {
           end: { column: 18, line: 1, offset: 17 },
         },
       ],
+      quote: AttributeQuote.Double,
       start: { column: 7, line: 1, offset: 6 },
       end: { column: 19, line: 1, offset: 18 },
     });
     expect(attrs[1]).toEqual({
       name: "bindtap",
+      local: "bindtap",
       value: [
         {
           type: "Expression",
@@ -70,7 +78,7 @@ describe("parse", () => {
           end: { column: 42, line: 1, offset: 41 },
         },
       ],
-
+      quote: AttributeQuote.Double,
       start: { column: 20, line: 1, offset: 19 },
       end: { column: 43, line: 1, offset: 42 },
     });
@@ -103,6 +111,11 @@ describe("parse", () => {
       },
     ]);
   });
+  it("parseAsync", async () => {
+    const result = await parseAsync(`<text>Hello {{name}}</text>`);
+    expect(result.children[0].type).toEqual("Element");
+    await expect(parseAsync(`<view></text>`)).rejects.toThrow();
+  });
   it("wxs", () => {
     const result = parse(`<wxs>console.log(a)</wxs>`);
     expect(result.children[0].children[0].content).toEqual("console.log(a)");